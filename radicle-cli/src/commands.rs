@@ -2,6 +2,8 @@
 pub mod rad_auth;
 #[path = "commands/block.rs"]
 pub mod rad_block;
+#[path = "commands/bundle.rs"]
+pub mod rad_bundle;
 #[path = "commands/checkout.rs"]
 pub mod rad_checkout;
 #[path = "commands/clean.rs"]
@@ -12,10 +14,16 @@ pub mod rad_clone;
 pub mod rad_cob;
 #[path = "commands/config.rs"]
 pub mod rad_config;
+#[path = "commands/dashboard.rs"]
+pub mod rad_dashboard;
 #[path = "commands/debug.rs"]
 pub mod rad_debug;
 #[path = "commands/diff.rs"]
 pub mod rad_diff;
+#[path = "commands/discuss.rs"]
+pub mod rad_discuss;
+#[path = "commands/export.rs"]
+pub mod rad_export;
 #[path = "commands/follow.rs"]
 pub mod rad_follow;
 #[path = "commands/fork.rs"]
@@ -24,6 +32,8 @@ pub mod rad_fork;
 pub mod rad_help;
 #[path = "commands/id.rs"]
 pub mod rad_id;
+#[path = "commands/import.rs"]
+pub mod rad_import;
 #[path = "commands/inbox.rs"]
 pub mod rad_inbox;
 #[path = "commands/init.rs"]
@@ -58,3 +68,5 @@ pub mod rad_unfollow;
 pub mod rad_unseed;
 #[path = "commands/watch.rs"]
 pub mod rad_watch;
+#[path = "commands/wiki.rs"]
+pub mod rad_wiki;
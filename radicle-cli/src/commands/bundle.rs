@@ -0,0 +1,298 @@
+use std::ffi::OsString;
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context as _};
+
+use radicle::git;
+use radicle::prelude::{NodeId, RepoId};
+use radicle::storage::git::Repository;
+use radicle::storage::{ReadRepository, ReadStorage, WriteRepository};
+use radicle::Profile;
+use radicle_fetch::transport::local::UploadPack;
+use radicle_fetch::{Allowed, BlockList, FetchLimit, FetchResult};
+
+use crate::terminal as term;
+use crate::terminal::args::{Args, Error, Help};
+
+pub const HELP: Help = Help {
+    name: "bundle",
+    description: "Synchronize repositories using bundle files",
+    version: env!("RADICLE_VERSION"),
+    usage: r#"
+Usage
+
+    rad bundle create <file> [<option>...]
+    rad bundle apply <file> [<option>...]
+
+    Repositories can be synchronized without a network, by carrying a
+    bundle file between machines.
+
+    The `create` command writes a Git bundle containing the repository
+    identity and every namespace in local storage, along with its
+    signed references (`rad/sigrefs`) and collaborative objects.
+
+    The `apply` command updates local storage from a bundle. The bundle
+    is verified the same way a fetch from a peer is: the signed
+    references of every namespace are validated, the identity's
+    delegate threshold must be met, and namespaces of blocked peers are
+    ignored. Only repositories that are seeded can be applied, using
+    the seeding scope to decide which namespaces are kept.
+
+Create options
+
+    --repo <rid>    Operate on the given repository (default: cwd)
+
+Options
+
+    --help          Print help
+"#,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum OperationName {
+    Create,
+    Apply,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Operation {
+    Create { path: PathBuf, repo: Option<RepoId> },
+    Apply { path: PathBuf },
+}
+
+#[derive(Debug)]
+pub struct Options {
+    pub op: Operation,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut op: Option<OperationName> = None;
+        let mut path: Option<PathBuf> = None;
+        let mut repo = None;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("help") | Short('h') => {
+                    return Err(Error::Help.into());
+                }
+                Long("repo") if op == Some(OperationName::Create) => {
+                    let val = parser.value()?;
+                    let rid = term::args::rid(&val)?;
+
+                    repo = Some(rid);
+                }
+                Value(val) if op.is_none() => match val.to_string_lossy().as_ref() {
+                    "create" => op = Some(OperationName::Create),
+                    "apply" => op = Some(OperationName::Apply),
+
+                    unknown => anyhow::bail!("unknown operation '{}'", unknown),
+                },
+                Value(val) if path.is_none() => {
+                    path = Some(PathBuf::from(val));
+                }
+                _ => {
+                    return Err(anyhow!(arg.unexpected()));
+                }
+            }
+        }
+
+        let op =
+            op.ok_or_else(|| anyhow!("an operation must be provided; see `rad bundle --help`"))?;
+        let path = path.ok_or_else(|| anyhow!("a bundle file must be provided"))?;
+        let op = match op {
+            OperationName::Create => Operation::Create { path, repo },
+            OperationName::Apply => Operation::Apply { path },
+        };
+
+        Ok((Options { op }, vec![]))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let profile = ctx.profile()?;
+
+    match options.op {
+        Operation::Create { path, repo } => {
+            let rid = if let Some(rid) = repo {
+                rid
+            } else {
+                radicle::rad::cwd().map(|(_, rid)| rid)?
+            };
+            create(rid, &path, &profile)?;
+        }
+        Operation::Apply { path } => {
+            apply(&path, &profile)?;
+        }
+    }
+    Ok(())
+}
+
+/// Write a bundle of the repository's identity and namespaces to `path`.
+fn create(rid: RepoId, path: &Path, profile: &Profile) -> anyhow::Result<()> {
+    let repo = profile.storage.repository(rid)?;
+    let namespaces = repo.remote_ids()?.count();
+    let output = std::env::current_dir()?.join(path);
+
+    git::run::<_, _, &str, &str>(
+        repo.path(),
+        [
+            "bundle".as_ref(),
+            "create".as_ref(),
+            "--quiet".as_ref(),
+            output.as_os_str(),
+            git::refs::storage::IDENTITY_BRANCH.as_str().as_ref(),
+            "--glob=refs/namespaces/*".as_ref(),
+        ],
+        [],
+    )
+    .with_context(|| format!("failed to create bundle {}", path.display()))?;
+
+    term::success!(
+        "Created bundle of {} with {namespaces} namespace(s) at {}",
+        term::format::tertiary(rid),
+        term::format::tertiary(path.display())
+    );
+
+    Ok(())
+}
+
+/// Verify the bundle found at `path`, and apply it to storage.
+fn apply(path: &Path, profile: &Profile) -> anyhow::Result<()> {
+    let bundle = std::env::current_dir()?.join(path);
+    let tmp = tempfile::tempdir()?;
+    let unpacked = unpack(&bundle, tmp.path())
+        .map_err(|e| anyhow!("failed to read bundle {}: {e}", path.display()))?;
+    let rid = unpacked.id;
+    let local = *profile.id();
+    let remote = remote(&unpacked, &local)?;
+
+    let policies = profile.policies()?;
+    let allowed = Allowed::from_config(rid, &policies)?;
+    let blocked = BlockList::from_config(&policies)?;
+    let stream = UploadPack::spawn(tmp.path())?;
+    let storage = &profile.storage;
+
+    let result = if storage.contains(&rid)? {
+        let repo = storage.repository(rid)?;
+        let mut handle = radicle_fetch::Handle::new(local, repo, allowed, blocked, stream)?;
+
+        radicle_fetch::pull(&mut handle, FetchLimit::default(), remote, None)?
+    } else {
+        let (repo, lock) = storage.lock_repository(rid)?;
+        let mut handle = radicle_fetch::Handle::new(local, repo, allowed, blocked, stream)?;
+        let result = radicle_fetch::clone(&mut handle, FetchLimit::default(), remote)?;
+
+        if result.is_success() {
+            std::fs::rename(lock.path(), storage.path_of(&rid))?;
+        }
+        result
+    };
+
+    match result {
+        FetchResult::Failed {
+            threshold,
+            delegates,
+            validations,
+        } => {
+            for warning in validations.iter() {
+                term::warning(warning.to_string());
+            }
+            anyhow::bail!(
+                "bundle {} failed verification: {} delegate(s) failed validation, and the threshold of {threshold} was not met",
+                path.display(),
+                delegates.len(),
+            );
+        }
+        FetchResult::Success {
+            applied,
+            remotes,
+            validations,
+        } => {
+            for warning in validations.iter() {
+                term::warning(warning.to_string());
+            }
+            let repo = storage.repository(rid)?;
+            repo.set_identity_head()?;
+            repo.set_head()?;
+            cache(&repo, profile)?;
+
+            let updated = applied.updated.iter().filter(|u| !u.is_skipped()).count();
+            term::success!(
+                "Applied bundle of {} with {} namespace(s)",
+                term::format::tertiary(rid),
+                remotes.len()
+            );
+            term::info!("{updated} reference(s) updated");
+        }
+    }
+    Ok(())
+}
+
+/// Unpack the bundle into a new repository at `path`, laid out like storage.
+fn unpack(bundle: &Path, path: &Path) -> anyhow::Result<Repository> {
+    let raw = git::raw::Repository::init_bare(path)?;
+    git::run::<_, _, &str, &str>(
+        path,
+        [
+            "fetch".as_ref(),
+            "--quiet".as_ref(),
+            bundle.as_os_str(),
+            "refs/rad/id:refs/rad/id".as_ref(),
+            "refs/namespaces/*:refs/namespaces/*".as_ref(),
+        ],
+        [],
+    )?;
+
+    // The repository identifier is the initial identity document, found
+    // at the root of the identity history.
+    let head = raw
+        .refname_to_id(git::refs::storage::IDENTITY_BRANCH.as_str())
+        .context("bundle doesn't contain a repository identity")?;
+    let mut walk = raw.revwalk()?;
+    walk.push(head)?;
+    let root = walk
+        .last()
+        .ok_or_else(|| anyhow!("empty identity history"))??;
+    let doc = raw
+        .find_commit(root)?
+        .tree()?
+        .get_path(&Path::new("embeds").join(*radicle::identity::doc::PATH))?
+        .id();
+    let repo = Repository::open(path, RepoId::from(doc))?;
+
+    Ok(repo)
+}
+
+/// Pick the namespace to fetch from, preferring one of the delegates.
+fn remote(repo: &Repository, local: &NodeId) -> anyhow::Result<NodeId> {
+    let doc = repo.identity_doc_at(repo.identity_head()?)?;
+    let mut remotes = repo
+        .remote_ids()?
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .filter(|id| id != local)
+        .collect::<Vec<_>>();
+    remotes.sort_by_key(|id| !doc.is_delegate(id));
+
+    remotes
+        .first()
+        .copied()
+        .ok_or_else(|| anyhow!("bundle doesn't contain any namespaces other than your own"))
+}
+
+/// Update the issue and patch caches after new objects were applied.
+fn cache(repo: &Repository, profile: &Profile) -> anyhow::Result<()> {
+    profile
+        .issues_mut(repo)?
+        .write_all(|_, _| ControlFlow::Continue(()))?;
+    profile
+        .patches_mut(repo)?
+        .write_all(|_, _| ControlFlow::Continue(()))?;
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 #![allow(clippy::or_fun_call)]
 use std::ffi::OsString;
+use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time;
@@ -44,11 +45,16 @@ Usage
     For private repositories, use the `--private` and `--seed` options, to clone directly
     from known seeds in the privacy set.
 
+    To clone a large repository quickly, use the `--depth` option. Only the canonical
+    branch of the repository delegates is fetched, truncated to the given number of
+    commits. Partial repositories are not served to other peers.
+
 Options
 
         --scope <scope>     Follow scope: `followed` or `all` (default: all)
     -s, --seed <nid>        Clone from this seed (may be specified multiple times)
         --private           Clone a private repository
+        --depth <n>         Only fetch the last <n> commits of the canonical branch
         --timeout <secs>    Timeout for fetching repository (default: 9)
         --help              Print help
 
@@ -63,6 +69,8 @@ pub struct Options {
     directory: Option<PathBuf>,
     /// The seeding scope of the repository.
     scope: Scope,
+    /// The history depth, if this is a partial clone.
+    depth: Option<NonZeroUsize>,
     /// Sync settings.
     sync: SyncSettings,
 }
@@ -74,6 +82,7 @@ impl Args for Options {
         let mut parser = lexopt::Parser::from_args(args);
         let mut id: Option<RepoId> = None;
         let mut scope = Scope::All;
+        let mut depth = None;
         let mut sync = SyncSettings::default();
         let mut directory = None;
 
@@ -94,6 +103,11 @@ impl Args for Options {
                 Long("private") => {
                     sync.force = true;
                 }
+                Long("depth") => {
+                    let value = parser.value()?;
+
+                    depth = Some(term::args::parse_value("depth", value)?);
+                }
                 Long("timeout") => {
                     let value = parser.value()?;
                     let secs = term::args::number(&value)?;
@@ -129,6 +143,7 @@ impl Args for Options {
                 id,
                 directory,
                 scope,
+                depth,
                 sync,
             },
             vec![],
@@ -147,7 +162,19 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
         );
     }
 
-    let (working, repo, doc, proj) = clone(
+    if let Some(depth) = options.depth {
+        if profile.storage.contains(&options.id)? {
+            anyhow::bail!(
+                "repository {} is already in storage, it can't be cloned partially",
+                options.id
+            );
+        }
+        profile
+            .policies_mut()?
+            .set_depth(&options.id, Some(depth))?;
+    }
+
+    let (working, repo, doc, proj) = match clone(
         options.id,
         options.directory.clone(),
         options.scope,
@@ -155,7 +182,16 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
         &mut node,
         &signer,
         &profile.storage,
-    )?;
+    ) {
+        Ok(cloned) => cloned,
+        Err(err) => {
+            // Don't leave the depth behind, so that a later clone or fetch isn't partial.
+            if options.depth.is_some() {
+                profile.policies_mut()?.set_depth(&options.id, None)?;
+            }
+            return Err(err.into());
+        }
+    };
     let delegates = doc
         .delegates
         .iter()
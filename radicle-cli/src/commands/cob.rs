@@ -1,4 +1,5 @@
 use std::ffi::OsString;
+use std::path::PathBuf;
 use std::str::FromStr;
 
 use anyhow::anyhow;
@@ -7,9 +8,11 @@ use nonempty::NonEmpty;
 use radicle::cob;
 use radicle::prelude::RepoId;
 use radicle::storage::ReadStorage;
+use radicle::Node;
 use radicle_cob::object::collaboration::list;
 
 use crate::git::Rev;
+use crate::node;
 use crate::terminal as term;
 use crate::terminal::args::{Args, Error, Help};
 
@@ -22,33 +25,70 @@ Usage
 
     rad cob <command> [<option>...]
     rad cob list --repo <rid> --type <typename>
-    rad cob show --repo <rid> --type <typename> --object <oid>
+    rad cob show --repo <rid> --type <typename> --object <oid> [--ops]
+    rad cob create --repo <rid> --type <typename> [<option>...] <action>...
+    rad cob update --repo <rid> --type <typename> --object <oid> [<option>...] <action>...
 
 Commands
 
     list       List all COBs of a given type (--object is not needed)
-    show       Show a COB as raw operations
+    show       Show a COB's state, as evaluated by the evaluator registered for its type,
+               or as raw operations if there is none
+    create     Create a COB from JSON actions, and print its ID
+    update     Add JSON actions to a COB, and print the ID of the new operation
+
+    Actions are JSON objects, stored as given. This allows tools to define their
+    own COB types; the types managed by other `rad` commands can't be changed this way.
+
+    To show the state of COBs of such a type, an evaluator can be registered for it
+    under `evaluators` in the configuration, eg.
+
+        "evaluators": { "com.example.incident": { "command": "/usr/bin/incident-eval" } }
+
+    The evaluator is given the COB's operations as JSON on its standard input, and
+    prints the COB's state as JSON on its standard output. It runs in a sandbox,
+    without network access and with a read-only file system, unless its `sandbox`
+    option is set to `false`. Sandboxing is only supported on Linux.
 
 Options
 
-    --help     Print help
+    --message, -m <string>   Message of the operation (default: "Create <typename>" or "Update <typename>")
+    --embed <path>           Embed a file in the operation (may be given multiple times)
+    --no-announce            Don't announce the change to the network
+    --ops                    Show raw operations, even if an evaluator is registered
+    --help                   Print help
 "#,
 };
 
 enum OperationName {
     List,
     Show,
+    Create,
+    Update,
 }
 
 enum Operation {
     List,
-    Show(Rev),
+    Show {
+        oid: Rev,
+        ops: bool,
+    },
+    Create {
+        actions: NonEmpty<serde_json::Value>,
+    },
+    Update {
+        oid: Rev,
+        actions: NonEmpty<serde_json::Value>,
+    },
 }
 
 pub struct Options {
     rid: RepoId,
     op: Operation,
     type_name: cob::TypeName,
+    message: Option<String>,
+    embeds: Vec<PathBuf>,
+    announce: bool,
 }
 
 impl Args for Options {
@@ -60,14 +100,45 @@ impl Args for Options {
         let mut type_name: Option<cob::TypeName> = None;
         let mut oid: Option<Rev> = None;
         let mut rid: Option<RepoId> = None;
+        let mut message: Option<String> = None;
+        let mut embeds = Vec::new();
+        let mut actions = Vec::new();
+        let mut announce = true;
+        let mut ops = false;
 
         while let Some(arg) = parser.next()? {
             match arg {
                 Value(val) if op.is_none() => match val.to_string_lossy().as_ref() {
                     "l" | "list" => op = Some(OperationName::List),
                     "s" | "show" => op = Some(OperationName::Show),
+                    "c" | "create" => op = Some(OperationName::Create),
+                    "u" | "update" => op = Some(OperationName::Update),
                     unknown => anyhow::bail!("unknown operation '{unknown}'"),
                 },
+                Value(val) if matches!(op, Some(OperationName::Create | OperationName::Update)) => {
+                    let val = term::args::string(&val);
+                    let action = serde_json::from_str::<serde_json::Value>(&val)
+                        .map_err(|e| anyhow!("invalid action '{val}': {e}"))?;
+
+                    if !action.is_object() {
+                        anyhow::bail!("invalid action '{val}': expected a JSON object");
+                    }
+                    actions.push(action);
+                }
+                Long("message") | Short('m') => {
+                    let v = parser.value()?;
+                    message = Some(term::args::string(&v));
+                }
+                Long("embed") => {
+                    let v = parser.value()?;
+                    embeds.push(PathBuf::from(v));
+                }
+                Long("no-announce") => {
+                    announce = false;
+                }
+                Long("ops") => {
+                    ops = true;
+                }
                 Long("type") | Short('t') => {
                     let v = parser.value()?;
                     let v = term::args::string(&v);
@@ -99,15 +170,32 @@ impl Args for Options {
                 op: {
                     match op.ok_or_else(|| anyhow!("a command must be specified"))? {
                         OperationName::List => Operation::List,
-                        OperationName::Show => Operation::Show(oid.ok_or_else(|| {
-                            anyhow!("an object id must be specified with `--object")
-                        })?),
+                        OperationName::Show => Operation::Show {
+                            oid: oid.ok_or_else(|| {
+                                anyhow!("an object id must be specified with `--object")
+                            })?,
+                            ops,
+                        },
+                        OperationName::Create => Operation::Create {
+                            actions: NonEmpty::from_vec(actions)
+                                .ok_or_else(|| anyhow!("at least one action must be specified"))?,
+                        },
+                        OperationName::Update => Operation::Update {
+                            oid: oid.ok_or_else(|| {
+                                anyhow!("an object id must be specified with `--object")
+                            })?,
+                            actions: NonEmpty::from_vec(actions)
+                                .ok_or_else(|| anyhow!("at least one action must be specified"))?,
+                        },
                     }
                 },
                 rid: rid
                     .ok_or_else(|| anyhow!("a repository id must be specified with `--repo`"))?,
                 type_name: type_name
                     .ok_or_else(|| anyhow!("an object type must be specified with `--type`"))?,
+                message,
+                embeds,
+                announce,
             },
             vec![],
         ))
@@ -118,6 +206,11 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
     let profile = ctx.profile()?;
     let storage = &profile.storage;
     let repo = storage.repository(options.rid)?;
+    let announce = options.announce
+        && matches!(
+            options.op,
+            Operation::Create { .. } | Operation::Update { .. }
+        );
 
     match options.op {
         Operation::List => {
@@ -126,8 +219,23 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
                 println!("{}", cob.id);
             }
         }
-        Operation::Show(oid) => {
+        Operation::Show { oid, ops } => {
             let oid = oid.resolve(&repo.backend)?;
+
+            if !ops && profile.config.evaluators.contains_key(&options.type_name) {
+                let state = profile
+                    .external_cobs(&repo)?
+                    .get(&options.type_name, &oid)?
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "object `{oid}` of type `{}` was not found",
+                            options.type_name
+                        )
+                    })?;
+                println!("{}", serde_json::to_string_pretty(&state)?);
+
+                return Ok(());
+            }
             let ops = cob::store::ops(&oid, &options.type_name, &repo)?;
 
             for op in ops.into_iter().rev() {
@@ -161,7 +269,70 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
                 }
             }
         }
+        Operation::Create { actions } => {
+            let signer = term::signer(&profile)?;
+            let embeds = embeds(&options.embeds)?;
+            let message = options
+                .message
+                .unwrap_or_else(|| format!("Create {}", options.type_name));
+            let id = cob::store::create_raw(
+                &repo,
+                &options.type_name,
+                &message,
+                actions,
+                embeds,
+                &signer,
+            )?;
+            println!("{id}");
+        }
+        Operation::Update { oid, actions } => {
+            let signer = term::signer(&profile)?;
+            let oid = oid.resolve(&repo.backend)?;
+            let embeds = embeds(&options.embeds)?;
+            let message = options
+                .message
+                .unwrap_or_else(|| format!("Update {}", options.type_name));
+            let entry = cob::store::update_raw(
+                &repo,
+                &options.type_name,
+                oid,
+                &message,
+                actions,
+                embeds,
+                &signer,
+            )?;
+            println!("{entry}");
+        }
+    }
+
+    if announce {
+        let mut node = Node::new(profile.socket());
+        node::announce(
+            &repo,
+            node::SyncSettings::default(),
+            node::SyncReporting::default(),
+            &mut node,
+            &profile,
+        )?;
     }
 
     Ok(())
 }
+
+/// Read files to embed in an operation, named after their file name.
+fn embeds(paths: &[PathBuf]) -> anyhow::Result<Vec<cob::Embed>> {
+    paths
+        .iter()
+        .map(|path| {
+            let name = path
+                .file_name()
+                .ok_or_else(|| anyhow!("invalid embed path {}", path.display()))?
+                .to_string_lossy()
+                .to_string();
+            let content = std::fs::read(path)
+                .map_err(|e| anyhow!("failed to read embed {}: {e}", path.display()))?;
+
+            Ok(cob::Embed { name, content })
+        })
+        .collect()
+}
@@ -0,0 +1,144 @@
+use std::collections::BTreeSet;
+use std::ffi::OsString;
+
+use anyhow::anyhow;
+
+use radicle::dashboard::{self, Category, Filter, Object, SortBy};
+
+use crate::terminal as term;
+use crate::terminal::args;
+use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::format::Author;
+
+use term::Element as _;
+
+pub const HELP: Help = Help {
+    name: "dashboard",
+    description: "Show issues and patches that need your attention",
+    version: env!("RADICLE_VERSION"),
+    usage: r#"
+Usage
+
+    rad dashboard [<option>...]
+
+    Lists the issues and patches of all seeded repositories that need your
+    attention: open patches awaiting your review, patches you authored that
+    others have been active on since you last were, and open issues assigned
+    to you.
+
+    A patch awaits your review if you are a delegate of its repository or are
+    assigned to it, and haven't reviewed its latest revision.
+
+    Items are read from the COB caches; run `rad issue cache` or
+    `rad patch cache` if a repository's items are missing.
+
+Options
+
+    --review             Show patches awaiting your review
+    --authored           Show patches you authored with new activity
+    --assigned           Show issues assigned to you
+    --repo <rid>         Only show items of the given repository (may be repeated)
+    --sort-by <field>    Sort by `updated`, `repository` or `category`
+                         (default: updated)
+    --help               Print help
+"#,
+};
+
+pub struct Options {
+    filter: Filter,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut categories = BTreeSet::new();
+        let mut repos = BTreeSet::new();
+        let mut sort = SortBy::default();
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("review") => {
+                    categories.insert(Category::Review);
+                }
+                Long("authored") => {
+                    categories.insert(Category::Authored);
+                }
+                Long("assigned") => {
+                    categories.insert(Category::Assigned);
+                }
+                Long("repo") => {
+                    let val = parser.value()?;
+                    repos.insert(args::rid(&val)?);
+                }
+                Long("sort-by") => {
+                    let val = parser.value()?;
+
+                    sort = term::args::string(&val)
+                        .parse()
+                        .map_err(|e| anyhow!("{e}, see `rad dashboard --help`"))?;
+                }
+                Long("help") | Short('h') => {
+                    return Err(Error::Help.into());
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+
+        Ok((
+            Options {
+                filter: Filter {
+                    categories,
+                    repos,
+                    sort,
+                },
+            },
+            vec![],
+        ))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let profile = ctx.profile()?;
+    let items = dashboard::items(&profile, &options.filter)?;
+
+    if items.is_empty() {
+        term::print(term::format::italic("Nothing to show."));
+        return Ok(());
+    }
+
+    let mut table = term::Table::new(term::table::TableOptions::bordered());
+    table.push([
+        term::format::bold(String::from("Category")).into(),
+        term::format::bold(String::from("Repository")).into(),
+        term::format::bold(String::from("ID")).into(),
+        term::format::bold(String::from("Title")).into(),
+        term::format::bold(String::from("Author")).into(),
+        term::Line::blank(),
+        term::format::bold(String::from("Updated")).into(),
+    ]);
+    table.divider();
+
+    for item in items {
+        let author = item.object.author();
+        let (alias, did) = Author::new(&author, &profile).labels();
+        let category = match item.object {
+            Object::Issue(..) => format!("issue {}", item.category),
+            Object::Patch(..) => format!("patch {}", item.category),
+        };
+
+        table.push([
+            term::format::dim(category).into(),
+            term::format::default(item.name).into(),
+            term::format::tertiary(term::format::cob(item.object.id())).into(),
+            term::format::default(item.object.title().to_owned()).into(),
+            alias.into(),
+            did.into(),
+            term::format::timestamp(item.updated).dim().italic().into(),
+        ]);
+    }
+    table.print();
+
+    Ok(())
+}
@@ -0,0 +1,581 @@
+use std::ffi::OsString;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context as _};
+
+use radicle::cob::common::Reaction;
+use radicle::cob::discussion::{Category, Discussion, Discussions};
+use radicle::cob::thread;
+use radicle::prelude::RepoId;
+use radicle::storage::git::Repository;
+use radicle::storage::WriteStorage;
+use radicle::{Node, Profile};
+
+use crate::git::Rev;
+use crate::node;
+use crate::terminal as term;
+use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::format::Author;
+use crate::terminal::patch::Message;
+use crate::terminal::Element;
+
+pub const HELP: Help = Help {
+    name: "discuss",
+    description: "Manage discussions",
+    version: env!("RADICLE_VERSION"),
+    usage: r#"
+Usage
+
+    rad discuss [<option>...]
+    rad discuss list [--category <category>] [--pinned] [<option>...]
+    rad discuss open [--title <title>] [--message <text>] [--category <category>] [<option>...]
+    rad discuss show <discussion-id> [--header] [<option>...]
+    rad discuss edit <discussion-id> [--title <title>] [--category <category> | --no-category]
+                     [<option>...]
+    rad discuss comment <discussion-id> [--message <text>] [--reply-to <comment-id>] [<option>...]
+    rad discuss react <discussion-id> --to <comment-id> [--emoji <char>] [--undo] [<option>...]
+    rad discuss answer <discussion-id> (<comment-id> | --unset) [<option>...]
+    rad discuss pin <discussion-id> [--undo] [<option>...]
+    rad discuss delete <discussion-id> [<option>...]
+
+    Discussions are repository-level conversations, eg. about design decisions or
+    questions from users. Replies can be accepted as the answer to a discussion by
+    its author, and delegates can pin discussions so that they are listed first.
+
+List options
+
+        --category <category>  Only show discussions in the given category
+        --pinned               Only show pinned discussions
+
+Show options
+
+        --header               Show only the discussion header, hiding the replies
+
+React options
+
+        --to <comment-id>      The comment to react to
+        --emoji <char>         The reaction emoji (default: 👍)
+        --undo                 Remove the reaction
+
+Pin options
+
+        --undo                 Unpin the discussion
+
+Options
+
+        --repo <rid>           Operate on the given repository (default: cwd)
+        --no-announce          Don't announce the changes to peers
+    -q, --quiet                Don't print anything
+        --help                 Print help
+"#,
+};
+
+#[derive(Default, Debug, PartialEq, Eq)]
+pub enum OperationName {
+    Answer,
+    Comment,
+    Delete,
+    Edit,
+    #[default]
+    List,
+    Open,
+    Pin,
+    React,
+    Show,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Operation {
+    Answer {
+        id: Rev,
+        comment: Option<Rev>,
+    },
+    Comment {
+        id: Rev,
+        message: Message,
+        reply_to: Option<Rev>,
+    },
+    Delete {
+        id: Rev,
+    },
+    Edit {
+        id: Rev,
+        title: Option<String>,
+        category: Option<Option<Category>>,
+    },
+    List {
+        category: Option<Category>,
+        pinned: bool,
+    },
+    Open {
+        title: Option<String>,
+        message: Option<String>,
+        category: Option<Category>,
+    },
+    Pin {
+        id: Rev,
+        pinned: bool,
+    },
+    React {
+        id: Rev,
+        to: Rev,
+        reaction: Reaction,
+        active: bool,
+    },
+    Show {
+        id: Rev,
+        header: bool,
+    },
+}
+
+#[derive(Debug)]
+pub struct Options {
+    pub op: Operation,
+    pub repo: Option<RepoId>,
+    pub announce: bool,
+    pub quiet: bool,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut op: Option<OperationName> = None;
+        let mut id: Option<Rev> = None;
+        let mut comment: Option<Rev> = None;
+        let mut title: Option<String> = None;
+        let mut text: Option<String> = None;
+        let mut category: Option<Option<Category>> = None;
+        let mut message = Message::default();
+        let mut reply_to: Option<Rev> = None;
+        let mut to: Option<Rev> = None;
+        let mut reaction: Option<Reaction> = None;
+        let mut pinned = false;
+        let mut header = false;
+        let mut undo = false;
+        let mut unset = false;
+        let mut announce = true;
+        let mut quiet = false;
+        let mut repo = None;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("help") | Short('h') => {
+                    return Err(Error::Help.into());
+                }
+
+                // List, open and edit options.
+                Long("category")
+                    if matches!(
+                        op,
+                        None | Some(
+                            OperationName::List | OperationName::Open | OperationName::Edit
+                        )
+                    ) =>
+                {
+                    let val = parser.value()?;
+                    let val = term::args::string(&val);
+
+                    category = Some(Some(Category::new(val)?));
+                }
+                Long("no-category") if op == Some(OperationName::Edit) => {
+                    category = Some(None);
+                }
+                Long("pinned") if matches!(op, None | Some(OperationName::List)) => {
+                    pinned = true;
+                }
+                Long("title") if matches!(op, Some(OperationName::Open | OperationName::Edit)) => {
+                    title = Some(term::args::string(&parser.value()?));
+                }
+
+                // Open and comment options.
+                Long("message") | Short('m') if op == Some(OperationName::Open) => {
+                    text = Some(term::args::string(&parser.value()?));
+                }
+                Long("message") | Short('m') if op == Some(OperationName::Comment) => {
+                    let val = parser.value()?;
+                    let txt = term::args::string(&val);
+
+                    message.append(&txt);
+                }
+                Long("reply-to") if op == Some(OperationName::Comment) => {
+                    reply_to = Some(term::args::rev(&parser.value()?)?);
+                }
+
+                // Show options.
+                Long("header") if op == Some(OperationName::Show) => {
+                    header = true;
+                }
+
+                // React options.
+                Long("to") if op == Some(OperationName::React) => {
+                    to = Some(term::args::rev(&parser.value()?)?);
+                }
+                Long("emoji") if op == Some(OperationName::React) => {
+                    if let Some(emoji) = parser.value()?.to_str() {
+                        reaction =
+                            Some(Reaction::from_str(emoji).map_err(|_| anyhow!("invalid emoji"))?);
+                    }
+                }
+                Long("undo") if matches!(op, Some(OperationName::React | OperationName::Pin)) => {
+                    undo = true;
+                }
+
+                // Answer options.
+                Long("unset") if op == Some(OperationName::Answer) => {
+                    unset = true;
+                }
+
+                // Options.
+                Long("no-announce") => {
+                    announce = false;
+                }
+                Long("quiet") | Short('q') => {
+                    quiet = true;
+                }
+                Long("repo") => {
+                    let val = parser.value()?;
+                    let rid = term::args::rid(&val)?;
+
+                    repo = Some(rid);
+                }
+
+                Value(val) if op.is_none() => match val.to_string_lossy().as_ref() {
+                    "a" | "answer" => op = Some(OperationName::Answer),
+                    "c" | "comment" => op = Some(OperationName::Comment),
+                    "d" | "delete" => op = Some(OperationName::Delete),
+                    "e" | "edit" => op = Some(OperationName::Edit),
+                    "l" | "list" => op = Some(OperationName::List),
+                    "o" | "open" => op = Some(OperationName::Open),
+                    "p" | "pin" => op = Some(OperationName::Pin),
+                    "r" | "react" => op = Some(OperationName::React),
+                    "w" | "show" => op = Some(OperationName::Show),
+
+                    unknown => anyhow::bail!("unknown operation '{}'", unknown),
+                },
+                Value(val) if op == Some(OperationName::Answer) && id.is_some() => {
+                    comment = Some(term::args::rev(&val)?);
+                }
+                Value(val) if op.is_some() && id.is_none() => {
+                    id = Some(term::args::rev(&val)?);
+                }
+                _ => {
+                    return Err(anyhow!(arg.unexpected()));
+                }
+            }
+        }
+
+        let op = match op.unwrap_or_default() {
+            OperationName::Answer => {
+                let id = id.ok_or_else(|| anyhow!("a discussion must be provided"))?;
+                if comment.is_none() && !unset {
+                    anyhow::bail!("a comment must be provided, or `--unset` to unset the answer");
+                }
+                Operation::Answer { id, comment }
+            }
+            OperationName::Comment => Operation::Comment {
+                id: id.ok_or_else(|| anyhow!("a discussion must be provided"))?,
+                message,
+                reply_to,
+            },
+            OperationName::Delete => Operation::Delete {
+                id: id.ok_or_else(|| anyhow!("a discussion to remove must be provided"))?,
+            },
+            OperationName::Edit => Operation::Edit {
+                id: id.ok_or_else(|| anyhow!("a discussion must be provided"))?,
+                title,
+                category,
+            },
+            OperationName::List => Operation::List {
+                category: category.flatten(),
+                pinned,
+            },
+            OperationName::Open => Operation::Open {
+                title,
+                message: text,
+                category: category.flatten(),
+            },
+            OperationName::Pin => Operation::Pin {
+                id: id.ok_or_else(|| anyhow!("a discussion must be provided"))?,
+                pinned: !undo,
+            },
+            OperationName::React => Operation::React {
+                id: id.ok_or_else(|| anyhow!("a discussion must be provided"))?,
+                to: to.ok_or_else(|| anyhow!("a comment to react to must be provided"))?,
+                reaction: match reaction {
+                    Some(reaction) => reaction,
+                    None => Reaction::new('👍')?,
+                },
+                active: !undo,
+            },
+            OperationName::Show => Operation::Show {
+                id: id.ok_or_else(|| anyhow!("a discussion must be provided"))?,
+                header,
+            },
+        };
+
+        Ok((
+            Options {
+                op,
+                repo,
+                announce,
+                quiet,
+            },
+            vec![],
+        ))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let profile = ctx.profile()?;
+    let rid = if let Some(rid) = options.repo {
+        rid
+    } else {
+        radicle::rad::cwd().map(|(_, rid)| rid)?
+    };
+    let repo = profile.storage.repository_mut(rid)?;
+    let announce =
+        options.announce && !matches!(&options.op, Operation::List { .. } | Operation::Show { .. });
+    let mut discussions = Discussions::open(&repo)?;
+
+    match options.op {
+        Operation::List { category, pinned } => {
+            list(&discussions, category.as_ref(), pinned, &profile)?;
+        }
+        Operation::Show { id, header } => {
+            let id = id.resolve(&repo.backend)?;
+            let discussion = discussions
+                .get(&id)?
+                .context("No discussion with the given ID exists")?;
+
+            term::discussion::show(&discussion, &id, header, &profile)?;
+        }
+        Operation::Open {
+            title,
+            message,
+            category,
+        } => {
+            let signer = term::signer(&profile)?;
+            let (title, body) = if let (Some(t), Some(b)) = (title.as_ref(), message.as_ref()) {
+                (t.to_owned(), b.to_owned())
+            } else if let Some((t, b)) = term::discussion::get_title_body(title, message)? {
+                (t, b)
+            } else {
+                anyhow::bail!("aborting discussion creation due to empty title or body");
+            };
+            let discussion = discussions.create(title, body, category, [], &signer)?;
+
+            if options.quiet {
+                term::print(discussion.id());
+            } else {
+                term::discussion::show(&discussion, discussion.id(), true, &profile)?;
+            }
+        }
+        Operation::Edit {
+            id,
+            title,
+            category,
+        } => {
+            let signer = term::signer(&profile)?;
+            let id = id.resolve(&repo.backend)?;
+            let mut discussion = discussions.get_mut(&id)?;
+
+            if title.is_none() && category.is_none() {
+                anyhow::bail!("nothing to edit, please specify `--title` or `--category`");
+            }
+            discussion.transaction("Edit", &signer, |tx| {
+                if let Some(title) = title {
+                    tx.edit(title)?;
+                }
+                if let Some(category) = category {
+                    tx.categorize(category)?;
+                }
+                Ok(())
+            })?;
+
+            if !options.quiet {
+                term::discussion::show(&discussion, &id, true, &profile)?;
+            }
+        }
+        Operation::Comment {
+            id,
+            message,
+            reply_to,
+        } => {
+            let signer = term::signer(&profile)?;
+            let id = id.resolve(&repo.backend)?;
+            let mut discussion = discussions.get_mut(&id)?;
+            let (body, reply_to) = prompt_comment(message, reply_to, &discussion, &repo)?;
+            let mentions = thread::mentions(&body, &profile);
+            let comment_id = discussion.comment(body, reply_to, vec![], mentions, &signer)?;
+
+            if options.quiet {
+                term::print(comment_id);
+            } else {
+                let comment = discussion.thread().comment(&comment_id).unwrap();
+                term::comment::widget(&comment_id, comment, &profile).print();
+            }
+        }
+        Operation::React {
+            id,
+            to,
+            reaction,
+            active,
+        } => {
+            let signer = term::signer(&profile)?;
+            let id = id.resolve(&repo.backend)?;
+            let to = to.resolve(&repo.backend)?;
+            let mut discussion = discussions.get_mut(&id)?;
+
+            discussion.react(to, reaction, active, &signer)?;
+        }
+        Operation::Answer { id, comment } => {
+            let signer = term::signer(&profile)?;
+            let id = id.resolve(&repo.backend)?;
+            let comment = comment.map(|c| c.resolve(&repo.backend)).transpose()?;
+            let mut discussion = discussions.get_mut(&id)?;
+
+            discussion.accept_answer(comment, &signer)?;
+        }
+        Operation::Pin { id, pinned } => {
+            let signer = term::signer(&profile)?;
+            let id = id.resolve(&repo.backend)?;
+            let mut discussion = discussions.get_mut(&id)?;
+
+            discussion.pin(pinned, &signer)?;
+        }
+        Operation::Delete { id } => {
+            let signer = term::signer(&profile)?;
+            let id = id.resolve(&repo.backend)?;
+
+            discussions.remove(&id, &signer)?;
+        }
+    }
+
+    if announce {
+        let mut node = Node::new(profile.socket());
+        node::announce(
+            &repo,
+            node::SyncSettings::default(),
+            node::SyncReporting::default(),
+            &mut node,
+            &profile,
+        )?;
+    }
+
+    Ok(())
+}
+
+fn list(
+    discussions: &Discussions<'_, Repository>,
+    category: Option<&Category>,
+    pinned: bool,
+    profile: &Profile,
+) -> anyhow::Result<()> {
+    let mut all = Vec::new();
+    for result in discussions.all()? {
+        let Ok((id, discussion)) = result else {
+            // Skip discussions that failed to load.
+            continue;
+        };
+        if category.is_some() && discussion.category() != category {
+            continue;
+        }
+        if pinned && !discussion.is_pinned() {
+            continue;
+        }
+        all.push((id, discussion));
+    }
+
+    if all.is_empty() {
+        term::print(term::format::italic("Nothing to show."));
+        return Ok(());
+    }
+
+    // Pinned discussions come first, then the most recent ones.
+    all.sort_by(|(id1, d1), (id2, d2)| {
+        let by_pinned = d2.is_pinned().cmp(&d1.is_pinned());
+        let by_timestamp = d2.timestamp().cmp(&d1.timestamp());
+        let by_id = id1.cmp(id2);
+
+        by_pinned.then(by_timestamp).then(by_id)
+    });
+
+    let mut table = term::Table::new(term::table::TableOptions::bordered());
+    table.push([
+        term::format::dim(String::from("●")).into(),
+        term::format::bold(String::from("ID")).into(),
+        term::format::bold(String::from("Title")).into(),
+        term::format::bold(String::from("Author")).into(),
+        term::Line::blank(),
+        term::format::bold(String::from("Category")).into(),
+        term::format::bold(String::from("Replies")).into(),
+        term::format::bold(String::from("Opened")).into(),
+    ]);
+    table.divider();
+
+    for (id, discussion) in all {
+        let author = discussion.author().id;
+        let (alias, did) = Author::new(&author, profile).labels();
+
+        table.push([
+            if discussion.is_pinned() {
+                term::format::primary("▲").into()
+            } else if discussion.answer().is_some() {
+                term::format::positive("●").into()
+            } else {
+                term::format::dim("●").into()
+            },
+            term::format::tertiary(term::format::cob(&id))
+                .to_owned()
+                .into(),
+            term::format::default(discussion.title().to_owned()).into(),
+            alias.into(),
+            did.into(),
+            term::format::secondary(
+                discussion
+                    .category()
+                    .map(|c| c.to_string())
+                    .unwrap_or_default(),
+            )
+            .into(),
+            term::format::dim(discussion.replies().count().to_string()).into(),
+            term::format::timestamp(discussion.timestamp())
+                .dim()
+                .italic()
+                .into(),
+        ]);
+    }
+    table.print();
+
+    Ok(())
+}
+
+/// Get a comment from the user, by prompting.
+fn prompt_comment(
+    message: Message,
+    reply_to: Option<Rev>,
+    discussion: &Discussion,
+    repo: &Repository,
+) -> anyhow::Result<(String, thread::CommentId)> {
+    let (root, r) = discussion.root();
+    let (reply_to, help) = if let Some(rev) = reply_to {
+        let id = rev.resolve::<radicle::git::Oid>(&repo.backend)?;
+        let parent = discussion
+            .thread()
+            .comment(&id)
+            .ok_or(anyhow::anyhow!("comment '{rev}' not found"))?;
+
+        (id, parent.body().trim())
+    } else {
+        (*root, r.body().trim())
+    };
+    let help = format!("\n{}\n", term::format::html::commented(help));
+    let body = message.get(&help)?;
+
+    if body.is_empty() {
+        anyhow::bail!("aborting operation due to empty comment");
+    }
+    Ok((body, reply_to))
+}
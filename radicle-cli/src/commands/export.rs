@@ -0,0 +1,145 @@
+use std::ffi::OsString;
+use std::fs::File;
+use std::io;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context as _};
+
+use radicle::cob::archive;
+use radicle::prelude::RepoId;
+use radicle::storage::ReadStorage;
+
+use crate::terminal as term;
+use crate::terminal::args::{Args, Error, Help};
+
+pub const HELP: Help = Help {
+    name: "export",
+    description: "Export collaborative objects to an archive",
+    version: env!("RADICLE_VERSION"),
+    usage: r#"
+Usage
+
+    rad export [--output <file>] [<option>...]
+    rad export verify <file> [<option>...]
+
+    Exports the identity, issues and patches of a repository to a
+    self-contained archive, in JSON Lines format. The archive contains
+    the signed change history of every object, as well as its
+    materialized state.
+
+    The `verify` command checks an archive without access to the
+    repository it was exported from: the content and signature of
+    every change is verified, and the history of every object is
+    replayed.
+
+Options
+
+    --output, -o <file>  Write the archive to the given file (default: stdout)
+    --repo <rid>         Operate on the given repository (default: cwd)
+    --help               Print help
+"#,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Operation {
+    Export { output: Option<PathBuf> },
+    Verify { path: PathBuf },
+}
+
+#[derive(Debug)]
+pub struct Options {
+    pub op: Operation,
+    pub repo: Option<RepoId>,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut verify = false;
+        let mut path: Option<PathBuf> = None;
+        let mut output: Option<PathBuf> = None;
+        let mut repo = None;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("help") | Short('h') => {
+                    return Err(Error::Help.into());
+                }
+                Long("output") | Short('o') => {
+                    output = Some(PathBuf::from(parser.value()?));
+                }
+                Long("repo") => {
+                    let val = parser.value()?;
+                    let rid = term::args::rid(&val)?;
+
+                    repo = Some(rid);
+                }
+                Value(val) if !verify && path.is_none() => match val.to_string_lossy().as_ref() {
+                    "verify" => verify = true,
+
+                    unknown => anyhow::bail!("unknown operation '{}'", unknown),
+                },
+                Value(val) if verify && path.is_none() => {
+                    path = Some(PathBuf::from(val));
+                }
+                _ => {
+                    return Err(anyhow!(arg.unexpected()));
+                }
+            }
+        }
+
+        let op = if verify {
+            Operation::Verify {
+                path: path.ok_or_else(|| anyhow!("an archive to verify must be provided"))?,
+            }
+        } else {
+            Operation::Export { output }
+        };
+
+        Ok((Options { op, repo }, vec![]))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    match options.op {
+        Operation::Export { output } => {
+            let profile = ctx.profile()?;
+            let rid = if let Some(rid) = options.repo {
+                rid
+            } else {
+                radicle::rad::cwd().map(|(_, rid)| rid)?
+            };
+            let repo = profile.storage.repository(rid)?;
+
+            if let Some(path) = output {
+                let file = File::create(&path)
+                    .with_context(|| format!("failed to create {}", path.display()))?;
+                let summary = archive::export(&repo, io::BufWriter::new(file))?;
+
+                term::success!(
+                    "Exported {} object(s) and {} change(s) to {}",
+                    summary.objects.values().sum::<usize>(),
+                    summary.changes,
+                    term::format::tertiary(path.display())
+                );
+            } else {
+                archive::export(&repo, io::stdout().lock())?;
+            }
+        }
+        Operation::Verify { path } => {
+            let file =
+                File::open(&path).with_context(|| format!("failed to open {}", path.display()))?;
+            let (header, summary) = archive::verify(io::BufReader::new(file))
+                .map_err(|e| anyhow!("archive {} failed verification: {e}", path.display()))?;
+
+            term::success!("Archive of {} verified", term::format::tertiary(header.rid));
+            for (typename, count) in summary.objects {
+                term::info!("{count} {typename} object(s)");
+            }
+            term::info!("{} change(s)", summary.changes);
+        }
+    }
+    Ok(())
+}
@@ -15,12 +15,17 @@ pub const HELP: Help = Help {
 const COMMANDS: &[Help] = &[
     rad_auth::HELP,
     rad_block::HELP,
+    rad_bundle::HELP,
     rad_checkout::HELP,
     rad_clone::HELP,
     rad_config::HELP,
+    rad_dashboard::HELP,
+    rad_discuss::HELP,
+    rad_export::HELP,
     rad_fork::HELP,
     rad_help::HELP,
     rad_id::HELP,
+    rad_import::HELP,
     rad_init::HELP,
     rad_inbox::HELP,
     rad_inspect::HELP,
@@ -38,6 +43,7 @@ const COMMANDS: &[Help] = &[
     rad_remote::HELP,
     rad_stats::HELP,
     rad_sync::HELP,
+    rad_wiki::HELP,
 ];
 
 #[derive(Default)]
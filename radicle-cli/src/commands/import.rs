@@ -0,0 +1,390 @@
+#[path = "import/github.rs"]
+mod github;
+#[path = "import/gitlab.rs"]
+mod gitlab;
+#[path = "import/mbox.rs"]
+mod mbox;
+
+use std::ffi::OsString;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context as _};
+
+use radicle::cob::common::{Label, Origin, Timestamp};
+use radicle::cob::issue::{CloseReason, State};
+use radicle::cob::patch::MergeTarget;
+use radicle::crypto::Signer;
+use radicle::prelude::RepoId;
+use radicle::storage::git::Repository;
+use radicle::storage::{ReadRepository, SignRepository, WriteRepository, WriteStorage};
+use radicle::{git, Node, Profile};
+
+use crate::node;
+use crate::terminal as term;
+use crate::terminal::args::{Args, Error, Help};
+
+pub const HELP: Help = Help {
+    name: "import",
+    description: "Import issues and patches from other systems",
+    version: env!("RADICLE_VERSION"),
+    usage: r#"
+Usage
+
+    rad import github <file> [<option>...]
+    rad import gitlab <file> [<option>...]
+    rad import mbox <file> [<option>...]
+
+    Recreates the issues and patches of an existing project as
+    collaborative objects in a Radicle repository.
+
+    The `github` and `gitlab` commands read a JSON array of issues, as
+    returned by the respective forge APIs, or by `gh issue list --json`.
+    Issue comments are imported when they are included in the export,
+    under the `comments` (GitHub) or `notes` (GitLab) key of each issue.
+    Pull requests found in a GitHub export are skipped, since the export
+    doesn't contain their code.
+
+    The `mbox` command reads a mailbox of patch emails, as produced by
+    `git format-patch`, and opens a patch for every patch series it
+    contains. The changes are applied on top of the repository's
+    canonical default branch.
+
+    Since the imported objects are signed by your key, the original
+    author and date of every issue, comment and patch is recorded
+    alongside it, as its origin. Patch commits keep their original
+    author and date.
+
+Options
+
+    --repo <rid>       Operate on the given repository (default: cwd)
+    --no-announce      Don't announce the imported objects to peers
+    --quiet, -q        Don't print anything
+    --help             Print help
+"#,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum OperationName {
+    Github,
+    Gitlab,
+    Mbox,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Operation {
+    Github { path: PathBuf },
+    Gitlab { path: PathBuf },
+    Mbox { path: PathBuf },
+}
+
+#[derive(Debug)]
+pub struct Options {
+    pub op: Operation,
+    pub repo: Option<RepoId>,
+    pub announce: bool,
+    pub quiet: bool,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut op: Option<OperationName> = None;
+        let mut path: Option<PathBuf> = None;
+        let mut repo = None;
+        let mut announce = true;
+        let mut quiet = false;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("help") | Short('h') => {
+                    return Err(Error::Help.into());
+                }
+                Long("no-announce") => {
+                    announce = false;
+                }
+                Long("quiet") | Short('q') => {
+                    quiet = true;
+                }
+                Long("repo") => {
+                    let val = parser.value()?;
+                    let rid = term::args::rid(&val)?;
+
+                    repo = Some(rid);
+                }
+                Value(val) if op.is_none() => match val.to_string_lossy().as_ref() {
+                    "github" => op = Some(OperationName::Github),
+                    "gitlab" => op = Some(OperationName::Gitlab),
+                    "mbox" => op = Some(OperationName::Mbox),
+
+                    unknown => anyhow::bail!("unknown operation '{}'", unknown),
+                },
+                Value(val) if path.is_none() => {
+                    path = Some(PathBuf::from(val));
+                }
+                _ => {
+                    return Err(anyhow!(arg.unexpected()));
+                }
+            }
+        }
+
+        let op = op.ok_or_else(|| anyhow!("an import source must be specified"))?;
+        let path = path.ok_or_else(|| anyhow!("a file to import must be provided"))?;
+        let op = match op {
+            OperationName::Github => Operation::Github { path },
+            OperationName::Gitlab => Operation::Gitlab { path },
+            OperationName::Mbox => Operation::Mbox { path },
+        };
+
+        Ok((
+            Options {
+                op,
+                repo,
+                announce,
+                quiet,
+            },
+            vec![],
+        ))
+    }
+}
+
+/// An issue exported from a forge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    /// Issue number on the forge.
+    pub number: u64,
+    pub title: String,
+    pub body: String,
+    /// Username of the original author.
+    pub author: String,
+    /// Original creation time, as found in the export.
+    pub created_at: String,
+    pub labels: Vec<String>,
+    pub closed: bool,
+    pub comments: Vec<Comment>,
+}
+
+/// An issue comment exported from a forge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comment {
+    pub body: String,
+    /// Username of the original author.
+    pub author: String,
+    /// Original creation time, as found in the export.
+    pub created_at: String,
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let profile = ctx.profile()?;
+    let rid = if let Some(rid) = options.repo {
+        rid
+    } else {
+        radicle::rad::cwd().map(|(_, rid)| rid)?
+    };
+    let repo = profile.storage.repository_mut(rid)?;
+    let signer = term::signer(&profile)?;
+
+    match &options.op {
+        Operation::Github { path } => {
+            let json = fs::read_to_string(path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            let (issues, skipped) = github::parse(&json)?;
+
+            import_issues("GitHub", issues, &repo, &profile, &signer, options.quiet)?;
+
+            if skipped > 0 && !options.quiet {
+                term::warning(format!("Skipped {skipped} pull request(s)"));
+            }
+        }
+        Operation::Gitlab { path } => {
+            let json = fs::read_to_string(path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            let issues = gitlab::parse(&json)?;
+
+            import_issues("GitLab", issues, &repo, &profile, &signer, options.quiet)?;
+        }
+        Operation::Mbox { path } => {
+            let mbox =
+                fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+            let mbox = String::from_utf8_lossy(&mbox);
+            let series = mbox::parse(&mbox)?;
+
+            import_patches(series, &repo, &profile, &signer, options.quiet)?;
+        }
+    }
+
+    if options.announce {
+        let mut node = Node::new(profile.socket());
+        node::announce(
+            &repo,
+            node::SyncSettings::default(),
+            node::SyncReporting::default(),
+            &mut node,
+            &profile,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Create an issue COB for each exported issue, along with its comments.
+fn import_issues<G: Signer>(
+    forge: &str,
+    exported: Vec<Issue>,
+    repo: &Repository,
+    profile: &Profile,
+    signer: &G,
+    quiet: bool,
+) -> anyhow::Result<()> {
+    let mut issues = profile.issues_mut(repo)?;
+
+    for exported in exported {
+        let labels = exported
+            .labels
+            .iter()
+            .map(|l| label(l))
+            .collect::<Result<Vec<_>, _>>()?;
+        let source = format!("{forge} issue #{}", exported.number);
+        let mut issue = issues.create(
+            &exported.title,
+            exported.body.trim(),
+            &labels,
+            &[],
+            [],
+            signer,
+        )?;
+        let (root, _) = issue.root();
+        let root = *root;
+
+        issue.origin(
+            root,
+            Origin {
+                source: source.clone(),
+                author: exported.author.clone(),
+                timestamp: rfc3339(&exported.created_at)?,
+            },
+            signer,
+        )?;
+
+        for comment in &exported.comments {
+            let body = comment.body.trim();
+            if body.is_empty() {
+                continue;
+            }
+            let id = issue.comment(body, root, [], [], signer)?;
+
+            issue.origin(
+                id,
+                Origin {
+                    source: source.clone(),
+                    author: comment.author.clone(),
+                    timestamp: rfc3339(&comment.created_at)?,
+                },
+                signer,
+            )?;
+        }
+        if exported.closed {
+            issue.lifecycle(
+                State::Closed {
+                    reason: CloseReason::Other,
+                },
+                signer,
+            )?;
+        }
+        if !quiet {
+            term::success!(
+                "Imported issue #{} as {}",
+                exported.number,
+                term::format::tertiary(issue.id())
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Apply each patch series on top of the canonical head, and open a patch for it.
+fn import_patches<G: Signer>(
+    series: Vec<mbox::Series>,
+    repo: &Repository,
+    profile: &Profile,
+    signer: &G,
+    quiet: bool,
+) -> anyhow::Result<()> {
+    let (_, base) = repo.canonical_head()?;
+    let nid = signer.public_key();
+    let mut patches = profile.patches_mut(repo)?;
+
+    for series in series {
+        let head = series.apply(repo.raw(), base)?;
+        let (title, description) = series.message();
+        let origin = Origin {
+            source: String::from("a patch email"),
+            author: series.author(),
+            timestamp: rfc2822(series.date())?,
+        };
+        let mut patch = patches.create(
+            title,
+            description,
+            MergeTarget::default(),
+            base,
+            head,
+            &[],
+            signer,
+        )?;
+        let id = patch.id;
+        let (revision, _) = patch.root();
+
+        patch.origin(revision, origin, signer)?;
+
+        repo.raw().reference(
+            git::refs::patch(&id).with_namespace(nid.into()).as_str(),
+            head.into(),
+            true,
+            "Create reference for patch head",
+        )?;
+
+        if !quiet {
+            term::success!(
+                "Imported patch {} as {}",
+                term::format::italic(&series.subject()),
+                term::format::tertiary(id)
+            );
+        }
+    }
+    repo.sign_refs(signer)?;
+
+    Ok(())
+}
+
+/// Parse an RFC 3339 date, as found in forge exports.
+fn rfc3339(date: &str) -> anyhow::Result<Timestamp> {
+    let date = chrono::DateTime::parse_from_rfc3339(date)
+        .with_context(|| format!("invalid date '{date}'"))?;
+
+    timestamp(date.timestamp())
+}
+
+/// Parse an RFC 2822 date, as found in emails.
+fn rfc2822(date: &str) -> anyhow::Result<Timestamp> {
+    let date = chrono::DateTime::parse_from_rfc2822(date)
+        .with_context(|| format!("invalid email date '{date}'"))?;
+
+    timestamp(date.timestamp())
+}
+
+/// Convert seconds since the epoch to a timestamp.
+fn timestamp(secs: i64) -> anyhow::Result<Timestamp> {
+    let secs = u64::try_from(secs).map_err(|_| anyhow!("dates before 1970 are not supported"))?;
+
+    Ok(Timestamp::from_secs(secs))
+}
+
+/// Convert a forge label to a valid label, by replacing whitespace.
+fn label(name: &str) -> anyhow::Result<Label> {
+    let name = name.trim().replace(char::is_whitespace, "-");
+    let label = Label::new(name)?;
+
+    Ok(label)
+}
@@ -0,0 +1,183 @@
+//! GitHub issue exports.
+//!
+//! Both the REST API format (`user`, `created_at`) and the format of the
+//! `gh` command-line tool (`author`, `createdAt`) are supported.
+use serde::Deserialize;
+
+use super::{Comment, Issue};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Export {
+    number: u64,
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(alias = "user")]
+    author: User,
+    #[serde(alias = "created_at")]
+    created_at: String,
+    #[serde(default)]
+    labels: Vec<Label>,
+    state: String,
+    #[serde(default)]
+    comments: Comments,
+    #[serde(default, alias = "pull_request")]
+    pull_request: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Label {
+    name: String,
+}
+
+/// Comments are only included in the `gh` format. The REST API only
+/// includes the comment count.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Comments {
+    List(Vec<ExportComment>),
+    Count(#[allow(dead_code)] u64),
+}
+
+impl Default for Comments {
+    fn default() -> Self {
+        Self::List(Vec::new())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportComment {
+    #[serde(default)]
+    body: String,
+    #[serde(alias = "user")]
+    author: User,
+    #[serde(alias = "created_at")]
+    created_at: String,
+}
+
+/// Parse a GitHub issue export. Returns the issues, and the number of pull
+/// requests that were skipped.
+pub fn parse(json: &str) -> anyhow::Result<(Vec<Issue>, usize)> {
+    let exports: Vec<Export> = serde_json::from_str(json)?;
+    let mut skipped = 0;
+    let mut issues = Vec::with_capacity(exports.len());
+
+    for e in exports {
+        if e.pull_request.is_some() {
+            skipped += 1;
+            continue;
+        }
+        let comments = match e.comments {
+            Comments::List(comments) => comments
+                .into_iter()
+                .map(|c| Comment {
+                    body: c.body,
+                    author: c.author.login,
+                    created_at: c.created_at,
+                })
+                .collect(),
+            Comments::Count(_) => Vec::new(),
+        };
+
+        issues.push(Issue {
+            number: e.number,
+            title: e.title,
+            body: e.body.unwrap_or_default(),
+            author: e.author.login,
+            created_at: e.created_at,
+            labels: e.labels.into_iter().map(|l| l.name).collect(),
+            closed: e.state.eq_ignore_ascii_case("closed"),
+            comments,
+        });
+    }
+    // Exports are usually sorted newest first.
+    issues.sort_by_key(|i| i.number);
+
+    Ok((issues, skipped))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_rest() {
+        let json = r#"[
+            {
+                "number": 2,
+                "title": "Add a README",
+                "body": null,
+                "user": { "login": "bob" },
+                "created_at": "2023-02-01T10:00:00Z",
+                "labels": [{ "name": "good first issue" }],
+                "state": "closed",
+                "comments": 3
+            },
+            {
+                "number": 1,
+                "title": "Fix the build",
+                "body": "It's broken.",
+                "user": { "login": "alice" },
+                "created_at": "2023-01-01T10:00:00Z",
+                "labels": [],
+                "state": "open",
+                "comments": 0,
+                "pull_request": { "url": "https://api.github.com/repos/acme/acme/pulls/1" }
+            }
+        ]"#;
+        let (issues, skipped) = parse(json).unwrap();
+
+        assert_eq!(skipped, 1);
+        assert_eq!(
+            issues,
+            vec![Issue {
+                number: 2,
+                title: String::from("Add a README"),
+                body: String::new(),
+                author: String::from("bob"),
+                created_at: String::from("2023-02-01T10:00:00Z"),
+                labels: vec![String::from("good first issue")],
+                closed: true,
+                comments: vec![],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_gh() {
+        let json = r#"[
+            {
+                "number": 7,
+                "title": "Crash on startup",
+                "body": "Segfault.",
+                "author": { "login": "alice" },
+                "createdAt": "2023-01-01T10:00:00Z",
+                "labels": [{ "name": "bug" }],
+                "state": "OPEN",
+                "comments": [
+                    { "author": { "login": "bob" }, "body": "Same here.", "createdAt": "2023-01-02T10:00:00Z" }
+                ]
+            }
+        ]"#;
+        let (issues, skipped) = parse(json).unwrap();
+
+        assert_eq!(skipped, 0);
+        assert_eq!(issues.len(), 1);
+        assert!(!issues[0].closed);
+        assert_eq!(
+            issues[0].comments,
+            vec![Comment {
+                body: String::from("Same here."),
+                author: String::from("bob"),
+                created_at: String::from("2023-01-02T10:00:00Z"),
+            }]
+        );
+    }
+}
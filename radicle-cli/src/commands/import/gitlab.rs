@@ -0,0 +1,113 @@
+//! GitLab issue exports, in the format of the REST API.
+use serde::Deserialize;
+
+use super::{Comment, Issue};
+
+#[derive(Debug, Deserialize)]
+struct Export {
+    iid: u64,
+    title: String,
+    #[serde(default)]
+    description: Option<String>,
+    author: User,
+    created_at: String,
+    #[serde(default)]
+    labels: Vec<String>,
+    state: String,
+    #[serde(default)]
+    notes: Vec<Note>,
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    username: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Note {
+    #[serde(default)]
+    body: String,
+    author: User,
+    created_at: String,
+    /// System notes record events such as label changes, and aren't comments.
+    #[serde(default)]
+    system: bool,
+}
+
+/// Parse a GitLab issue export.
+pub fn parse(json: &str) -> anyhow::Result<Vec<Issue>> {
+    let exports: Vec<Export> = serde_json::from_str(json)?;
+    let mut issues = exports
+        .into_iter()
+        .map(|e| {
+            let mut notes = e.notes;
+            notes.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+            Issue {
+                number: e.iid,
+                title: e.title,
+                body: e.description.unwrap_or_default(),
+                author: e.author.username,
+                created_at: e.created_at,
+                labels: e.labels,
+                closed: e.state == "closed",
+                comments: notes
+                    .into_iter()
+                    .filter(|n| !n.system)
+                    .map(|n| Comment {
+                        body: n.body,
+                        author: n.author.username,
+                        created_at: n.created_at,
+                    })
+                    .collect(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    issues.sort_by_key(|i| i.number);
+
+    Ok(issues)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let json = r#"[
+            {
+                "iid": 3,
+                "title": "Update dependencies",
+                "description": "They're old.",
+                "author": { "username": "alice" },
+                "created_at": "2023-03-01T10:00:00.000Z",
+                "labels": ["chore"],
+                "state": "opened",
+                "notes": [
+                    { "body": "added ~chore label", "author": { "username": "alice" }, "created_at": "2023-03-01T10:00:01.000Z", "system": true },
+                    { "body": "On it.", "author": { "username": "bob" }, "created_at": "2023-03-02T10:00:00.000Z", "system": false }
+                ]
+            }
+        ]"#;
+        let issues = parse(json).unwrap();
+
+        assert_eq!(
+            issues,
+            vec![Issue {
+                number: 3,
+                title: String::from("Update dependencies"),
+                body: String::from("They're old."),
+                author: String::from("alice"),
+                created_at: String::from("2023-03-01T10:00:00.000Z"),
+                labels: vec![String::from("chore")],
+                closed: false,
+                comments: vec![Comment {
+                    body: String::from("On it."),
+                    author: String::from("bob"),
+                    created_at: String::from("2023-03-02T10:00:00.000Z"),
+                }],
+            }]
+        );
+    }
+}
@@ -0,0 +1,405 @@
+//! Mailboxes of patch emails, as produced by `git format-patch`.
+use anyhow::{anyhow, Context as _};
+use chrono::DateTime;
+
+use radicle::git;
+use radicle::git::raw;
+
+/// A patch email.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Email {
+    /// Author name.
+    pub name: String,
+    /// Author email address.
+    pub email: String,
+    /// The `Date` header, as found in the email.
+    pub date: String,
+    /// Subject, without the `[PATCH]` prefix.
+    pub subject: String,
+    /// Position of the email in its series, and the size of the series.
+    pub position: Option<(usize, usize)>,
+    /// Commit message body, ie. everything before the `---` separator.
+    pub message: String,
+    /// The diff, if any.
+    pub diff: String,
+}
+
+impl Email {
+    /// Parse a single email.
+    fn parse(raw: &str) -> anyhow::Result<Self> {
+        let (headers, body) = raw
+            .split_once("\n\n")
+            .ok_or_else(|| anyhow!("email is missing a body"))?;
+        let mut from = None;
+        let mut date = None;
+        let mut subject = None;
+
+        // Unfold continuation lines.
+        let headers = headers.replace("\n ", " ").replace("\n\t", " ");
+        for header in headers.lines() {
+            let Some((key, value)) = header.split_once(':') else {
+                continue;
+            };
+            let value = value.trim().to_owned();
+
+            match key.to_ascii_lowercase().as_str() {
+                "from" => from = Some(value),
+                "date" => date = Some(value),
+                "subject" => subject = Some(value),
+                _ => {}
+            }
+        }
+        let from = from.ok_or_else(|| anyhow!("email is missing a `From` header"))?;
+        let date = date.ok_or_else(|| anyhow!("email is missing a `Date` header"))?;
+        let subject = subject.ok_or_else(|| anyhow!("email is missing a `Subject` header"))?;
+
+        let (name, email) = match from.rsplit_once('<') {
+            Some((name, email)) => (
+                name.trim().trim_matches('"').to_owned(),
+                email.trim_end_matches('>').to_owned(),
+            ),
+            None => (from.clone(), from),
+        };
+        let (subject, position) = match subject.strip_prefix('[').and_then(|s| s.split_once(']')) {
+            Some((prefix, subject)) => {
+                let position = prefix
+                    .split_whitespace()
+                    .filter_map(|s| s.split_once('/'))
+                    .find_map(|(n, m)| Some((n.parse().ok()?, m.parse().ok()?)));
+
+                (subject.trim().to_owned(), position)
+            }
+            None => (subject, None),
+        };
+
+        let mut message = Vec::new();
+        let mut diff = Vec::new();
+        let mut lines = body.lines();
+
+        for line in lines.by_ref() {
+            if line == "---" {
+                break;
+            }
+            message.push(line);
+        }
+        for line in lines.by_ref() {
+            if line.starts_with("diff --git ") {
+                diff.push(line);
+                break;
+            }
+        }
+        // Lines left in the current hunk, on the old and new side. A `-- ` line within a
+        // hunk is a removed `- ` line, and not the start of the email signature.
+        let (mut old, mut new) = (0usize, 0usize);
+
+        for line in lines {
+            if old > 0 || new > 0 {
+                match line.chars().next() {
+                    Some('-') => old = old.saturating_sub(1),
+                    Some('+') => new = new.saturating_sub(1),
+                    Some('\\') => {}
+                    _ => {
+                        old = old.saturating_sub(1);
+                        new = new.saturating_sub(1);
+                    }
+                }
+            } else if let Some(counts) = hunk(line) {
+                (old, new) = counts;
+            } else if line == "-- " {
+                // Start of the email signature.
+                break;
+            }
+            diff.push(line);
+        }
+        let diff = if diff.is_empty() {
+            String::new()
+        } else {
+            diff.join("\n") + "\n"
+        };
+
+        Ok(Self {
+            name,
+            email,
+            date,
+            subject,
+            position,
+            message: message.join("\n").trim().to_owned(),
+            diff,
+        })
+    }
+
+    /// Commit the email's changes on top of the given parent.
+    fn commit(&self, repo: &raw::Repository, parent: &raw::Commit) -> anyhow::Result<git::Oid> {
+        let date = DateTime::parse_from_rfc2822(&self.date)
+            .with_context(|| format!("invalid email date '{}'", self.date))?;
+        let time = raw::Time::new(date.timestamp(), date.offset().local_minus_utc() / 60);
+        let author = raw::Signature::new(&self.name, &self.email, &time)?;
+        let diff = raw::Diff::from_buffer(self.diff.as_bytes())?;
+        let mut index = repo
+            .apply_to_tree(&parent.tree()?, &diff, None)
+            .with_context(|| format!("failed to apply '{}'", self.subject))?;
+        let tree = repo.find_tree(index.write_tree_to(repo)?)?;
+        let message = if self.message.is_empty() {
+            self.subject.clone()
+        } else {
+            format!("{}\n\n{}", self.subject, self.message)
+        };
+        let oid = repo.commit(None, &author, &author, &message, &tree, &[parent])?;
+
+        Ok(oid.into())
+    }
+}
+
+/// Parse the line counts of a hunk header, eg. `@@ -1,3 +1,4 @@`.
+fn hunk(line: &str) -> Option<(usize, usize)> {
+    let range = line.strip_prefix("@@ -")?.split(" @@").next()?;
+    let (old, new) = range.split_once(" +")?;
+    let count = |range: &str| match range.split_once(',') {
+        Some((_, count)) => count.parse().ok(),
+        None => Some(1),
+    };
+
+    Some((count(old)?, count(new)?))
+}
+
+/// A patch series, ie. one or more patch emails, optionally preceded by a
+/// cover letter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Series {
+    pub cover: Option<Email>,
+    pub patches: Vec<Email>,
+}
+
+impl Series {
+    /// The first email of the series.
+    fn first(&self) -> &Email {
+        self.cover.as_ref().unwrap_or(&self.patches[0])
+    }
+
+    /// Author of the series.
+    pub fn author(&self) -> String {
+        let first = self.first();
+        format!("{} <{}>", first.name, first.email)
+    }
+
+    /// Date of the series.
+    pub fn date(&self) -> &str {
+        &self.first().date
+    }
+
+    /// Subject of the series.
+    pub fn subject(&self) -> &str {
+        &self.first().subject
+    }
+
+    /// Title and description of the series.
+    pub fn message(&self) -> (String, String) {
+        let first = self.first();
+        (first.subject.clone(), first.message.clone())
+    }
+
+    /// Apply the series on top of `base`, and return the resulting head.
+    pub fn apply(&self, repo: &raw::Repository, base: git::Oid) -> anyhow::Result<git::Oid> {
+        let mut head = base;
+
+        for patch in &self.patches {
+            let parent = repo.find_commit(head.into())?;
+            head = patch.commit(repo, &parent)?;
+        }
+        Ok(head)
+    }
+}
+
+/// Parse a mailbox into patch series.
+pub fn parse(mbox: &str) -> anyhow::Result<Vec<Series>> {
+    let mut emails = Vec::new();
+    let mut current: Option<Vec<&str>> = None;
+    let mut blank = true;
+
+    for line in mbox.lines() {
+        if blank && line.starts_with("From ") {
+            if let Some(lines) = current.replace(Vec::new()) {
+                emails.push(lines);
+            }
+        } else if let Some(lines) = &mut current {
+            // Un-escape `From ` lines quoted by the mailbox format.
+            let unquoted = line.trim_start_matches('>');
+            if unquoted.starts_with("From ") && unquoted.len() < line.len() {
+                lines.push(&line[1..]);
+            } else {
+                lines.push(line);
+            }
+        }
+        blank = line.is_empty();
+    }
+    emails.extend(current);
+
+    let mut series: Vec<Series> = Vec::new();
+    for lines in emails {
+        let email = Email::parse(&lines.join("\n"))?;
+
+        match email.position {
+            Some((0, _)) => series.push(Series {
+                cover: Some(email),
+                patches: vec![],
+            }),
+            Some((n, _)) if n > 1 => match series.last_mut() {
+                Some(last)
+                    if last.first().position.map(|(_, m)| m) == email.position.map(|(_, m)| m) =>
+                {
+                    last.patches.push(email)
+                }
+                _ => anyhow::bail!("patch '{}' is not part of a series", email.subject),
+            },
+            Some(_) => match series.last_mut() {
+                // The first patch following a cover letter.
+                Some(last) if last.patches.is_empty() => last.patches.push(email),
+                _ => series.push(Series {
+                    cover: None,
+                    patches: vec![email],
+                }),
+            },
+            None => series.push(Series {
+                cover: None,
+                patches: vec![email],
+            }),
+        }
+    }
+    if let Some(s) = series.iter().find(|s| s.patches.is_empty()) {
+        anyhow::bail!("patch series '{}' has no patches", s.subject());
+    }
+    if let Some(e) = series
+        .iter()
+        .flat_map(|s| s.patches.iter())
+        .find(|e| e.diff.is_empty())
+    {
+        anyhow::bail!("patch '{}' has no diff", e.subject);
+    }
+    Ok(series)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const MBOX: &str = "\
+From 0000000000000000000000000000000000000000 Mon Sep 17 00:00:00 2001
+From: Alice Liddell <alice@example.com>
+Date: Sun, 1 Jan 2023 10:00:00 +0100
+Subject: [PATCH 0/2] Improve the
+ README
+
+A couple of README fixes.
+
+From 0000000000000000000000000000000000000000 Mon Sep 17 00:00:00 2001
+From: Alice Liddell <alice@example.com>
+Date: Sun, 1 Jan 2023 10:00:00 +0100
+Subject: [PATCH 1/2] Add a title
+
+Every README needs one.
+>From here on, it's better.
+---
+ README | 1 +
+ 1 file changed, 1 insertion(+)
+
+diff --git a/README b/README
+index e69de29..3b18e51 100644
+--- a/README
++++ b/README
+@@ -0,0 +1 @@
++hello world
+--\x20
+2.40.0
+
+From 0000000000000000000000000000000000000000 Mon Sep 17 00:00:00 2001
+From: Alice Liddell <alice@example.com>
+Date: Sun, 1 Jan 2023 10:05:00 +0100
+Subject: [PATCH 2/2] Remove the title
+
+---
+diff --git a/README b/README
+index 3b18e51..e69de29 100644
+--- a/README
++++ b/README
+@@ -1 +0,0 @@
+-hello world
+--\x20
+2.40.0
+
+From 0000000000000000000000000000000000000000 Mon Sep 17 00:00:00 2001
+From: Bob <bob@example.com>
+Date: Mon, 2 Jan 2023 10:00:00 +0000
+Subject: [PATCH] Add a license
+
+---
+diff --git a/LICENSE b/LICENSE
+new file mode 100644
+index 0000000..3b18e51
+--- /dev/null
++++ b/LICENSE
+@@ -0,0 +1 @@
++hello world
+";
+
+    #[test]
+    fn test_parse() {
+        let series = parse(MBOX).unwrap();
+
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[0].subject(), "Improve the README");
+        assert_eq!(series[0].author(), "Alice Liddell <alice@example.com>");
+        assert_eq!(series[0].patches.len(), 2);
+        assert_eq!(
+            series[0].patches[0].message,
+            "Every README needs one.\nFrom here on, it's better."
+        );
+        assert!(series[0].patches[0].diff.ends_with("+hello world\n"));
+        assert_eq!(series[1].cover, None);
+        assert_eq!(
+            series[1].message(),
+            ("Add a license".to_owned(), String::new())
+        );
+        assert_eq!(series[1].date(), "Mon, 2 Jan 2023 10:00:00 +0000");
+    }
+
+    #[test]
+    fn test_parse_signature_separator() {
+        let mbox = "\
+From 0000000000000000000000000000000000000000 Mon Sep 17 00:00:00 2001
+From: Bob <bob@example.com>
+Date: Mon, 2 Jan 2023 10:00:00 +0000
+Subject: [PATCH] Remove empty list item
+
+---
+diff --git a/README b/README
+index 3b18e51..e69de29 100644
+--- a/README
++++ b/README
+@@ -1,2 +1 @@
+ hello world
+--\x20
+--\x20
+2.40.0
+";
+        let series = parse(mbox).unwrap();
+        let diff = &series[0].patches[0].diff;
+
+        assert!(diff.ends_with(" hello world\n-- \n"), "{diff}");
+    }
+
+    #[test]
+    fn test_apply() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (repo, base) = radicle::test::fixtures::repository(tmp.path());
+        let series = parse(MBOX).unwrap();
+
+        let head = series[1].apply(&repo, base.into()).unwrap();
+        let commit = repo.find_commit(head.into()).unwrap();
+
+        assert_eq!(commit.parent_id(0).unwrap(), base);
+        assert_eq!(commit.summary(), Some("Add a license"));
+        assert_eq!(commit.author().name(), Some("Bob"));
+        assert_eq!(commit.author().when().seconds(), 1672653600);
+        assert!(commit.tree().unwrap().get_name("LICENSE").is_some());
+    }
+}
@@ -33,6 +33,10 @@ Usage
     rad inbox list [<option>...]
     rad inbox show <id> [<option>...]
     rad inbox clear [<option>...]
+    rad inbox watch [<option>...]
+    rad inbox mute [<option>...]
+    rad inbox unwatch [<option>...]
+    rad inbox rules [<option>...]
 
     By default, this command lists all items in your inbox.
     If your working directory is a Radicle repository, it only shows item
@@ -44,6 +48,14 @@ Usage
 
     The `rad inbox clear` command will delete all notifications in the inbox.
 
+    By default, you are notified of all updates to the repositories you have
+    contributed to. The `rad inbox watch` command sets which updates of a
+    repository you are notified of, either for the whole repository, for issues
+    or patches only, or for a single object. `rad inbox mute` turns these
+    notifications off, and `rad inbox unwatch` removes the rule, restoring the
+    default. When more than one rule applies to an update, the most specific
+    one is used. The `rad inbox rules` command lists the rules that are set.
+
 Options
 
     --all                Operate on all repositories
@@ -52,6 +64,15 @@ Options
     --reverse, -r        Reverse the list
     --show-unknown       Show any updates that were not recognized
     --help               Print help
+
+Rule options
+
+    --issues             Apply the rule to issues
+    --patches            Apply the rule to patches
+    --object <id>        Apply the rule to the given issue, patch or other object
+    --relevant           Only notify about issues and patches you authored or
+                         are assigned to (watch)
+    --mentions           Only notify when you are mentioned (watch)
 "#,
 };
 
@@ -61,6 +82,49 @@ enum Operation {
     List,
     Show,
     Clear,
+    Watch,
+    Mute,
+    Unwatch,
+    Rules,
+}
+
+/// The references a notification rule applies to.
+#[derive(Default, Debug, Clone)]
+enum Target {
+    /// The whole repository.
+    #[default]
+    Repository,
+    /// All objects of the given type.
+    Cobs(cob::TypeName),
+    /// A single object.
+    Object(cob::ObjectId),
+}
+
+impl Target {
+    fn pattern(&self) -> git::PatternString {
+        match self {
+            Self::Repository => notifications::pattern::all(),
+            Self::Cobs(type_name) => notifications::pattern::cobs(type_name),
+            Self::Object(id) => notifications::pattern::cob(id),
+        }
+    }
+
+    /// Describe the references of the given repo this target applies to.
+    fn describe(&self, rid: RepoId) -> String {
+        let rid = term::format::tertiary(rid);
+
+        match self {
+            Self::Repository => rid.to_string(),
+            Self::Cobs(type_name) if *type_name == *cob::issue::TYPENAME => {
+                format!("issues of {rid}")
+            }
+            Self::Cobs(type_name) if *type_name == *cob::patch::TYPENAME => {
+                format!("patches of {rid}")
+            }
+            Self::Cobs(type_name) => format!("{type_name} objects of {rid}"),
+            Self::Object(id) => format!("{} of {rid}", term::format::cob(id)),
+        }
+    }
 }
 
 #[derive(Default, Debug)]
@@ -83,6 +147,8 @@ pub struct Options {
     mode: Mode,
     sort_by: SortBy,
     show_unknown: bool,
+    target: Target,
+    interest: Interest,
 }
 
 impl Args for Options {
@@ -96,6 +162,8 @@ impl Args for Options {
         let mut reverse = None;
         let mut field = None;
         let mut show_unknown = false;
+        let mut target = Target::default();
+        let mut interest = Interest::default();
 
         while let Some(arg) = parser.next()? {
             match arg {
@@ -111,6 +179,24 @@ impl Args for Options {
                 Long("show-unknown") => {
                     show_unknown = true;
                 }
+                Long("issues") => {
+                    target = Target::Cobs(cob::issue::TYPENAME.clone());
+                }
+                Long("patches") => {
+                    target = Target::Cobs(cob::patch::TYPENAME.clone());
+                }
+                Long("object") => {
+                    let val = parser.value()?;
+                    let id = term::args::cob(&val)?;
+
+                    target = Target::Object(id);
+                }
+                Long("relevant") if op == Some(Operation::Watch) => {
+                    interest = Interest::Relevant;
+                }
+                Long("mentions") if op == Some(Operation::Watch) => {
+                    interest = Interest::Mentions;
+                }
                 Long("sort-by") => {
                     let val = parser.value()?;
 
@@ -134,6 +220,10 @@ impl Args for Options {
                     "list" => op = Some(Operation::List),
                     "show" => op = Some(Operation::Show),
                     "clear" => op = Some(Operation::Clear),
+                    "watch" => op = Some(Operation::Watch),
+                    "mute" => op = Some(Operation::Mute),
+                    "unwatch" => op = Some(Operation::Unwatch),
+                    "rules" => op = Some(Operation::Rules),
                     cmd => return Err(anyhow!("unknown command `{cmd}`, see `rad inbox --help`")),
                 },
                 Value(val) if op.is_some() && mode.is_none() => {
@@ -168,6 +258,8 @@ impl Args for Options {
                 mode,
                 sort_by,
                 show_unknown,
+                target,
+                interest,
             },
             vec![],
         ))
@@ -183,6 +275,8 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
         mode,
         sort_by,
         show_unknown,
+        target,
+        interest,
     } = options;
 
     match op {
@@ -196,6 +290,10 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
         ),
         Operation::Clear => clear(mode, &mut notifs),
         Operation::Show => show(mode, &mut notifs, storage, &profile),
+        Operation::Watch => watch(mode, target, interest, &mut notifs),
+        Operation::Mute => watch(mode, target, Interest::None, &mut notifs),
+        Operation::Unwatch => unwatch(mode, target, &mut notifs),
+        Operation::Rules => rules(mode, &notifs.read_only()),
     }
 }
 
@@ -311,6 +409,12 @@ where
                     None => continue,
                 }
             }
+            NotificationKind::Mention { typed_id, .. } => {
+                match NotificationRow::cob(typed_id, &n, &issues, &patches, &repo)? {
+                    Some(row) => row.mention(),
+                    None => continue,
+                }
+            }
             NotificationKind::Unknown { refname } => {
                 if show_unknown {
                     NotificationRow::unknown(refname, &n, &repo)?
@@ -469,6 +573,14 @@ impl NotificationRow {
         )))
     }
 
+    /// Mark the row as a mention of the user.
+    fn mention(self) -> Self {
+        Self {
+            category: term::format::dim(format!("{} mention", self.category.content())),
+            ..self
+        }
+    }
+
     fn unknown<S>(refname: &Qualified<'static>, n: &Notification, repo: &S) -> anyhow::Result<Self>
     where
         S: ReadRepository,
@@ -529,8 +641,17 @@ fn show(
     let n = notifs.get(id)?;
     let repo = storage.repository(n.repo)?;
 
+    if !n.activity.is_empty() {
+        for activity in &n.activity {
+            activity_line(activity, profile).print();
+        }
+        term::blank();
+    }
+
     match n.kind {
-        NotificationKind::Cob { typed_id } if typed_id.is_issue() => {
+        NotificationKind::Cob { typed_id } | NotificationKind::Mention { typed_id, .. }
+            if typed_id.is_issue() =>
+        {
             let issues = profile.issues(&repo)?;
             let issue = issues.get(&typed_id.id)?.unwrap();
 
@@ -541,7 +662,9 @@ fn show(
                 profile,
             )?;
         }
-        NotificationKind::Cob { typed_id } if typed_id.is_patch() => {
+        NotificationKind::Cob { typed_id } | NotificationKind::Mention { typed_id, .. }
+            if typed_id.is_patch() =>
+        {
             let patches = profile.patches(&repo)?;
             let patch = patches.get(&typed_id.id)?.unwrap();
 
@@ -574,3 +697,110 @@ fn show(
 
     Ok(())
 }
+
+/// Render a summary of an operation, eg. "alice z6Mk…sXbR commented 9ed9e10 now".
+fn activity_line(activity: &Activity, profile: &Profile) -> term::Line {
+    term::format::Author::new(&activity.author, profile)
+        .line()
+        .space()
+        .item(activity.kind.to_string())
+        .space()
+        .item(term::format::secondary(term::format::oid(activity.entry)))
+        .space()
+        .item(term::format::timestamp(activity.timestamp).dim())
+}
+
+/// Get the repository a rule command operates on.
+fn rule_repo(mode: Mode) -> anyhow::Result<RepoId> {
+    match mode {
+        Mode::ByRepo(rid) => Ok(rid),
+        Mode::Contextual => radicle::rad::cwd().map(|(_, rid)| rid).map_err(|_| {
+            Error::WithHint {
+                err: anyhow!("not a radicle repository"),
+                hint: "to set a rule on another repository, use the `--repo` option",
+            }
+            .into()
+        }),
+        Mode::All => anyhow::bail!("rules can only be set on a single repository"),
+        Mode::ById(_) => anyhow::bail!("rules can't be set on notifications"),
+    }
+}
+
+fn watch(
+    mode: Mode,
+    target: Target,
+    interest: Interest,
+    notifs: &mut notifications::StoreWriter,
+) -> anyhow::Result<()> {
+    let rid = rule_repo(mode)?;
+    let updated = notifs.watch(&rid, &target.pattern(), interest)?;
+    let target = target.describe(rid);
+
+    if !updated {
+        term::info!("Notifications for {target} are already set to `{interest}`");
+    } else if interest == Interest::None {
+        term::success!("Notifications for {target} muted");
+    } else {
+        term::success!("Notifications for {target} set to `{interest}`");
+    }
+    Ok(())
+}
+
+fn unwatch(
+    mode: Mode,
+    target: Target,
+    notifs: &mut notifications::StoreWriter,
+) -> anyhow::Result<()> {
+    let rid = rule_repo(mode)?;
+    let removed = notifs.unwatch(&rid, &target.pattern())?;
+    let target = target.describe(rid);
+
+    if removed {
+        term::success!("Notification rule for {target} removed");
+    } else {
+        term::info!("No notification rule set for {target}");
+    }
+    Ok(())
+}
+
+fn rules(mode: Mode, notifs: &notifications::StoreReader) -> anyhow::Result<()> {
+    let repo = match mode {
+        Mode::All => None,
+        Mode::ByRepo(rid) => Some(rid),
+        Mode::Contextual => radicle::rad::cwd().ok().map(|(_, rid)| rid),
+        Mode::ById(_) => anyhow::bail!("the `rules` command does not take IDs"),
+    };
+    let mut table = term::Table::new(term::TableOptions::bordered());
+    table.push([
+        term::format::default(String::from("Repository")),
+        term::format::default(String::from("Pattern")),
+        term::format::default(String::from("Interest")),
+    ]);
+    table.divider();
+
+    let mut empty = true;
+    for rule in notifs.rules()? {
+        let Rule {
+            repo: rid,
+            pattern,
+            interest,
+        } = rule?;
+
+        if repo.is_some_and(|repo| repo != rid) {
+            continue;
+        }
+        table.push([
+            term::format::tertiary(rid.to_string()),
+            term::format::default(pattern.to_string()),
+            term::format::default(interest.to_string()),
+        ]);
+        empty = false;
+    }
+
+    if empty {
+        term::print(term::format::italic("No notification rules are set."));
+    } else {
+        table.print();
+    }
+    Ok(())
+}
@@ -10,6 +10,7 @@ use anyhow::{anyhow, Context as _};
 use radicle::cob::common::{Label, Reaction};
 use radicle::cob::issue;
 use radicle::cob::issue::{CloseReason, State};
+use radicle::cob::query::Query;
 use radicle::cob::thread;
 use radicle::crypto::Signer;
 use radicle::issue::cache::Issues as _;
@@ -39,7 +40,8 @@ Usage
     rad issue [<option>...]
     rad issue delete <issue-id> [<option>...]
     rad issue edit <issue-id> [<option>...]
-    rad issue list [--assigned <did>] [--all | --closed | --open | --solved] [<option>...]
+    rad issue list [--assigned <did>] [--all | --closed | --open | --solved]
+                   [--query <query>] [--saved <name>] [<option>...]
     rad issue open [--title <title>] [--description <text>] [--label <label>] [<option>...]
     rad issue react <issue-id> [--emoji <char>] [--to <comment>] [<option>...]
     rad issue assign <issue-id> [--add <did>] [--delete <did>] [<option>...]
@@ -47,6 +49,9 @@ Usage
     rad issue comment <issue-id> [--message <message>] [--reply-to <comment-id>] [<option>...]
     rad issue show <issue-id> [<option>...]
     rad issue state <issue-id> [--closed | --open | --solved] [<option>...]
+    rad issue task <issue-id> --add <text> [--after <task-id>] [--issue <issue-id> | --patch <patch-id>] [<option>...]
+    rad issue task <issue-id> (--check | --uncheck | --delete) <task-id> [<option>...]
+    rad issue task <issue-id> --move <task-id> [--after <task-id>] [<option>...]
     rad issue cache [<issue-id>] [<option>...]
 
 Assign options
@@ -63,6 +68,25 @@ Label options
 
     Note: --add takes precedence over --delete
 
+Task options
+
+        --add <text>           Add a task with the given description
+        --check <task-id>      Mark a task as done
+        --uncheck <task-id>    Mark a task as not done
+        --move <task-id>       Move a task
+        --delete <task-id>     Delete a task
+        --after <task-id>      Add or move the task after the given task, instead of at the start
+        --issue <issue-id>     Link the added task to an issue
+        --patch <patch-id>     Link the added task to a patch
+
+List options
+
+        --query <query>        Only show issues matching the query, eg. `label:bug updated:>2024-01-01`
+        --saved <name>         Only show issues matching the query saved under `queries.<name>`
+                               in the configuration
+
+    When a query is given, issues in any state are shown, unless a state option is given.
+
 Show options
 
         --debug                Show the issue as Rust debug output
@@ -90,6 +114,7 @@ pub enum OperationName {
     React,
     Show,
     State,
+    Task,
     Cache,
 }
 
@@ -147,12 +172,45 @@ pub enum Operation {
     List {
         assigned: Option<Assigned>,
         state: Option<State>,
+        query: Option<Query>,
+        saved: Option<String>,
+    },
+    Task {
+        id: Rev,
+        action: TaskAction,
     },
     Cache {
         id: Option<Rev>,
     },
 }
 
+/// Object a new task is linked to.
+#[derive(Debug, PartialEq, Eq)]
+pub enum TaskLink {
+    Issue(Rev),
+    Patch(Rev),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum TaskAction {
+    Add {
+        body: String,
+        after: Option<Rev>,
+        link: Option<TaskLink>,
+    },
+    Check {
+        task: Rev,
+        done: bool,
+    },
+    Move {
+        task: Rev,
+        after: Option<Rev>,
+    },
+    Delete {
+        task: Rev,
+    },
+}
+
 #[derive(Debug, Default, PartialEq, Eq)]
 pub struct AssignOptions {
     pub add: BTreeSet<Did>,
@@ -186,6 +244,9 @@ impl Args for Options {
         let mut comment_id: Option<thread::CommentId> = None;
         let mut description: Option<String> = None;
         let mut state: Option<State> = Some(State::Open);
+        let mut default_state = true;
+        let mut query = None;
+        let mut saved = None;
         let mut labels = Vec::new();
         let mut assignees = Vec::new();
         let mut format = Format::default();
@@ -196,6 +257,9 @@ impl Args for Options {
         let mut debug = false;
         let mut assign_opts = AssignOptions::default();
         let mut label_opts = LabelOptions::default();
+        let mut task_action: Option<TaskAction> = None;
+        let mut task_after: Option<Rev> = None;
+        let mut task_link: Option<TaskLink> = None;
         let mut repo = None;
 
         while let Some(arg) = parser.next()? {
@@ -207,19 +271,29 @@ impl Args for Options {
                 // List options.
                 Long("all") if op.is_none() || op == Some(OperationName::List) => {
                     state = None;
+                    default_state = false;
                 }
                 Long("closed") if op.is_none() || op == Some(OperationName::List) => {
                     state = Some(State::Closed {
                         reason: CloseReason::Other,
                     });
+                    default_state = false;
                 }
                 Long("open") if op.is_none() || op == Some(OperationName::List) => {
                     state = Some(State::Open);
+                    default_state = false;
                 }
                 Long("solved") if op.is_none() || op == Some(OperationName::List) => {
                     state = Some(State::Closed {
                         reason: CloseReason::Solved,
                     });
+                    default_state = false;
+                }
+                Long("query") if op.is_none() || op == Some(OperationName::List) => {
+                    query = Some(term::args::query(&parser.value()?)?);
+                }
+                Long("saved") if op.is_none() || op == Some(OperationName::List) => {
+                    saved = Some(term::args::string(&parser.value()?));
                 }
 
                 // Open options.
@@ -308,6 +382,44 @@ impl Args for Options {
                         .delete
                         .insert(term::args::did(&parser.value()?)?);
                 }
+
+                // Task options
+                Long("add") if op == Some(OperationName::Task) => {
+                    let val = parser.value()?;
+                    let body = term::args::string(&val);
+
+                    task_action = Some(TaskAction::Add {
+                        body,
+                        after: None,
+                        link: None,
+                    });
+                }
+                Long("check") | Long("uncheck") if op == Some(OperationName::Task) => {
+                    let done = arg == Long("check");
+                    let task = term::args::rev(&parser.value()?)?;
+
+                    task_action = Some(TaskAction::Check { task, done });
+                }
+                Long("move") if op == Some(OperationName::Task) => {
+                    let task = term::args::rev(&parser.value()?)?;
+
+                    task_action = Some(TaskAction::Move { task, after: None });
+                }
+                Long("delete") if op == Some(OperationName::Task) => {
+                    let task = term::args::rev(&parser.value()?)?;
+
+                    task_action = Some(TaskAction::Delete { task });
+                }
+                Long("after") if op == Some(OperationName::Task) => {
+                    task_after = Some(term::args::rev(&parser.value()?)?);
+                }
+                Long("issue") if op == Some(OperationName::Task) => {
+                    task_link = Some(TaskLink::Issue(term::args::rev(&parser.value()?)?));
+                }
+                Long("patch") if op == Some(OperationName::Task) => {
+                    task_link = Some(TaskLink::Patch(term::args::rev(&parser.value()?)?));
+                }
+
                 Long("assigned") | Short('a') if assigned.is_none() => {
                     if let Ok(val) = parser.value() {
                         let peer = term::args::did(&val)?;
@@ -358,6 +470,7 @@ impl Args for Options {
                     "s" | "state" => op = Some(OperationName::State),
                     "assign" => op = Some(OperationName::Assign),
                     "label" => op = Some(OperationName::Label),
+                    "task" => op = Some(OperationName::Task),
                     "cache" => op = Some(OperationName::Cache),
 
                     unknown => anyhow::bail!("unknown operation '{}'", unknown),
@@ -414,7 +527,45 @@ impl Args for Options {
                 id: id.ok_or_else(|| anyhow!("an issue to label must be provided"))?,
                 opts: label_opts,
             },
-            OperationName::List => Operation::List { assigned, state },
+            OperationName::List => Operation::List {
+                assigned,
+                // Queries match issues in any state, unless a state is given.
+                state: if default_state && (query.is_some() || saved.is_some()) {
+                    None
+                } else {
+                    state
+                },
+                query,
+                saved,
+            },
+            OperationName::Task => {
+                let action =
+                    task_action.ok_or_else(|| anyhow!("a task operation must be provided"))?;
+
+                if task_link.is_some() && !matches!(action, TaskAction::Add { .. }) {
+                    anyhow::bail!("`--issue` and `--patch` can only be used with `--add`");
+                }
+                if task_after.is_some()
+                    && !matches!(action, TaskAction::Add { .. } | TaskAction::Move { .. })
+                {
+                    anyhow::bail!("`--after` can only be used with `--add` or `--move`");
+                }
+                Operation::Task {
+                    id: id.ok_or_else(|| anyhow!("an issue must be provided"))?,
+                    action: match action {
+                        TaskAction::Add { body, .. } => TaskAction::Add {
+                            body,
+                            after: task_after,
+                            link: task_link,
+                        },
+                        TaskAction::Move { task, .. } => TaskAction::Move {
+                            task,
+                            after: task_after,
+                        },
+                        action => action,
+                    },
+                }
+            }
             OperationName::Cache => Operation::Cache { id },
         };
 
@@ -447,6 +598,7 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
                 | Operation::Delete { .. }
                 | Operation::Assign { .. }
                 | Operation::Label { .. }
+                | Operation::Task { .. }
         );
 
     let mut issues = profile.issues_mut(&repo)?;
@@ -484,7 +636,8 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
             let issue_id = id.resolve::<cob::ObjectId>(&repo.backend)?;
             let mut issue = issues.get_mut(&issue_id)?;
             let (body, reply_to) = prompt_comment(message, reply_to, &issue, &repo)?;
-            let comment_id = issue.comment(body, reply_to, vec![], &signer)?;
+            let mentions = thread::mentions(&body, &profile);
+            let comment_id = issue.comment(body, reply_to, vec![], mentions, &signer)?;
 
             if options.quiet {
                 term::print(comment_id);
@@ -577,14 +730,44 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
                 .collect::<Vec<_>>();
             issue.label(labels, &signer)?;
         }
-        Operation::List { assigned, state } => {
-            list(issues, &assigned, &state, &profile)?;
+        Operation::List {
+            assigned,
+            state,
+            query,
+            saved,
+        } => {
+            let query =
+                match saved {
+                    Some(name) => {
+                        let saved =
+                            profile.config.queries.get(&name).cloned().ok_or_else(|| {
+                                anyhow!("query '{name}' not found in configuration")
+                            })?;
+                        Some(query.unwrap_or_default().and(saved))
+                    }
+                    None => query,
+                };
+            list(issues, &assigned, &state, query.as_ref(), &profile)?;
         }
         Operation::Delete { id } => {
             let signer = term::signer(&profile)?;
             let id = id.resolve(&repo.backend)?;
             issues.remove(&id, &signer)?;
         }
+        Operation::Task { id, action } => {
+            let signer = term::signer(&profile)?;
+            let id = id.resolve(&repo.backend)?;
+            let Ok(mut issue) = issues.get_mut(&id) else {
+                anyhow::bail!("Issue `{id}` not found");
+            };
+            let task_id = task(&mut issue, action, &repo, &signer)?;
+
+            if options.quiet {
+                term::print(task_id);
+            } else {
+                term::issue::show(&issue, issue.id(), Format::Header, &profile)?;
+            }
+        }
         Operation::Cache { id } => {
             let id = id.map(|id| id.resolve(&repo.backend)).transpose()?;
             cache::run(id, &repo, &profile)?;
@@ -605,10 +788,67 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Apply a task action to an issue, and return the id of the task.
+fn task<R, C, G>(
+    issue: &mut issue::IssueMut<'_, '_, R, C>,
+    action: TaskAction,
+    repo: &storage::git::Repository,
+    signer: &G,
+) -> anyhow::Result<issue::TaskId>
+where
+    R: WriteRepository + cob::Store,
+    C: cob::cache::Update<issue::Issue>,
+    G: Signer,
+{
+    let resolve = |rev: Rev| -> anyhow::Result<issue::TaskId> {
+        let id = rev.resolve(&repo.backend)?;
+        if issue.task(&id).is_none() {
+            anyhow::bail!("Task `{id}` not found");
+        }
+        Ok(id)
+    };
+
+    match action {
+        TaskAction::Add { body, after, link } => {
+            let after = after.map(resolve).transpose()?;
+            let link = match link {
+                Some(TaskLink::Issue(rev)) => Some(issue::TaskLink::Issue {
+                    id: rev.resolve(&repo.backend)?,
+                }),
+                Some(TaskLink::Patch(rev)) => Some(issue::TaskLink::Patch {
+                    id: rev.resolve(&repo.backend)?,
+                }),
+                None => None,
+            };
+            Ok(issue.add_task(body, after, link, signer)?)
+        }
+        TaskAction::Check { task, done } => {
+            let task = resolve(task)?;
+            issue.check_task(task, done, signer)?;
+
+            Ok(task)
+        }
+        TaskAction::Move { task, after } => {
+            let task = resolve(task)?;
+            let after = after.map(resolve).transpose()?;
+            issue.move_task(task, after, signer)?;
+
+            Ok(task)
+        }
+        TaskAction::Delete { task } => {
+            let task = resolve(task)?;
+            issue.remove_task(task, signer)?;
+
+            Ok(task)
+        }
+    }
+}
+
 fn list<C>(
     cache: C,
     assigned: &Option<Assigned>,
     state: &Option<State>,
+    query: Option<&Query>,
     profile: &profile::Profile,
 ) -> anyhow::Result<()>
 where
@@ -626,7 +866,10 @@ where
     };
 
     let mut all = Vec::new();
-    let issues = cache.list()?;
+    let issues = match query {
+        Some(query) => cache.list_by_query(query)?,
+        None => cache.list()?,
+    };
     for result in issues {
         let Ok((id, issue)) = result else {
             // Skip issues that failed to load.
@@ -771,7 +1014,7 @@ where
                 tx.edit(t)?;
             }
             if let Some(d) = description {
-                tx.edit_comment(root, d, vec![])?;
+                tx.edit_comment(root, d, vec![], vec![])?;
             }
             Ok(())
         })?;
@@ -789,7 +1032,7 @@ where
 
     issue.transaction("Edit", signer, |tx| {
         tx.edit(title)?;
-        tx.edit_comment(root, description, vec![])?;
+        tx.edit_comment(root, description, vec![], vec![])?;
 
         Ok(())
     })?;
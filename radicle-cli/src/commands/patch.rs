@@ -35,6 +35,7 @@ use std::ffi::OsString;
 use anyhow::anyhow;
 
 use radicle::cob::patch::PatchId;
+use radicle::cob::query::Query;
 use radicle::cob::{patch, Label};
 use radicle::patch::cache::Patches as _;
 use radicle::storage::git::transport;
@@ -137,6 +138,11 @@ List options
         --authored             Show only patches that you have authored
         --author <did>         Show only patched where the given user is an author
                                (may be specified multiple times)
+        --query <query>        Show only patches matching the query, eg. `review:accepted has:unresolved`
+        --saved <name>         Show only patches matching the query saved under `queries.<name>`
+                               in the configuration
+
+    When a query is given, patches in any state are shown, unless a state option is given.
 
 Ready options
 
@@ -242,6 +248,8 @@ pub enum Operation {
     },
     List {
         filter: Option<patch::Status>,
+        query: Option<Query>,
+        saved: Option<String>,
     },
     Edit {
         patch_id: Rev,
@@ -308,6 +316,9 @@ impl Args for Options {
         let mut revision_id = None;
         let mut message = Message::default();
         let mut filter = Some(patch::Status::Open);
+        let mut default_filter = true;
+        let mut query = None;
+        let mut saved = None;
         let mut diff = false;
         let mut debug = false;
         let mut undo = false;
@@ -490,18 +501,29 @@ impl Args for Options {
                 // List options.
                 Long("all") => {
                     filter = None;
+                    default_filter = false;
                 }
                 Long("draft") => {
                     filter = Some(patch::Status::Draft);
+                    default_filter = false;
                 }
                 Long("archived") => {
                     filter = Some(patch::Status::Archived);
+                    default_filter = false;
                 }
                 Long("merged") => {
                     filter = Some(patch::Status::Merged);
+                    default_filter = false;
                 }
                 Long("open") => {
                     filter = Some(patch::Status::Open);
+                    default_filter = false;
+                }
+                Long("query") if op.is_none() || op == Some(OperationName::List) => {
+                    query = Some(term::args::query(&parser.value()?)?);
+                }
+                Long("saved") if op.is_none() || op == Some(OperationName::List) => {
+                    saved = Some(term::args::string(&parser.value()?));
                 }
                 Long("authored") => {
                     authored = true;
@@ -581,7 +603,16 @@ impl Args for Options {
         }
 
         let op = match op.unwrap_or_default() {
-            OperationName::List => Operation::List { filter },
+            OperationName::List => Operation::List {
+                // Queries match patches in any state, unless a state is given.
+                filter: if default_filter && (query.is_some() || saved.is_some()) {
+                    None
+                } else {
+                    filter
+                },
+                query,
+                saved,
+            },
             OperationName::Show => Operation::Show {
                 patch_id: patch_id.ok_or_else(|| anyhow!("a patch must be provided"))?,
                 diff,
@@ -680,12 +711,33 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
     transport::local::register(profile.storage.clone());
 
     match options.op {
-        Operation::List { filter } => {
+        Operation::List {
+            filter,
+            query,
+            saved,
+        } => {
             let mut authors: BTreeSet<Did> = options.authors.iter().cloned().collect();
             if options.authored {
                 authors.insert(profile.did());
             }
-            list::run(filter.as_ref(), authors, &repository, &profile)?;
+            let query =
+                match saved {
+                    Some(name) => {
+                        let saved =
+                            profile.config.queries.get(&name).cloned().ok_or_else(|| {
+                                anyhow!("query '{name}' not found in configuration")
+                            })?;
+                        Some(query.unwrap_or_default().and(saved))
+                    }
+                    None => query,
+                };
+            list::run(
+                filter.as_ref(),
+                authors,
+                query.as_ref(),
+                &repository,
+                &profile,
+            )?;
         }
         Operation::Show {
             patch_id,
@@ -2,6 +2,7 @@ use super::*;
 
 use radicle::cob;
 use radicle::cob::patch;
+use radicle::cob::thread;
 use radicle::cob::thread::CommentId;
 use radicle::patch::ByRevision;
 use radicle::prelude::*;
@@ -33,7 +34,8 @@ pub fn run(
         .ok_or_else(|| anyhow!("Patch revision `{revision_id}` not found"))?;
     let mut patch = patch::PatchMut::new(patch_id, patch, &mut patches);
     let (body, reply_to) = prompt(message, reply_to, &revision, repo)?;
-    let comment_id = patch.comment(revision_id, body, reply_to, None, vec![], &signer)?;
+    let mentions = thread::mentions(&body, profile);
+    let comment_id = patch.comment(revision_id, body, reply_to, None, vec![], mentions, &signer)?;
     let comment = patch
         .revision(&revision_id)
         .ok_or(anyhow!("error retrieving revision `{revision_id}`"))?
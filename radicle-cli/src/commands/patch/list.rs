@@ -2,6 +2,7 @@ use std::collections::{BTreeMap, BTreeSet};
 
 use radicle::cob::patch;
 use radicle::cob::patch::{Patch, PatchId};
+use radicle::cob::query::Query;
 use radicle::patch::cache::Patches as _;
 use radicle::prelude::*;
 use radicle::profile::Profile;
@@ -18,21 +19,28 @@ use crate::terminal::patch as common;
 pub fn run(
     filter: Option<&patch::Status>,
     authors: BTreeSet<Did>,
+    query: Option<&Query>,
     repository: &Repository,
     profile: &Profile,
 ) -> anyhow::Result<()> {
     let patches = profile.patches(repository)?;
 
     let mut all = Vec::new();
-    let iter = match filter {
-        Some(status) => patches.list_by_status(status)?,
-        None => patches.list()?,
+    let iter = match (query, filter) {
+        (Some(query), _) => patches.list_by_query(query)?,
+        (None, Some(status)) => patches.list_by_status(status)?,
+        (None, None) => patches.list()?,
     };
     for patch in iter {
         let Ok((id, patch)) = patch else {
             // Skip patches that failed to load.
             continue;
         };
+        if let (Some(_), Some(status)) = (query, filter) {
+            if patch::Status::from(patch.state()) != *status {
+                continue;
+            }
+        }
         if !authors.is_empty() {
             if !authors.contains(patch.author().id()) {
                 continue;
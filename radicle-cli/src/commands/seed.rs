@@ -2,8 +2,10 @@ use std::ffi::OsString;
 
 use anyhow::anyhow;
 
+use localtime::LocalDuration;
 use radicle::node::policy;
 use radicle::node::policy::Scope;
+use radicle::node::sync::Schedule;
 use radicle::node::Handle;
 use radicle::{prelude::*, storage, Node};
 use radicle_term::Element as _;
@@ -21,6 +23,8 @@ pub const HELP: Help = Help {
 Usage
 
     rad seed [<rid>] [--[no-]fetch] [--scope <scope>] [<option>...]
+    rad seed <rid> [--fetch-every <mins>] [--announce-every <mins>] [--resync-every <mins>]
+    rad seed <rid> --no-schedule
 
     The `seed` command, when no Repository ID (<rid>) is provided, will list the
     repositories being seeded.
@@ -33,12 +37,21 @@ Usage
     On the other hand, with `followed`, only the repository delegates will be followed,
     plus any remote that is explicitly followed via `rad follow <nid>`.
 
+    A sync schedule can also be set, for the node to periodically fetch the
+    repository from its preferred seeds, re-announce our refs, or fetch from all
+    connected seeds. This keeps the repository fresh, even if gossip messages were
+    missed. An interval of `0` removes the task from the schedule.
+
 Options
 
-    --[no-]fetch           Fetch repository after updating seeding policy
-    --scope <scope>        Peer follow scope for this repository
-    --verbose, -v          Verbose output
-    --help                 Print help
+    --[no-]fetch              Fetch repository after updating seeding policy
+    --scope <scope>           Peer follow scope for this repository
+    --fetch-every <mins>      Fetch from preferred seeds at this interval
+    --announce-every <mins>   Re-announce our refs at this interval
+    --resync-every <mins>     Fetch from all connected seeds at this interval
+    --no-schedule             Remove the sync schedule of this repository
+    --verbose, -v             Verbose output
+    --help                    Print help
 "#,
 };
 
@@ -48,10 +61,42 @@ pub enum Operation {
         rid: RepoId,
         fetch: bool,
         scope: Scope,
+        schedule: Intervals,
     },
     List,
 }
 
+/// Sync schedule intervals to update. A zero interval removes the task.
+#[derive(Debug, Default)]
+pub struct Intervals {
+    pub fetch: Option<LocalDuration>,
+    pub announce: Option<LocalDuration>,
+    pub resync: Option<LocalDuration>,
+}
+
+impl Intervals {
+    /// Check whether no intervals are to be updated.
+    pub fn is_empty(&self) -> bool {
+        self.fetch.is_none() && self.announce.is_none() && self.resync.is_none()
+    }
+
+    /// Apply the intervals to a schedule.
+    pub fn apply(&self, schedule: &mut Schedule) {
+        let task =
+            |interval: LocalDuration| (interval > LocalDuration::from_secs(0)).then_some(interval);
+
+        if let Some(interval) = self.fetch {
+            schedule.fetch = task(interval);
+        }
+        if let Some(interval) = self.announce {
+            schedule.announce = task(interval);
+        }
+        if let Some(interval) = self.resync {
+            schedule.resync = task(interval);
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Options {
     pub op: Operation,
@@ -66,6 +111,7 @@ impl Args for Options {
         let mut rid: Option<RepoId> = None;
         let mut scope: Option<Scope> = None;
         let mut fetch: Option<bool> = None;
+        let mut schedule = Intervals::default();
         let mut verbose = false;
 
         while let Some(arg) = parser.next()? {
@@ -83,6 +129,27 @@ impl Args for Options {
                 Long("no-fetch") => {
                     fetch = Some(false);
                 }
+                Long("fetch-every") => {
+                    let val = parser.value()?;
+                    schedule.fetch = Some(minutes(&val)?);
+                }
+                Long("announce-every") => {
+                    let val = parser.value()?;
+                    schedule.announce = Some(minutes(&val)?);
+                }
+                Long("resync-every") => {
+                    let val = parser.value()?;
+                    schedule.resync = Some(minutes(&val)?);
+                }
+                Long("no-schedule") => {
+                    let none = LocalDuration::from_secs(0);
+
+                    schedule = Intervals {
+                        fetch: Some(none),
+                        announce: Some(none),
+                        resync: Some(none),
+                    };
+                }
                 Long("verbose") | Short('v') => verbose = true,
                 Long("help") | Short('h') => {
                     return Err(Error::Help.into());
@@ -98,6 +165,7 @@ impl Args for Options {
                 rid,
                 fetch: fetch.unwrap_or(true),
                 scope: scope.unwrap_or(Scope::All),
+                schedule,
             },
             None => Operation::List,
         };
@@ -111,9 +179,18 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
     let mut node = radicle::Node::new(profile.socket());
 
     match options.op {
-        Operation::Seed { rid, fetch, scope } => {
+        Operation::Seed {
+            rid,
+            fetch,
+            scope,
+            schedule,
+        } => {
             update(rid, scope, &mut node, &profile)?;
 
+            if !schedule.is_empty() {
+                reschedule(rid, &schedule, &profile)?;
+            }
+
             if fetch && node.is_running() {
                 sync::fetch(
                     rid,
@@ -145,6 +222,43 @@ pub fn update(
     Ok(())
 }
 
+/// Update the sync schedule of a repository.
+pub fn reschedule(rid: RepoId, intervals: &Intervals, profile: &Profile) -> anyhow::Result<()> {
+    let mut store = profile.policies_mut()?;
+    let mut schedule = store.schedule(&rid)?.unwrap_or(Schedule::new(rid));
+
+    intervals.apply(&mut schedule);
+    store.set_schedule(&schedule)?;
+
+    if schedule.is_empty() {
+        term::success!("Sync schedule removed for {}", term::format::tertiary(rid));
+        return Ok(());
+    }
+    let tasks = [
+        ("fetch", schedule.fetch),
+        ("announce", schedule.announce),
+        ("re-sync", schedule.resync),
+    ]
+    .into_iter()
+    .filter_map(|(task, interval)| interval.map(|i| format!("{task} every {i}")))
+    .collect::<Vec<_>>();
+
+    term::success!(
+        "Sync schedule updated for {}: {}",
+        term::format::tertiary(rid),
+        tasks.join(", ")
+    );
+
+    Ok(())
+}
+
+/// Parse an interval in minutes.
+fn minutes(val: &OsString) -> anyhow::Result<LocalDuration> {
+    let mins = term::args::number(val)?;
+
+    Ok(LocalDuration::from_mins(mins as u64))
+}
+
 pub fn delete(rid: RepoId, node: &mut Node, profile: &Profile) -> anyhow::Result<()> {
     if project::unseed(rid, node, profile)? {
         term::success!("Seeding policy for {} removed", term::format::tertiary(rid));
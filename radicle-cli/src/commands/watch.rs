@@ -1,14 +1,20 @@
+use std::collections::HashMap;
 use std::ffi::OsString;
 use std::{thread, time};
 
 use anyhow::{anyhow, Context as _};
 
+use radicle::cob::{issue, patch, TypedId};
 use radicle::git;
-use radicle::prelude::{NodeId, RepoId};
+use radicle::node::notifications::{Activity, ActivityKind};
+use radicle::node::{Event, Handle as _};
+use radicle::prelude::{Did, NodeId, Profile, RepoId};
+use radicle::storage::git::Repository;
 use radicle::storage::{ReadRepository, ReadStorage};
 
 use crate::terminal as term;
 use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::Element as _;
 
 pub const HELP: Help = Help {
     name: "wait",
@@ -17,14 +23,23 @@ pub const HELP: Help = Help {
     usage: r#"
 Usage
 
+    rad watch [--repo <rid>] [--issues] [--patches] [--author <did>]... [<option>...]
     rad watch -r <ref> [-t <oid>] [--repo <rid>] [<option>...]
 
-    Watches a Git reference, and optionally exits when it reaches a target value.
-    If no target value is passed, exits when the target changes.
+    Without a reference, follows the activity on the issues and patches of a
+    repository as it reaches your node, eg. new comments, revisions and reviews,
+    as well as the seeds that start or stop seeding it. Your node must be running.
+
+    With a reference, watches a Git reference, and optionally exits when it reaches
+    a target value. If no target value is passed, exits when the target changes.
 
 Options
 
         --repo      <rid>       The repository to watch (default: `rad .`)
+        --issues                Only show activity on issues
+        --patches               Only show activity on patches
+        --author    <did>       Only show activity by this author (may be specified
+                                multiple times)
         --node      <nid>       The namespace under which this reference exists
                                 (default: `rad self --nid`)
     -r, --ref       <ref>       The fully-qualified Git reference (branch, tag, etc.) to watch,
@@ -40,11 +55,12 @@ Options
 
 pub struct Options {
     rid: Option<RepoId>,
-    refstr: git::RefString,
+    refstr: Option<git::RefString>,
     target: Option<git::Oid>,
     nid: Option<NodeId>,
     interval: time::Duration,
     timeout: time::Duration,
+    filter: Filter,
 }
 
 impl Args for Options {
@@ -58,6 +74,7 @@ impl Args for Options {
         let mut refstr: Option<git::RefString> = None;
         let mut interval: Option<time::Duration> = None;
         let mut timeout: time::Duration = time::Duration::MAX;
+        let mut filter = Filter::default();
 
         while let Some(arg) = parser.next()? {
             match arg {
@@ -97,6 +114,18 @@ impl Args for Options {
 
                     timeout = value;
                 }
+                Long("issues") => {
+                    filter.issues = true;
+                }
+                Long("patches") => {
+                    filter.patches = true;
+                }
+                Long("author") => {
+                    let value = parser.value()?;
+                    let value = term::args::did(&value)?;
+
+                    filter.authors.push(value);
+                }
                 Long("help") | Short('h') => {
                     return Err(Error::Help.into());
                 }
@@ -104,14 +133,19 @@ impl Args for Options {
             }
         }
 
+        if refstr.is_none() && target.is_some() {
+            anyhow::bail!("a reference must be provided");
+        }
+
         Ok((
             Options {
                 rid,
-                refstr: refstr.ok_or_else(|| anyhow!("a reference must be provided"))?,
+                refstr,
                 nid,
                 target,
                 interval: interval.unwrap_or(time::Duration::from_secs(1)),
                 timeout,
+                filter,
             },
             vec![],
         ))
@@ -121,11 +155,6 @@ impl Args for Options {
 pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
     let profile = ctx.profile()?;
     let storage = &profile.storage;
-    let qualified = options
-        .refstr
-        .qualified()
-        .ok_or_else(|| anyhow!("reference must be fully-qualified, eg. 'refs/heads/master'"))?;
-    let nid = options.nid.unwrap_or(profile.public_key);
     let rid = match options.rid {
         Some(rid) => rid,
         None => {
@@ -135,6 +164,14 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
         }
     };
     let repo = storage.repository(rid)?;
+
+    let Some(refstr) = &options.refstr else {
+        return follow(&repo, options.filter, options.timeout, &profile);
+    };
+    let qualified = refstr
+        .qualified()
+        .ok_or_else(|| anyhow!("reference must be fully-qualified, eg. 'refs/heads/master'"))?;
+    let nid = options.nid.unwrap_or(profile.public_key);
     let now = time::SystemTime::now();
 
     if let Some(target) = options.target {
@@ -173,3 +210,277 @@ fn reference<R: ReadRepository>(
         Err(e) => Err(e),
     }
 }
+
+/// Which activity to show when following a repository.
+#[derive(Debug, Default)]
+pub struct Filter {
+    /// Show activity on issues.
+    issues: bool,
+    /// Show activity on patches.
+    patches: bool,
+    /// Only show activity by these authors, or by anyone if empty.
+    authors: Vec<Did>,
+}
+
+impl Filter {
+    /// Whether activity on the given object by the given author should be shown.
+    fn matches(&self, typed_id: &TypedId, activity: &Activity) -> bool {
+        let kind = match (self.issues, self.patches) {
+            (false, false) => true,
+            (issues, patches) => {
+                (issues && typed_id.is_issue()) || (patches && typed_id.is_patch())
+            }
+        };
+        kind && (self.authors.is_empty() || self.authors.iter().any(|a| **a == activity.author))
+    }
+
+    /// Whether seeding events should be shown.
+    fn seeds(&self) -> bool {
+        !self.issues && !self.patches && self.authors.is_empty()
+    }
+}
+
+/// Keeps track of the COB heads of every namespace of a repository, to summarize what
+/// changed from one update to the next.
+struct Feed {
+    heads: HashMap<TypedId, HashMap<NodeId, git::Oid>>,
+}
+
+impl Feed {
+    /// Start following the given repository from its current state.
+    fn new(repo: &Repository) -> anyhow::Result<Self> {
+        Ok(Self {
+            heads: heads(repo)?,
+        })
+    }
+
+    /// Get the activity that happened since the last update, in causal order per object.
+    /// Operations that reached more than one namespace are only returned once.
+    fn update(&mut self, repo: &Repository) -> anyhow::Result<Vec<(TypedId, Activity)>> {
+        let heads = heads(repo)?;
+        let mut activity = Vec::new();
+
+        for (typed_id, tips) in &heads {
+            let known = self
+                .heads
+                .get(typed_id)
+                .map(|h| h.values().copied().collect::<Vec<_>>())
+                .unwrap_or_default();
+            let mut seen = Vec::new();
+
+            for tip in tips.values() {
+                if known.contains(tip) {
+                    continue;
+                }
+                for a in Activity::between(repo, typed_id, *tip, &known)? {
+                    if !seen.contains(&a) {
+                        seen.push(a.clone());
+                        activity.push((typed_id.clone(), a));
+                    }
+                }
+            }
+        }
+        self.heads = heads;
+        activity.sort_by_key(|(_, a)| a.timestamp);
+
+        Ok(activity)
+    }
+}
+
+/// Get the COB heads of every namespace of a repository.
+fn heads(repo: &Repository) -> anyhow::Result<HashMap<TypedId, HashMap<NodeId, git::Oid>>> {
+    let mut heads: HashMap<_, HashMap<_, _>> = HashMap::new();
+
+    for remote in repo.remote_ids()? {
+        let remote = remote?;
+        for (refname, oid) in repo.references_of(&remote)?.iter() {
+            let Some(refname) = refname.qualified() else {
+                continue;
+            };
+            let Ok(Some(typed_id)) = TypedId::from_qualified(&refname) else {
+                continue;
+            };
+            heads.entry(typed_id).or_default().insert(remote, *oid);
+        }
+    }
+    Ok(heads)
+}
+
+/// Stream the activity on a repository as it reaches the node.
+fn follow(
+    repo: &Repository,
+    filter: Filter,
+    timeout: time::Duration,
+    profile: &Profile,
+) -> anyhow::Result<()> {
+    let node = radicle::Node::new(profile.socket());
+    if !node.is_running() {
+        anyhow::bail!(
+            "to watch a repository, your node must be running. To start it, run `rad node start`"
+        );
+    }
+    let events = node.subscribe(timeout)?;
+    let mut feed = Feed::new(repo)?;
+    let started = time::Instant::now();
+
+    for event in events {
+        let event = match event {
+            Ok(event) => event,
+            Err(radicle::node::Error::TimedOut) => break,
+            Err(e) => return Err(e.into()),
+        };
+        match event {
+            Event::RefsFetched { rid, .. } | Event::LocalRefsAnnounced { rid, .. }
+                if rid == repo.id =>
+            {
+                for (typed_id, activity) in feed.update(repo)? {
+                    if filter.matches(&typed_id, &activity) {
+                        activity_line(repo, &typed_id, &activity, profile)?.print();
+                    }
+                }
+            }
+            Event::SeedDiscovered { rid, nid } if rid == repo.id && filter.seeds() => {
+                term::format::Author::new(&nid, profile)
+                    .line()
+                    .space()
+                    .item(term::format::dim("started seeding"))
+                    .print();
+            }
+            Event::SeedDropped { rid, nid } if rid == repo.id && filter.seeds() => {
+                term::format::Author::new(&nid, profile)
+                    .line()
+                    .space()
+                    .item(term::format::dim("stopped seeding"))
+                    .print();
+            }
+            _ => {}
+        }
+        if started.elapsed() >= timeout {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Render an operation on an issue or patch, eg.
+/// "bob z6Mk…Bq3u commented on issue 9ed9e10 No license file now".
+fn activity_line(
+    repo: &Repository,
+    typed_id: &TypedId,
+    activity: &Activity,
+    profile: &Profile,
+) -> anyhow::Result<term::Line> {
+    let (kind, title) = if typed_id.is_issue() {
+        let title = issue::Issues::open(repo)?
+            .get(&typed_id.id)?
+            .map(|i| i.title().to_owned());
+        ("issue", title)
+    } else {
+        let title = patch::Patches::open(repo)?
+            .get(&typed_id.id)?
+            .map(|p| p.title().to_owned());
+        ("patch", title)
+    };
+    let (action, suffix) = match &activity.kind {
+        ActivityKind::Opened => ("opened", None),
+        ActivityKind::Edited => ("edited", None),
+        ActivityKind::Commented => ("commented on", None),
+        ActivityKind::Revised => ("pushed a new revision of", None),
+        ActivityKind::Reviewed {
+            verdict: Some(patch::Verdict::Accept),
+        } => ("accepted", None),
+        ActivityKind::Reviewed {
+            verdict: Some(patch::Verdict::Reject),
+        } => ("rejected", None),
+        ActivityKind::Reviewed { verdict: None } => ("reviewed", None),
+        ActivityKind::Merged { .. } => ("merged", None),
+        ActivityKind::StateChanged { state } => ("marked", Some(format!("as {state}"))),
+    };
+    let mut line = term::format::Author::new(&activity.author, profile)
+        .line()
+        .space()
+        .item(action)
+        .space()
+        .item(kind)
+        .space()
+        .item(term::format::tertiary(term::format::cob(&typed_id.id)))
+        .space()
+        .item(title.unwrap_or_default());
+
+    if let Some(suffix) = suffix {
+        line = line.space().item(suffix);
+    }
+    Ok(line
+        .space()
+        .item(term::format::timestamp(activity.timestamp).dim()))
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use radicle::cob::thread;
+    use radicle::crypto::Signer as _;
+    use radicle::test;
+
+    use super::*;
+
+    #[test]
+    fn test_feed() {
+        let test::setup::Network { alice, bob, .. } = test::setup::Network::default();
+        let mut feed = Feed::new(&bob.repo).unwrap();
+        let kinds = |activity: Vec<(TypedId, Activity)>| {
+            activity
+                .into_iter()
+                .map(|(_, a)| (a.author, a.kind))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(feed.update(&bob.repo).unwrap(), vec![]);
+
+        let mut issues = issue::Cache::no_cache(&*alice.repo).unwrap();
+        let issue = issues
+            .create("No license", "We need one.", &[], &[], [], &alice.signer)
+            .unwrap();
+        let id = *issue.id();
+        let (root, _) = issue.root();
+        let root: thread::CommentId = *root;
+        bob.repo.fetch(&alice);
+
+        assert_eq!(
+            kinds(feed.update(&bob.repo).unwrap()),
+            vec![(*alice.signer.public_key(), ActivityKind::Opened)]
+        );
+
+        let mut issues = issue::Cache::no_cache(&*bob.repo).unwrap();
+        let mut issue = issues.get_mut(&id).unwrap();
+        issue.comment("MIT?", root, [], [], &bob.signer).unwrap();
+
+        let activity = feed.update(&bob.repo).unwrap();
+        assert_eq!(
+            kinds(activity.clone()),
+            vec![(*bob.signer.public_key(), ActivityKind::Commented)]
+        );
+        assert_eq!(feed.update(&bob.repo).unwrap(), vec![]);
+
+        let (typed_id, activity) = &activity[0];
+        let filter = Filter {
+            patches: true,
+            ..Filter::default()
+        };
+        assert!(!filter.matches(typed_id, activity));
+
+        let filter = Filter {
+            authors: vec![Did::from(*alice.signer.public_key())],
+            ..Filter::default()
+        };
+        assert!(!filter.matches(typed_id, activity));
+        assert!(!filter.seeds());
+
+        let filter = Filter {
+            issues: true,
+            authors: vec![Did::from(*bob.signer.public_key())],
+            ..Filter::default()
+        };
+        assert!(filter.matches(typed_id, activity));
+    }
+}
@@ -0,0 +1,489 @@
+use std::ffi::OsString;
+
+use anyhow::{anyhow, Context as _};
+
+use radicle::cob::wiki::{PageId, PagePath, Wiki};
+use radicle::prelude::RepoId;
+use radicle::storage::git::Repository;
+use radicle::storage::WriteStorage;
+use radicle::{Node, Profile};
+
+use crate::git::Rev;
+use crate::node;
+use crate::terminal as term;
+use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::format::Author;
+use crate::terminal::Element as _;
+
+pub const HELP: Help = Help {
+    name: "wiki",
+    description: "Manage wiki pages",
+    version: env!("RADICLE_VERSION"),
+    usage: r#"
+Usage
+
+    rad wiki [<option>...]
+    rad wiki list [<option>...]
+    rad wiki create --path <path> [--title <title>] [--message <text>] [<option>...]
+    rad wiki show <page> [--revision <revision>] [--raw] [<option>...]
+    rad wiki edit <page> [--title <title>] [--path <path>] [--message <text>] [<option>...]
+    rad wiki history <page> [<option>...]
+    rad wiki diff <page> <revision> [<revision>] [<option>...]
+    rad wiki redact <page> <revision> [<option>...]
+    rad wiki delete <page> [<option>...]
+
+    The wiki holds the documentation of a repository. Unlike the repository's
+    files, wiki pages can be edited by anyone, without patches. Every edit of
+    a page body is kept as a revision, which can be compared to other revisions,
+    or redacted by its author or by the repository delegates.
+
+    Pages can be referred to by path, eg. `guides/install`, or by id.
+
+Show options
+
+        --revision <revision>  Show the given revision of the page (default: latest)
+        --raw                  Only print the page body, as markdown
+
+Edit options
+
+        --title <title>        Set the page title
+        --path <path>          Move the page to a new path
+    -m, --message <text>       Set the page body (default: open the editor)
+
+Diff options
+
+    When only one revision is given, it is compared to the latest revision.
+
+Options
+
+        --repo <rid>           Operate on the given repository (default: cwd)
+        --no-announce          Don't announce the changes to peers
+    -q, --quiet                Don't print anything
+        --help                 Print help
+"#,
+};
+
+#[derive(Default, Debug, PartialEq, Eq)]
+pub enum OperationName {
+    Create,
+    Delete,
+    Diff,
+    Edit,
+    History,
+    #[default]
+    List,
+    Redact,
+    Show,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Operation {
+    Create {
+        path: PagePath,
+        title: Option<String>,
+        message: Option<String>,
+    },
+    Delete {
+        page: Rev,
+    },
+    Diff {
+        page: Rev,
+        from: Rev,
+        to: Option<Rev>,
+    },
+    Edit {
+        page: Rev,
+        title: Option<String>,
+        path: Option<PagePath>,
+        message: Option<String>,
+    },
+    History {
+        page: Rev,
+    },
+    List,
+    Redact {
+        page: Rev,
+        revision: Rev,
+    },
+    Show {
+        page: Rev,
+        revision: Option<Rev>,
+        raw: bool,
+    },
+}
+
+#[derive(Debug)]
+pub struct Options {
+    pub op: Operation,
+    pub repo: Option<RepoId>,
+    pub announce: bool,
+    pub quiet: bool,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut op: Option<OperationName> = None;
+        let mut page: Option<Rev> = None;
+        let mut revisions: Vec<Rev> = Vec::new();
+        let mut title: Option<String> = None;
+        let mut path: Option<PagePath> = None;
+        let mut message: Option<String> = None;
+        let mut raw = false;
+        let mut announce = true;
+        let mut quiet = false;
+        let mut repo = None;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("help") | Short('h') => {
+                    return Err(Error::Help.into());
+                }
+
+                // Create and edit options.
+                Long("title")
+                    if matches!(op, Some(OperationName::Create | OperationName::Edit)) =>
+                {
+                    title = Some(term::args::string(&parser.value()?));
+                }
+                Long("path") if matches!(op, Some(OperationName::Create | OperationName::Edit)) => {
+                    let val = parser.value()?;
+                    let val = term::args::string(&val);
+
+                    path = Some(PagePath::new(val)?);
+                }
+                Long("message") | Short('m')
+                    if matches!(op, Some(OperationName::Create | OperationName::Edit)) =>
+                {
+                    message = Some(term::args::string(&parser.value()?));
+                }
+
+                // Show options.
+                Long("revision") if op == Some(OperationName::Show) => {
+                    revisions.push(term::args::rev(&parser.value()?)?);
+                }
+                Long("raw") if op == Some(OperationName::Show) => {
+                    raw = true;
+                }
+
+                // Options.
+                Long("no-announce") => {
+                    announce = false;
+                }
+                Long("quiet") | Short('q') => {
+                    quiet = true;
+                }
+                Long("repo") => {
+                    let val = parser.value()?;
+                    let rid = term::args::rid(&val)?;
+
+                    repo = Some(rid);
+                }
+
+                Value(val) if op.is_none() => match val.to_string_lossy().as_ref() {
+                    "c" | "create" => op = Some(OperationName::Create),
+                    "d" | "delete" => op = Some(OperationName::Delete),
+                    "diff" => op = Some(OperationName::Diff),
+                    "e" | "edit" => op = Some(OperationName::Edit),
+                    "h" | "history" => op = Some(OperationName::History),
+                    "l" | "list" => op = Some(OperationName::List),
+                    "r" | "redact" => op = Some(OperationName::Redact),
+                    "w" | "show" => op = Some(OperationName::Show),
+
+                    unknown => anyhow::bail!("unknown operation '{}'", unknown),
+                },
+                Value(val)
+                    if matches!(op, Some(OperationName::Diff | OperationName::Redact))
+                        && page.is_some() =>
+                {
+                    revisions.push(term::args::rev(&val)?);
+                }
+                Value(val)
+                    if op.is_some() && op != Some(OperationName::Create) && page.is_none() =>
+                {
+                    page = Some(term::args::rev(&val)?);
+                }
+                _ => {
+                    return Err(anyhow!(arg.unexpected()));
+                }
+            }
+        }
+
+        let op = match op.unwrap_or_default() {
+            OperationName::Create => Operation::Create {
+                path: path.ok_or_else(|| anyhow!("a page path must be provided with `--path`"))?,
+                title,
+                message,
+            },
+            OperationName::Delete => Operation::Delete {
+                page: page.ok_or_else(|| anyhow!("a page to remove must be provided"))?,
+            },
+            OperationName::Diff => {
+                let page = page.ok_or_else(|| anyhow!("a page must be provided"))?;
+                let mut revisions = revisions.into_iter();
+                let from = revisions
+                    .next()
+                    .ok_or_else(|| anyhow!("a revision to compare must be provided"))?;
+                let to = revisions.next();
+
+                if revisions.next().is_some() {
+                    anyhow::bail!("at most two revisions can be compared");
+                }
+                Operation::Diff { page, from, to }
+            }
+            OperationName::Edit => Operation::Edit {
+                page: page.ok_or_else(|| anyhow!("a page must be provided"))?,
+                title,
+                path,
+                message,
+            },
+            OperationName::History => Operation::History {
+                page: page.ok_or_else(|| anyhow!("a page must be provided"))?,
+            },
+            OperationName::List => Operation::List,
+            OperationName::Redact => {
+                let page = page.ok_or_else(|| anyhow!("a page must be provided"))?;
+                let mut revisions = revisions.into_iter();
+                let revision = revisions
+                    .next()
+                    .ok_or_else(|| anyhow!("a revision to redact must be provided"))?;
+
+                if revisions.next().is_some() {
+                    anyhow::bail!("only one revision can be redacted at a time");
+                }
+                Operation::Redact { page, revision }
+            }
+            OperationName::Show => Operation::Show {
+                page: page.ok_or_else(|| anyhow!("a page must be provided"))?,
+                revision: revisions.pop(),
+                raw,
+            },
+        };
+
+        Ok((
+            Options {
+                op,
+                repo,
+                announce,
+                quiet,
+            },
+            vec![],
+        ))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let profile = ctx.profile()?;
+    let rid = if let Some(rid) = options.repo {
+        rid
+    } else {
+        radicle::rad::cwd().map(|(_, rid)| rid)?
+    };
+    let repo = profile.storage.repository_mut(rid)?;
+    let announce = options.announce
+        && !matches!(
+            &options.op,
+            Operation::List
+                | Operation::Show { .. }
+                | Operation::History { .. }
+                | Operation::Diff { .. }
+        );
+    let mut wiki = Wiki::open(&repo)?;
+
+    match options.op {
+        Operation::List => {
+            list(&wiki, &profile)?;
+        }
+        Operation::Show {
+            page,
+            revision,
+            raw,
+        } => {
+            let id = resolve(&page, &wiki, &repo)?;
+            let page = wiki.get(&id)?.context("No page with the given ID exists")?;
+            let revision = match revision {
+                Some(rev) => rev.resolve(&repo.backend)?,
+                None => *page.revision().0,
+            };
+            let Some(r) = page.revision_by_id(&revision) else {
+                anyhow::bail!("revision {revision} was not found or was redacted");
+            };
+
+            if raw {
+                term::print(r.body());
+            } else {
+                term::wiki::show(&page, &id, &revision, &profile)?;
+            }
+        }
+        Operation::Create {
+            path,
+            title,
+            message,
+        } => {
+            let signer = term::signer(&profile)?;
+            let (title, body) = if let (Some(t), Some(b)) = (title.as_ref(), message.as_ref()) {
+                (t.to_owned(), b.to_owned())
+            } else if let Some((t, b)) = term::wiki::get_title_body(title, message)? {
+                (t, b)
+            } else {
+                anyhow::bail!("aborting page creation due to empty title");
+            };
+            let page = wiki.create(path, title, body, [], &signer)?;
+
+            if options.quiet {
+                term::print(page.id());
+            } else {
+                term::wiki::show(&page, page.id(), page.revision().0, &profile)?;
+            }
+        }
+        Operation::Edit {
+            page,
+            title,
+            path,
+            message,
+        } => {
+            let signer = term::signer(&profile)?;
+            let id = resolve(&page, &wiki, &repo)?;
+            let mut page = wiki.get_mut(&id)?;
+            let body = match message {
+                Some(body) => Some(body),
+                // Only open the editor when nothing else is being edited.
+                None if title.is_none() && path.is_none() => term::wiki::get_body(page.body())?,
+                None => None,
+            };
+
+            if title.is_none() && path.is_none() && body.is_none() {
+                anyhow::bail!("nothing to edit, the page was left unchanged");
+            }
+            if let Some(path) = path {
+                page.move_to(path, &signer)?;
+            }
+            if title.is_some() || body.is_some() {
+                page.transaction("Edit", &signer, |tx| {
+                    if let Some(title) = title {
+                        tx.edit(title)?;
+                    }
+                    if let Some(body) = body {
+                        tx.revise(body, [])?;
+                    }
+                    Ok(())
+                })?;
+            }
+
+            if !options.quiet {
+                term::wiki::show(&page, &id, page.revision().0, &profile)?;
+            }
+        }
+        Operation::History { page } => {
+            let id = resolve(&page, &wiki, &repo)?;
+            let page = wiki.get(&id)?.context("No page with the given ID exists")?;
+
+            term::wiki::history(&page, &profile);
+        }
+        Operation::Diff { page, from, to } => {
+            let id = resolve(&page, &wiki, &repo)?;
+            let page = wiki.get(&id)?.context("No page with the given ID exists")?;
+            let from = from.resolve(&repo.backend)?;
+            let to = match to {
+                Some(to) => to.resolve(&repo.backend)?,
+                None => *page.revision().0,
+            };
+
+            term::wiki::diff(&page.diff(&from, &to)?);
+        }
+        Operation::Redact { page, revision } => {
+            let signer = term::signer(&profile)?;
+            let id = resolve(&page, &wiki, &repo)?;
+            let revision = revision.resolve(&repo.backend)?;
+            let mut page = wiki.get_mut(&id)?;
+
+            page.redact(revision, &signer)?;
+        }
+        Operation::Delete { page } => {
+            let signer = term::signer(&profile)?;
+            let id = resolve(&page, &wiki, &repo)?;
+
+            wiki.remove(&id, &signer)?;
+        }
+    }
+
+    if announce {
+        let mut node = Node::new(profile.socket());
+        node::announce(
+            &repo,
+            node::SyncSettings::default(),
+            node::SyncReporting::default(),
+            &mut node,
+            &profile,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Resolve a page given by path or by id.
+fn resolve(page: &Rev, wiki: &Wiki<'_, Repository>, repo: &Repository) -> anyhow::Result<PageId> {
+    if let Ok(path) = PagePath::new(page.as_str()) {
+        if let Some((id, _)) = wiki.find(&path)? {
+            return Ok(id);
+        }
+    }
+    let id = page
+        .resolve(&repo.backend)
+        .with_context(|| format!("no page found with path or id '{page}'"))?;
+
+    Ok(id)
+}
+
+fn list(wiki: &Wiki<'_, Repository>, profile: &Profile) -> anyhow::Result<()> {
+    let mut all = Vec::new();
+    for result in wiki.all()? {
+        let Ok((id, page)) = result else {
+            // Skip pages that failed to load.
+            continue;
+        };
+        all.push((id, page));
+    }
+
+    if all.is_empty() {
+        term::print(term::format::italic("Nothing to show."));
+        return Ok(());
+    }
+    all.sort_by(|(id1, p1), (id2, p2)| p1.path().cmp(p2.path()).then(id1.cmp(id2)));
+
+    let mut table = term::Table::new(term::table::TableOptions::bordered());
+    table.push([
+        term::format::bold(String::from("ID")).into(),
+        term::format::bold(String::from("Path")).into(),
+        term::format::bold(String::from("Title")).into(),
+        term::format::bold(String::from("Author")).into(),
+        term::Line::blank(),
+        term::format::bold(String::from("Revisions")).into(),
+        term::format::bold(String::from("Updated")).into(),
+    ]);
+    table.divider();
+
+    for (id, page) in all {
+        let (alias, did) = Author::new(page.author().id(), profile).labels();
+
+        table.push([
+            term::format::tertiary(term::format::cob(&id))
+                .to_owned()
+                .into(),
+            term::format::secondary(page.path().to_string()).into(),
+            term::format::default(page.title().to_owned()).into(),
+            alias.into(),
+            did.into(),
+            term::format::dim(page.history().count().to_string()).into(),
+            term::format::timestamp(page.updated_at())
+                .dim()
+                .italic()
+                .into(),
+        ]);
+    }
+    table.print();
+
+    Ok(())
+}
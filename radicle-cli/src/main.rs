@@ -140,6 +140,13 @@ fn run_other(exe: &str, args: &[OsString]) -> Result<(), Option<anyhow::Error>>
                 args.to_vec(),
             );
         }
+        "bundle" => {
+            term::run_command_args::<rad_bundle::Options, _>(
+                rad_bundle::HELP,
+                rad_bundle::run,
+                args.to_vec(),
+            );
+        }
         "checkout" => {
             term::run_command_args::<rad_checkout::Options, _>(
                 rad_checkout::HELP,
@@ -168,6 +175,13 @@ fn run_other(exe: &str, args: &[OsString]) -> Result<(), Option<anyhow::Error>>
                 args.to_vec(),
             );
         }
+        "dashboard" => {
+            term::run_command_args::<rad_dashboard::Options, _>(
+                rad_dashboard::HELP,
+                rad_dashboard::run,
+                args.to_vec(),
+            );
+        }
         "diff" => {
             term::run_command_args::<rad_diff::Options, _>(
                 rad_diff::HELP,
@@ -175,6 +189,13 @@ fn run_other(exe: &str, args: &[OsString]) -> Result<(), Option<anyhow::Error>>
                 args.to_vec(),
             );
         }
+        "discuss" => {
+            term::run_command_args::<rad_discuss::Options, _>(
+                rad_discuss::HELP,
+                rad_discuss::run,
+                args.to_vec(),
+            );
+        }
         "debug" => {
             term::run_command_args::<rad_debug::Options, _>(
                 rad_debug::HELP,
@@ -182,6 +203,13 @@ fn run_other(exe: &str, args: &[OsString]) -> Result<(), Option<anyhow::Error>>
                 args.to_vec(),
             );
         }
+        "export" => {
+            term::run_command_args::<rad_export::Options, _>(
+                rad_export::HELP,
+                rad_export::run,
+                args.to_vec(),
+            );
+        }
         "follow" => {
             term::run_command_args::<rad_follow::Options, _>(
                 rad_follow::HELP,
@@ -206,6 +234,13 @@ fn run_other(exe: &str, args: &[OsString]) -> Result<(), Option<anyhow::Error>>
         "id" => {
             term::run_command_args::<rad_id::Options, _>(rad_id::HELP, rad_id::run, args.to_vec());
         }
+        "import" => {
+            term::run_command_args::<rad_import::Options, _>(
+                rad_import::HELP,
+                rad_import::run,
+                args.to_vec(),
+            );
+        }
         "inbox" => term::run_command_args::<rad_inbox::Options, _>(
             rad_inbox::HELP,
             rad_inbox::run,
@@ -320,6 +355,11 @@ fn run_other(exe: &str, args: &[OsString]) -> Result<(), Option<anyhow::Error>>
             rad_watch::run,
             args.to_vec(),
         ),
+        "wiki" => term::run_command_args::<rad_wiki::Options, _>(
+            rad_wiki::HELP,
+            rad_wiki::run,
+            args.to_vec(),
+        ),
         other => {
             let exe = format!("{NAME}-{exe}");
             let status = process::Command::new(exe).args(args).status();
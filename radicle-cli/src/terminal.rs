@@ -4,10 +4,12 @@ pub mod format;
 pub mod io;
 pub use io::signer;
 pub mod comment;
+pub mod discussion;
 pub mod highlight;
 pub mod issue;
 pub mod json;
 pub mod patch;
+pub mod wiki;
 
 use std::ffi::OsString;
 use std::process;
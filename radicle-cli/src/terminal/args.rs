@@ -202,6 +202,11 @@ pub fn patch(val: &OsString) -> anyhow::Result<patch::PatchId> {
     patch::PatchId::from_str(&val).map_err(|_| anyhow!("invalid Patch ID '{}'", val))
 }
 
+pub fn query(val: &OsString) -> anyhow::Result<cob::query::Query> {
+    let val = val.to_string_lossy();
+    cob::query::Query::from_str(&val).map_err(|e| anyhow!("invalid query '{}': {e}", val))
+}
+
 pub fn cob(val: &OsString) -> anyhow::Result<cob::ObjectId> {
     let val = val.to_string_lossy();
     cob::ObjectId::from_str(&val).map_err(|_| anyhow!("invalid Object ID '{}'", val))
@@ -1,4 +1,6 @@
 use radicle::cob::thread::{Comment, CommentId};
+use radicle::cob::Origin;
+use radicle::node::AliasStore as _;
 use radicle::Profile;
 
 use crate::terminal as term;
@@ -26,8 +28,43 @@ pub fn header<T>(
             .into()]))
 }
 
+/// Return the users mentioned in a comment as a [`term::Line`], if there are any.
+pub fn mentions<T>(comment: &Comment<T>, profile: &Profile) -> Option<term::Line> {
+    if comment.mentions().is_empty() {
+        return None;
+    }
+    let mut labels = vec![term::format::dim("Mentions").into()];
+
+    for did in comment.mentions() {
+        let author = Author::new(did.as_key(), profile);
+        let name = match profile.alias(did.as_key()) {
+            Some(alias) => alias.to_string(),
+            None => term::format::node(did.as_key()).to_string(),
+        };
+        labels.push(term::format::tertiary(format!("@{name}")).into());
+        labels.extend(author.you());
+    }
+    Some(term::Line::spaced(labels))
+}
+
+/// Return where imported content came from as a [`term::Line`].
+pub fn origin(origin: &Origin) -> term::Line {
+    term::Line::spaced([
+        term::format::dim("Imported from").into(),
+        term::format::default(origin.source.clone()).into(),
+        term::format::dim("by").into(),
+        term::format::default(origin.author.clone()).into(),
+        term::format::timestamp(origin.timestamp).dim().into(),
+    ])
+}
+
 /// Return a full comment widget as a [`term::Element`].
 pub fn widget<'a, T>(id: &CommentId, comment: &Comment<T>, profile: &Profile) -> term::VStack<'a> {
-    term::vstack::bordered(header(id, comment, profile))
-        .child(term::textarea(comment.body()).wrap(60))
+    let widget = term::vstack::bordered(header(id, comment, profile))
+        .child(term::textarea(comment.body()).wrap(60));
+
+    match mentions(comment, profile) {
+        Some(line) => widget.child(term::Label::blank()).child(line),
+        None => widget,
+    }
 }
@@ -0,0 +1,121 @@
+use std::io;
+
+use radicle_term::table::TableOptions;
+use radicle_term::{Table, VStack};
+
+use radicle::cob;
+use radicle::cob::discussion;
+use radicle::Profile;
+
+use crate::terminal as term;
+use crate::terminal::format::Author;
+use crate::terminal::Element;
+
+pub const OPEN_MSG: &str = r#"
+<!--
+Please enter a discussion title and body.
+
+The first line is the discussion title. The body follows,
+and must be separated by a blank line, just like a commit
+message. Markdown is supported in the title and body.
+-->
+"#;
+
+pub fn get_title_body(
+    title: Option<String>,
+    body: Option<String>,
+) -> io::Result<Option<(String, String)>> {
+    term::patch::Message::edit_title_description(title, body, OPEN_MSG)
+}
+
+pub fn show(
+    discussion: &discussion::Discussion,
+    id: &cob::ObjectId,
+    header: bool,
+    profile: &Profile,
+) -> anyhow::Result<()> {
+    let author = discussion.author();
+    let author = Author::new(author.id(), profile);
+
+    let mut attrs = Table::<2, term::Line>::new(TableOptions {
+        spacing: 2,
+        ..TableOptions::default()
+    });
+
+    attrs.push([
+        term::format::tertiary("Title".to_owned()).into(),
+        term::format::bold(discussion.title().to_owned()).into(),
+    ]);
+
+    attrs.push([
+        term::format::tertiary("Discussion".to_owned()).into(),
+        term::format::bold(id.to_string()).into(),
+    ]);
+
+    attrs.push([
+        term::format::tertiary("Author".to_owned()).into(),
+        author.line(),
+    ]);
+
+    if let Some(category) = discussion.category() {
+        attrs.push([
+            term::format::tertiary("Category".to_owned()).into(),
+            term::format::secondary(category.to_string()).into(),
+        ]);
+    }
+
+    if discussion.is_pinned() {
+        attrs.push([
+            term::format::tertiary("Pinned".to_owned()).into(),
+            term::format::positive("yes".to_owned()).into(),
+        ]);
+    }
+
+    if let Some((answer, _)) = discussion.answer() {
+        attrs.push([
+            term::format::tertiary("Answer".to_owned()).into(),
+            term::format::oid(*answer).into(),
+        ]);
+    }
+
+    let body = discussion.body();
+    let mut widget = VStack::default()
+        .border(Some(term::colors::FAINT))
+        .child(attrs)
+        .children(if !body.is_empty() {
+            vec![
+                term::Label::blank().boxed(),
+                term::textarea(body.trim()).wrap(60).boxed(),
+            ]
+        } else {
+            vec![]
+        });
+
+    if !header {
+        let answer = discussion.answer().map(|(id, _)| *id);
+
+        for (id, comment) in discussion.replies() {
+            let mut hstack = term::comment::header(id, comment, profile);
+            if Some(*id) == answer {
+                hstack.push(term::Line::new(term::Label::space()));
+                hstack.push(term::Line::new(term::format::positive("✓ answer")));
+            }
+            widget = widget.divider();
+            widget.push(hstack);
+            widget.push(term::textarea(comment.body()).wrap(60));
+
+            let reactions = comment
+                .reactions()
+                .into_iter()
+                .map(|(reaction, authors)| format!("{} {}", reaction.emoji(), authors.len()))
+                .collect::<Vec<_>>();
+            if !reactions.is_empty() {
+                widget.push(term::Label::blank());
+                widget.push(term::Line::new(term::format::dim(reactions.join("  "))));
+            }
+        }
+    }
+    widget.print();
+
+    Ok(())
+}
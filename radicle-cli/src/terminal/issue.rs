@@ -103,7 +103,19 @@ pub fn show(
         },
     ]);
 
+    let (done, total) = issue.progress();
+    if total > 0 {
+        attrs.push([
+            term::format::tertiary("Tasks".to_owned()).into(),
+            term::Line::spaced([
+                term::format::default(format!("{done}/{total}")).into(),
+                term::format::dim("done").into(),
+            ]),
+        ]);
+    }
+
     let description = issue.description();
+    let (_, root) = issue.root();
     let mut widget = VStack::default()
         .border(Some(term::colors::FAINT))
         .child(attrs)
@@ -114,7 +126,18 @@ pub fn show(
             ]
         } else {
             vec![]
-        });
+        })
+        .children(root.origin().map(|origin| {
+            VStack::default()
+                .child(term::Label::blank())
+                .child(term::comment::origin(origin))
+                .boxed()
+        }));
+
+    if total > 0 {
+        widget = widget.divider();
+        widget.push(tasks(issue));
+    }
 
     if format == Format::Full {
         for (id, comment) in issue.replies() {
@@ -123,9 +146,48 @@ pub fn show(
             widget = widget.divider();
             widget.push(hstack);
             widget.push(term::textarea(comment.body()).wrap(60));
+            if let Some(line) = term::comment::mentions(comment, profile) {
+                widget.push(term::Label::blank());
+                widget.push(line);
+            }
+            if let Some(origin) = comment.origin() {
+                widget.push(term::Label::blank());
+                widget.push(term::comment::origin(origin));
+            }
         }
     }
     widget.print();
 
     Ok(())
 }
+
+/// Show the task list of an issue.
+fn tasks(issue: &issue::Issue) -> Table<4, term::Line> {
+    let mut table = Table::<4, term::Line>::new(TableOptions {
+        spacing: 1,
+        ..TableOptions::default()
+    });
+
+    for (id, task) in issue.tasks() {
+        let link = match task.link() {
+            Some(issue::TaskLink::Issue { id }) => {
+                term::format::dim(format!("issue {}", term::format::oid(*id))).into()
+            }
+            Some(issue::TaskLink::Patch { id }) => {
+                term::format::dim(format!("patch {}", term::format::oid(*id))).into()
+            }
+            None => term::Line::blank(),
+        };
+        table.push([
+            if task.is_done() {
+                term::format::positive("[x]").into()
+            } else {
+                term::format::dim("[ ]").into()
+            },
+            term::format::tertiary(term::format::oid(*id)).into(),
+            term::format::default(task.body().to_owned()).into(),
+            link,
+        ]);
+    }
+    table
+}
@@ -418,6 +418,7 @@ pub fn show(
 
     let commits = patch_commit_lines(patch, stored)?;
     let description = patch.description().trim();
+    let (_, root) = patch.root();
     let mut widget = term::VStack::default()
         .border(Some(term::colors::FAINT))
         .child(attrs)
@@ -429,6 +430,12 @@ pub fn show(
         } else {
             vec![]
         })
+        .children(root.origin().map(|origin| {
+            term::VStack::default()
+                .child(term::Label::blank())
+                .child(term::comment::origin(origin))
+                .boxed()
+        }))
         .divider()
         .children(commits.into_iter().map(|l| l.boxed()))
         .divider();
@@ -444,6 +451,10 @@ pub fn show(
             widget = widget.divider();
             widget.push(hstack);
             widget.push(term::textarea(comment.body()).wrap(60));
+            if let Some(line) = term::comment::mentions(comment, profile) {
+                widget.push(term::Label::blank());
+                widget.push(line);
+            }
         }
     }
     widget.print();
@@ -0,0 +1,161 @@
+use std::io;
+use std::io::IsTerminal as _;
+
+use radicle_term::table::TableOptions;
+use radicle_term::{Table, VStack};
+
+use radicle::cob::wiki;
+use radicle::Profile;
+
+use crate::terminal as term;
+use crate::terminal::format::Author;
+use crate::terminal::Element;
+
+pub const CREATE_MSG: &str = r#"
+<!--
+Please enter a page title and body.
+
+The first line is the page title. The body follows,
+and must be separated by a blank line, just like a commit
+message. Markdown is supported in the body.
+-->
+"#;
+
+pub fn get_title_body(
+    title: Option<String>,
+    body: Option<String>,
+) -> io::Result<Option<(String, String)>> {
+    term::patch::Message::edit_title_description(title, body, CREATE_MSG)
+}
+
+/// Open the editor on the current body of a page.
+/// Returns `None` if the body wasn't changed.
+pub fn get_body(current: &str) -> io::Result<Option<String>> {
+    if !io::stderr().is_terminal() {
+        return Ok(None);
+    }
+    let body = term::Editor::new().extension("markdown").edit(current)?;
+
+    Ok(body.filter(|b| b.trim() != current.trim()))
+}
+
+pub fn show(
+    page: &wiki::Page,
+    id: &wiki::PageId,
+    revision: &wiki::RevisionId,
+    profile: &Profile,
+) -> anyhow::Result<()> {
+    let author = Author::new(page.author().id(), profile);
+
+    let mut attrs = Table::<2, term::Line>::new(TableOptions {
+        spacing: 2,
+        ..TableOptions::default()
+    });
+
+    attrs.push([
+        term::format::tertiary("Title".to_owned()).into(),
+        term::format::bold(page.title().to_owned()).into(),
+    ]);
+
+    attrs.push([
+        term::format::tertiary("Page".to_owned()).into(),
+        term::format::bold(id.to_string()).into(),
+    ]);
+
+    attrs.push([
+        term::format::tertiary("Path".to_owned()).into(),
+        term::format::secondary(page.path().to_string()).into(),
+    ]);
+
+    attrs.push([
+        term::format::tertiary("Author".to_owned()).into(),
+        author.line(),
+    ]);
+
+    attrs.push([
+        term::format::tertiary("Revision".to_owned()).into(),
+        term::format::oid(*revision).into(),
+    ]);
+
+    let body = page
+        .revision_by_id(revision)
+        .map(|r| r.body())
+        .unwrap_or_default();
+    let widget = VStack::default()
+        .border(Some(term::colors::FAINT))
+        .child(attrs)
+        .children(if !body.is_empty() {
+            vec![
+                term::Label::blank().boxed(),
+                term::textarea(body.trim()).wrap(80).boxed(),
+            ]
+        } else {
+            vec![]
+        });
+    widget.print();
+
+    Ok(())
+}
+
+/// Print the history of a page, most recent revision first.
+pub fn history(page: &wiki::Page, profile: &Profile) {
+    let (head, _) = page.revision();
+    let mut table = term::Table::new(term::table::TableOptions::bordered());
+
+    table.push([
+        term::format::dim(String::from("●")).into(),
+        term::format::bold(String::from("Revision")).into(),
+        term::format::bold(String::from("Author")).into(),
+        term::Line::blank(),
+        term::format::bold(String::from("Revised")).into(),
+    ]);
+    table.divider();
+
+    for (id, revision) in page.history().rev() {
+        let Some(revision) = revision else {
+            table.push([
+                term::format::dim("●").into(),
+                term::format::tertiary(term::format::oid(*id)).into(),
+                term::format::italic("redacted").dim().into(),
+                term::Line::blank(),
+                term::Line::blank(),
+            ]);
+            continue;
+        };
+        let (alias, did) = Author::new(revision.author().id(), profile).labels();
+
+        table.push([
+            if id == head {
+                term::format::positive("●").into()
+            } else {
+                term::format::dim("●").into()
+            },
+            term::format::tertiary(term::format::oid(*id)).into(),
+            alias.into(),
+            did.into(),
+            term::format::timestamp(revision.timestamp())
+                .dim()
+                .italic()
+                .into(),
+        ]);
+    }
+    table.print();
+}
+
+/// Print a unified diff of a page body.
+pub fn diff(diff: &str) {
+    for line in diff.lines() {
+        let line = if line.starts_with("+++") || line.starts_with("---") {
+            term::format::bold(line)
+        } else if line.starts_with("@@") {
+            term::format::tertiary(line)
+        } else if line.starts_with('+') {
+            term::format::positive(line)
+        } else if line.starts_with('-') {
+            term::format::negative(line)
+        } else {
+            term::format::default(line)
+        };
+        term::print(line);
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::path::Path;
 use std::str::FromStr;
 use std::{env, net, thread, time};
@@ -7,6 +8,7 @@ use radicle::node;
 use radicle::node::address::Store as _;
 use radicle::node::config::seeds::{RADICLE_COMMUNITY_NODE, RADICLE_TEAM_NODE};
 use radicle::node::routing::Store as _;
+use radicle::node::sync::Replication;
 use radicle::node::Handle as _;
 use radicle::node::{Address, Alias, DEFAULT_TIMEOUT};
 use radicle::prelude::{NodeId, RepoId};
@@ -147,6 +149,123 @@ fn rad_issue() {
     test("examples/rad-issue.md", &working, Some(home), []).unwrap();
 }
 
+#[test]
+fn rad_issue_task() {
+    let mut environment = Environment::new();
+    let profile = environment.profile(config::profile("alice"));
+    let home = &profile.home;
+    let working = environment.tmp().join("working");
+
+    // Setup a test repository.
+    fixtures::repository(&working);
+
+    test("examples/rad-init.md", &working, Some(home), []).unwrap();
+    test("examples/rad-issue-task.md", &working, Some(home), []).unwrap();
+}
+
+#[test]
+fn rad_issue_query() {
+    let mut environment = Environment::new();
+    let profile = environment.profile(profile::Config {
+        queries: BTreeMap::from_iter([(
+            String::from("bugs"),
+            "label:bug state:open".parse().unwrap(),
+        )]),
+        ..config::profile("alice")
+    });
+    let home = &profile.home;
+    let working = environment.tmp().join("working");
+
+    // Setup a test repository.
+    fixtures::repository(&working);
+
+    test("examples/rad-init.md", &working, Some(home), []).unwrap();
+    test("examples/rad-issue-query.md", &working, Some(home), []).unwrap();
+}
+
+#[test]
+fn rad_discuss() {
+    let mut environment = Environment::new();
+    let profile = environment.profile(config::profile("alice"));
+    let home = &profile.home;
+    let working = environment.tmp().join("working");
+
+    // Setup a test repository.
+    fixtures::repository(&working);
+
+    test("examples/rad-init.md", &working, Some(home), []).unwrap();
+    test("examples/rad-discuss.md", &working, Some(home), []).unwrap();
+}
+
+#[test]
+fn rad_wiki() {
+    let mut environment = Environment::new();
+    let profile = environment.profile(config::profile("alice"));
+    let home = &profile.home;
+    let working = environment.tmp().join("working");
+
+    // Setup a test repository.
+    fixtures::repository(&working);
+
+    test("examples/rad-init.md", &working, Some(home), []).unwrap();
+    test("examples/rad-wiki.md", &working, Some(home), []).unwrap();
+}
+
+#[test]
+fn rad_export() {
+    let mut environment = Environment::new();
+    let profile = environment.profile(config::profile("alice"));
+    let home = &profile.home;
+    let working = environment.tmp().join("working");
+
+    // Setup a test repository.
+    fixtures::repository(&working);
+
+    test("examples/rad-init.md", &working, Some(home), []).unwrap();
+    test("examples/rad-export.md", &working, Some(home), []).unwrap();
+}
+
+#[test]
+fn rad_bundle() {
+    let mut environment = Environment::new();
+    let alice = environment.profile(config::profile("alice"));
+    let bob = environment.profile(config::profile("bob"));
+    let working = environment.tmp().join("working");
+
+    // Setup a test repository.
+    fixtures::repository(&working);
+
+    test("examples/rad-init.md", &working, Some(&alice.home), []).unwrap();
+    test(
+        "examples/rad-bundle-create.md",
+        &working,
+        Some(&alice.home),
+        [],
+    )
+    .unwrap();
+    test(
+        "examples/rad-bundle-apply.md",
+        environment.tmp(),
+        Some(&bob.home),
+        [],
+    )
+    .unwrap();
+}
+
+#[test]
+fn rad_import() {
+    let mut environment = Environment::new();
+    let profile = environment.profile(config::profile("alice"));
+    let home = &profile.home;
+    let working = environment.tmp().join("working");
+
+    // Setup a test repository.
+    fixtures::repository(&working);
+
+    test("examples/rad-init.md", &working, Some(home), []).unwrap();
+    test("examples/rad-import.md", &working, Some(home), []).unwrap();
+}
+
 #[test]
 fn rad_cob() {
     let mut environment = Environment::new();
@@ -2064,6 +2183,68 @@ fn rad_patch_open_explore() {
     .unwrap();
 }
 
+#[test]
+fn git_push_replication() {
+    let mut environment = Environment::new();
+    let seed = environment
+        .node(Config {
+            policy: Policy::Allow,
+            scope: Scope::All,
+            ..config::seed("seed")
+        })
+        .spawn();
+    let rid = RepoId::from_str("z3yXbb1sR6UG6ixxV2YF9jUP7ABra").unwrap();
+    let replication = Replication {
+        replicas: 1,
+        seeds: [seed.id].into(),
+        timeout: localtime::LocalDuration::from_secs(9),
+        required: true,
+    };
+    let bob = environment.profile(profile::Config {
+        replication: BTreeMap::from_iter([(rid, replication.clone())]),
+        ..config::profile("bob")
+    });
+    let mut bob = Node::new(bob).spawn();
+    let working = environment.tmp().join("working");
+
+    fixtures::repository(&working);
+
+    bob.connect(&seed);
+    bob.init("heartwood", "", &working).unwrap();
+    bob.converge([&seed]);
+
+    test(
+        "examples/git-push-replication.md",
+        &working,
+        Some(&bob.home),
+        [],
+    )
+    .unwrap();
+
+    // Require a second seed, that we'll never hear from.
+    let path = bob.home.config();
+    let mut config = profile::Config::load(&path).unwrap();
+    config.replication.insert(
+        rid,
+        Replication {
+            replicas: 2,
+            seeds: [seed.id, RADICLE_TEAM_NODE.id].into(),
+            timeout: localtime::LocalDuration::from_secs(1),
+            ..replication
+        },
+    );
+    std::fs::remove_file(&path).unwrap();
+    config.write(&path).unwrap();
+
+    test(
+        "examples/git-push-replication-required.md",
+        &working,
+        Some(&bob.home),
+        [],
+    )
+    .unwrap();
+}
+
 #[test]
 fn rad_init_private() {
     let mut environment = Environment::new();
@@ -2423,6 +2604,138 @@ fn rad_inbox() {
         .unwrap();
 }
 
+#[test]
+fn rad_inbox_watch() {
+    let mut environment = Environment::new();
+    let mut alice = environment.node(Config::test(Alias::new("alice")));
+    let bob = environment.node(Config::test(Alias::new("bob")));
+    let working = environment.tmp().join("working");
+    let (repo1, _) = fixtures::repository(working.join("alice").join("heartwood"));
+    let (repo2, _) = fixtures::repository(working.join("alice").join("radicle-git"));
+    let rid1 = alice.project_from("heartwood", "Radicle Heartwood Protocol & Stack", &repo1);
+    let rid2 = alice.project_from("radicle-git", "Radicle Git", &repo2);
+
+    let alice = alice.spawn();
+    let mut bob = bob.spawn();
+
+    bob.connect(&alice).converge([&alice]);
+    bob.clone(rid1, working.join("bob")).unwrap();
+    bob.clone(rid2, working.join("bob")).unwrap();
+
+    formula(&environment.tmp(), "examples/rad-inbox-watch.md")
+        .unwrap()
+        .home(
+            "alice",
+            working.join("alice"),
+            [("RAD_HOME", alice.home.path().display())],
+        )
+        .home(
+            "bob",
+            working.join("bob"),
+            [("RAD_HOME", bob.home.path().display())],
+        )
+        .run()
+        .unwrap();
+}
+
+#[test]
+fn rad_inbox_mention() {
+    let mut environment = Environment::new();
+    let alice = environment.node(Config::test(Alias::new("alice")));
+    let bob = environment.node(Config::test(Alias::new("bob")));
+    let working = environment.tmp().join("working");
+    let acme = RepoId::from_str("z42hL2jL4XNk6K8oHQaSWfMgCL7ji").unwrap();
+
+    // Setup a test repository.
+    fixtures::repository(working.join("alice"));
+
+    test(
+        "examples/rad-init.md",
+        working.join("alice"),
+        Some(&alice.home),
+        [],
+    )
+    .unwrap();
+
+    let mut alice = alice.spawn();
+    let mut bob = bob.spawn();
+
+    bob.handle.seed(acme, Scope::All).unwrap();
+    alice.connect(&bob).converge([&bob]);
+
+    test(
+        "examples/rad-clone.md",
+        working.join("bob"),
+        Some(&bob.home),
+        [],
+    )
+    .unwrap();
+
+    formula(&environment.tmp(), "examples/rad-inbox-mention.md")
+        .unwrap()
+        .home(
+            "alice",
+            working.join("alice"),
+            [("RAD_HOME", alice.home.path().display())],
+        )
+        .home(
+            "bob",
+            working.join("bob"),
+            [("RAD_HOME", bob.home.path().display())],
+        )
+        .run()
+        .unwrap();
+}
+
+#[test]
+fn rad_dashboard() {
+    let mut environment = Environment::new();
+    let alice = environment.node(Config::test(Alias::new("alice")));
+    let bob = environment.node(Config::test(Alias::new("bob")));
+    let working = environment.tmp().join("working");
+    let acme = RepoId::from_str("z42hL2jL4XNk6K8oHQaSWfMgCL7ji").unwrap();
+
+    // Setup a test repository.
+    fixtures::repository(working.join("alice"));
+
+    test(
+        "examples/rad-init.md",
+        working.join("alice"),
+        Some(&alice.home),
+        [],
+    )
+    .unwrap();
+
+    let mut alice = alice.spawn();
+    let mut bob = bob.spawn();
+
+    bob.handle.seed(acme, Scope::All).unwrap();
+    alice.connect(&bob).converge([&bob]);
+
+    test(
+        "examples/rad-clone.md",
+        working.join("bob"),
+        Some(&bob.home),
+        [],
+    )
+    .unwrap();
+
+    formula(&environment.tmp(), "examples/rad-dashboard.md")
+        .unwrap()
+        .home(
+            "alice",
+            working.join("alice"),
+            [("RAD_HOME", alice.home.path().display())],
+        )
+        .home(
+            "bob",
+            working.join("bob"),
+            [("RAD_HOME", bob.home.path().display())],
+        )
+        .run()
+        .unwrap();
+}
+
 #[test]
 fn rad_patch_fetch_2() {
     let mut environment = Environment::new();
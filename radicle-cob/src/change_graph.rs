@@ -8,7 +8,7 @@ use radicle_dag::Dag;
 
 use crate::{
     change, object, object::collaboration::Evaluate, signatures::ExtendedSignature,
-    CollaborativeObject, Entry, EntryId, History, ObjectId, TypeName,
+    CollaborativeObject, Entry, EntryId, History, ObjectId, Snapshot, TypeName,
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -93,8 +93,18 @@ impl ChangeGraph {
     /// Given a graph evaluate it to produce a collaborative object. This will
     /// filter out branches of the graph which do not have valid signatures.
     pub(crate) fn evaluate<S, T: Evaluate<S>>(
+        self,
+        store: &S,
+    ) -> Result<CollaborativeObject<T>, EvaluateError> {
+        self.evaluate_from(store, None)
+    }
+
+    /// Like [`ChangeGraph::evaluate`], but starts from the given snapshot if it can be
+    /// used for this graph, only applying the entries that aren't part of it.
+    pub(crate) fn evaluate_from<S, T: Evaluate<S>>(
         mut self,
         store: &S,
+        snapshot: Option<Snapshot<T>>,
     ) -> Result<CollaborativeObject<T>, EvaluateError> {
         let root = *self.object_id;
         let root = self
@@ -107,26 +117,43 @@ impl ChangeGraph {
         }
         // Evaluate the root separately, since we can't have a COB without a valid root.
         // Then, traverse the graph starting from the root's dependents.
-        let mut object =
-            T::init(&root.value, store).map_err(|e| EvaluateError::Init(Box::new(e)))?;
+        let (mut object, applied) = match snapshot
+            .and_then(|snapshot| self.covered(&snapshot).map(|c| (snapshot.object, c)))
+        {
+            Some((object, covered)) => {
+                log::trace!(
+                    target: "cob",
+                    "Resuming evaluation of {} from snapshot covering {} of {} entries",
+                    self.object_id,
+                    covered.len(),
+                    self.graph.len()
+                );
+                (object, covered)
+            }
+            None => (
+                T::init(&root.value, store).map_err(|e| EvaluateError::Init(Box::new(e)))?,
+                BTreeSet::new(),
+            ),
+        };
         let children = Vec::from_iter(root.dependents.iter().cloned());
         let manifest = root.manifest.clone();
         let root = root.id;
 
-        self.graph.prune(&children, |_, entry, siblings| {
-            // Check the entry signatures are valid.
-            if !entry.valid_signatures() {
-                return ControlFlow::Break(());
-            }
-            // Apply the entry to the state, and if there's an error, prune that branch.
-            if object
-                .apply(entry, siblings.map(|(k, n)| (k, &n.value)), store)
-                .is_err()
-            {
-                return ControlFlow::Break(());
-            }
-            ControlFlow::Continue(())
-        });
+        self.graph
+            .prune_except(&children, &applied, |_, entry, siblings| {
+                // Check the entry signatures are valid.
+                if !entry.valid_signatures() {
+                    return ControlFlow::Break(());
+                }
+                // Apply the entry to the state, and if there's an error, prune that branch.
+                if object
+                    .apply(entry, siblings.map(|(k, n)| (k, &n.value)), store)
+                    .is_err()
+                {
+                    return ControlFlow::Break(());
+                }
+                ControlFlow::Continue(())
+            });
 
         Ok(CollaborativeObject {
             manifest,
@@ -136,6 +163,43 @@ impl ChangeGraph {
         })
     }
 
+    /// Get the entries covered by a snapshot, if evaluation can resume from it.
+    ///
+    /// This is the case if all of the snapshot's tips are in the graph, and every entry that
+    /// isn't covered by the snapshot descends from all of them. Only then are the covered
+    /// entries guaranteed to come before all others in the traversal order, as they would when
+    /// evaluating the graph from its root. For example, this isn't the case once a concurrent
+    /// branch that forked off before the snapshot is merged in.
+    fn covered<T>(&self, snapshot: &Snapshot<T>) -> Option<BTreeSet<Oid>> {
+        if snapshot.tips.is_empty() {
+            return None;
+        }
+        let mut covered = BTreeSet::new();
+
+        for tip in &snapshot.tips {
+            if !self.graph.contains(tip) {
+                return None;
+            }
+            covered.insert(*tip);
+            covered.extend(self.graph.ancestors(tip));
+        }
+        let rest = self.graph.len() - covered.len();
+
+        for tip in &snapshot.tips {
+            let descendants = self
+                .graph
+                .descendants(tip)
+                .into_iter()
+                .filter(|id| !covered.contains(id))
+                .count();
+
+            if descendants != rest {
+                return None;
+            }
+        }
+        Some(covered)
+    }
+
     /// Get the tips of the collaborative object
     pub(crate) fn tips(&self) -> BTreeSet<Oid> {
         self.graph.tips().map(|(_, change)| *change.id()).collect()
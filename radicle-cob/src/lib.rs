@@ -84,8 +84,8 @@ pub use type_name::TypeName;
 
 pub mod object;
 pub use object::{
-    create, get, info, list, remove, update, CollaborativeObject, Create, Evaluate, ObjectId,
-    Update, Updated,
+    create, get, get_from, info, list, list_from, remove, update, CollaborativeObject, Create,
+    Evaluate, ObjectId, Snapshot, Update, Updated,
 };
 
 #[cfg(test)]
@@ -9,8 +9,8 @@ use thiserror::Error;
 
 pub mod collaboration;
 pub use collaboration::{
-    create, get, info, list, parse_refstr, remove, update, CollaborativeObject, Create, Evaluate,
-    Update, Updated,
+    create, get, get_from, info, list, list_from, parse_refstr, remove, update,
+    CollaborativeObject, Create, Evaluate, Snapshot, Update, Updated,
 };
 
 pub mod storage;
@@ -1,4 +1,5 @@
 // Copyright © 2022 The Radicle Link Contributors
+use std::collections::BTreeSet;
 use std::convert::Infallible;
 use std::fmt::Debug;
 
@@ -14,12 +15,12 @@ mod create;
 pub use create::{create, Create};
 
 mod get;
-pub use get::get;
+pub use get::{get, get_from};
 
 pub mod info;
 
 mod list;
-pub use list::list;
+pub use list::{list, list_from};
 
 mod remove;
 pub use remove::remove;
@@ -62,6 +63,20 @@ impl<T> CollaborativeObject<T> {
     }
 }
 
+/// The state of an object, evaluated from the part of its history that ends at `tips`.
+///
+/// Since entries are content-addressed, the tips identify all of the entries the state was
+/// evaluated from. A snapshot can be used to resume evaluation of an object whose history has
+/// grown, as long as every entry added since was made on top of all of the snapshot's tips.
+/// Otherwise, the object is evaluated from its root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot<T> {
+    /// The tips of the history the object was evaluated from.
+    pub tips: BTreeSet<Oid>,
+    /// The evaluated object.
+    pub object: T,
+}
+
 /// An object that can be built by evaluating a history.
 pub trait Evaluate<R>: Sized + Debug + 'static {
     type Error: std::error::Error + Send + Sync + 'static;
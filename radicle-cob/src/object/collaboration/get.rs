@@ -1,6 +1,8 @@
 // Copyright © 2022 The Radicle Link Contributors
 
-use crate::{change_graph::ChangeGraph, CollaborativeObject, Evaluate, ObjectId, Store, TypeName};
+use crate::{
+    change_graph::ChangeGraph, CollaborativeObject, Evaluate, ObjectId, Snapshot, Store, TypeName,
+};
 
 use super::error;
 
@@ -18,6 +20,23 @@ pub fn get<T, S>(
     typename: &TypeName,
     oid: &ObjectId,
 ) -> Result<Option<CollaborativeObject<T>>, error::Retrieve>
+where
+    T: Evaluate<S>,
+    S: Store,
+{
+    get_from(storage, typename, oid, None)
+}
+
+/// Get a [`CollaborativeObject`], if it exists, resuming its evaluation from the given
+/// [`Snapshot`] if possible.
+///
+/// See [`get`] for more information.
+pub fn get_from<T, S>(
+    storage: &S,
+    typename: &TypeName,
+    oid: &ObjectId,
+    snapshot: Option<Snapshot<T>>,
+) -> Result<Option<CollaborativeObject<T>>, error::Retrieve>
 where
     T: Evaluate<S>,
     S: Store,
@@ -27,6 +46,10 @@ where
         .map_err(|err| error::Retrieve::Refs { err: Box::new(err) })?;
 
     ChangeGraph::load(storage, tip_refs.iter(), typename, oid)
-        .map(|graph| graph.evaluate(storage).map_err(error::Retrieve::evaluate))
+        .map(|graph| {
+            graph
+                .evaluate_from(storage, snapshot)
+                .map_err(error::Retrieve::evaluate)
+        })
         .transpose()
 }
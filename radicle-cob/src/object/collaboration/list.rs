@@ -1,6 +1,8 @@
 // Copyright © 2022 The Radicle Link Contributors
 
-use crate::{change_graph::ChangeGraph, CollaborativeObject, Evaluate, Store, TypeName};
+use crate::{
+    change_graph::ChangeGraph, CollaborativeObject, Evaluate, ObjectId, Snapshot, Store, TypeName,
+};
 
 use super::error;
 
@@ -18,6 +20,23 @@ pub fn list<T, S>(
 where
     T: Evaluate<S>,
     S: Store,
+{
+    list_from(storage, typename, |_| None)
+}
+
+/// List a set of [`CollaborativeObject`], resuming the evaluation of each object from
+/// the [`Snapshot`] returned by `snapshot`, if possible.
+///
+/// See [`list`] for more information.
+pub fn list_from<T, S, F>(
+    storage: &S,
+    typename: &TypeName,
+    mut snapshot: F,
+) -> Result<Vec<CollaborativeObject<T>>, error::Retrieve>
+where
+    T: Evaluate<S>,
+    S: Store,
+    F: FnMut(&ObjectId) -> Option<Snapshot<T>>,
 {
     let references = storage
         .types(typename)
@@ -26,8 +45,11 @@ where
     let mut result = Vec::new();
     for (oid, tip_refs) in references {
         log::trace!(target: "cob", "Loading object '{oid}'");
-        let loaded = ChangeGraph::load(storage, tip_refs.iter(), typename, &oid)
-            .map(|graph| graph.evaluate(storage).map_err(error::Retrieve::evaluate));
+        let loaded = ChangeGraph::load(storage, tip_refs.iter(), typename, &oid).map(|graph| {
+            graph
+                .evaluate_from(storage, snapshot(&oid))
+                .map_err(error::Retrieve::evaluate)
+        });
 
         match loaded {
             Some(Ok(obj)) => {
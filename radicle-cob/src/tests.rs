@@ -7,8 +7,8 @@ use nonempty::{nonempty, NonEmpty};
 use qcheck::Arbitrary;
 
 use crate::{
-    create, get, list, object, test::arbitrary::Invalid, update, Create, Entry, ObjectId, TypeName,
-    Update, Updated, Version,
+    create, get, get_from, list, object, test::arbitrary::Invalid, update, Create, Entry, ObjectId,
+    Snapshot, TypeName, Update, Updated, Version,
 };
 
 use super::test;
@@ -235,6 +235,94 @@ fn traverse_cobs() {
     assert_eq!(contents, vec![b"issue 1".to_vec(), b"issue 2".to_vec()]);
 }
 
+#[test]
+fn snapshot_cobs() {
+    let storage = test::Storage::new();
+    let terry = gen::<MockSigner>(1);
+    let neil = gen::<MockSigner>(2);
+    let proj = test::Project::new(&storage, "discworld", *terry.public_key()).unwrap();
+    let typename = "xyz.rad.issue".parse::<TypeName>().unwrap();
+    let cob = create::<NonEmpty<Entry>, _, _>(
+        &storage,
+        &terry,
+        Some(proj.content_id),
+        vec![],
+        terry.public_key(),
+        Create {
+            contents: nonempty!(b"root".to_vec()),
+            type_name: typename.clone(),
+            message: "creating xyz.rad.issue".to_string(),
+            embeds: vec![],
+            version: Version::default(),
+        },
+    )
+    .unwrap();
+    let id = *cob.id();
+    let update = |signer: &MockSigner, contents: &[u8]| {
+        update::<NonEmpty<Entry>, _, _>(
+            &storage,
+            signer,
+            Some(proj.content_id),
+            vec![],
+            signer.public_key(),
+            Update {
+                changes: nonempty!(contents.to_vec()),
+                object_id: id,
+                type_name: typename.clone(),
+                embeds: vec![],
+                message: "commenting on xyz.rad.issue".to_string(),
+            },
+        )
+        .unwrap()
+    };
+    let contents = |entries: &NonEmpty<Entry>| {
+        entries
+            .iter()
+            .map(|entry| entry.contents().head.clone())
+            .collect::<Vec<_>>()
+    };
+
+    update(&terry, b"a");
+    let a = get::<NonEmpty<Entry>, _>(&storage, &typename, &id)
+        .unwrap()
+        .unwrap();
+    // Leave the root out of the snapshot, to tell whether evaluation resumed from it.
+    let snapshot = Snapshot {
+        tips: a.history().tips(),
+        object: NonEmpty::new(a.object.last().clone()),
+    };
+
+    update(&terry, b"b");
+    let b = get_from(&storage, &typename, &id, Some(snapshot.clone()))
+        .unwrap()
+        .unwrap();
+    assert_eq!(contents(&b.object), vec![b"a".to_vec(), b"b".to_vec()]);
+
+    // Neil makes a change on top of the root, concurrently with terry's changes.
+    let raw = storage.as_raw();
+    let terry_ref = format!("refs/rad/{}/cobs/{typename}/{id}", terry.public_key());
+    let neil_ref = format!("refs/rad/{}/cobs/{typename}/{id}", neil.public_key());
+    let tip = raw.find_reference(&terry_ref).unwrap().target().unwrap();
+
+    raw.find_reference(&terry_ref).unwrap().delete().unwrap();
+    raw.reference(&neil_ref, git2::Oid::from(*id), false, "forking")
+        .unwrap();
+    update(&neil, b"c");
+    raw.reference(&terry_ref, tip, false, "restoring").unwrap();
+
+    // Once merged in, the concurrent change invalidates the snapshot.
+    let c = get_from(&storage, &typename, &id, Some(snapshot))
+        .unwrap()
+        .unwrap();
+    let expected = get::<NonEmpty<Entry>, _>(&storage, &typename, &id)
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(c.object.len(), 4);
+    assert_eq!(c.object.first().contents().head, b"root".to_vec());
+    assert_eq!(contents(&c.object), contents(&expected.object));
+}
+
 #[quickcheck]
 fn parse_refstr(oid: ObjectId, typename: TypeName) {
     let suffix = refname!("refs/cobs")
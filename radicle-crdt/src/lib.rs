@@ -6,11 +6,14 @@ pub mod clock;
 pub mod gmap;
 pub mod gset;
 pub mod immutable;
+pub mod list;
 pub mod lwwmap;
 pub mod lwwreg;
 pub mod lwwset;
 pub mod ord;
 pub mod redactable;
+pub mod seq;
+pub mod text;
 
 #[cfg(any(test, feature = "test"))]
 pub mod test;
@@ -21,11 +24,14 @@ pub use clock::Lamport;
 pub use gmap::GMap;
 pub use gset::GSet;
 pub use immutable::Immutable;
+pub use list::List;
 pub use lwwmap::LWWMap;
 pub use lwwreg::LWWReg;
 pub use lwwset::LWWSet;
 pub use ord::{Max, Min};
 pub use redactable::Redactable;
+pub use seq::Seq;
+pub use text::Text;
 
 ////////////////////////////////////////////////////////////////////////////////
 
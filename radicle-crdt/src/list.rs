@@ -0,0 +1,327 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::seq::Seq;
+use crate::Semilattice;
+
+/// An item of a [`List`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Item<V, P> {
+    /// Item value.
+    value: V,
+    /// Current position of the item, ie. the greatest position it was placed at.
+    position: P,
+    /// Whether the item was removed. Removals win over concurrent moves.
+    removed: bool,
+}
+
+/// An ordered list of items with stable keys, supporting insertions, moves and removals.
+///
+/// Items are identified by keys of type `K`, and placed at positions of type `P`, which are
+/// ordered using a [`Seq`]. Moving an item places it at a new position, and when an item is
+/// moved concurrently, the greatest position wins. As with [`Seq`], positions must be
+/// consistent with causality, eg. by starting with a [`crate::Lamport`] clock.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    rename_all = "camelCase",
+    bound(
+        serialize = "K: Serialize, V: Serialize, P: Serialize",
+        deserialize = "K: Deserialize<'de> + Ord, V: Deserialize<'de>, P: Deserialize<'de> + Ord"
+    )
+)]
+pub struct List<K, V, P> {
+    /// Item positions. Each position refers to the item that was placed there.
+    positions: Seq<K, P>,
+    /// Items, by key.
+    #[serde(with = "items")]
+    items: BTreeMap<K, Item<V, P>>,
+}
+
+impl<K, V, P> Default for List<K, V, P> {
+    fn default() -> Self {
+        Self {
+            positions: Seq::default(),
+            items: BTreeMap::new(),
+        }
+    }
+}
+
+impl<K: Ord + Clone, V, P: Ord + Clone> List<K, V, P> {
+    /// Create an empty list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert an item after the given item, or at the start if `None`.
+    ///
+    /// Returns `false` if the item we are inserting after doesn't exist, or if the key
+    /// or position is already in use.
+    pub fn insert(&mut self, after: Option<&K>, key: K, position: P, value: V) -> bool {
+        if self.items.contains_key(&key) {
+            return false;
+        }
+        if !self.place(after, key.clone(), position.clone()) {
+            return false;
+        }
+        self.items.insert(
+            key,
+            Item {
+                value,
+                position,
+                removed: false,
+            },
+        );
+        true
+    }
+
+    /// Move an item after the given item, or to the start if `None`.
+    ///
+    /// Returns `false` if either item doesn't exist, or if the position is already in use.
+    pub fn move_after(&mut self, key: &K, after: Option<&K>, position: P) -> bool {
+        if !self.items.contains_key(key) {
+            return false;
+        }
+        if !self.place(after, key.clone(), position.clone()) {
+            return false;
+        }
+        if let Some(item) = self.items.get_mut(key) {
+            if position > item.position {
+                item.position = position;
+            }
+        }
+        true
+    }
+
+    /// Remove an item. Returns `false` if the item doesn't exist.
+    pub fn remove(&mut self, key: &K) -> bool {
+        if let Some(item) = self.items.get_mut(key) {
+            item.removed = true;
+            return true;
+        }
+        false
+    }
+
+    /// Get an item, if it exists and wasn't removed.
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.items.get(key).filter(|i| !i.removed).map(|i| &i.value)
+    }
+
+    /// Get an item mutably, if it exists and wasn't removed.
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.items
+            .get_mut(key)
+            .filter(|i| !i.removed)
+            .map(|i| &mut i.value)
+    }
+
+    /// Check whether an item exists and wasn't removed.
+    pub fn contains(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Check whether an item was removed.
+    pub fn is_removed(&self, key: &K) -> bool {
+        self.items.get(key).is_some_and(|i| i.removed)
+    }
+
+    /// Iterate over all the positions items were ever placed at, including past positions
+    /// and positions of removed items.
+    pub fn positions(&self) -> impl Iterator<Item = &P> {
+        self.positions.ids()
+    }
+
+    /// Iterate over the items, in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.positions.iter().filter_map(|(position, key)| {
+            let item = self.items.get(key)?;
+
+            if item.removed || &item.position != position {
+                return None;
+            }
+            Some((key, &item.value))
+        })
+    }
+
+    /// Number of items.
+    pub fn len(&self) -> usize {
+        self.items.values().filter(|i| !i.removed).count()
+    }
+
+    /// Whether there are no items.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Place an item at a new position, after the current position of the given item.
+    fn place(&mut self, after: Option<&K>, key: K, position: P) -> bool {
+        let after = match after {
+            Some(k) => match self.items.get(k) {
+                Some(item) => Some(item.position.clone()),
+                None => return false,
+            },
+            None => None,
+        };
+        self.positions.insert(after, position, key)
+    }
+}
+
+impl<K, V, P> Semilattice for List<K, V, P>
+where
+    K: Ord + Clone,
+    V: Semilattice,
+    P: Ord + Clone,
+{
+    fn merge(&mut self, other: Self) {
+        self.positions.merge(other.positions);
+
+        for (key, other) in other.items {
+            if let Some(item) = self.items.get_mut(&key) {
+                if other.position > item.position {
+                    item.position = other.position;
+                }
+                item.removed |= other.removed;
+                item.value.merge(other.value);
+            } else {
+                self.items.insert(key, other);
+            }
+        }
+    }
+}
+
+/// Serialize items as a list of pairs, since keys aren't necessarily strings.
+mod items {
+    use std::collections::BTreeMap;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S, K, V>(items: &BTreeMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        K: Serialize,
+        V: Serialize,
+    {
+        serializer.collect_seq(items)
+    }
+
+    pub fn deserialize<'de, D, K, V>(deserializer: D) -> Result<BTreeMap<K, V>, D::Error>
+    where
+        D: Deserializer<'de>,
+        K: Deserialize<'de> + Ord,
+        V: Deserialize<'de>,
+    {
+        Vec::<(K, V)>::deserialize(deserializer).map(BTreeMap::from_iter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use qcheck_macros::quickcheck;
+
+    use super::*;
+    use crate::ord::Max;
+    use crate::test;
+
+    fn keys(list: &List<char, Max<u8>, u16>) -> String {
+        list.iter().map(|(k, _)| k).collect()
+    }
+
+    #[quickcheck]
+    fn prop_semilattice(a: Vec<(u8, u8)>, b: Vec<(u8, u8)>, c: Vec<(u8, u8)>) {
+        // Build three lists sharing a common history, each with their own insertions,
+        // moves and removals.
+        let mut base = List::new();
+        let mut position = 0u16;
+        let mut replicas = Vec::new();
+
+        base.insert(None, 'a', position, Max::from(0));
+
+        for (r, ops) in [a, b, c].into_iter().enumerate() {
+            let mut replica = base.clone();
+            let mut keys = vec!['a'];
+
+            for (i, (op, n)) in ops.into_iter().enumerate() {
+                let target = keys[n as usize % keys.len()];
+                position += 1;
+
+                match op % 4 {
+                    0 | 1 => {
+                        let key = char::from(b'b' + (r * 64 + i % 64) as u8 % 24);
+                        if replica.insert(Some(&target), key, position, Max::from(n)) {
+                            keys.push(key);
+                        }
+                    }
+                    2 => {
+                        replica.move_after(&target, None, position);
+                    }
+                    _ => {
+                        replica.remove(&target);
+                    }
+                }
+            }
+            replicas.push(replica);
+        }
+        test::assert_laws(&replicas[0], &replicas[1], &replicas[2]);
+    }
+
+    #[test]
+    fn test_insert_move_remove() {
+        let mut list = List::new();
+
+        assert!(list.insert(None, 'a', 1, Max::from(0)));
+        assert!(list.insert(Some(&'a'), 'b', 2, Max::from(0)));
+        assert!(list.insert(Some(&'b'), 'c', 3, Max::from(0)));
+        assert!(!list.insert(Some(&'z'), 'd', 4, Max::from(0)));
+        assert!(!list.insert(None, 'a', 4, Max::from(0)));
+        assert_eq!(keys(&list), "abc");
+
+        assert!(list.move_after(&'a', Some(&'c'), 4));
+        assert_eq!(keys(&list), "bca");
+
+        assert!(list.move_after(&'c', None, 5));
+        assert_eq!(keys(&list), "cba");
+
+        assert!(list.remove(&'b'));
+        assert_eq!(keys(&list), "ca");
+        assert_eq!(list.len(), 2);
+        assert!(!list.contains(&'b'));
+        assert!(list.is_removed(&'b'));
+        assert!(!list.is_removed(&'z'));
+        assert_eq!(list.get(&'b'), None);
+    }
+
+    #[test]
+    fn test_concurrent_moves() {
+        let mut base = List::new();
+        base.insert(None, 'a', 1, Max::from(0));
+        base.insert(Some(&'a'), 'b', 2, Max::from(0));
+        base.insert(Some(&'b'), 'c', 3, Max::from(0));
+
+        // Both replicas move the same item; the greatest position wins.
+        let mut x = base.clone();
+        x.move_after(&'a', Some(&'b'), 4);
+
+        let mut y = base.clone();
+        y.move_after(&'a', Some(&'c'), 5);
+
+        let merged = x.clone().join(y.clone());
+        assert_eq!(merged, y.join(x));
+        assert_eq!(keys(&merged), "bca");
+    }
+
+    #[test]
+    fn test_concurrent_move_remove() {
+        let mut base = List::new();
+        base.insert(None, 'a', 1, Max::from(0));
+        base.insert(Some(&'a'), 'b', 2, Max::from(0));
+
+        let mut x = base.clone();
+        x.move_after(&'a', Some(&'b'), 3);
+
+        let mut y = base;
+        y.remove(&'a');
+
+        assert_eq!(keys(&x.join(y)), "b");
+    }
+}
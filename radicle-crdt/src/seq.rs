@@ -0,0 +1,264 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Semilattice;
+
+/// An element of a [`Seq`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    rename_all = "camelCase",
+    bound(deserialize = "T: Deserialize<'de>, I: Deserialize<'de> + Ord")
+)]
+struct Element<T, I> {
+    /// Element id.
+    id: I,
+    /// The element this element was inserted after, or `None` if it was inserted
+    /// at the start of the sequence.
+    after: Option<I>,
+    /// Element value.
+    value: T,
+    /// Tags of the removals of this element. An element is visible as long as this is empty.
+    #[serde(default = "BTreeSet::new", skip_serializing_if = "BTreeSet::is_empty")]
+    removed: BTreeSet<I>,
+}
+
+/// Replicated Growable Array (RGA).
+///
+/// An ordered sequence where elements are inserted after other elements, and removed by
+/// leaving a tombstone. Concurrent insertions after the same element are ordered by
+/// descending id, which means that element ids must be consistent with causality: an
+/// element must have a greater id than any element it was inserted after. This is
+/// achieved by starting ids with a [`crate::Lamport`] clock.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    transparent,
+    bound(deserialize = "T: Deserialize<'de>, I: Deserialize<'de> + Ord")
+)]
+pub struct Seq<T, I> {
+    elements: Vec<Element<T, I>>,
+}
+
+impl<T, I> Default for Seq<T, I> {
+    fn default() -> Self {
+        Self {
+            elements: Vec::new(),
+        }
+    }
+}
+
+impl<T, I: Ord + Clone> Seq<T, I> {
+    /// Create an empty sequence.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a value after the given element, or at the start if `None`.
+    ///
+    /// Returns `false` if the element we are inserting after doesn't exist, or if an
+    /// element with the same id was already inserted.
+    pub fn insert(&mut self, after: Option<I>, id: I, value: T) -> bool {
+        self.integrate(Element {
+            id,
+            after,
+            value,
+            removed: BTreeSet::new(),
+        })
+    }
+
+    /// Remove an element. The tag identifies the removal, and is used to know whether
+    /// a replica has seen it, when iterating with [`Seq::iter_at`].
+    ///
+    /// Returns `false` if the element doesn't exist.
+    pub fn remove(&mut self, id: &I, tag: I) -> bool {
+        if let Some(ix) = self.position(id) {
+            self.elements[ix].removed.insert(tag);
+            return true;
+        }
+        false
+    }
+
+    /// Check whether an element exists and is visible.
+    pub fn contains(&self, id: &I) -> bool {
+        self.position(id)
+            .is_some_and(|ix| self.elements[ix].removed.is_empty())
+    }
+
+    /// Iterate over the visible elements.
+    pub fn iter(&self) -> impl Iterator<Item = (&I, &T)> {
+        self.elements
+            .iter()
+            .filter(|e| e.removed.is_empty())
+            .map(|e| (&e.id, &e.value))
+    }
+
+    /// Iterate over the elements visible to a replica which has only seen the insertions
+    /// and removals for which `seen` returns `true`.
+    pub fn iter_at<'a>(
+        &'a self,
+        seen: impl Fn(&I) -> bool + 'a,
+    ) -> impl Iterator<Item = (&'a I, &'a T)> + 'a {
+        self.elements
+            .iter()
+            .filter(move |e| seen(&e.id) && !e.removed.iter().any(&seen))
+            .map(|e| (&e.id, &e.value))
+    }
+
+    /// Iterate over the ids of all elements, including removed ones.
+    pub fn ids(&self) -> impl Iterator<Item = &I> {
+        self.elements.iter().map(|e| &e.id)
+    }
+
+    /// Number of visible elements.
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Whether there are no visible elements.
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
+    fn position(&self, id: &I) -> Option<usize> {
+        self.elements.iter().position(|e| &e.id == id)
+    }
+
+    fn integrate(&mut self, element: Element<T, I>) -> bool {
+        if self.position(&element.id).is_some() {
+            return false;
+        }
+        let mut ix = match &element.after {
+            None => 0,
+            Some(after) => match self.position(after) {
+                Some(ix) => ix + 1,
+                None => return false,
+            },
+        };
+        // Skip over concurrent insertions with greater ids, as well as the elements
+        // inserted after them, which necessarily have greater ids as well.
+        while ix < self.elements.len() && self.elements[ix].id > element.id {
+            ix += 1;
+        }
+        self.elements.insert(ix, element);
+
+        true
+    }
+}
+
+impl<T, I: Ord + Clone> Semilattice for Seq<T, I> {
+    fn merge(&mut self, other: Self) {
+        let mut elements = other.elements;
+        // Insert in id order, so that an element is always inserted after the element
+        // it depends on.
+        elements.sort_by(|a, b| a.id.cmp(&b.id));
+
+        for element in elements {
+            if let Some(ix) = self.position(&element.id) {
+                self.elements[ix].removed.extend(element.removed);
+            } else {
+                self.integrate(element);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use qcheck_macros::quickcheck;
+
+    use super::*;
+    use crate::test;
+
+    fn seq(ops: &[(Option<u8>, u8, char)], removed: &[(u8, u8)]) -> Seq<char, u8> {
+        let mut seq = Seq::new();
+        for (after, id, value) in ops {
+            seq.insert(*after, *id, *value);
+        }
+        for (id, tag) in removed {
+            seq.remove(id, *tag);
+        }
+        seq
+    }
+
+    fn string(seq: &Seq<char, u8>) -> String {
+        seq.iter().map(|(_, c)| c).collect()
+    }
+
+    #[quickcheck]
+    fn prop_semilattice(a: Vec<u8>, b: Vec<u8>, c: Vec<u8>, removed: Vec<u8>) {
+        // Build three sequences sharing a common history, each with their own
+        // insertions after arbitrary elements.
+        let mut base = Seq::new();
+        let mut id = 0u16;
+        let mut replicas = Vec::new();
+
+        base.insert(None, (0, 0), 'x');
+
+        for (r, inserts) in [a, b, c].into_iter().enumerate() {
+            let mut replica = base.clone();
+            let mut ids = vec![(0u16, 0u8)];
+
+            for n in inserts {
+                id += 1;
+                let after = ids[n as usize % ids.len()];
+                let new = (id, r as u8);
+
+                replica.insert(Some(after), new, n as char);
+                ids.push(new);
+            }
+            for n in &removed {
+                let target = ids[*n as usize % ids.len()];
+                replica.remove(&target, (u16::MAX, r as u8));
+            }
+            replicas.push(replica);
+        }
+        test::assert_laws(&replicas[0], &replicas[1], &replicas[2]);
+    }
+
+    #[test]
+    fn test_insert() {
+        let s = seq(&[(None, 1, 'a'), (Some(1), 2, 'b'), (Some(2), 3, 'c')], &[]);
+        assert_eq!(string(&s), "abc");
+
+        let s = seq(&[(None, 1, 'a'), (None, 2, 'b'), (None, 3, 'c')], &[]);
+        assert_eq!(string(&s), "cba");
+
+        let s = seq(&[(None, 1, 'a'), (Some(9), 2, 'b')], &[]);
+        assert_eq!(string(&s), "a");
+    }
+
+    #[test]
+    fn test_remove() {
+        let s = seq(
+            &[(None, 1, 'a'), (Some(1), 2, 'b'), (Some(2), 3, 'c')],
+            &[(2, 4)],
+        );
+        assert_eq!(string(&s), "ac");
+        assert!(!s.contains(&2));
+        assert_eq!(s.len(), 2);
+        assert_eq!(
+            s.iter_at(|id| *id < 4).map(|(_, c)| c).collect::<String>(),
+            "abc"
+        );
+        assert_eq!(
+            s.iter_at(|id| *id < 3).map(|(_, c)| c).collect::<String>(),
+            "ab"
+        );
+    }
+
+    #[test]
+    fn test_concurrent_insert() {
+        let base = seq(&[(None, 1, 'a'), (Some(1), 2, 'b')], &[]);
+
+        let mut alice = base.clone();
+        alice.insert(Some(1), 3, 'x');
+        alice.insert(Some(3), 5, 'y');
+
+        let mut bob = base;
+        bob.insert(Some(1), 4, 'z');
+
+        let merged = alice.clone().join(bob.clone());
+        assert_eq!(string(&merged), "azxyb");
+        assert_eq!(merged, bob.join(alice));
+    }
+}
@@ -0,0 +1,302 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::clock::Lamport;
+use crate::seq::Seq;
+use crate::Semilattice;
+
+/// Above this number of cells in the diff table, lines that differ between the old and the
+/// new text are replaced wholesale instead of diffed. With 4-byte cells, this keeps the
+/// table under 1 MiB.
+const MAX_DIFF_CELLS: usize = 1 << 18;
+
+/// Identifies a line in a [`Text`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineId<I> {
+    /// Clock of the edit that inserted or removed the line.
+    pub clock: Lamport,
+    /// The edit that inserted or removed the line.
+    pub edit: I,
+    /// Index of the line within the edit.
+    pub index: usize,
+}
+
+/// Text that can be edited concurrently, line by line.
+///
+/// Edits are whole texts, which are diffed against the text as it was seen by the editor
+/// and turned into line insertions and removals. This means that concurrent edits to
+/// different lines are both kept. When the same line is changed concurrently, both
+/// versions of the line are kept.
+///
+/// Merging is line-based only: concurrent changes within a single line are never merged
+/// character by character, and concurrent edits that don't conflict line-wise may still
+/// not make sense together, eg. when one edit moves a paragraph that the other changes.
+///
+/// Edits are identified by `I`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent, bound(deserialize = "I: Deserialize<'de> + Ord"))]
+pub struct Text<I> {
+    lines: Seq<String, LineId<I>>,
+}
+
+impl<I> Default for Text<I> {
+    fn default() -> Self {
+        Self {
+            lines: Seq::default(),
+        }
+    }
+}
+
+impl<I: Ord + Clone> Text<I> {
+    /// Create an empty text.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Edit the text. The edit changes the text seen by the editor into `new`. The editor
+    /// is assumed to have seen the edits for which `seen` returns `true`, as well as any
+    /// previous changes made under the same edit id. What the editor has seen must be
+    /// consistent with causality, eg. it can't have seen an edit that was made after it.
+    pub fn edit(&mut self, edit: I, new: &str, seen: impl Fn(&I) -> bool) {
+        let seen = |id: &I| *id == edit || seen(id);
+        // New lines must come after every line the editor has seen.
+        let clock = self
+            .lines
+            .ids()
+            .filter(|id| seen(&id.edit))
+            .map(|id| id.clock)
+            .max()
+            .unwrap_or_default()
+            .tick();
+        let base = self
+            .lines
+            .iter_at(|id| seen(&id.edit))
+            .map(|(id, line)| (id.clone(), line.clone()))
+            .collect::<Vec<_>>();
+        let old = base.iter().map(|(_, l)| l.as_str()).collect::<Vec<_>>();
+        let new = lines(new);
+        let tag = LineId {
+            clock,
+            edit: edit.clone(),
+            index: 0,
+        };
+        let mut after = None;
+        let mut index = self.lines.ids().filter(|id| id.edit == edit).count();
+
+        for change in diff(&old, &new) {
+            match change {
+                Change::Keep(i) => {
+                    after = Some(base[i].0.clone());
+                }
+                Change::Remove(i) => {
+                    self.lines.remove(&base[i].0, tag.clone());
+                    after = Some(base[i].0.clone());
+                }
+                Change::Insert(j) => {
+                    let id = LineId {
+                        clock,
+                        edit: edit.clone(),
+                        index,
+                    };
+                    index += 1;
+
+                    self.lines.insert(after, id.clone(), new[j].to_owned());
+                    after = Some(id);
+                }
+            }
+        }
+    }
+
+    /// The text as seen by an editor who has only seen the edits for which `seen`
+    /// returns `true`.
+    pub fn at(&self, seen: impl Fn(&I) -> bool) -> String {
+        join(self.lines.iter_at(|id| seen(&id.edit)).map(|(_, l)| l))
+    }
+
+    /// Whether the text is empty.
+    pub fn is_empty(&self) -> bool {
+        self.to_string().is_empty()
+    }
+}
+
+impl<I: Ord + Clone> fmt::Display for Text<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", join(self.lines.iter().map(|(_, l)| l)))
+    }
+}
+
+impl<I: Ord + Clone> Semilattice for Text<I> {
+    fn merge(&mut self, other: Self) {
+        self.lines.merge(other.lines);
+    }
+}
+
+/// A change turning an old list of lines into a new one.
+#[derive(Debug, PartialEq, Eq)]
+enum Change {
+    /// Keep the old line at the given index.
+    Keep(usize),
+    /// Remove the old line at the given index.
+    Remove(usize),
+    /// Insert the new line at the given index.
+    Insert(usize),
+}
+
+/// Split a string into lines. Unlike [`str::lines`], the result can be joined back into
+/// the original string.
+fn lines(s: &str) -> Vec<&str> {
+    if s.is_empty() {
+        return vec![];
+    }
+    s.split('\n').collect()
+}
+
+fn join<'a>(lines: impl Iterator<Item = &'a String>) -> String {
+    lines.map(String::as_str).collect::<Vec<_>>().join("\n")
+}
+
+/// Compute the changes from `old` to `new`, using the longest common subsequence of lines.
+fn diff(old: &[&str], new: &[&str]) -> Vec<Change> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (n, m) = (old.len() - prefix - suffix, new.len() - prefix - suffix);
+    let mut changes = (0..prefix).map(Change::Keep).collect::<Vec<_>>();
+
+    if n * m > MAX_DIFF_CELLS {
+        changes.extend((prefix..prefix + n).map(Change::Remove));
+        changes.extend((prefix..prefix + m).map(Change::Insert));
+    } else {
+        // Length of the longest common subsequence of `old[i..]` and `new[j..]`,
+        // within the middle section.
+        let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i][j] = if old[prefix + i] == new[prefix + j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < n && j < m {
+            if old[prefix + i] == new[prefix + j] {
+                changes.push(Change::Keep(prefix + i));
+                i += 1;
+                j += 1;
+            } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+                changes.push(Change::Remove(prefix + i));
+                i += 1;
+            } else {
+                changes.push(Change::Insert(prefix + j));
+                j += 1;
+            }
+        }
+        changes.extend((prefix + i..prefix + n).map(Change::Remove));
+        changes.extend((prefix + j..prefix + m).map(Change::Insert));
+    }
+    changes.extend((old.len() - suffix..old.len()).map(Change::Keep));
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    /// Apply an edit, given the edits seen by the editor.
+    fn edit(text: &mut Text<u8>, edit: u8, new: &str, seen: &[u8]) {
+        let seen = seen.iter().copied().collect::<BTreeSet<_>>();
+        text.edit(edit, new, |e| seen.contains(e));
+    }
+
+    #[test]
+    fn test_diff() {
+        assert_eq!(
+            diff(&["a", "b", "c"], &["a", "x", "c"]),
+            vec![
+                Change::Keep(0),
+                Change::Remove(1),
+                Change::Insert(1),
+                Change::Keep(2)
+            ]
+        );
+        assert_eq!(
+            diff(&["a", "b"], &["b", "c"]),
+            vec![Change::Remove(0), Change::Keep(1), Change::Insert(1)]
+        );
+        assert_eq!(diff(&[], &["a"]), vec![Change::Insert(0)]);
+        assert_eq!(diff(&["a"], &[]), vec![Change::Remove(0)]);
+    }
+
+    #[test]
+    fn test_sequential_edits() {
+        let mut text = Text::new();
+
+        edit(&mut text, 1, "Hello\nWorld", &[]);
+        assert_eq!(text.to_string(), "Hello\nWorld");
+
+        edit(&mut text, 2, "Hello\nthere\nWorld\n", &[1]);
+        assert_eq!(text.to_string(), "Hello\nthere\nWorld\n");
+
+        edit(&mut text, 3, "", &[1, 2]);
+        assert_eq!(text.to_string(), "");
+        assert!(text.is_empty());
+        assert_eq!(text.at(|e| *e <= 2), "Hello\nthere\nWorld\n");
+    }
+
+    #[test]
+    fn test_repeated_edit() {
+        let mut text = Text::new();
+
+        edit(&mut text, 1, "a\nb", &[]);
+        edit(&mut text, 1, "a\nc", &[]);
+        assert_eq!(text.to_string(), "a\nc");
+    }
+
+    #[test]
+    fn test_concurrent_edits() {
+        let mut base = Text::new();
+        edit(&mut base, 1, "# Title\nOne\nTwo\nThree", &[]);
+
+        // Edits are applied in a different order on each replica.
+        let mut a = base.clone();
+        edit(&mut a, 2, "# Title\nOne!\nTwo\nThree", &[1]);
+        edit(&mut a, 3, "# Title\nOne\nTwo\nThree\nFour", &[1]);
+
+        let mut b = base.clone();
+        edit(&mut b, 3, "# Title\nOne\nTwo\nThree\nFour", &[1]);
+        edit(&mut b, 2, "# Title\nOne!\nTwo\nThree", &[1]);
+
+        assert_eq!(a, b);
+        assert_eq!(a.to_string(), "# Title\nOne!\nTwo\nThree\nFour");
+
+        // A later edit that has seen both.
+        edit(&mut a, 4, "# Title\nOne!\nThree\nFour", &[1, 2, 3]);
+        assert_eq!(a.to_string(), "# Title\nOne!\nThree\nFour");
+    }
+
+    #[test]
+    fn test_concurrent_edits_same_line() {
+        let mut base = Text::new();
+        edit(&mut base, 1, "a\nb\nc", &[]);
+
+        let mut x = base.clone();
+        edit(&mut x, 2, "a\nx\nc", &[1]);
+
+        let mut y = base;
+        edit(&mut y, 3, "a\ny\nc", &[1]);
+
+        let merged = x.clone().join(y.clone());
+        assert_eq!(merged, y.join(x));
+        assert_eq!(merged.to_string(), "a\ny\nx\nc");
+    }
+}
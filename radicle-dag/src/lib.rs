@@ -201,7 +201,20 @@ impl<K: Ord + Copy, V> Dag<K, V> {
     /// To continue traversing a branch, return [`ControlFlow::Continue`] from the
     /// filter function. To stop traversal of a branch and prune it,
     /// return [`ControlFlow::Break`].
-    pub fn prune<F>(&mut self, roots: &[K], mut filter: F)
+    pub fn prune<F>(&mut self, roots: &[K], filter: F)
+    where
+        F: for<'r> FnMut(
+            &'r K,
+            &'r Node<K, V>,
+            Box<dyn Iterator<Item = (&'r K, &'r Node<K, V>)> + 'r>,
+        ) -> ControlFlow<()>,
+    {
+        self.prune_except(roots, &BTreeSet::new(), filter)
+    }
+
+    /// Like [`Dag::prune`], but nodes in `skip` are not passed to the filter function,
+    /// and are therefore never pruned. They keep their place in the traversal order.
+    pub fn prune_except<F>(&mut self, roots: &[K], skip: &BTreeSet<K>, mut filter: F)
     where
         F: for<'r> FnMut(
             &'r K,
@@ -217,6 +230,9 @@ impl<K: Ord + Copy, V> Dag<K, V> {
         }
 
         for next in result {
+            if skip.contains(&next) {
+                continue;
+            }
             if let Some(node) = self.graph.get(&next) {
                 let siblings = self
                     .siblings_of(node)
@@ -303,6 +319,22 @@ impl<K: Ord + Copy, V> Dag<K, V> {
         }
     }
 
+    /// Get the keys of all nodes that transitively depend on the given node.
+    pub fn descendants(&self, key: &K) -> BTreeSet<K> {
+        self.graph
+            .get(key)
+            .map(|node| self.descendants_of(node).into_iter().collect())
+            .unwrap_or_default()
+    }
+
+    /// Get the keys of all nodes the given node transitively depends on.
+    pub fn ancestors(&self, key: &K) -> BTreeSet<K> {
+        self.graph
+            .get(key)
+            .map(|node| self.ancestors_of(node).into_iter().collect())
+            .unwrap_or_default()
+    }
+
     fn descendants_of(&self, from: &Node<K, V>) -> Vec<K> {
         let mut visited = BTreeSet::new();
         let mut stack = VecDeque::new();
@@ -834,6 +866,34 @@ mod tests {
         assert_eq!(dag.sorted(), vec!["R", "A1", "A2"]);
     }
 
+    #[test]
+    fn test_prune_except() {
+        let mut dag = Dag::new();
+
+        dag.node("R", ());
+        dag.node("A1", ());
+        dag.node("A2", ());
+        dag.node("B1", ());
+        dag.node("B2", ());
+
+        dag.dependency("A1", "R");
+        dag.dependency("A2", "R");
+        dag.dependency("B1", "A1");
+        dag.dependency("B1", "A2");
+        dag.dependency("B2", "B1");
+
+        let skip = dag.ancestors(&"B1");
+        assert_eq!(skip, BTreeSet::from(["R", "A1", "A2"]));
+
+        let mut visited = Vec::new();
+        dag.prune_except(&["R"], &skip, |key, _, _| {
+            visited.push(*key);
+            ControlFlow::Continue(())
+        });
+        assert_eq!(visited, vec!["B1", "B2"]);
+        assert_eq!(dag.descendants(&"A2"), BTreeSet::from(["B1", "B2"]));
+    }
+
     #[test]
     fn test_siblings() {
         let mut dag = Dag::new();
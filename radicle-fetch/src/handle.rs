@@ -1,3 +1,4 @@
+use std::num::NonZeroUsize;
 use std::sync::atomic::{self, AtomicBool};
 use std::sync::Arc;
 
@@ -26,6 +27,9 @@ pub struct Handle<S> {
     /// key in [`crate::pull`], however, we choose to allow the local
     /// peer's key in [`crate::clone`].
     pub(crate) blocked: BlockList,
+    /// If set, the fetch is partial: only the canonical branch of the
+    /// delegates is fetched, up to the given commit depth.
+    pub(crate) depth: Option<NonZeroUsize>,
     // Signals to the pack writer to interrupt the process
     pub(crate) interrupt: Arc<AtomicBool>,
 }
@@ -50,10 +54,21 @@ impl<S> Handle<S> {
             allowed: follow,
             transport,
             blocked,
+            depth: None,
             interrupt: Arc::new(AtomicBool::new(false)),
         })
     }
 
+    /// Make the fetches performed by this handle partial, limiting the
+    /// history fetched to `depth` commits of the canonical branch.
+    ///
+    /// If `depth` is `None` and the repository is partial, the missing
+    /// history is fetched on the next pull.
+    pub fn with_depth(mut self, depth: Option<NonZeroUsize>) -> Self {
+        self.depth = depth;
+        self
+    }
+
     pub fn is_blocked(&self, key: &PublicKey) -> bool {
         self.blocked.is_blocked(key)
     }
@@ -26,25 +26,33 @@
 //!
 //!   1. [`SpecialRefs`]: see above.
 //!   2. [`DataRefs`]: see above.
+//!
+//! ### Partial
+//!
+//! Both actions can be made partial, in which case the [`DataRefs`]
+//! stage only fetches the canonical branch of each delegate, up to a
+//! given commit depth. See [`History`].
 
 use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::num::NonZeroUsize;
 
 use bstr::BString;
 use either::Either;
 use gix_protocol::handshake::Ref;
 use nonempty::NonEmpty;
 use radicle::crypto::PublicKey;
-use radicle::git::{refname, Component, Namespaced, Qualified};
+use radicle::git::{refname, Component, Namespaced, Qualified, RefString};
 use radicle::storage::git::Repository;
 use radicle::storage::refs::{RefsAt, Special};
 use radicle::storage::ReadRepository;
 
 use crate::git::refs::{Policy, Update, Updates};
+use crate::git::repository;
 use crate::policy::BlockList;
 use crate::refs::{ReceivedRef, ReceivedRefname};
 use crate::sigrefs;
 use crate::state::FetchState;
-use crate::transport::WantsHaves;
+use crate::transport::{WantsHaves, WantsHavesError, INFINITE_DEPTH};
 use crate::{policy, refs};
 
 pub mod error {
@@ -404,10 +412,50 @@ pub struct DataRefs {
     /// The set of signed references from each remote that was
     /// fetched.
     pub remotes: sigrefs::RemoteRefs,
+    /// How much history to fetch.
+    pub history: History,
     /// The data limit for this stage of fetching.
     pub limit: u64,
 }
 
+/// The amount of history fetched by the [`DataRefs`] stage.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum History {
+    /// Fetch the complete history of all signed references.
+    #[default]
+    Full,
+    /// Fetch the complete history of all signed references into a
+    /// partial repository, recovering the history that it is missing.
+    Unshallow,
+    /// Only fetch the `branch` reference, up to `depth` commits from
+    /// its tip.
+    ///
+    /// N.b. the identity COB references are always included, since
+    /// their history was already fetched as part of `rad/id`.
+    Shallow {
+        branch: RefString,
+        depth: NonZeroUsize,
+    },
+}
+
+impl History {
+    /// Whether the reference `name` is fetched.
+    pub fn includes(&self, name: &RefString) -> bool {
+        match self {
+            Self::Full | Self::Unshallow => true,
+            Self::Shallow { branch, .. } => {
+                branch == name
+                    || name
+                        .strip_prefix(refname!("refs/cobs"))
+                        .and_then(|name| name.components().next())
+                        .map_or(false, |typename| {
+                            typename.as_str() == radicle::cob::identity::TYPENAME.as_str()
+                        })
+            }
+        }
+    }
+}
+
 impl ProtocolStage for DataRefs {
     // We don't need to ask for refs since we have all reference names
     // and `Oid`s in `rad/sigrefs`.
@@ -434,15 +482,40 @@ impl ProtocolStage for DataRefs {
     ) -> Result<WantsHaves, error::WantsHaves> {
         let mut wants_haves = WantsHaves::default();
 
-        for (remote, loaded) in &self.remotes {
-            wants_haves.add(
-                refdb,
-                loaded.refs.iter().filter_map(|(refname, tip)| {
-                    let refname = Qualified::from_refstr(refname)
-                        .map(|refname| refname.with_namespace(Component::from(remote)))?;
-                    Some((refname, *tip))
-                }),
-            )?;
+        match &self.history {
+            History::Full => {
+                for (remote, loaded) in &self.remotes {
+                    wants_haves.add(
+                        refdb,
+                        loaded.refs.iter().filter_map(|(refname, tip)| {
+                            let refname = Qualified::from_refstr(refname)
+                                .map(|refname| refname.with_namespace(Component::from(remote)))?;
+                            Some((refname, *tip))
+                        }),
+                    )?;
+                }
+            }
+            History::Unshallow => {
+                // N.b. our history is cut off at the shallow commits, so
+                // it can't be used for negotiation: we ask for all tips
+                // without any `have`s instead.
+                for loaded in self.remotes.values() {
+                    wants_haves.wants.extend(loaded.refs.values().copied());
+                }
+                wants_haves.deepen = Some(INFINITE_DEPTH);
+            }
+            History::Shallow { branch, depth } => {
+                for loaded in self.remotes.values() {
+                    let Some((_, tip)) = loaded.refs.iter().find(|(name, _)| *name == branch)
+                    else {
+                        continue;
+                    };
+                    if !repository::contains(refdb, *tip).map_err(WantsHavesError::from)? {
+                        wants_haves.want(*tip);
+                    }
+                }
+                wants_haves.deepen = Some(depth.get());
+            }
         }
 
         Ok(wants_haves)
@@ -463,6 +536,10 @@ impl ProtocolStage for DataRefs {
                     .and_then(|q| refs::ReceivedRefname::remote(*remote, q).to_namespaced())
                     .expect("we checked sigrefs well-formedness in wants_refs already");
                 signed.insert(tracking.clone());
+
+                if !self.history.includes(name) {
+                    continue;
+                }
                 updates.add(
                     *remote,
                     Update::Direct {
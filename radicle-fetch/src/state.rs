@@ -1,4 +1,4 @@
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::time::Instant;
 
 use gix_protocol::handshake;
@@ -10,12 +10,14 @@ use radicle::prelude::Verified;
 use radicle::storage;
 use radicle::storage::refs::{RefsAt, SignedRefs};
 use radicle::storage::{
-    git::Validation, Remote, RemoteId, RemoteRepository, Remotes, ValidateRepository, Validations,
+    git::Validation, ReadRepository as _, Remote, RemoteId, RemoteRepository, Remotes,
+    ValidateRepository, Validations,
 };
 
 use crate::git;
 use crate::git::refs::{Applied, Update};
 use crate::git::repository;
+use crate::policy::Allowed;
 use crate::sigrefs::SignedRefsAt;
 use crate::stage;
 use crate::stage::ProtocolStage;
@@ -160,6 +162,8 @@ pub struct FetchState {
     sigrefs: SigrefTips,
     /// Seen reference tips, per remote.
     tips: BTreeMap<PublicKey, Vec<Update<'static>>>,
+    /// The amount of history fetched for data refs.
+    history: stage::History,
 }
 
 impl FetchState {
@@ -294,7 +298,11 @@ impl FetchState {
         S: transport::ConnectionStream,
     {
         match refs_at {
-            Some(refs_at) => {
+            Some(mut refs_at) => {
+                // N.b. partial fetches only replicate delegates.
+                if handle.depth.is_some() {
+                    refs_at.retain(|r| delegates.contains(&r.remote));
+                }
                 let sigrefs_at = stage::SigrefsAt {
                     remote,
                     delegates: delegates.clone(),
@@ -310,7 +318,14 @@ impl FetchState {
                 Ok(signed_refs)
             }
             None => {
-                let followed = handle.allowed();
+                // N.b. partial fetches only replicate delegates.
+                let followed = if handle.depth.is_some() {
+                    Allowed::Followed {
+                        remotes: HashSet::new(),
+                    }
+                } else {
+                    handle.allowed()
+                };
                 log::trace!(target: "fetch", "Followed nodes {:?}", followed);
                 let special_refs = stage::SpecialRefs {
                     blocked: handle.blocked.clone(),
@@ -417,9 +432,26 @@ impl FetchState {
             start.elapsed().as_millis()
         );
 
+        self.history = match handle.depth {
+            Some(depth) => match anchor.project() {
+                Ok(proj) => stage::History::Shallow {
+                    branch: radicle::git::refs::branch(proj.default_branch()).to_ref_string(),
+                    depth,
+                },
+                Err(e) => {
+                    log::warn!(target: "fetch", "Fetching full history, no canonical branch found: {e}");
+                    stage::History::Full
+                }
+            },
+            None if handle.repo.is_partial() => stage::History::Unshallow,
+            None => stage::History::Full,
+        };
+        log::trace!(target: "fetch", "Fetching data refs with history {:?}", self.history);
+
         let data_refs = stage::DataRefs {
             remote,
             remotes: signed_refs,
+            history: self.history.clone(),
             limit: limit.refs,
         };
         self.run_stage(handle, handshake, &data_refs)?;
@@ -712,7 +744,12 @@ impl<'a, S> ValidateRepository for Cached<'a, S> {
 
         // The refs that are left in the map, are ones that were signed, but are not
         // in the repository. If any are left, bail.
-        for (name, _) in signed.into_iter() {
+        //
+        // N.b. refs that were not fetched due to a partial fetch are expected to be missing.
+        for (name, _) in signed
+            .into_iter()
+            .filter(|(name, _)| self.state.history.includes(name))
+        {
             validations.push(Validation::MissingRef {
                 refname: name,
                 remote: remote.id,
@@ -1,14 +1,17 @@
 pub(crate) mod fetch;
+pub mod local;
 pub(crate) mod ls_refs;
 
 use std::collections::BTreeSet;
-use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::str::FromStr as _;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
+use std::{fs, io};
 
 use bstr::BString;
 use gix_features::progress::prodash::progress;
+use gix_protocol::fetch::response::ShallowUpdate;
 use gix_protocol::handshake;
 use gix_protocol::FetchConnection;
 use gix_transport::client;
@@ -23,6 +26,10 @@ use thiserror::Error;
 use crate::git::oid;
 use crate::git::repository;
 
+/// The `deepen` value used to fetch the complete history of a partial
+/// repository. This is the same value used by `git fetch --unshallow`.
+pub(crate) const INFINITE_DEPTH: usize = 0x7fffffff;
+
 /// Open a reader and writer stream to pass to the ls-refs and fetch
 /// processes for communicating during their respective protocols.
 pub trait ConnectionStream {
@@ -117,15 +124,19 @@ where
     /// Perform the fetch with the server side.
     pub(crate) fn fetch(
         &mut self,
-        wants_haves: WantsHaves,
+        mut wants_haves: WantsHaves,
         interrupt: Arc<AtomicBool>,
         handshake: &handshake::Outcome,
     ) -> io::Result<()> {
+        if wants_haves.deepen.is_some() {
+            wants_haves.shallow = shallow::read(&self.git_dir)?;
+        }
         log::trace!(
             target: "fetch",
-            "Running fetch wants={:?}, haves={:?}",
+            "Running fetch wants={:?}, haves={:?}, deepen={:?}",
             wants_haves.wants,
-            wants_haves.haves
+            wants_haves.haves,
+            wants_haves.deepen,
         );
         let out = {
             let (read, write) = self.stream.open().map_err(io_other)?;
@@ -143,6 +154,7 @@ where
         };
         let pack_path = out
             .pack
+            .as_ref()
             .ok_or_else(|| {
                 io::Error::new(
                     io::ErrorKind::UnexpectedEof,
@@ -150,18 +162,22 @@ where
                 )
             })?
             .index_path
+            .as_ref()
             .expect("written packfile must have a path");
 
         // Validate we got all requested tips in the pack
         //
         // N.b. the lookup is a binary search so is efficient for
         // searching any given oid.
+        //
+        // Shallow commits that we already have are not sent again
+        // when deepening, so they are not expected in the pack.
         {
             use gix_pack::index::File;
 
             let idx = File::at(pack_path, gix_hash::Kind::Sha1).map_err(io_other)?;
-            for oid in wants_haves.wants {
-                if idx.lookup(oid::to_object_id(oid)).is_none() {
+            for oid in wants_haves.wants.difference(&wants_haves.shallow) {
+                if idx.lookup(oid::to_object_id(*oid)).is_none() {
                     return Err(io::Error::new(
                         io::ErrorKind::NotFound,
                         format!("wanted {oid} not found in pack"),
@@ -170,6 +186,17 @@ where
             }
         }
 
+        if !out.shallow.is_empty() {
+            let mut commits = wants_haves.shallow;
+            for update in out.shallow {
+                match update {
+                    ShallowUpdate::Shallow(oid) => commits.insert(oid::to_oid(oid)),
+                    ShallowUpdate::Unshallow(oid) => commits.remove(&oid::to_oid(oid)),
+                };
+            }
+            shallow::write(&self.git_dir, &commits)?;
+        }
+
         Ok(())
     }
 
@@ -285,6 +312,12 @@ pub enum WantsHavesError {
 pub(crate) struct WantsHaves {
     pub wants: BTreeSet<Oid>,
     pub haves: BTreeSet<Oid>,
+    /// Limit the history fetched to the given number of commits from
+    /// each of the `wants`.
+    pub deepen: Option<usize>,
+    /// The commits at which our history is cut off, if the repository
+    /// is partial.
+    pub shallow: BTreeSet<Oid>,
 }
 
 impl WantsHaves {
@@ -343,6 +376,44 @@ impl WantsHaves {
     }
 }
 
+/// Reading and writing of the Git `shallow` file, which lists the
+/// commits at which the history of a partial repository is cut off.
+mod shallow {
+    use std::io::Write as _;
+
+    use super::*;
+
+    /// Read the set of shallow commits of the repository at `git_dir`.
+    pub fn read(git_dir: &Path) -> io::Result<BTreeSet<Oid>> {
+        let contents = match fs::read_to_string(git_dir.join("shallow")) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(BTreeSet::new()),
+            Err(e) => return Err(e),
+        };
+        contents
+            .lines()
+            .map(|line| Oid::from_str(line.trim()).map_err(io_other))
+            .collect()
+    }
+
+    /// Write the set of shallow commits of the repository at
+    /// `git_dir`, removing the file if the set is empty.
+    pub fn write(git_dir: &Path, commits: &BTreeSet<Oid>) -> io::Result<()> {
+        let path = git_dir.join("shallow");
+        if commits.is_empty() {
+            return match fs::remove_file(path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            };
+        }
+        let mut file = io::BufWriter::new(fs::File::create(path)?);
+        for oid in commits {
+            writeln!(file, "{oid}")?;
+        }
+        file.flush()
+    }
+}
+
 fn agent_name() -> io::Result<String> {
     Ok(format!(
         "git/{}",
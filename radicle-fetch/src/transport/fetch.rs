@@ -104,6 +104,9 @@ pub struct Fetch {
 pub struct FetchOut {
     pub refs: Vec<Ref>,
     pub pack: Option<pack::bundle::write::Outcome>,
+    /// The changes to the set of shallow commits, if the fetch was
+    /// deepened.
+    pub shallow: Vec<fetch::response::ShallowUpdate>,
 }
 
 // FIXME: the delegate pattern will be removed in the near future and
@@ -125,6 +128,9 @@ impl<'a> Delegate for &'a mut Fetch {
                     object: *id,
                 },
             ));
+        self.out
+            .shallow
+            .extend_from_slice(previous_response.shallow_updates());
         let pack = self
             .pack_writer
             .write_pack(input, progress)
@@ -151,6 +157,19 @@ impl<'a> DelegateBlocking for &'a mut Fetch {
             arguments.have(oid::to_object_id(*oid));
         }
 
+        if let Some(depth) = self.wants_haves.deepen {
+            if !arguments.can_use_deepen() {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "server does not support shallow fetches",
+                ));
+            }
+            for oid in &self.wants_haves.shallow {
+                arguments.shallow(oid::to_object_id(*oid));
+            }
+            arguments.deepen(depth);
+        }
+
         // N.b. sends `done` packet
         Ok(fetch::Action::Cancel)
     }
@@ -206,6 +225,7 @@ where
         out: FetchOut {
             refs: Vec::new(),
             pack: None,
+            shallow: Vec::new(),
         },
     };
 
@@ -0,0 +1,163 @@
+use std::io;
+use std::path::Path;
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+use super::{ConnectionStream, SignalEof};
+
+/// A [`ConnectionStream`] served by a `git upload-pack` process
+/// running on a local repository.
+///
+/// This allows running the fetch protocol, and thus its validation,
+/// against repositories that are not served by a remote node, e.g. a
+/// repository unpacked from a bundle.
+pub struct UploadPack {
+    child: Child,
+    stdout: ChildStdout,
+    stdin: Stdin,
+}
+
+impl UploadPack {
+    /// Spawn `git upload-pack` on the repository found at `git_dir`.
+    pub fn spawn(git_dir: &Path) -> io::Result<Self> {
+        let mut child = Command::new("git")
+            .current_dir(git_dir)
+            .env_clear()
+            .envs(std::env::vars().filter(|(key, _)| key == "PATH" || key.starts_with("GIT_TRACE")))
+            .env("GIT_PROTOCOL", "version=2")
+            .args([
+                "-c",
+                "uploadpack.allowAnySha1InWant=true",
+                "-c",
+                "uploadpack.allowRefInWant=true",
+                "-c",
+                "lsrefs.unborn=ignore",
+                "upload-pack",
+                "--strict",
+                ".",
+            ])
+            .stdout(Stdio::piped())
+            .stdin(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()?;
+
+        let stdin = child.stdin.take().expect("upload-pack stdin is piped");
+        let stdout = child.stdout.take().expect("upload-pack stdout is piped");
+
+        Ok(Self {
+            child,
+            stdout,
+            stdin: Stdin {
+                inner: Some(stdin),
+                header: Header::default(),
+            },
+        })
+    }
+}
+
+impl ConnectionStream for UploadPack {
+    type Read = ChildStdout;
+    type Write = Stdin;
+    type Error = io::Error;
+
+    fn open(&mut self) -> Result<(&mut Self::Read, &mut Self::Write), Self::Error> {
+        Ok((&mut self.stdout, &mut self.stdin))
+    }
+}
+
+impl Drop for UploadPack {
+    fn drop(&mut self) {
+        // N.b. the process normally exits once its input is closed,
+        // but make sure it doesn't outlive the fetch if it was
+        // aborted.
+        self.stdin.inner.take();
+        if let Err(e) = self.child.kill() {
+            log::trace!(target: "fetch", "Failed to kill upload-pack process: {e}");
+        }
+        self.child.wait().ok();
+    }
+}
+
+/// The input of the `git upload-pack` process.
+///
+/// The client starts the exchange with a `git-daemon` style request
+/// line, naming the service and repository. It is consumed here, in
+/// place of the daemon, since `upload-pack` doesn't expect it.
+pub struct Stdin {
+    inner: Option<ChildStdin>,
+    header: Header,
+}
+
+/// The state of the request line.
+enum Header {
+    /// The request line is still being read, and is buffered here.
+    Reading(Vec<u8>),
+    /// The request line was consumed, and the remaining input is
+    /// forwarded as is.
+    Done,
+}
+
+impl Default for Header {
+    fn default() -> Self {
+        Self::Reading(Vec::new())
+    }
+}
+
+impl Stdin {
+    fn inner(&mut self) -> io::Result<&mut ChildStdin> {
+        self.inner
+            .as_mut()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::BrokenPipe, "upload-pack input closed"))
+    }
+}
+
+impl io::Write for Stdin {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let Header::Reading(line) = &mut self.header else {
+            return self.inner()?.write(buf);
+        };
+        line.extend_from_slice(buf);
+
+        let Some(len) = line.get(..4) else {
+            return Ok(buf.len());
+        };
+        let len = std::str::from_utf8(len)
+            .ok()
+            .and_then(|len| usize::from_str_radix(len, 16).ok())
+            .filter(|len| *len > 4)
+            .ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "invalid git request line")
+            })?;
+        if line.len() < len {
+            return Ok(buf.len());
+        }
+        if !line[4..len].starts_with(b"git-upload-pack ") {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "only the upload-pack service is supported",
+            ));
+        }
+        let rest = line.split_off(len);
+
+        self.header = Header::Done;
+        self.inner()?.write_all(&rest)?;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.inner.as_mut() {
+            Some(stdin) => stdin.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl SignalEof for Stdin {
+    type Error = io::Error;
+
+    /// Closing the input of `upload-pack` ends the process.
+    fn eof(&mut self) -> Result<(), Self::Error> {
+        self.inner.take();
+        Ok(())
+    }
+}
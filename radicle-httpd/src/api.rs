@@ -1,6 +1,5 @@
 pub mod auth;
 
-use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -14,11 +13,12 @@ use radicle::patch::cache::Patches as _;
 use radicle::storage::git::Repository;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use tokio::sync::RwLock;
 use tower_http::cors::{self, CorsLayer};
 
 use radicle::cob::issue;
 use radicle::cob::patch;
+use radicle::cob::query::Query;
+use radicle::dashboard::{Category, SortBy};
 use radicle::identity::{DocAt, RepoId};
 use radicle::node::policy::Scope;
 use radicle::node::routing::Store;
@@ -36,23 +36,24 @@ use crate::Options;
 
 pub const VERSION: &str = env!("RADICLE_VERSION");
 
-/// Identifier for sessions
-type SessionId = String;
-
 #[derive(Clone)]
 pub struct Context {
     profile: Arc<Profile>,
-    sessions: Arc<RwLock<HashMap<SessionId, auth::Session>>>,
+    sessions: auth::Store,
     cache: Option<Cache>,
+    events: v1::events::Events,
 }
 
 impl Context {
-    pub fn new(profile: Arc<Profile>, options: &Options) -> Self {
-        Self {
+    pub fn new(profile: Arc<Profile>, options: &Options) -> Result<Self, auth::store::Error> {
+        let sessions = auth::Store::open(profile.home.node().join(auth::SESSIONS_DB_FILE))?;
+
+        Ok(Self {
             profile,
-            sessions: Default::default(),
+            sessions,
             cache: options.cache.map(Cache::new),
-        }
+            events: v1::events::Events::default(),
+        })
     }
 
     pub fn project_info<R: ReadRepository + radicle::cob::Store>(
@@ -83,6 +84,23 @@ impl Context {
         })
     }
 
+    /// Get the query of an issue or patch listing, adding the terms of the given query
+    /// saved in the profile configuration.
+    pub fn query(&self, q: Option<Query>, saved: Option<&str>) -> Result<Option<Query>, Error> {
+        let Some(name) = saved else {
+            return Ok(q);
+        };
+        let saved = self
+            .profile
+            .config
+            .queries
+            .get(name)
+            .cloned()
+            .ok_or(Error::NotFound)?;
+
+        Ok(Some(q.unwrap_or_default().and(saved)))
+    }
+
     /// Get a repository by RID, checking to make sure we're allowed to view it.
     pub fn repo(&self, rid: RepoId) -> Result<(Repository, DocAt), error::Error> {
         let repo = self.profile.storage.repository(rid)?;
@@ -99,10 +117,13 @@ impl Context {
         &self.profile
     }
 
-    #[cfg(test)]
-    pub fn sessions(&self) -> &Arc<RwLock<HashMap<SessionId, auth::Session>>> {
+    pub fn sessions(&self) -> &auth::Store {
         &self.sessions
     }
+
+    pub fn events(&self) -> &v1::events::Events {
+        &self.events
+    }
 }
 
 pub fn router(ctx: Context) -> Router {
@@ -167,7 +188,60 @@ pub struct RawQuery {
 pub struct CobsQuery<T> {
     pub page: Option<usize>,
     pub per_page: Option<usize>,
+    /// Only return objects in this state. Defaults to open objects, or objects in any
+    /// state if a query is given.
     pub state: Option<T>,
+    /// Only return objects matching this query.
+    pub q: Option<Query>,
+    /// Only return objects matching the query saved under this name.
+    pub saved: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscussionsQuery {
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+    /// Only return discussions in this category.
+    pub category: Option<radicle::cob::discussion::Category>,
+    /// Only return pinned discussions.
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WikiQuery {
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+    /// Only return the page with this path.
+    pub path: Option<radicle::cob::wiki::PagePath>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationsQuery {
+    /// Only return notifications for this repository.
+    pub rid: Option<RepoId>,
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EventsQuery {
+    /// Only relay events about this repository.
+    pub rid: Option<RepoId>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardQuery {
+    /// Only return items of this category.
+    pub category: Option<Category>,
+    /// Only return items of this repository.
+    pub rid: Option<RepoId>,
+    pub sort_by: Option<SortBy>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
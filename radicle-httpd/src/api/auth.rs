@@ -1,3 +1,9 @@
+pub mod store;
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
 use time::serde::timestamp;
 use time::{Duration, OffsetDateTime};
@@ -8,37 +14,183 @@ use radicle::node::Alias;
 use crate::api::error::Error;
 use crate::api::Context;
 
+pub use store::Store;
+
 pub const UNAUTHORIZED_SESSIONS_EXPIRATION: Duration = Duration::seconds(60);
 pub const AUTHORIZED_SESSIONS_EXPIRATION: Duration = Duration::weeks(1);
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+/// Sessions database file name, under the profile's node directory.
+pub const SESSIONS_DB_FILE: &str = "sessions.db";
+
+/// Identifier for sessions, also used as bearer token.
+pub type SessionId = String;
+
+/// Generate a new random session identifier, using the operating system's random source.
+pub fn session_id() -> SessionId {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+    const LEN: usize = 32;
+
+    let mut id = String::with_capacity(LEN);
+    let mut buf = [0u8; LEN];
+
+    while id.len() < LEN {
+        getrandom::getrandom(&mut buf).expect("auth::session_id: random source is available");
+        // Bytes above the largest multiple of the alphabet size are discarded, so that
+        // every character is equally likely.
+        id.extend(
+            buf.iter()
+                .filter(|b| (**b as usize) < ALPHABET.len() * (256 / ALPHABET.len()))
+                .map(|b| ALPHABET[*b as usize % ALPHABET.len()] as char)
+                .take(LEN - id.len()),
+        );
+    }
+    id
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum AuthState {
     Authorized,
     Unauthorized,
 }
 
-#[derive(Clone, Deserialize)]
+impl fmt::Display for AuthState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Authorized => write!(f, "authorized"),
+            Self::Unauthorized => write!(f, "unauthorized"),
+        }
+    }
+}
+
+impl FromStr for AuthState {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "authorized" => Ok(Self::Authorized),
+            "unauthorized" => Ok(Self::Unauthorized),
+            _ => Err(ParseError(s.to_owned())),
+        }
+    }
+}
+
+/// What a session or API token is allowed to do.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    /// Read-only access to authenticated endpoints.
+    Read,
+    /// Create and update issues.
+    Issues,
+    /// Create and update patches.
+    Patches,
+    /// Create and update discussions.
+    Discussions,
+    /// Create and edit wiki pages.
+    Wiki,
+    /// Full access, including the node's policies.
+    Admin,
+}
+
+impl Scope {
+    /// Check whether this scope grants the required scope.
+    /// Any scope grants read access, and the admin scope grants everything.
+    pub fn allows(&self, required: Scope) -> bool {
+        *self == required || *self == Self::Admin || required == Self::Read
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read => write!(f, "read"),
+            Self::Issues => write!(f, "issues"),
+            Self::Patches => write!(f, "patches"),
+            Self::Discussions => write!(f, "discussions"),
+            Self::Wiki => write!(f, "wiki"),
+            Self::Admin => write!(f, "admin"),
+        }
+    }
+}
+
+impl FromStr for Scope {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read" => Ok(Self::Read),
+            "issues" => Ok(Self::Issues),
+            "patches" => Ok(Self::Patches),
+            "discussions" => Ok(Self::Discussions),
+            "wiki" => Ok(Self::Wiki),
+            "admin" => Ok(Self::Admin),
+            _ => Err(ParseError(s.to_owned())),
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("invalid value '{0}'")]
+pub struct ParseError(String);
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct Session {
     pub status: AuthState,
     pub public_key: PublicKey,
     pub alias: Alias,
+    /// Scopes granted to this session.
+    pub scopes: BTreeSet<Scope>,
+    /// Name of the API token, if this session is one.
+    #[serde(default)]
+    pub label: Option<String>,
     #[serde(with = "timestamp")]
     pub issued_at: OffsetDateTime,
     #[serde(with = "timestamp")]
     pub expires_at: OffsetDateTime,
 }
 
-pub async fn validate(ctx: &Context, token: &str) -> Result<(), Error> {
-    let sessions_store = ctx.sessions.read().await;
-    let session = sessions_store
-        .get(token)
+impl Session {
+    /// Check whether the session grants the required scope.
+    pub fn allows(&self, required: Scope) -> bool {
+        self.scopes.iter().any(|s| s.allows(required))
+    }
+
+    /// Check whether the session is authorized and not expired.
+    pub fn is_valid(&self, now: OffsetDateTime) -> bool {
+        self.status == AuthState::Authorized && self.expires_at > now
+    }
+}
+
+pub async fn validate(ctx: &Context, token: &str, scope: Scope) -> Result<(), Error> {
+    let session = ctx
+        .sessions
+        .get(token)?
         .ok_or(Error::Auth("Unauthorized"))?;
 
-    if session.status != AuthState::Authorized || session.expires_at <= OffsetDateTime::now_utc() {
+    if !session.is_valid(OffsetDateTime::now_utc()) {
         return Err(Error::Auth("Unauthorized"));
     }
+    if !session.allows(scope) {
+        return Err(Error::Forbidden("Insufficient scope"));
+    }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_scope_allows() {
+        assert!(Scope::Read.allows(Scope::Read));
+        assert!(!Scope::Read.allows(Scope::Issues));
+        assert!(Scope::Issues.allows(Scope::Read));
+        assert!(Scope::Issues.allows(Scope::Issues));
+        assert!(!Scope::Issues.allows(Scope::Patches));
+        assert!(!Scope::Patches.allows(Scope::Admin));
+        assert!(Scope::Admin.allows(Scope::Patches));
+    }
+}
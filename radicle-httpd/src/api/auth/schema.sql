@@ -0,0 +1,31 @@
+--
+-- HTTP daemon sessions database.
+--
+
+-- Browser sessions and API tokens.
+create table if not exists "sessions" (
+  -- SHA-256 hash of the session ID, in hex. The ID itself is used as bearer
+  -- token, and is never stored.
+  "id"                 text      primary key not null,
+  -- Whether the session was authorized.
+  --
+  -- Valid values are:
+  --
+  -- "authorized"      the session was signed in, or is an API token.
+  -- "unauthorized"    the session is waiting to be signed in.
+  --
+  "status"             text      not null,
+  -- Public key of the session owner.
+  "public_key"         text      not null,
+  -- Alias of the session owner.
+  "alias"              text      not null,
+  -- Scopes granted to the session, separated by commas.
+  "scopes"             text      not null,
+  -- Name of the API token. Browser sessions have no label.
+  "label"              text,
+  -- Time at which the session was issued, in seconds since the epoch.
+  "issued_at"          integer   not null,
+  -- Time at which the session expires, in seconds since the epoch.
+  "expires_at"         integer   not null
+  --
+) strict;
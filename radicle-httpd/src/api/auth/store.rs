@@ -0,0 +1,244 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use sha2::{Digest, Sha256};
+use sqlite as sql;
+use thiserror::Error;
+use time::OffsetDateTime;
+
+use radicle::crypto::PublicKeyError;
+use radicle::node::AliasError;
+
+use super::{AuthState, Scope, Session};
+
+/// How long to wait for the database lock to be released before failing.
+const DB_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(6);
+
+#[derive(Error, Debug)]
+pub enum Error {
+    /// An Internal error.
+    #[error("internal error: {0}")]
+    Internal(#[from] sql::Error),
+    /// A stored session could not be decoded.
+    #[error("invalid session {hash}: {reason}")]
+    Invalid { hash: String, reason: String },
+}
+
+/// Persistent store of sessions and API tokens.
+///
+/// Session ids are bearer tokens, so only their SHA-256 hash is stored, and sessions
+/// are looked up by the hash of the token presented.
+#[derive(Clone)]
+pub struct Store {
+    db: Arc<sql::ConnectionThreadSafe>,
+}
+
+impl fmt::Debug for Store {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Store(..)")
+    }
+}
+
+impl Store {
+    const SCHEMA: &'static str = include_str!("schema.sql");
+
+    /// Open a sessions database at the given path. Creates the database if it doesn't exist.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let mut db = sql::Connection::open_thread_safe(path)?;
+        db.set_busy_timeout(DB_TIMEOUT.as_millis() as usize)?;
+        db.execute(Self::SCHEMA)?;
+
+        Ok(Self { db: Arc::new(db) })
+    }
+
+    /// Hash a session id, as stored in the database.
+    pub fn hash(id: &str) -> String {
+        Sha256::digest(id.as_bytes())
+            .iter()
+            .fold(String::new(), |mut hash, b| {
+                let _ = write!(hash, "{b:02x}");
+                hash
+            })
+    }
+
+    /// Get a session.
+    pub fn get(&self, id: &str) -> Result<Option<Session>, Error> {
+        let mut stmt = self.db.prepare(
+            "SELECT id, status, public_key, alias, scopes, label, issued_at, expires_at
+             FROM `sessions` WHERE id = ?",
+        )?;
+        stmt.bind((1, Self::hash(id).as_str()))?;
+
+        if let Some(row) = stmt.into_iter().next() {
+            let (_, session) = Self::session_from_row(&row?)?;
+
+            return Ok(Some(session));
+        }
+        Ok(None)
+    }
+
+    /// Insert or replace a session.
+    pub fn insert(&self, id: &str, session: &Session) -> Result<(), Error> {
+        let mut stmt = self.db.prepare(
+            "INSERT OR REPLACE INTO `sessions`
+             (id, status, public_key, alias, scopes, label, issued_at, expires_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )?;
+        let scopes = session
+            .scopes
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+
+        stmt.bind((1, Self::hash(id).as_str()))?;
+        stmt.bind((2, session.status.to_string().as_str()))?;
+        stmt.bind((3, session.public_key.to_human().as_str()))?;
+        stmt.bind((4, session.alias.as_ref()))?;
+        stmt.bind((5, scopes.as_str()))?;
+        stmt.bind((6, session.label.as_deref()))?;
+        stmt.bind((7, session.issued_at.unix_timestamp()))?;
+        stmt.bind((8, session.expires_at.unix_timestamp()))?;
+        stmt.next()?;
+
+        Ok(())
+    }
+
+    /// Remove a session. Returns whether the session existed.
+    pub fn remove(&self, id: &str) -> Result<bool, Error> {
+        self.remove_hash(&Self::hash(id))
+    }
+
+    /// Remove a session given the hash of its id, as returned by [`Store::list`].
+    /// Returns whether the session existed.
+    pub fn remove_hash(&self, hash: &str) -> Result<bool, Error> {
+        let mut stmt = self.db.prepare("DELETE FROM `sessions` WHERE id = ?")?;
+
+        stmt.bind((1, hash))?;
+        stmt.next()?;
+
+        Ok(self.db.change_count() > 0)
+    }
+
+    /// Get all sessions, oldest first, along with the hash of their id.
+    pub fn list(&self) -> Result<Vec<(String, Session)>, Error> {
+        let stmt = self.db.prepare(
+            "SELECT id, status, public_key, alias, scopes, label, issued_at, expires_at
+             FROM `sessions` ORDER BY issued_at, id",
+        )?;
+        let mut sessions = Vec::new();
+
+        for row in stmt.into_iter() {
+            sessions.push(Self::session_from_row(&row?)?);
+        }
+        Ok(sessions)
+    }
+
+    /// Remove sessions that expired before the given time.
+    /// Returns the number of sessions removed.
+    pub fn prune(&self, now: OffsetDateTime) -> Result<usize, Error> {
+        let mut stmt = self
+            .db
+            .prepare("DELETE FROM `sessions` WHERE expires_at <= ?")?;
+
+        stmt.bind((1, now.unix_timestamp()))?;
+        stmt.next()?;
+
+        Ok(self.db.change_count())
+    }
+
+    fn session_from_row(row: &sql::Row) -> Result<(String, Session), Error> {
+        let id = row.try_read::<&str, _>("id")?.to_owned();
+        let invalid = |reason: String| Error::Invalid {
+            hash: id.clone(),
+            reason,
+        };
+        let status = AuthState::from_str(row.try_read::<&str, _>("status")?)
+            .map_err(|e| invalid(e.to_string()))?;
+        let public_key = row
+            .try_read::<&str, _>("public_key")?
+            .parse()
+            .map_err(|e: PublicKeyError| invalid(e.to_string()))?;
+        let alias = row
+            .try_read::<&str, _>("alias")?
+            .parse()
+            .map_err(|e: AliasError| invalid(e.to_string()))?;
+        let scopes = row
+            .try_read::<&str, _>("scopes")?
+            .split(',')
+            .filter(|s| !s.is_empty())
+            .map(Scope::from_str)
+            .collect::<Result<BTreeSet<_>, _>>()
+            .map_err(|e| invalid(e.to_string()))?;
+        let label = row.try_read::<Option<&str>, _>("label")?.map(String::from);
+        let issued_at = OffsetDateTime::from_unix_timestamp(row.try_read("issued_at")?)
+            .map_err(|e| invalid(e.to_string()))?;
+        let expires_at = OffsetDateTime::from_unix_timestamp(row.try_read("expires_at")?)
+            .map_err(|e| invalid(e.to_string()))?;
+
+        Ok((
+            id,
+            Session {
+                status,
+                public_key,
+                alias,
+                scopes,
+                label,
+                issued_at,
+                expires_at,
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sessions() {
+        let db = Store::open(":memory:").unwrap();
+        let now = OffsetDateTime::from_unix_timestamp(1700000000).unwrap();
+        let session = Session {
+            status: AuthState::Authorized,
+            public_key: "z6MknSLrJoTcukLrE435hVNQT4JUhbvWLX4kUzqkEStBU8Vi"
+                .parse()
+                .unwrap(),
+            alias: "alice".parse().unwrap(),
+            scopes: BTreeSet::from([Scope::Issues, Scope::Patches]),
+            label: Some(String::from("ci")),
+            issued_at: now,
+            expires_at: now + time::Duration::hours(1),
+        };
+        let expired = Session {
+            status: AuthState::Unauthorized,
+            scopes: BTreeSet::from([Scope::Admin]),
+            label: None,
+            expires_at: now,
+            ..session.clone()
+        };
+
+        assert_eq!(db.get("token").unwrap(), None);
+        db.insert("token", &session).unwrap();
+        db.insert("expired", &expired).unwrap();
+        assert_eq!(db.get("token").unwrap(), Some(session.clone()));
+        // Sessions issued at the same time are ordered by hash.
+        let mut sessions = vec![
+            (Store::hash("expired"), expired),
+            (Store::hash("token"), session),
+        ];
+        sessions.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        assert_eq!(db.list().unwrap(), sessions);
+        assert!(db.list().unwrap().iter().all(|(id, _)| id != "token"));
+        assert_eq!(db.prune(now).unwrap(), 1);
+        assert!(db.remove("token").unwrap());
+        assert!(!db.remove("token").unwrap());
+        assert!(!db.remove_hash(&Store::hash("token")).unwrap());
+        assert!(db.list().unwrap().is_empty());
+    }
+}
@@ -14,6 +14,18 @@ pub enum Error {
     #[error("could not authenticate: {0}")]
     Auth(&'static str),
 
+    /// The session isn't allowed to perform the request.
+    #[error("forbidden: {0}")]
+    Forbidden(&'static str),
+
+    /// The service is temporarily unable to handle the request.
+    #[error("unavailable: {0}")]
+    Unavailable(&'static str),
+
+    /// Sessions store error.
+    #[error(transparent)]
+    Sessions(#[from] crate::api::auth::store::Error),
+
     /// An error occurred with env variables.
     #[error(transparent)]
     Env(#[from] std::env::VarError),
@@ -46,6 +58,14 @@ pub enum Error {
     #[error(transparent)]
     CobPatch(#[from] radicle::cob::patch::Error),
 
+    /// Cob discussion error.
+    #[error(transparent)]
+    CobDiscussion(#[from] radicle::cob::discussion::Error),
+
+    /// Cob wiki error.
+    #[error(transparent)]
+    CobWiki(#[from] radicle::cob::wiki::Error),
+
     /// Cob patch cache error.
     #[error(transparent)]
     CachePatch(#[from] radicle::cob::patch::cache::Error),
@@ -54,6 +74,10 @@ pub enum Error {
     #[error(transparent)]
     CobStore(#[from] radicle::cob::store::Error),
 
+    /// COB evaluation error.
+    #[error(transparent)]
+    CobExternal(#[from] radicle::cob::external::Error),
+
     /// Repository error.
     #[error(transparent)]
     Repository(#[from] radicle::storage::RepositoryError),
@@ -90,6 +114,14 @@ pub enum Error {
     #[error(transparent)]
     TrackingStore(#[from] radicle::node::policy::store::Error),
 
+    /// Notifications store error.
+    #[error(transparent)]
+    Notifications(#[from] radicle::node::notifications::store::Error),
+
+    /// Dashboard error.
+    #[error(transparent)]
+    Dashboard(#[from] radicle::dashboard::Error),
+
     /// Node database error.
     #[error(transparent)]
     Database(#[from] radicle::node::db::Error),
@@ -111,7 +143,22 @@ impl IntoResponse for Error {
             Error::CobStore(e @ radicle::cob::store::Error::NotFound(_, _)) => {
                 (StatusCode::NOT_FOUND, Some(e.to_string()))
             }
+            Error::CobStore(e @ radicle::cob::store::Error::Builtin(_)) => {
+                (StatusCode::BAD_REQUEST, Some(e.to_string()))
+            }
+            Error::CobExternal(e @ radicle::cob::external::Error::NotRegistered(_)) => {
+                (StatusCode::NOT_FOUND, Some(e.to_string()))
+            }
+            Error::CobWiki(e @ radicle::cob::wiki::Error::PathExists(_)) => {
+                (StatusCode::CONFLICT, Some(e.to_string()))
+            }
+            Error::CobWiki(
+                e @ (radicle::cob::wiki::Error::MissingRevision(_)
+                | radicle::cob::wiki::Error::RedactedRevision(_)),
+            ) => (StatusCode::NOT_FOUND, Some(e.to_string())),
             Error::Auth(msg) => (StatusCode::UNAUTHORIZED, Some(msg.to_string())),
+            Error::Forbidden(msg) => (StatusCode::FORBIDDEN, Some(msg.to_string())),
+            Error::Unavailable(msg) => (StatusCode::SERVICE_UNAVAILABLE, Some(msg.to_string())),
             Error::Crypto(msg) => (StatusCode::BAD_REQUEST, Some(msg.to_string())),
             Error::Surf(radicle_surf::Error::Git(e)) if radicle::git::is_not_found_err(&e) => {
                 (StatusCode::NOT_FOUND, Some(e.message().to_owned()))
@@ -136,6 +183,15 @@ impl IntoResponse for Error {
                 (StatusCode::NOT_FOUND, Some(err.to_string()))
             }
             Error::BadRequest(msg) => (StatusCode::BAD_REQUEST, Some(msg)),
+            Error::Node(e @ radicle::node::Error::Connect(..)) => {
+                (StatusCode::SERVICE_UNAVAILABLE, Some(e.to_string()))
+            }
+            Error::CacheIssue(e @ radicle::cob::issue::cache::Error::Query(_)) => {
+                (StatusCode::BAD_REQUEST, Some(e.to_string()))
+            }
+            Error::CachePatch(e @ radicle::cob::patch::cache::Error::Query(_)) => {
+                (StatusCode::BAD_REQUEST, Some(e.to_string()))
+            }
             other => {
                 tracing::error!("Error: {message}");
                 tracing::debug!("Error Debug: {:?}", other);
@@ -9,13 +9,17 @@ use radicle::cob::{CodeLocation, Reaction};
 use radicle::patch::ReviewId;
 use serde_json::{json, Value};
 
-use radicle::cob::issue::{Issue, IssueId};
+use radicle::cob::discussion::{Discussion, DiscussionId};
+use radicle::cob::issue::{Issue, IssueId, Task, TaskId};
 use radicle::cob::patch::{Merge, Patch, PatchId, Review};
 use radicle::cob::thread::{Comment, CommentId, Edit};
-use radicle::cob::{ActorId, Author};
+use radicle::cob::wiki::{Page, PageId, Revision, RevisionId};
+use radicle::cob::{ActorId, Author, TypedId};
+use radicle::dashboard;
 use radicle::git::RefString;
+use radicle::node::notifications::{Activity, Notification, NotificationKind};
 use radicle::node::{Alias, AliasStore};
-use radicle::prelude::NodeId;
+use radicle::prelude::{Did, NodeId};
 use radicle::storage::{git, refs, RemoteRepository};
 use radicle_surf::blob::Blob;
 use radicle_surf::tree::{EntryKind, Tree};
@@ -49,6 +53,8 @@ pub(crate) fn session(session_id: String, session: &Session) -> Value {
       "status": session.status,
       "publicKey": session.public_key,
       "alias": session.alias,
+      "scopes": session.scopes,
+      "label": session.label,
       "issuedAt": session.issued_at.unix_timestamp(),
       "expiresAt": session.expires_at.unix_timestamp()
     })
@@ -114,9 +120,66 @@ pub(crate) fn issue(id: IssueId, issue: Issue, aliases: &impl AliasStore) -> Val
         ).collect::<Vec<_>>(),
         "discussion": issue.comments().map(|(id, c)| issue_comment(id, c, aliases)).collect::<Vec<_>>(),
         "labels": issue.labels().collect::<Vec<_>>(),
+        "tasks": issue.tasks().map(|(id, t)| task(id, t, aliases)).collect::<Vec<_>>(),
     })
 }
 
+/// Returns JSON for a `discussion`.
+pub(crate) fn discussion(
+    id: DiscussionId,
+    discussion: Discussion,
+    aliases: &impl AliasStore,
+) -> Value {
+    json!({
+        "id": id.to_string(),
+        "author": author(&discussion.author(), aliases.alias(discussion.author().id())),
+        "title": discussion.title(),
+        "category": discussion.category(),
+        "pinned": discussion.is_pinned(),
+        "answer": discussion.answer().map(|(id, _)| *id),
+        "discussion": discussion.comments().map(|(id, c)| issue_comment(id, c, aliases)).collect::<Vec<_>>(),
+    })
+}
+
+/// Returns JSON for a wiki `page` and its current revision.
+pub(crate) fn wiki_page(id: PageId, page: &Page, aliases: &impl AliasStore) -> Value {
+    let (revision, current) = page.revision();
+
+    json!({
+        "id": id.to_string(),
+        "author": author(page.author(), aliases.alias(page.author().id())),
+        "path": page.path(),
+        "title": page.title(),
+        "revision": revision,
+        "body": page.body(),
+        "embeds": current.map(|r| r.embeds().to_vec()).unwrap_or_default(),
+        "timestamp": page.timestamp().as_secs(),
+        "updatedAt": page.updated_at().as_secs(),
+    })
+}
+
+/// Returns JSON for a wiki page `Revision`. Redacted revisions have no content.
+pub(crate) fn wiki_revision(
+    id: &RevisionId,
+    revision: Option<&Revision>,
+    aliases: &impl AliasStore,
+) -> Value {
+    match revision {
+        Some(r) => json!({
+            "id": *id,
+            "author": author(r.author(), aliases.alias(r.author().id())),
+            "body": r.body(),
+            "embeds": r.embeds().to_vec(),
+            "timestamp": r.timestamp().as_secs(),
+            "redacted": false,
+        }),
+        None => json!({
+            "id": *id,
+            "redacted": true,
+        }),
+    }
+}
+
 /// Returns JSON for a `patch`.
 pub(crate) fn patch(
     id: PatchId,
@@ -184,6 +247,97 @@ fn reactions(
         .collect::<Vec<_>>()
 }
 
+/// Returns JSON for a `Notification` and fills in author aliases when present.
+pub(crate) fn notification(n: &Notification, aliases: &impl AliasStore) -> Value {
+    let kind = match &n.kind {
+        NotificationKind::Cob { typed_id } => json!({
+            "type": "cob",
+            "typeName": typed_id.type_name,
+            "id": typed_id.id.to_string(),
+        }),
+        NotificationKind::Mention { typed_id, comment } => json!({
+            "type": "mention",
+            "typeName": typed_id.type_name,
+            "id": typed_id.id.to_string(),
+            "comment": comment,
+        }),
+        NotificationKind::Branch { name } => json!({
+            "type": "branch",
+            "name": name,
+        }),
+        NotificationKind::Unknown { refname } => json!({
+            "type": "unknown",
+            "refname": refname,
+        }),
+    };
+
+    json!({
+        "id": n.id,
+        "rid": n.repo,
+        "remote": n.remote,
+        "ref": n.qualified,
+        "old": n.update.old(),
+        "new": n.update.new(),
+        "kind": kind,
+        "read": n.status.is_read(),
+        "timestamp": n.timestamp.as_millis(),
+        "activity": n.activity.iter().map(|a| activity(a, aliases)).collect::<Vec<_>>(),
+    })
+}
+
+/// Returns JSON for an update to a COB, along with the operations it brought in.
+pub(crate) fn cob_update(
+    typed_id: &TypedId,
+    remote: &NodeId,
+    title: Option<&str>,
+    activity: &[Activity],
+    aliases: &impl AliasStore,
+) -> Value {
+    json!({
+        "typeName": typed_id.type_name,
+        "id": typed_id.id.to_string(),
+        "remote": remote,
+        "title": title,
+        "activity": activity.iter().map(|a| self::activity(a, aliases)).collect::<Vec<_>>(),
+    })
+}
+
+/// Returns JSON for a notification `Activity` and fills in `alias` when present.
+fn activity(activity: &Activity, aliases: &impl AliasStore) -> Value {
+    let mut value = json!({
+        "entry": activity.entry,
+        "author": author(&Author::from(activity.author), aliases.alias(&activity.author)),
+        "timestamp": activity.timestamp.as_secs(),
+    });
+    if let (Some(obj), Ok(Value::Object(kind))) =
+        (value.as_object_mut(), serde_json::to_value(&activity.kind))
+    {
+        obj.extend(kind);
+    }
+    value
+}
+
+/// Returns JSON for a dashboard `Item` and fills in the author's `alias` when present.
+pub(crate) fn dashboard_item(item: &dashboard::Item, aliases: &impl AliasStore) -> Value {
+    let (kind, state) = match &item.object {
+        dashboard::Object::Issue(_, issue) => ("issue", json!(issue.state())),
+        dashboard::Object::Patch(_, patch) => ("patch", json!(patch.state())),
+    };
+    let author_id = item.object.author();
+
+    json!({
+        "rid": item.rid,
+        "name": item.name,
+        "category": item.category,
+        "type": kind,
+        "id": item.object.id().to_string(),
+        "title": item.object.title(),
+        "state": state,
+        "author": author(&Author::from(author_id), aliases.alias(&author_id)),
+        "updated": item.updated.as_secs(),
+    })
+}
+
 /// Returns JSON for an `author` and fills in `alias` when present.
 fn author(author: &Author, alias: Option<Alias>) -> Value {
     match alias {
@@ -225,9 +379,18 @@ fn edit(edit: &Edit, aliases: &impl AliasStore) -> Value {
       "body": edit.body,
       "timestamp": edit.timestamp.as_secs(),
       "embeds": edit.embeds,
+      "mentions": mentions(&edit.mentions, aliases),
     })
 }
 
+/// Returns JSON for the users mentioned in a comment and fills in `alias` when present.
+fn mentions(mentions: &[Did], aliases: &impl AliasStore) -> Vec<Value> {
+    mentions
+        .iter()
+        .map(|did| author(&Author::from(*did.as_key()), aliases.alias(did.as_key())))
+        .collect()
+}
+
 /// Returns JSON for a Issue `Comment`.
 fn issue_comment(id: &CommentId, comment: &Comment, aliases: &impl AliasStore) -> Value {
     json!({
@@ -236,6 +399,7 @@ fn issue_comment(id: &CommentId, comment: &Comment, aliases: &impl AliasStore) -
         "body": comment.body(),
         "edits": comment.edits().map(|e| edit(e, aliases)).collect::<Vec<_>>(),
         "embeds": comment.embeds().to_vec(),
+        "mentions": mentions(comment.mentions(), aliases),
         "reactions": reactions(comment.reactions(), None, aliases),
         "timestamp": comment.timestamp().as_secs(),
         "replyTo": comment.reply_to(),
@@ -243,6 +407,18 @@ fn issue_comment(id: &CommentId, comment: &Comment, aliases: &impl AliasStore) -
     })
 }
 
+/// Returns JSON for an issue `Task`.
+fn task(id: &TaskId, task: &Task, aliases: &impl AliasStore) -> Value {
+    json!({
+        "id": *id,
+        "author": author(task.author(), aliases.alias(task.author().id())),
+        "body": task.body(),
+        "done": task.is_done(),
+        "link": task.link(),
+        "timestamp": task.timestamp().as_secs(),
+    })
+}
+
 /// Returns JSON for a Patch `Comment`.
 fn patch_comment(
     id: &CommentId,
@@ -255,6 +431,7 @@ fn patch_comment(
         "body": comment.body(),
         "edits": comment.edits().map(|e| edit(e, aliases)).collect::<Vec<_>>(),
         "embeds": comment.embeds().to_vec(),
+        "mentions": mentions(comment.mentions(), aliases),
         "reactions": reactions(comment.reactions(), None, aliases),
         "timestamp": comment.timestamp().as_secs(),
         "replyTo": comment.reply_to(),
@@ -275,6 +452,7 @@ fn review_comment(
         "body": comment.body(),
         "edits": comment.edits().map(|e| edit(e, aliases)).collect::<Vec<_>>(),
         "embeds": comment.embeds().to_vec(),
+        "mentions": mentions(comment.mentions(), aliases),
         "reactions": reactions(comment.reactions(), None, aliases),
         "timestamp": comment.timestamp().as_secs(),
         "replyTo": comment.reply_to(),
@@ -1,5 +1,8 @@
+mod dashboard;
 mod delegates;
+pub mod events;
 mod node;
+mod notifications;
 mod profile;
 mod projects;
 mod sessions;
@@ -22,6 +25,9 @@ pub fn router(ctx: Context) -> Router {
         .merge(root_router)
         .merge(node::router(ctx.clone()))
         .merge(profile::router(ctx.clone()))
+        .merge(notifications::router(ctx.clone()))
+        .merge(dashboard::router(ctx.clone()))
+        .merge(events::router(ctx.clone()))
         .merge(sessions::router(ctx.clone()))
         .merge(delegates::router(ctx.clone()))
         .merge(projects::router(ctx.clone()))
@@ -58,6 +64,21 @@ async fn root_handler(State(ctx): State<Context>) -> impl IntoResponse {
                 "rel": "profile",
                 "type": "GET"
             },
+            {
+                "href": "/notifications",
+                "rel": "notifications",
+                "type": "GET"
+            },
+            {
+                "href": "/dashboard",
+                "rel": "dashboard",
+                "type": "GET"
+            },
+            {
+                "href": "/events",
+                "rel": "events",
+                "type": "GET"
+            },
             {
                 "href": "/stats",
                 "rel": "stats",
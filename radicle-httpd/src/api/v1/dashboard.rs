@@ -0,0 +1,119 @@
+use axum::extract::State;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::{Json, Router};
+use axum_auth::AuthBearer;
+
+use radicle::dashboard::{self, Filter};
+
+use crate::api::error::Error;
+use crate::api::{self, json, Context, DashboardQuery};
+use crate::axum_extra::Query;
+
+pub fn router(ctx: Context) -> Router {
+    Router::new()
+        .route("/dashboard", get(dashboard_handler))
+        .with_state(ctx)
+}
+
+/// List the issues and patches of all seeded repositories that need the local user's attention.
+/// `GET /dashboard?category=<category>&rid=<rid>&sortBy=<sortBy>`
+async fn dashboard_handler(
+    State(ctx): State<Context>,
+    AuthBearer(token): AuthBearer,
+    Query(qs): Query<DashboardQuery>,
+) -> impl IntoResponse {
+    api::auth::validate(&ctx, &token, api::auth::Scope::Read).await?;
+
+    let DashboardQuery {
+        category,
+        rid,
+        sort_by,
+    } = qs;
+    let filter = Filter {
+        categories: category.into_iter().collect(),
+        repos: rid.into_iter().collect(),
+        sort: sort_by.unwrap_or_default(),
+    };
+    let aliases = ctx.profile.aliases();
+    let items = dashboard::items(&ctx.profile, &filter)?
+        .iter()
+        .map(|item| json::dashboard_item(item, &aliases))
+        .collect::<Vec<_>>();
+
+    Ok::<_, Error>(Json(items))
+}
+
+#[cfg(test)]
+mod routes {
+    use axum::http::StatusCode;
+    use radicle::storage::ReadStorage;
+    use serde_json::json;
+
+    use crate::test::{self, get_auth, RID, SESSION_ID, TIMESTAMP};
+
+    #[tokio::test]
+    async fn test_dashboard_unauthorized() {
+        let tmp = tempfile::tempdir().unwrap();
+        let seed = test::seed(tmp.path());
+        let app = super::router(seed);
+        let response = get_auth(&app, "/dashboard", Some(SESSION_ID.to_owned())).await;
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_dashboard() {
+        let tmp = tempfile::tempdir().unwrap();
+        let seed = test::seed(tmp.path());
+        let signer = radicle_crypto::test::signer::MockSigner::from_seed([0xff; 32]);
+        let repo = seed
+            .profile
+            .storage
+            .repository(RID.parse().unwrap())
+            .unwrap();
+        let mut issues = seed.profile.issues_mut(&repo).unwrap();
+        let issue = issues
+            .create(
+                "Add a license",
+                "We need one.",
+                &[],
+                &[seed.profile.did()],
+                [],
+                &signer,
+            )
+            .unwrap();
+        let issue_id = issue.id().to_string();
+        let app = super::router(seed.clone());
+
+        test::create_session(seed.clone()).await;
+
+        let response = get_auth(
+            &app,
+            "/dashboard?category=assigned",
+            Some(SESSION_ID.to_owned()),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.json().await,
+            json!([
+              {
+                "rid": RID,
+                "name": "hello-world",
+                "category": "assigned",
+                "type": "issue",
+                "id": issue_id,
+                "title": "Add a license",
+                "state": { "status": "open" },
+                "author": {
+                  "id": seed.profile.did(),
+                  "alias": "seed",
+                },
+                "updated": TIMESTAMP,
+              }
+            ])
+        );
+    }
+}
@@ -0,0 +1,395 @@
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::State;
+use axum::response::sse::{self, KeepAlive, Sse};
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use axum_auth::AuthBearer;
+use futures_util::stream;
+use serde_json::Value;
+use tokio::sync::{broadcast, oneshot, Mutex};
+
+use radicle::cob::{issue, patch, TypedId};
+use radicle::git;
+use radicle::node::notifications::{activity, Activity};
+use radicle::node::{Event, Handle, NodeId};
+use radicle::prelude::RepoId;
+use radicle::storage::git::Repository;
+use radicle::storage::RefUpdate;
+use radicle::Node;
+
+use crate::api;
+use crate::api::error::Error;
+use crate::api::{json, Context, EventsQuery};
+use crate::axum_extra::Query;
+
+/// How long to wait for a node event before checking whether clients are still there.
+const SUBSCRIBE_TIMEOUT: Duration = Duration::from_secs(30);
+/// Maximum number of clients subscribed to events at the same time.
+pub const MAX_SUBSCRIBERS: usize = 32;
+/// Number of events buffered for each client. Clients that fall further behind miss events.
+const EVENTS_CAPACITY: usize = 64;
+
+/// A single subscription to the events of the local node, shared by all clients.
+#[derive(Clone, Default)]
+pub struct Events {
+    sender: Arc<Mutex<Option<broadcast::Sender<Event>>>>,
+}
+
+impl Events {
+    /// Subscribe to node events. The node is only subscribed to by the first client;
+    /// later clients share its subscription, which ends once all clients are gone.
+    pub async fn subscribe(&self, node: Node) -> Result<broadcast::Receiver<Event>, Error> {
+        let mut sender = self.sender.lock().await;
+        if let Some(sender) = sender.as_ref() {
+            return Self::join(sender);
+        }
+        let (tx, rx) = broadcast::channel(EVENTS_CAPACITY);
+        let (ready, subscribed) = oneshot::channel();
+        let shared = self.sender.clone();
+
+        // Nb. the sender is shared before the node is subscribed to, so that the lock
+        // isn't held while waiting for the node. Clients that subscribe in the meantime
+        // get no events if the node can't be subscribed to.
+        *sender = Some(tx.clone());
+        drop(sender);
+
+        // Reading from the node socket blocks, so events are read on their own thread.
+        tokio::task::spawn_blocking({
+            let tx = tx.clone();
+            move || {
+                let events = match node.subscribe(SUBSCRIBE_TIMEOUT) {
+                    Ok(events) => {
+                        ready.send(Ok(())).ok();
+                        events
+                    }
+                    Err(e) => {
+                        ready.send(Err(e)).ok();
+                        return;
+                    }
+                };
+                Self::relay(events, tx, shared);
+            }
+        });
+        let result = match subscribed.await {
+            Ok(Ok(())) => Ok(rx),
+            Ok(Err(e)) => Err(e.into()),
+            Err(_) => Err(Error::Unavailable("event subscription failed")),
+        };
+        if result.is_err() {
+            let mut sender = self.sender.lock().await;
+            if sender.as_ref().is_some_and(|s| s.same_channel(&tx)) {
+                sender.take();
+            }
+        }
+        result
+    }
+
+    /// Share an existing subscription, unless it has too many subscribers.
+    fn join(sender: &broadcast::Sender<Event>) -> Result<broadcast::Receiver<Event>, Error> {
+        if sender.receiver_count() >= MAX_SUBSCRIBERS {
+            return Err(Error::Unavailable("too many event subscribers"));
+        }
+        Ok(sender.subscribe())
+    }
+
+    /// Relay node events to subscribers, until either the node or all subscribers go away.
+    fn relay(
+        events: impl Iterator<Item = Result<Event, radicle::node::Error>>,
+        tx: broadcast::Sender<Event>,
+        shared: Arc<Mutex<Option<broadcast::Sender<Event>>>>,
+    ) {
+        for event in events {
+            match event {
+                Ok(event) => {
+                    tx.send(event).ok();
+                }
+                Err(radicle::node::Error::TimedOut) => {}
+                Err(e) => {
+                    tracing::error!("Error reading node events: {e}");
+                    break;
+                }
+            }
+            // Nb. the lock is held while checking, so that no client subscribes in the
+            // meantime.
+            let mut sender = shared.blocking_lock();
+            if tx.receiver_count() == 0 {
+                if sender.as_ref().is_some_and(|s| s.same_channel(&tx)) {
+                    sender.take();
+                }
+                return;
+            }
+        }
+        let mut sender = shared.blocking_lock();
+        if sender.as_ref().is_some_and(|s| s.same_channel(&tx)) {
+            sender.take();
+        }
+    }
+}
+
+pub fn router(ctx: Context) -> Router {
+    Router::new()
+        .route("/events", get(events_handler))
+        .with_state(ctx)
+}
+
+/// Relay the events of the local node as server-sent events. Events about private
+/// repositories are left out, and fetched refs come with the COB operations they
+/// brought in.
+/// `GET /events?rid=<rid>`
+async fn events_handler(
+    State(ctx): State<Context>,
+    AuthBearer(token): AuthBearer,
+    Query(qs): Query<EventsQuery>,
+) -> impl IntoResponse {
+    api::auth::validate(&ctx, &token, api::auth::Scope::Read).await?;
+
+    let EventsQuery { rid } = qs;
+    if let Some(rid) = rid {
+        ctx.repo(rid)?;
+    }
+    let rx = ctx
+        .events()
+        .subscribe(Node::new(ctx.profile.socket()))
+        .await?;
+    let stream = stream::unfold((rx, ctx), move |(mut rx, ctx)| async move {
+        loop {
+            match rx.recv().await {
+                Ok(event) => {
+                    if let Some(event) = self::event(&ctx, event, rid) {
+                        return Some((Ok::<_, Infallible>(event), (rx, ctx)));
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    tracing::warn!("Events client lagging behind, {n} event(s) skipped");
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    Ok::<_, Error>(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// Get the server-sent event for a node event, or `None` if it shouldn't be relayed.
+fn event(ctx: &Context, event: Event, filter: Option<RepoId>) -> Option<sse::Event> {
+    let rid = match &event {
+        Event::RefsFetched { rid, .. }
+        | Event::RefsSynced { rid, .. }
+        | Event::SeedDiscovered { rid, .. }
+        | Event::SeedDropped { rid, .. }
+        | Event::LocalRefsAnnounced { rid, .. }
+        | Event::RefsAnnounced { rid, .. } => Some(*rid),
+        Event::PeerConnected { .. }
+        | Event::PeerDisconnected { .. }
+        | Event::InventoryAnnounced { .. }
+        | Event::NodeAnnounced { .. } => None,
+    };
+    let repo = match (rid, filter) {
+        (Some(rid), Some(filter)) if rid != filter => return None,
+        (None, Some(_)) => return None,
+        (Some(rid), _) => Some(ctx.repo(rid).ok()?.0),
+        (None, None) => None,
+    };
+    let mut value = serde_json::to_value(&event).ok()?;
+    let kind = value.get("type")?.as_str()?.to_owned();
+
+    if let (Event::RefsFetched { updated, .. }, Some(repo), Value::Object(obj)) =
+        (&event, &repo, &mut value)
+    {
+        let aliases = ctx.profile.aliases();
+        let cobs = updated
+            .iter()
+            .filter_map(|update| cob_update(repo, update, updated, &aliases))
+            .collect::<Vec<_>>();
+
+        obj.insert("cobs".to_owned(), Value::Array(cobs));
+    }
+    sse::Event::default().event(kind).json_data(value).ok()
+}
+
+/// Get the JSON of a COB update, or `None` if the update isn't to a COB.
+fn cob_update(
+    repo: &Repository,
+    update: &RefUpdate,
+    updated: &[RefUpdate],
+    aliases: &impl radicle::node::AliasStore,
+) -> Option<Value> {
+    let (remote, refname) = git::parse_ref_namespaced::<NodeId>(update.name()).ok()?;
+    let typed_id = TypedId::from_qualified(&refname).ok()??;
+    let new = match update {
+        RefUpdate::Created { oid, .. } => *oid,
+        RefUpdate::Updated { new, .. } => *new,
+        RefUpdate::Deleted { .. } | RefUpdate::Skipped { .. } => return None,
+    };
+    let title = if typed_id.is_issue() {
+        issue::Issues::open(repo)
+            .ok()?
+            .get(&typed_id.id)
+            .ok()?
+            .map(|i| i.title().to_owned())
+    } else if typed_id.is_patch() {
+        patch::Patches::open(repo)
+            .ok()?
+            .get(&typed_id.id)
+            .ok()?
+            .map(|p| p.title().to_owned())
+    } else {
+        None
+    };
+    let activity = match activity::known(repo, &typed_id, updated)
+        .and_then(|known| Activity::between(repo, &typed_id, new, &known))
+    {
+        Ok(activity) => activity,
+        Err(e) => {
+            tracing::warn!("Error summarizing activity on {}: {e}", typed_id.id);
+            vec![]
+        }
+    };
+
+    Some(json::cob_update(
+        &typed_id,
+        &remote,
+        title.as_deref(),
+        &activity,
+        aliases,
+    ))
+}
+
+#[cfg(test)]
+mod routes {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixListener;
+    use std::str::FromStr;
+    use std::{fs, str, thread};
+
+    use axum::http::StatusCode;
+    use radicle::git;
+    use radicle::git::RefString;
+    use radicle::node::{Event, NodeId};
+    use radicle::prelude::RepoId;
+    use radicle::storage::{ReadRepository, ReadStorage, RefUpdate};
+    use serde_json::Value;
+
+    use crate::api::auth::Scope;
+    use crate::api::Context;
+    use crate::test::{
+        create_session_with_scopes, get, get_auth, seed, DID, ISSUE_ID, RID, RID_PRIVATE,
+        SESSION_ID,
+    };
+
+    /// Serve the given events on the node socket of the context, to a single subscriber.
+    fn serve(ctx: &Context, events: Vec<Event>) -> thread::JoinHandle<()> {
+        let socket = ctx.profile.socket();
+        fs::create_dir_all(socket.parent().unwrap()).unwrap();
+        fs::remove_file(&socket).ok();
+        let listener = UnixListener::bind(&socket).unwrap();
+
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut cmd = String::new();
+            BufReader::new(&stream).read_line(&mut cmd).unwrap();
+
+            for event in events {
+                serde_json::to_writer(&stream, &event).unwrap();
+                stream.write_all(b"\n").unwrap();
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_events_unauthorized() {
+        let tmp = tempfile::tempdir().unwrap();
+        let app = super::router(seed(tmp.path()));
+        let response = get(&app, "/events").await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_events_node_stopped() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ctx = seed(tmp.path());
+        create_session_with_scopes(ctx.clone(), [Scope::Read]).await;
+        let app = super::router(ctx);
+        let response = get_auth(&app, "/events", Some(SESSION_ID.to_owned())).await;
+
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn test_events() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ctx = seed(tmp.path());
+        let rid = RepoId::from_str(RID).unwrap();
+        let nid = ctx.profile.public_key;
+        let refname = RefString::try_from(format!(
+            "refs/namespaces/{nid}/refs/cobs/xyz.radicle.issue/{ISSUE_ID}"
+        ))
+        .unwrap();
+        let repo = ctx.profile.storage.repository(rid).unwrap();
+        let (_, qualified) = git::parse_ref_namespaced::<NodeId>(&refname).unwrap();
+        let oid = repo.reference_oid(&nid, &qualified).unwrap();
+        let events = vec![
+            Event::PeerConnected { nid },
+            Event::SeedDiscovered {
+                rid: RepoId::from_str(RID_PRIVATE).unwrap(),
+                nid,
+            },
+            Event::RefsFetched {
+                remote: nid,
+                rid,
+                updated: vec![RefUpdate::Created { name: refname, oid }],
+            },
+        ];
+        create_session_with_scopes(ctx.clone(), [Scope::Read]).await;
+        let server = serve(&ctx, events.clone());
+        let app = super::router(ctx.clone());
+        let response = get_auth(&app, "/events", Some(SESSION_ID.to_owned())).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.body().await;
+        let body = str::from_utf8(&body).unwrap();
+        let events = body
+            .split("\n\n")
+            .filter(|e| !e.is_empty())
+            .collect::<Vec<_>>();
+        server.join().unwrap();
+
+        assert_eq!(events.len(), 2);
+        assert!(events[0].starts_with("event: peerConnected\n"));
+        assert!(events[1].starts_with("event: refsFetched\n"));
+
+        let data: Value = serde_json::from_str(events[1].split_once("data: ").unwrap().1).unwrap();
+        assert_eq!(data["rid"], RID);
+        assert_eq!(data["cobs"][0]["typeName"], "xyz.radicle.issue");
+        assert_eq!(data["cobs"][0]["id"], ISSUE_ID);
+        assert_eq!(data["cobs"][0]["title"], "Issue #1");
+        assert_eq!(data["cobs"][0]["activity"][0]["type"], "opened");
+        assert_eq!(data["cobs"][0]["activity"][0]["author"]["id"], DID);
+
+        // Only events about the given repository are relayed.
+        let server = serve(
+            &ctx,
+            vec![
+                Event::PeerConnected { nid },
+                Event::SeedDiscovered { rid, nid },
+            ],
+        );
+        let response = get_auth(
+            &app,
+            format!("/events?rid={RID}"),
+            Some(SESSION_ID.to_owned()),
+        )
+        .await;
+        let body = response.body().await;
+        server.join().unwrap();
+
+        assert!(str::from_utf8(&body)
+            .unwrap()
+            .starts_with("event: seedDiscovered\n"));
+    }
+}
@@ -108,7 +108,7 @@ async fn node_policies_seed_handler(
     Path(project): Path<RepoId>,
     Query(qs): Query<PoliciesQuery>,
 ) -> impl IntoResponse {
-    api::auth::validate(&ctx, &token).await?;
+    api::auth::validate(&ctx, &token, api::auth::Scope::Admin).await?;
     let mut node = Node::new(ctx.profile.socket());
     node.seed(project, qs.scope.unwrap_or_default())?;
 
@@ -129,7 +129,7 @@ async fn node_policies_unseed_handler(
     AuthBearer(token): AuthBearer,
     Path(project): Path<RepoId>,
 ) -> impl IntoResponse {
-    api::auth::validate(&ctx, &token).await?;
+    api::auth::validate(&ctx, &token, api::auth::Scope::Admin).await?;
     let mut node = Node::new(ctx.profile.socket());
     node.unseed(project)?;
 
@@ -0,0 +1,152 @@
+use axum::extract::State;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::{Json, Router};
+use axum_auth::AuthBearer;
+
+use crate::api;
+use crate::api::error::Error;
+use crate::api::{json, Context, NotificationsQuery};
+use crate::axum_extra::Query;
+
+pub fn router(ctx: Context) -> Router {
+    Router::new()
+        .route("/notifications", get(notifications_handler))
+        .with_state(ctx)
+}
+
+/// List the local node's notifications, most recent first.
+/// `GET /notifications?rid=<rid>&page=<page>&perPage=<perPage>`
+async fn notifications_handler(
+    State(ctx): State<Context>,
+    AuthBearer(token): AuthBearer,
+    Query(qs): Query<NotificationsQuery>,
+) -> impl IntoResponse {
+    api::auth::validate(&ctx, &token, api::auth::Scope::Read).await?;
+
+    let NotificationsQuery {
+        rid,
+        page,
+        per_page,
+    } = qs;
+    let page = page.unwrap_or(0);
+    let per_page = per_page.unwrap_or(10);
+    let aliases = ctx.profile.aliases();
+    let store = ctx.profile.notifications_mut()?;
+    let notifications = store
+        .all()?
+        .filter_map(|n| n.ok())
+        .filter(|n| rid.map_or(true, |rid| n.repo == rid))
+        .skip(page * per_page)
+        .take(per_page)
+        .map(|n| json::notification(&n, &aliases))
+        .collect::<Vec<_>>();
+
+    Ok::<_, Error>(Json(notifications))
+}
+
+#[cfg(test)]
+mod routes {
+    use axum::http::StatusCode;
+    use localtime::LocalTime;
+    use radicle::cob::{ActorId, Timestamp};
+    use radicle::git;
+    use radicle::node::notifications::{Activity, ActivityKind};
+    use radicle::storage::RefUpdate;
+    use serde_json::json;
+
+    use crate::api::auth::Scope;
+    use crate::test::{self, get, get_auth, ISSUE_ID, RID, SESSION_ID, TIMESTAMP};
+
+    #[tokio::test]
+    async fn test_notifications_unauthorized() {
+        let tmp = tempfile::tempdir().unwrap();
+        let seed = test::seed(tmp.path());
+        let app = super::router(seed.clone());
+        let response = get(&app, "/notifications").await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = get_auth(&app, "/notifications", Some(SESSION_ID.to_owned())).await;
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_notifications() {
+        let tmp = tempfile::tempdir().unwrap();
+        let seed = test::seed(tmp.path());
+        let author: ActorId = *seed.profile.id();
+        let issue: git::Oid = ISSUE_ID.parse().unwrap();
+        let update = RefUpdate::Created {
+            name: git::RefString::try_from(format!(
+                "refs/namespaces/{author}/refs/cobs/xyz.radicle.issue/{ISSUE_ID}"
+            ))
+            .unwrap(),
+            oid: issue,
+        };
+        seed.profile
+            .notifications_mut()
+            .unwrap()
+            .insert_activity(
+                &RID.parse().unwrap(),
+                &update,
+                &[Activity {
+                    entry: issue,
+                    author,
+                    timestamp: Timestamp::from_secs(TIMESTAMP),
+                    kind: ActivityKind::Opened,
+                }],
+                None,
+                LocalTime::from_secs(TIMESTAMP),
+            )
+            .unwrap();
+
+        test::create_session_with_scopes(seed.clone(), [Scope::Read]).await;
+        let app = super::router(seed.clone());
+        let response = get_auth(&app, "/notifications", Some(SESSION_ID.to_owned())).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.json().await,
+            json!([
+              {
+                "id": 1,
+                "rid": RID,
+                "remote": author,
+                "ref": format!("refs/cobs/xyz.radicle.issue/{ISSUE_ID}"),
+                "old": null,
+                "new": ISSUE_ID,
+                "kind": {
+                  "type": "cob",
+                  "typeName": "xyz.radicle.issue",
+                  "id": ISSUE_ID,
+                },
+                "read": false,
+                "timestamp": TIMESTAMP * 1000,
+                "activity": [
+                  {
+                    "entry": ISSUE_ID,
+                    "author": {
+                      "id": format!("did:key:{author}"),
+                      "alias": "seed",
+                    },
+                    "timestamp": TIMESTAMP,
+                    "type": "opened",
+                  }
+                ],
+              }
+            ])
+        );
+
+        let response = get_auth(
+            &app,
+            "/notifications?rid=rad:z4XaCmN3jLSeiMvW15YTDpNbDHFhG",
+            Some(SESSION_ID.to_owned()),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.json().await, json!([]));
+    }
+}
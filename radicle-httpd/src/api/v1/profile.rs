@@ -80,6 +80,13 @@ mod routes {
                 "cli": {
                   "hints": true
                 },
+                "notifications": {
+                  "hook": null,
+                  "email": null,
+                  "socket": false
+                },
+                "queries": {},
+                "replication": {},
                 "node": {
                   "alias": "seed",
                   "listen": [],
@@ -114,7 +121,8 @@ mod routes {
                   },
                   "workers": 8,
                   "policy": "block",
-                  "scope": "all"
+                  "scope": "all",
+                  "discovery": "disabled"
                 }
               },
               "home": seed.profile.path()
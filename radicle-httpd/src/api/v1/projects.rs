@@ -8,14 +8,16 @@ use axum::routing::{get, patch, post};
 use axum::{Json, Router};
 use axum_auth::AuthBearer;
 use hyper::StatusCode;
+use nonempty::NonEmpty;
 use radicle_surf::blob::BlobRef;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use tower_http::set_header::SetResponseHeaderLayer;
 
 use radicle::cob::{
-    issue, issue::cache::Issues as _, patch, patch::cache::Patches as _, resolve_embed, Embed,
-    Label, Uri,
+    discussion, discussion::Discussions, issue, issue::cache::Issues as _, patch,
+    patch::cache::Patches as _, resolve_embed, store, thread, wiki, wiki::Wiki, Embed, Label,
+    ObjectId, TypeName, Uri,
 };
 use radicle::identity::{Did, RepoId};
 use radicle::node::routing::Store;
@@ -25,7 +27,10 @@ use radicle_surf::{diff, Glob, Oid, Repository};
 
 use crate::api::error::Error;
 use crate::api::project::Info;
-use crate::api::{self, announce_refs, CobsQuery, Context, PaginationQuery, ProjectQuery};
+use crate::api::{
+    self, announce_refs, CobsQuery, Context, DiscussionsQuery, PaginationQuery, ProjectQuery,
+    WikiQuery,
+};
 use crate::axum_extra::{immutable_response, Path, Query};
 
 const CACHE_1_HOUR: &str = "public, max-age=3600, must-revalidate";
@@ -66,6 +71,31 @@ pub fn router(ctx: Context) -> Router {
             "/projects/:project/issues/:id",
             patch(issue_update_handler).get(issue_handler),
         )
+        .route(
+            "/projects/:project/discussions",
+            post(discussion_create_handler).get(discussions_handler),
+        )
+        .route(
+            "/projects/:project/discussions/:id",
+            patch(discussion_update_handler).get(discussion_handler),
+        )
+        .route(
+            "/projects/:project/wiki",
+            post(wiki_create_handler).get(wiki_handler),
+        )
+        .route(
+            "/projects/:project/wiki/:id",
+            patch(wiki_update_handler).get(wiki_page_handler),
+        )
+        .route("/projects/:project/wiki/:id/raw", get(wiki_raw_handler))
+        .route(
+            "/projects/:project/wiki/:id/revisions/:revision",
+            get(wiki_revision_handler),
+        )
+        .route(
+            "/projects/:project/wiki/:id/diff/:from/:to",
+            get(wiki_diff_handler),
+        )
         .route(
             "/projects/:project/patches",
             post(patch_create_handler).get(patches_handler),
@@ -74,6 +104,14 @@ pub fn router(ctx: Context) -> Router {
             "/projects/:project/patches/:id",
             patch(patch_update_handler).get(patch_handler),
         )
+        .route(
+            "/projects/:project/cobs/:typename",
+            post(cob_create_handler).get(cobs_handler),
+        )
+        .route(
+            "/projects/:project/cobs/:typename/:id",
+            patch(cob_update_handler).get(cob_handler),
+        )
         .with_state(ctx)
         .layer(DefaultBodyLimit::max(MAX_BODY_LIMIT))
 }
@@ -567,16 +605,28 @@ async fn issues_handler(
         page,
         per_page,
         state,
+        q,
+        saved,
     } = qs;
     let page = page.unwrap_or(0);
     let per_page = per_page.unwrap_or(10);
-    let state = state.unwrap_or_default();
+    let query = ctx.query(q, saved.as_deref())?;
+    let state = match (state, &query) {
+        (None, Some(_)) => None,
+        (state, _) => Some(state.unwrap_or_default()),
+    };
     let issues = ctx.profile.issues(&repo)?;
-    let mut issues: Vec<_> = issues
-        .list()?
+    let iter = match &query {
+        Some(query) => issues.list_by_query(query)?,
+        None => issues.list()?,
+    };
+    let mut issues: Vec<_> = iter
         .filter_map(|r| {
             let (id, issue) = r.ok()?;
-            (state.matches(issue.state())).then_some((id, issue))
+            state
+                .as_ref()
+                .map_or(true, |s| s.matches(issue.state()))
+                .then_some((id, issue))
         })
         .collect::<Vec<_>>();
 
@@ -609,7 +659,7 @@ async fn issue_create_handler(
     Path(project): Path<RepoId>,
     Json(issue): Json<IssueCreate>,
 ) -> impl IntoResponse {
-    api::auth::validate(&ctx, &token).await?;
+    api::auth::validate(&ctx, &token, api::auth::Scope::Issues).await?;
 
     let (repo, _) = ctx.repo(project)?;
     let node = Node::new(ctx.profile.socket());
@@ -651,7 +701,7 @@ async fn issue_update_handler(
     Path((project, issue_id)): Path<(RepoId, Oid)>,
     Json(action): Json<issue::Action>,
 ) -> impl IntoResponse {
-    api::auth::validate(&ctx, &token).await?;
+    api::auth::validate(&ctx, &token, api::auth::Scope::Issues).await?;
 
     let (repo, _) = ctx.repo(project)?;
     let node = Node::new(ctx.profile.socket());
@@ -668,13 +718,15 @@ async fn issue_update_handler(
             body,
             reply_to,
             embeds,
+            mentions,
         } => {
             let embeds: Vec<Embed> = embeds
                 .into_iter()
                 .filter_map(|embed| resolve_embed(&repo, embed))
                 .collect();
+            let mentions = resolve_mentions(&body, mentions, ctx.profile.as_ref());
             if let Some(to) = reply_to {
-                issue.comment(body, to, embeds, &signer)?
+                issue.comment(body, to, embeds, mentions, &signer)?
             } else {
                 return Err(Error::BadRequest("`replyTo` missing".to_owned()));
             }
@@ -684,14 +736,28 @@ async fn issue_update_handler(
             reaction,
             active,
         } => issue.react(id, reaction, active, &signer)?,
-        issue::Action::CommentEdit { id, body, embeds } => {
+        issue::Action::CommentEdit {
+            id,
+            body,
+            embeds,
+            mentions,
+        } => {
             let embeds: Vec<Embed> = embeds
                 .into_iter()
                 .filter_map(|embed| resolve_embed(&repo, embed))
                 .collect();
-            issue.edit_comment(id, body, embeds, &signer)?
+            let mentions = resolve_mentions(&body, mentions, ctx.profile.as_ref());
+            issue.edit_comment(id, body, embeds, mentions, &signer)?
         }
         issue::Action::CommentRedact { id } => issue.redact_comment(id, &signer)?,
+        issue::Action::CommentOrigin { id, origin } => issue.origin(id, origin, &signer)?,
+        issue::Action::TaskAdd { body, after, link } => {
+            issue.add_task(body, after, link, &signer)?
+        }
+        issue::Action::TaskEdit { id, body, link } => issue.edit_task(id, body, link, &signer)?,
+        issue::Action::TaskCheck { id, done } => issue.check_task(id, done, &signer)?,
+        issue::Action::TaskMove { id, after } => issue.move_task(id, after, &signer)?,
+        issue::Action::TaskRemove { id } => issue.remove_task(id, &signer)?,
     };
 
     announce_refs(node, repo.id())?;
@@ -699,6 +765,16 @@ async fn issue_update_handler(
     Ok::<_, Error>(Json(json!({ "success": true, "id": id })))
 }
 
+/// Use the mentions given with a comment action, or parse them from the comment body
+/// if none were given.
+fn resolve_mentions(body: &str, mentions: Vec<Did>, aliases: &impl AliasStore) -> Vec<Did> {
+    if mentions.is_empty() {
+        thread::mentions(body, aliases)
+    } else {
+        mentions
+    }
+}
+
 /// Get project issue.
 /// `GET /projects/:project/issues/:id`
 async fn issue_handler(
@@ -716,6 +792,365 @@ async fn issue_handler(
     Ok::<_, Error>(Json(api::json::issue(issue_id.into(), issue, &aliases)))
 }
 
+/// Get project discussions list.
+/// `GET /projects/:project/discussions`
+async fn discussions_handler(
+    State(ctx): State<Context>,
+    Path(project): Path<RepoId>,
+    Query(qs): Query<DiscussionsQuery>,
+) -> impl IntoResponse {
+    let (repo, _) = ctx.repo(project)?;
+    let DiscussionsQuery {
+        page,
+        per_page,
+        category,
+        pinned,
+    } = qs;
+    let page = page.unwrap_or(0);
+    let per_page = per_page.unwrap_or(10);
+    let discussions = Discussions::open(&repo)?;
+    let mut discussions: Vec<_> = discussions
+        .all()?
+        .filter_map(|r| {
+            let (id, discussion) = r.ok()?;
+            let matches = (!pinned || discussion.is_pinned())
+                && category
+                    .as_ref()
+                    .map_or(true, |c| discussion.category() == Some(c));
+
+            matches.then_some((id, discussion))
+        })
+        .collect::<Vec<_>>();
+
+    // Pinned discussions come first, then the most recent ones.
+    discussions.sort_by(|(_, a), (_, b)| {
+        b.is_pinned()
+            .cmp(&a.is_pinned())
+            .then_with(|| b.timestamp().cmp(&a.timestamp()))
+    });
+    let aliases = &ctx.profile.aliases();
+    let discussions = discussions
+        .into_iter()
+        .map(|(id, discussion)| api::json::discussion(id, discussion, aliases))
+        .skip(page * per_page)
+        .take(per_page)
+        .collect::<Vec<_>>();
+
+    Ok::<_, Error>(Json(discussions))
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DiscussionCreate {
+    pub title: String,
+    pub body: String,
+    pub category: Option<discussion::Category>,
+    #[serde(default)]
+    pub embeds: Vec<Embed<Uri>>,
+}
+
+/// Create a new discussion.
+/// `POST /projects/:project/discussions`
+async fn discussion_create_handler(
+    State(ctx): State<Context>,
+    AuthBearer(token): AuthBearer,
+    Path(project): Path<RepoId>,
+    Json(discussion): Json<DiscussionCreate>,
+) -> impl IntoResponse {
+    api::auth::validate(&ctx, &token, api::auth::Scope::Discussions).await?;
+
+    let (repo, _) = ctx.repo(project)?;
+    let node = Node::new(ctx.profile.socket());
+    let signer = ctx
+        .profile
+        .signer()
+        .map_err(|_| Error::Auth("Unauthorized"))?;
+    let embeds: Vec<Embed> = discussion
+        .embeds
+        .into_iter()
+        .filter_map(|embed| resolve_embed(&repo, embed))
+        .collect();
+
+    let mut discussions = Discussions::open(&repo)?;
+    let discussion = discussions.create(
+        discussion.title,
+        discussion.body,
+        discussion.category,
+        embeds,
+        &signer,
+    )?;
+
+    announce_refs(node, repo.id())?;
+
+    Ok::<_, Error>((
+        StatusCode::CREATED,
+        Json(json!({ "success": true, "id": discussion.id().to_string() })),
+    ))
+}
+
+/// Update a discussion.
+/// `PATCH /projects/:project/discussions/:id`
+async fn discussion_update_handler(
+    State(ctx): State<Context>,
+    AuthBearer(token): AuthBearer,
+    Path((project, discussion_id)): Path<(RepoId, Oid)>,
+    Json(action): Json<discussion::Action>,
+) -> impl IntoResponse {
+    api::auth::validate(&ctx, &token, api::auth::Scope::Discussions).await?;
+
+    let (repo, _) = ctx.repo(project)?;
+    let node = Node::new(ctx.profile.socket());
+    let signer = ctx.profile.signer()?;
+    let mut discussions = Discussions::open(&repo)?;
+    let mut discussion = discussions.get_mut(&discussion_id.into())?;
+
+    let id = match action {
+        discussion::Action::Edit { title } => discussion.edit(title, &signer)?,
+        discussion::Action::Categorize { category } => discussion.categorize(category, &signer)?,
+        discussion::Action::Pin { pinned } => discussion.pin(pinned, &signer)?,
+        discussion::Action::Answer { id } => discussion.accept_answer(id, &signer)?,
+        discussion::Action::Comment {
+            body,
+            reply_to,
+            embeds,
+            mentions,
+        } => {
+            let embeds: Vec<Embed> = embeds
+                .into_iter()
+                .filter_map(|embed| resolve_embed(&repo, embed))
+                .collect();
+            let mentions = resolve_mentions(&body, mentions, ctx.profile.as_ref());
+            if let Some(to) = reply_to {
+                discussion.comment(body, to, embeds, mentions, &signer)?
+            } else {
+                return Err(Error::BadRequest("`replyTo` missing".to_owned()));
+            }
+        }
+        discussion::Action::CommentReact {
+            id,
+            reaction,
+            active,
+        } => discussion.react(id, reaction, active, &signer)?,
+        discussion::Action::CommentEdit {
+            id,
+            body,
+            embeds,
+            mentions,
+        } => {
+            let embeds: Vec<Embed> = embeds
+                .into_iter()
+                .filter_map(|embed| resolve_embed(&repo, embed))
+                .collect();
+            let mentions = resolve_mentions(&body, mentions, ctx.profile.as_ref());
+            discussion.edit_comment(id, body, embeds, mentions, &signer)?
+        }
+        discussion::Action::CommentRedact { id } => discussion.redact_comment(id, &signer)?,
+    };
+
+    announce_refs(node, repo.id())?;
+
+    Ok::<_, Error>(Json(json!({ "success": true, "id": id })))
+}
+
+/// Get project discussion.
+/// `GET /projects/:project/discussions/:id`
+async fn discussion_handler(
+    State(ctx): State<Context>,
+    Path((project, discussion_id)): Path<(RepoId, Oid)>,
+) -> impl IntoResponse {
+    let (repo, _) = ctx.repo(project)?;
+    let discussion = Discussions::open(&repo)?
+        .get(&discussion_id.into())?
+        .ok_or(Error::NotFound)?;
+    let aliases = ctx.profile.aliases();
+
+    Ok::<_, Error>(Json(api::json::discussion(
+        discussion_id.into(),
+        discussion,
+        &aliases,
+    )))
+}
+
+/// Get project wiki pages, ordered by path.
+/// `GET /projects/:project/wiki`
+async fn wiki_handler(
+    State(ctx): State<Context>,
+    Path(project): Path<RepoId>,
+    Query(qs): Query<WikiQuery>,
+) -> impl IntoResponse {
+    let (repo, _) = ctx.repo(project)?;
+    let WikiQuery {
+        page,
+        per_page,
+        path,
+    } = qs;
+    let page = page.unwrap_or(0);
+    let per_page = per_page.unwrap_or(10);
+    let wiki = Wiki::open(&repo)?;
+    let mut pages = match path {
+        Some(path) => wiki.find(&path)?.into_iter().collect::<Vec<_>>(),
+        None => wiki.all()?.filter_map(|r| r.ok()).collect::<Vec<_>>(),
+    };
+    pages.sort_by(|(a_id, a), (b_id, b)| a.path().cmp(b.path()).then(a_id.cmp(b_id)));
+
+    let aliases = &ctx.profile.aliases();
+    let pages = pages
+        .into_iter()
+        .map(|(id, p)| api::json::wiki_page(id, &p, aliases))
+        .skip(page * per_page)
+        .take(per_page)
+        .collect::<Vec<_>>();
+
+    Ok::<_, Error>(Json(pages))
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WikiPageCreate {
+    pub path: wiki::PagePath,
+    pub title: String,
+    pub body: String,
+    #[serde(default)]
+    pub embeds: Vec<Embed<Uri>>,
+}
+
+/// Create a new wiki page.
+/// `POST /projects/:project/wiki`
+async fn wiki_create_handler(
+    State(ctx): State<Context>,
+    AuthBearer(token): AuthBearer,
+    Path(project): Path<RepoId>,
+    Json(page): Json<WikiPageCreate>,
+) -> impl IntoResponse {
+    api::auth::validate(&ctx, &token, api::auth::Scope::Wiki).await?;
+
+    let (repo, _) = ctx.repo(project)?;
+    let node = Node::new(ctx.profile.socket());
+    let signer = ctx
+        .profile
+        .signer()
+        .map_err(|_| Error::Auth("Unauthorized"))?;
+    let embeds: Vec<Embed> = page
+        .embeds
+        .into_iter()
+        .filter_map(|embed| resolve_embed(&repo, embed))
+        .collect();
+
+    let mut wiki = Wiki::open(&repo)?;
+    let page = wiki.create(page.path, page.title, page.body, embeds, &signer)?;
+
+    announce_refs(node, repo.id())?;
+
+    Ok::<_, Error>((
+        StatusCode::CREATED,
+        Json(json!({ "success": true, "id": page.id().to_string() })),
+    ))
+}
+
+/// Update a wiki page.
+/// `PATCH /projects/:project/wiki/:id`
+async fn wiki_update_handler(
+    State(ctx): State<Context>,
+    AuthBearer(token): AuthBearer,
+    Path((project, page_id)): Path<(RepoId, Oid)>,
+    Json(action): Json<wiki::Action>,
+) -> impl IntoResponse {
+    api::auth::validate(&ctx, &token, api::auth::Scope::Wiki).await?;
+
+    let (repo, _) = ctx.repo(project)?;
+    let node = Node::new(ctx.profile.socket());
+    let signer = ctx.profile.signer()?;
+    let mut wiki = Wiki::open(&repo)?;
+    let mut page = wiki.get_mut(&page_id.into())?;
+
+    let id = match action {
+        wiki::Action::Edit { title } => page.edit(title, &signer)?,
+        wiki::Action::Move { path } => page.move_to(path, &signer)?,
+        wiki::Action::Revise { body, embeds } => {
+            let embeds: Vec<Embed> = embeds
+                .into_iter()
+                .filter_map(|embed| resolve_embed(&repo, embed))
+                .collect();
+            page.revise(body, embeds, &signer)?
+        }
+        wiki::Action::Redact { revision } => page.redact(revision, &signer)?,
+    };
+
+    announce_refs(node, repo.id())?;
+
+    Ok::<_, Error>(Json(json!({ "success": true, "id": id })))
+}
+
+/// Get a wiki page and its history.
+/// `GET /projects/:project/wiki/:id`
+async fn wiki_page_handler(
+    State(ctx): State<Context>,
+    Path((project, page_id)): Path<(RepoId, Oid)>,
+) -> impl IntoResponse {
+    let (repo, _) = ctx.repo(project)?;
+    let page = Wiki::open(&repo)?
+        .get(&page_id.into())?
+        .ok_or(Error::NotFound)?;
+    let aliases = ctx.profile.aliases();
+    let mut json = api::json::wiki_page(page_id.into(), &page, &aliases);
+
+    json["history"] = page
+        .history()
+        .map(|(id, r)| api::json::wiki_revision(id, r, &aliases))
+        .collect();
+
+    Ok::<_, Error>(Json(json))
+}
+
+/// Get the markdown body of a wiki page, for rendering.
+/// `GET /projects/:project/wiki/:id/raw`
+async fn wiki_raw_handler(
+    State(ctx): State<Context>,
+    Path((project, page_id)): Path<(RepoId, Oid)>,
+) -> impl IntoResponse {
+    let (repo, _) = ctx.repo(project)?;
+    let page = Wiki::open(&repo)?
+        .get(&page_id.into())?
+        .ok_or(Error::NotFound)?;
+
+    Ok::<_, Error>((
+        [(header::CONTENT_TYPE, "text/markdown; charset=utf-8")],
+        page.body().to_owned(),
+    ))
+}
+
+/// Get a revision of a wiki page.
+/// `GET /projects/:project/wiki/:id/revisions/:revision`
+async fn wiki_revision_handler(
+    State(ctx): State<Context>,
+    Path((project, page_id, revision_id)): Path<(RepoId, Oid, Oid)>,
+) -> impl IntoResponse {
+    let (repo, _) = ctx.repo(project)?;
+    let page = Wiki::open(&repo)?
+        .get(&page_id.into())?
+        .ok_or(Error::NotFound)?;
+    let (id, revision) = page
+        .history()
+        .find(|(id, _)| **id == revision_id)
+        .ok_or(Error::NotFound)?;
+    let aliases = ctx.profile.aliases();
+
+    Ok::<_, Error>(Json(api::json::wiki_revision(id, revision, &aliases)))
+}
+
+/// Get a unified diff of a wiki page body between two revisions.
+/// `GET /projects/:project/wiki/:id/diff/:from/:to`
+async fn wiki_diff_handler(
+    State(ctx): State<Context>,
+    Path((project, page_id, from, to)): Path<(RepoId, Oid, Oid, Oid)>,
+) -> impl IntoResponse {
+    let (repo, _) = ctx.repo(project)?;
+    let page = Wiki::open(&repo)?
+        .get(&page_id.into())?
+        .ok_or(Error::NotFound)?;
+    let diff = page.diff(&from, &to)?;
+
+    Ok::<_, Error>(Json(json!({ "from": from, "to": to, "diff": diff })))
+}
+
 #[derive(Deserialize, Serialize)]
 pub struct PatchCreate {
     pub title: String,
@@ -733,7 +1168,7 @@ async fn patch_create_handler(
     Path(project): Path<RepoId>,
     Json(patch): Json<PatchCreate>,
 ) -> impl IntoResponse {
-    api::auth::validate(&ctx, &token).await?;
+    api::auth::validate(&ctx, &token, api::auth::Scope::Patches).await?;
 
     let node = Node::new(ctx.profile.socket());
     let signer = ctx
@@ -772,7 +1207,7 @@ async fn patch_update_handler(
     Path((project, patch_id)): Path<(RepoId, Oid)>,
     Json(action): Json<patch::Action>,
 ) -> impl IntoResponse {
-    api::auth::validate(&ctx, &token).await?;
+    api::auth::validate(&ctx, &token, api::auth::Scope::Patches).await?;
 
     let node = Node::new(ctx.profile.socket());
     let signer = ctx
@@ -809,24 +1244,28 @@ async fn patch_update_handler(
             reply_to,
             location,
             embeds,
+            mentions,
         } => {
             let embeds: Vec<Embed> = embeds
                 .into_iter()
                 .filter_map(|embed| resolve_embed(&repo, embed))
                 .collect();
-            patch.review_comment(review, body, location, reply_to, embeds, &signer)?
+            let mentions = resolve_mentions(&body, mentions, ctx.profile.as_ref());
+            patch.review_comment(review, body, location, reply_to, embeds, mentions, &signer)?
         }
         patch::Action::ReviewCommentEdit {
             review,
             comment,
             body,
             embeds,
+            mentions,
         } => {
             let embeds: Vec<Embed> = embeds
                 .into_iter()
                 .filter_map(|embed| resolve_embed(&repo, embed))
                 .collect();
-            patch.edit_review_comment(review, comment, body, embeds, &signer)?
+            let mentions = resolve_mentions(&body, mentions, ctx.profile.as_ref());
+            patch.edit_review_comment(review, comment, body, embeds, mentions, &signer)?
         }
         patch::Action::ReviewCommentReact {
             review,
@@ -861,6 +1300,9 @@ async fn patch_update_handler(
             patch.edit_revision(revision, description, embeds, &signer)?
         }
         patch::Action::RevisionRedact { revision } => patch.redact(revision, &signer)?,
+        patch::Action::RevisionOrigin { revision, origin } => {
+            patch.origin(revision, origin, &signer)?
+        }
         patch::Action::RevisionReact {
             revision,
             reaction,
@@ -873,24 +1315,30 @@ async fn patch_update_handler(
             reply_to,
             location,
             embeds,
+            mentions,
         } => {
             let embeds: Vec<Embed> = embeds
                 .into_iter()
                 .filter_map(|embed| resolve_embed(&repo, embed))
                 .collect();
-            patch.comment(revision, body, reply_to, location, embeds, &signer)?
+            let mentions = resolve_mentions(&body, mentions, ctx.profile.as_ref());
+            patch.comment(
+                revision, body, reply_to, location, embeds, mentions, &signer,
+            )?
         }
         patch::Action::RevisionCommentEdit {
             revision,
             comment,
             body,
             embeds,
+            mentions,
         } => {
             let embeds: Vec<Embed> = embeds
                 .into_iter()
                 .filter_map(|embed| resolve_embed(&repo, embed))
                 .collect();
-            patch.comment_edit(revision, comment, body, embeds, &signer)?
+            let mentions = resolve_mentions(&body, mentions, ctx.profile.as_ref());
+            patch.comment_edit(revision, comment, body, embeds, mentions, &signer)?
         }
         patch::Action::RevisionCommentReact {
             revision,
@@ -920,16 +1368,28 @@ async fn patches_handler(
         page,
         per_page,
         state,
+        q,
+        saved,
     } = qs;
     let page = page.unwrap_or(0);
     let per_page = per_page.unwrap_or(10);
-    let state = state.unwrap_or_default();
+    let query = ctx.query(q, saved.as_deref())?;
+    let state = match (state, &query) {
+        (None, Some(_)) => None,
+        (state, _) => Some(state.unwrap_or_default()),
+    };
     let patches = ctx.profile.patches(&repo)?;
-    let mut patches = patches
-        .list()?
+    let iter = match &query {
+        Some(query) => patches.list_by_query(query)?,
+        None => patches.list()?,
+    };
+    let mut patches = iter
         .filter_map(|r| {
             let (id, patch) = r.ok()?;
-            (state.matches(patch.state())).then_some((id, patch))
+            state
+                .as_ref()
+                .map_or(true, |s| s.matches(patch.state()))
+                .then_some((id, patch))
         })
         .collect::<Vec<_>>();
     patches.sort_by(|(_, a), (_, b)| b.timestamp().cmp(&a.timestamp()));
@@ -963,6 +1423,119 @@ async fn patch_handler(
     )))
 }
 
+/// Get the states of all collaborative objects of a type with a registered evaluator.
+/// `GET /projects/:project/cobs/:typename`
+async fn cobs_handler(
+    State(ctx): State<Context>,
+    Path((project, type_name)): Path<(RepoId, TypeName)>,
+) -> impl IntoResponse {
+    let (repo, _) = ctx.repo(project)?;
+    let cobs = ctx
+        .profile
+        .external_cobs(&repo)?
+        .list(&type_name)?
+        .into_iter()
+        .map(|(id, state)| json!({ "id": id.to_string(), "state": state }))
+        .collect::<Vec<_>>();
+
+    Ok::<_, Error>(Json(cobs))
+}
+
+/// Get the state of a collaborative object of a type with a registered evaluator.
+/// `GET /projects/:project/cobs/:typename/:id`
+async fn cob_handler(
+    State(ctx): State<Context>,
+    Path((project, type_name, id)): Path<(RepoId, TypeName, ObjectId)>,
+) -> impl IntoResponse {
+    let (repo, _) = ctx.repo(project)?;
+    let state = ctx
+        .profile
+        .external_cobs(&repo)?
+        .get(&type_name, &id)?
+        .ok_or(Error::NotFound)?;
+
+    Ok::<_, Error>(Json(json!({ "id": id.to_string(), "state": state })))
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CobWrite {
+    #[serde(default)]
+    pub message: Option<String>,
+    pub actions: NonEmpty<serde_json::Value>,
+    #[serde(default)]
+    pub embeds: Vec<Embed<Uri>>,
+}
+
+/// Create a new collaborative object of any non built-in type.
+/// `POST /projects/:project/cobs/:typename`
+async fn cob_create_handler(
+    State(ctx): State<Context>,
+    AuthBearer(token): AuthBearer,
+    Path((project, type_name)): Path<(RepoId, TypeName)>,
+    Json(cob): Json<CobWrite>,
+) -> impl IntoResponse {
+    api::auth::validate(&ctx, &token, api::auth::Scope::Admin).await?;
+
+    let (repo, _) = ctx.repo(project)?;
+    let node = Node::new(ctx.profile.socket());
+    let signer = ctx
+        .profile
+        .signer()
+        .map_err(|_| Error::Auth("Unauthorized"))?;
+    let embeds: Vec<Embed> = cob
+        .embeds
+        .into_iter()
+        .filter_map(|embed| resolve_embed(&repo, embed))
+        .collect();
+    let message = cob.message.unwrap_or_else(|| String::from("Create object"));
+    let id = store::create_raw(&repo, &type_name, &message, cob.actions, embeds, &signer)?;
+
+    announce_refs(node, repo.id())?;
+
+    Ok::<_, Error>((
+        StatusCode::CREATED,
+        Json(json!({ "success": true, "id": id.to_string() })),
+    ))
+}
+
+/// Update a collaborative object of any non built-in type.
+/// `PATCH /projects/:project/cobs/:typename/:id`
+async fn cob_update_handler(
+    State(ctx): State<Context>,
+    AuthBearer(token): AuthBearer,
+    Path((project, type_name, id)): Path<(RepoId, TypeName, ObjectId)>,
+    Json(cob): Json<CobWrite>,
+) -> impl IntoResponse {
+    api::auth::validate(&ctx, &token, api::auth::Scope::Admin).await?;
+
+    let (repo, _) = ctx.repo(project)?;
+    let node = Node::new(ctx.profile.socket());
+    let signer = ctx
+        .profile
+        .signer()
+        .map_err(|_| Error::Auth("Unauthorized"))?;
+    let embeds: Vec<Embed> = cob
+        .embeds
+        .into_iter()
+        .filter_map(|embed| resolve_embed(&repo, embed))
+        .collect();
+    let message = cob.message.unwrap_or_else(|| String::from("Update object"));
+    let entry = store::update_raw(
+        &repo,
+        &type_name,
+        id,
+        &message,
+        cob.actions,
+        embeds,
+        &signer,
+    )?;
+
+    announce_refs(node, repo.id())?;
+
+    Ok::<_, Error>(Json(json!({ "success": true, "id": entry.to_string() })))
+}
+
 #[cfg(test)]
 mod routes {
     use std::net::SocketAddr;
@@ -974,6 +1547,7 @@ mod routes {
     use radicle::storage::ReadStorage;
     use serde_json::json;
 
+    use crate::api::auth;
     use crate::test::*;
 
     #[tokio::test]
@@ -1730,9 +2304,11 @@ mod routes {
                         },
                         "body": "Change 'hello world' to 'hello everyone'",
                         "timestamp": TIMESTAMP,
+                        "mentions": [],
                         "embeds": [],
                       },
                     ],
+                    "mentions": [],
                     "embeds": [],
                     "reactions": [],
                     "timestamp": TIMESTAMP,
@@ -1740,12 +2316,324 @@ mod routes {
                     "resolved": false,
                   }
                 ],
-                "labels": []
+                "labels": [],
+                "tasks": []
               }
             ])
         );
     }
 
+    #[tokio::test]
+    async fn test_projects_issues_query() {
+        let tmp = tempfile::tempdir().unwrap();
+        let app = super::router(seed(tmp.path()));
+
+        let response = get(&app, format!("/projects/{RID}/issues?q=title:issue")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let json = response.json().await;
+        assert_eq!(json.as_array().unwrap().len(), 1);
+        assert_eq!(json[0]["id"], ISSUE_ID);
+
+        let response = get(&app, format!("/projects/{RID}/issues?q=-title:issue")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.json().await, json!([]));
+
+        let response = get(
+            &app,
+            format!("/projects/{RID}/issues?q=title:issue&state=closed"),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.json().await, json!([]));
+
+        let response = get(&app, format!("/projects/{RID}/issues?q=review:accepted")).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = get(&app, format!("/projects/{RID}/issues?saved=unknown")).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_projects_scopes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ctx = contributor(tmp.path());
+        let app = super::router(ctx.to_owned());
+        let issue = || {
+            Some(Body::from(
+                serde_json::to_vec(&json!({
+                    "title": "Issue #2",
+                    "description": "",
+                    "labels": [],
+                    "embeds": [],
+                    "assignees": [],
+                }))
+                .unwrap(),
+            ))
+        };
+        let edit = || {
+            Some(Body::from(
+                serde_json::to_vec(
+                    &json!({ "type": "edit", "title": "Update", "target": "delegates" }),
+                )
+                .unwrap(),
+            ))
+        };
+
+        // A read-only session can't create issues.
+        create_session_with_scopes(ctx.clone(), [auth::Scope::Read]).await;
+        let response = post(
+            &app,
+            format!("/projects/{CONTRIBUTOR_RID}/issues"),
+            issue(),
+            Some(SESSION_ID.to_string()),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // A session scoped to issues can create them, but not update patches.
+        create_session_with_scopes(ctx, [auth::Scope::Issues]).await;
+        let response = post(
+            &app,
+            format!("/projects/{CONTRIBUTOR_RID}/issues"),
+            issue(),
+            Some(SESSION_ID.to_string()),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let response = patch(
+            &app,
+            format!("/projects/{CONTRIBUTOR_RID}/patches/{CONTRIBUTOR_PATCH_ID}"),
+            edit(),
+            Some(SESSION_ID.to_string()),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // Unknown tokens aren't authorized at all.
+        let response = post(
+            &app,
+            format!("/projects/{CONTRIBUTOR_RID}/issues"),
+            issue(),
+            Some(String::from("unknown")),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_projects_cobs_create() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ctx = contributor(tmp.path());
+        let app = super::router(ctx.to_owned());
+
+        create_session(ctx).await;
+
+        let body = serde_json::to_vec(&json!({
+            "message": "Open incident",
+            "actions": [{ "type": "open", "severity": "high" }],
+        }))
+        .unwrap();
+
+        let response = post(
+            &app,
+            format!("/projects/{CONTRIBUTOR_RID}/cobs/com.example.incident"),
+            Some(Body::from(body.clone())),
+            Some(SESSION_ID.to_string()),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        // Without a registered evaluator, objects of this type have no state to show.
+        let response = get(
+            &app,
+            format!("/projects/{CONTRIBUTOR_RID}/cobs/com.example.incident"),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = post(
+            &app,
+            format!("/projects/{CONTRIBUTOR_RID}/cobs/xyz.radicle.issue"),
+            Some(Body::from(body)),
+            Some(SESSION_ID.to_string()),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_projects_discussions() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ctx = contributor(tmp.path());
+        let app = super::router(ctx.to_owned());
+
+        create_session_with_scopes(ctx, [auth::Scope::Discussions]).await;
+
+        let body = serde_json::to_vec(&json!({
+            "title": "How do I run the tests?",
+            "body": "Is there a script for it?",
+            "category": "question",
+        }))
+        .unwrap();
+        let response = post(
+            &app,
+            format!("/projects/{CONTRIBUTOR_RID}/discussions"),
+            Some(Body::from(body)),
+            Some(SESSION_ID.to_string()),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let id = response.json().await["id"].as_str().unwrap().to_owned();
+
+        let body = serde_json::to_vec(&json!({
+            "type": "comment",
+            "body": "Use `cargo test`.",
+            "replyTo": id,
+        }))
+        .unwrap();
+        let response = patch(
+            &app,
+            format!("/projects/{CONTRIBUTOR_RID}/discussions/{id}"),
+            Some(Body::from(body)),
+            Some(SESSION_ID.to_string()),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let reply = response.json().await["id"].as_str().unwrap().to_owned();
+
+        let body = serde_json::to_vec(&json!({ "type": "answer", "id": reply })).unwrap();
+        let response = patch(
+            &app,
+            format!("/projects/{CONTRIBUTOR_RID}/discussions/{id}"),
+            Some(Body::from(body)),
+            Some(SESSION_ID.to_string()),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = get(
+            &app,
+            format!("/projects/{CONTRIBUTOR_RID}/discussions/{id}"),
+        )
+        .await;
+        let discussion = response.json().await;
+
+        assert_eq!(discussion["title"], "How do I run the tests?");
+        assert_eq!(discussion["category"], "question");
+        assert_eq!(discussion["pinned"], false);
+        assert_eq!(discussion["answer"], reply.as_str());
+        assert_eq!(discussion["discussion"].as_array().unwrap().len(), 2);
+
+        let response = get(
+            &app,
+            format!("/projects/{CONTRIBUTOR_RID}/discussions?category=announcement"),
+        )
+        .await;
+
+        assert_eq!(response.json().await, json!([]));
+
+        let response = get(
+            &app,
+            format!("/projects/{CONTRIBUTOR_RID}/discussions?category=question"),
+        )
+        .await;
+
+        assert_eq!(response.json().await[0]["id"], id.as_str());
+    }
+
+    #[tokio::test]
+    async fn test_projects_wiki() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ctx = contributor(tmp.path());
+        let app = super::router(ctx.to_owned());
+
+        create_session_with_scopes(ctx, [auth::Scope::Wiki]).await;
+
+        let body = serde_json::to_vec(&json!({
+            "path": "guides/install",
+            "title": "Installation",
+            "body": "Run `make`.\n",
+        }))
+        .unwrap();
+        let response = post(
+            &app,
+            format!("/projects/{CONTRIBUTOR_RID}/wiki"),
+            Some(Body::from(body.clone())),
+            Some(SESSION_ID.to_string()),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let id = response.id().await;
+
+        // Paths are unique.
+        let response = post(
+            &app,
+            format!("/projects/{CONTRIBUTOR_RID}/wiki"),
+            Some(Body::from(body)),
+            Some(SESSION_ID.to_string()),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let body = serde_json::to_vec(&json!({
+            "type": "revise",
+            "body": "Run `make install`.\n",
+        }))
+        .unwrap();
+        let response = patch(
+            &app,
+            format!("/projects/{CONTRIBUTOR_RID}/wiki/{id}"),
+            Some(Body::from(body)),
+            Some(SESSION_ID.to_string()),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let revision = response.id().await;
+
+        let response = get(&app, format!("/projects/{CONTRIBUTOR_RID}/wiki/{id}")).await;
+        let page = response.json().await;
+
+        assert_eq!(page["path"], "guides/install");
+        assert_eq!(page["title"], "Installation");
+        assert_eq!(page["body"], "Run `make install`.\n");
+        assert_eq!(page["revision"], revision.to_string());
+        assert_eq!(page["history"].as_array().unwrap().len(), 2);
+
+        let response = get(&app, format!("/projects/{CONTRIBUTOR_RID}/wiki/{id}/raw")).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(&response.body().await[..], b"Run `make install`.\n");
+
+        let response = get(
+            &app,
+            format!("/projects/{CONTRIBUTOR_RID}/wiki/{id}/diff/{id}/{revision}"),
+        )
+        .await;
+        let diff = response.json().await;
+        let diff = diff["diff"].as_str().unwrap();
+
+        assert!(diff.contains("-Run `make`."));
+        assert!(diff.contains("+Run `make install`."));
+
+        let response = get(
+            &app,
+            format!("/projects/{CONTRIBUTOR_RID}/wiki?path=guides/install"),
+        )
+        .await;
+
+        assert_eq!(response.json().await[0]["id"], id.to_string());
+    }
+
     #[tokio::test]
     async fn test_projects_issues_create() {
         const CREATED_ISSUE_ID: &str = "fcd0d5940b55df596cf8079fd1845903f1104bcd";
@@ -1760,6 +2648,7 @@ mod routes {
             "title": "Issue #2",
             "description": "Change 'hello world' to 'hello everyone'",
             "labels": ["bug"],
+            "mentions": [],
             "embeds": [
               {
                 "name": "example.html",
@@ -1818,6 +2707,7 @@ mod routes {
                     },
                     "body": "Change 'hello world' to 'hello everyone'",
                     "timestamp": TIMESTAMP,
+                    "mentions": [],
                     "embeds": [
                       {
                         "name": "example.html",
@@ -1826,6 +2716,7 @@ mod routes {
                     ],
                   },
                 ],
+                "mentions": [],
                 "embeds": [
                   {
                     "name": "example.html",
@@ -1840,6 +2731,7 @@ mod routes {
               "labels": [
                   "bug",
               ],
+              "tasks": [],
             })
         );
     }
@@ -1855,6 +2747,7 @@ mod routes {
         let body = serde_json::to_vec(&json!({
           "type": "comment",
           "body": "This is first-level comment",
+          "mentions": [],
           "embeds": [
             {
               "name": "image.jpg",
@@ -1899,6 +2792,7 @@ mod routes {
           "type": "comment.edit",
           "id": ISSUE_DISCUSSION_ID,
           "body": "EDIT: Change 'hello world' to 'hello anyone'",
+          "mentions": [],
           "embeds": [
             {
               "name":"image.jpg",
@@ -1969,6 +2863,7 @@ mod routes {
                       },
                       "body": "Change 'hello world' to 'hello everyone'",
                       "timestamp": TIMESTAMP,
+                      "mentions": [],
                       "embeds": [],
                     },
                     {
@@ -1978,6 +2873,7 @@ mod routes {
                       },
                       "body": "EDIT: Change 'hello world' to 'hello anyone'",
                       "timestamp": TIMESTAMP,
+                      "mentions": [],
                       "embeds": [
                         {
                           "name": "image.jpg",
@@ -1986,6 +2882,7 @@ mod routes {
                       ],
                     },
                   ],
+                  "mentions": [],
                   "embeds": [
                     {
                       "name": "image.jpg",
@@ -2009,6 +2906,7 @@ mod routes {
                 },
               ],
               "labels": [],
+              "tasks": [],
             })
         );
     }
@@ -2076,9 +2974,11 @@ mod routes {
                       },
                       "body": "Change 'hello world' to 'hello everyone'",
                       "timestamp": TIMESTAMP,
+                      "mentions": [],
                       "embeds": [],
                     },
                   ],
+                  "mentions": [],
                   "embeds": [],
                   "reactions": [],
                   "timestamp": TIMESTAMP,
@@ -2087,6 +2987,7 @@ mod routes {
                 },
               ],
               "labels": [],
+              "tasks": [],
             })
         );
     }
@@ -2102,6 +3003,7 @@ mod routes {
         let body = serde_json::to_vec(&json!({
           "type": "comment",
           "body": "This is a reply to the first comment",
+          "mentions": [],
           "embeds": [
             {
               "name": "image.jpg",
@@ -2159,9 +3061,11 @@ mod routes {
                       },
                       "body": "Change 'hello world' to 'hello everyone'",
                       "timestamp": TIMESTAMP,
+                      "mentions": [],
                       "embeds": [],
                     },
                   ],
+                  "mentions": [],
                   "embeds": [],
                   "reactions": [],
                   "timestamp": TIMESTAMP,
@@ -2183,6 +3087,7 @@ mod routes {
                       },
                       "body": "This is a reply to the first comment",
                       "timestamp": TIMESTAMP,
+                      "mentions": [],
                       "embeds": [
                         {
                           "name": "image.jpg",
@@ -2191,6 +3096,7 @@ mod routes {
                       ],
                     },
                   ],
+                  "mentions": [],
                   "embeds": [
                     {
                       "name": "image.jpg",
@@ -2204,6 +3110,7 @@ mod routes {
                 },
               ],
               "labels": [],
+              "tasks": [],
             })
         );
     }
@@ -2248,6 +3155,7 @@ mod routes {
                         },
                         "body": "change `hello world` in README to something else",
                         "timestamp": TIMESTAMP,
+                        "mentions": [],
                         "embeds": [],
                       },
                     ],
@@ -2304,6 +3212,7 @@ mod routes {
                         },
                         "body": "change `hello world` in README to something else",
                         "timestamp": TIMESTAMP,
+                        "mentions": [],
                         "embeds": [],
                       },
                     ],
@@ -2399,6 +3308,7 @@ mod routes {
                         },
                         "body": "Do some changes to README",
                         "timestamp": TIMESTAMP,
+                        "mentions": [],
                         "embeds": [],
                       },
                    ],
@@ -2479,6 +3389,7 @@ mod routes {
                       },
                       "body": "change `hello world` in README to something else",
                       "timestamp": TIMESTAMP,
+                      "mentions": [],
                       "embeds": [],
                     },
                   ],
@@ -2557,6 +3468,7 @@ mod routes {
                       },
                       "body": "change `hello world` in README to something else",
                       "timestamp": TIMESTAMP,
+                      "mentions": [],
                       "embeds": [],
                     },
                   ],
@@ -2634,6 +3546,7 @@ mod routes {
                       },
                       "body": "change `hello world` in README to something else",
                       "timestamp": TIMESTAMP,
+                      "mentions": [],
                       "embeds": [],
                     },
                   ],
@@ -2662,6 +3575,7 @@ mod routes {
                       },
                       "body": "This is a new revision",
                       "timestamp": TIMESTAMP,
+                      "mentions": [],
                       "embeds": [],
                     },
                   ],
@@ -2740,6 +3654,7 @@ mod routes {
                       },
                       "body": "change `hello world` in README to something else",
                       "timestamp": TIMESTAMP,
+                      "mentions": [],
                       "embeds": [],
                     },
                   ],
@@ -2861,6 +3776,7 @@ mod routes {
                       },
                       "body": "change `hello world` in README to something else",
                       "timestamp": TIMESTAMP,
+                      "mentions": [],
                       "embeds": [],
                     },
                     {
@@ -2870,6 +3786,7 @@ mod routes {
                       },
                       "body": "Let's change the description a bit",
                       "timestamp": TIMESTAMP,
+                      "mentions": [],
                       "embeds": [],
                     },
                   ],
@@ -2929,6 +3846,7 @@ mod routes {
           "type": "revision.comment",
           "revision": CONTRIBUTOR_PATCH_ID,
           "body": "This is a root level comment",
+          "mentions": [],
           "embeds": [
             {
               "name": "image.jpg",
@@ -2969,6 +3887,7 @@ mod routes {
           "revision": CONTRIBUTOR_PATCH_ID,
           "comment": comment_id,
           "body": "EDIT: This is a root level comment",
+          "mentions": [],
           "embeds": [
             {
               "name": "image.jpg",
@@ -2991,6 +3910,7 @@ mod routes {
           "revision": CONTRIBUTOR_PATCH_ID,
           "body": "This is a root level comment",
           "replyTo": comment_id,
+          "mentions": [],
           "embeds": [],
         }))
         .unwrap();
@@ -3041,6 +3961,7 @@ mod routes {
                       },
                       "body": "change `hello world` in README to something else",
                       "timestamp": TIMESTAMP,
+                      "mentions": [],
                       "embeds": [],
                     },
                   ],
@@ -3066,6 +3987,7 @@ mod routes {
                           },
                           "body": "This is a root level comment",
                           "timestamp": TIMESTAMP,
+                          "mentions": [],
                           "embeds": [
                             {
                                 "name": "image.jpg",
@@ -3080,6 +4002,7 @@ mod routes {
                           },
                           "body": "EDIT: This is a root level comment",
                           "timestamp": TIMESTAMP,
+                          "mentions": [],
                           "embeds": [
                            {
                                 "name": "image.jpg",
@@ -3088,6 +4011,7 @@ mod routes {
                           ],
                         },
                       ],
+                      "mentions": [],
                       "embeds": [
                         {
                           "name": "image.jpg",
@@ -3125,9 +4049,11 @@ mod routes {
                           },
                           "body": "This is a root level comment",
                           "timestamp": TIMESTAMP,
+                          "mentions": [],
                           "embeds": [],
                         },
                       ],
+                      "mentions": [],
                       "embeds": [],
                       "reactions": [],
                       "timestamp": TIMESTAMP,
@@ -3172,6 +4098,7 @@ mod routes {
           "type": "review.comment",
           "review": review_id,
           "body": "This is a comment on a review",
+          "mentions": [],
           "embeds": [
             {
               "name": "image.jpg",
@@ -3204,6 +4131,7 @@ mod routes {
           "type": "review.comment.edit",
           "review": review_id,
           "comment": comment_id,
+          "mentions": [],
           "embeds": [
             {
               "name": "image.jpg",
@@ -3287,6 +4215,7 @@ mod routes {
                       },
                       "body": "change `hello world` in README to something else",
                       "timestamp": TIMESTAMP,
+                      "mentions": [],
                       "embeds": [],
                     },
                   ],
@@ -3323,6 +4252,7 @@ mod routes {
                               },
                               "body": "This is a comment on a review",
                               "timestamp": 1671125284,
+                              "mentions": [],
                               "embeds": [
                                 {
                                   "name": "image.jpg",
@@ -3337,6 +4267,7 @@ mod routes {
                               },
                               "body": "EDIT: This is a comment on a review",
                               "timestamp": 1671125284,
+                              "mentions": [],
                               "embeds": [
                                 {
                                   "name": "image.jpg",
@@ -3345,6 +4276,7 @@ mod routes {
                               ],
                             },
                           ],
+                          "mentions": [],
                           "embeds": [
                             {
                               "name": "image.jpg",
@@ -3458,6 +4390,7 @@ mod routes {
                       },
                       "body": "change `hello world` in README to something else",
                       "timestamp": TIMESTAMP,
+                      "mentions": [],
                       "embeds": [],
                     },
                   ],
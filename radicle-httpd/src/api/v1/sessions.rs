@@ -1,4 +1,4 @@
-use std::iter::repeat_with;
+use std::collections::BTreeSet;
 
 use axum::extract::State;
 use axum::response::IntoResponse;
@@ -37,22 +37,23 @@ struct AuthChallenge {
 /// Create session.
 /// `POST /sessions`
 async fn session_create_handler(State(ctx): State<Context>) -> impl IntoResponse {
-    let mut rng = fastrand::Rng::new();
-    let session_id = repeat_with(|| rng.alphanumeric())
-        .take(32)
-        .collect::<String>();
+    let session_id = auth::session_id();
     let signer = ctx.profile.signer().map_err(Error::from)?;
+    let now = OffsetDateTime::now_utc();
     let session = Session {
         status: AuthState::Unauthorized,
         public_key: *signer.public_key(),
         alias: ctx.profile.config.node.alias.clone(),
-        issued_at: OffsetDateTime::now_utc(),
-        expires_at: OffsetDateTime::now_utc()
+        scopes: BTreeSet::from([auth::Scope::Admin]),
+        label: None,
+        issued_at: now,
+        expires_at: now
             .checked_add(auth::UNAUTHORIZED_SESSIONS_EXPIRATION)
             .unwrap(),
     };
-    let mut sessions = ctx.sessions.write().await;
-    sessions.insert(session_id.clone(), session.clone());
+    // Sessions that were never signed into, or that expired, are cleaned up here.
+    ctx.sessions.prune(now)?;
+    ctx.sessions.insert(&session_id, &session)?;
 
     Ok::<_, Error>((
         StatusCode::CREATED,
@@ -66,10 +67,9 @@ async fn session_handler(
     State(ctx): State<Context>,
     Path(session_id): Path<String>,
 ) -> impl IntoResponse {
-    let sessions = ctx.sessions.read().await;
-    let session = sessions.get(&session_id).ok_or(Error::NotFound)?;
+    let session = ctx.sessions.get(&session_id)?.ok_or(Error::NotFound)?;
 
-    Ok::<_, Error>(Json(json::session(session_id, session)))
+    Ok::<_, Error>(Json(json::session(session_id, &session)))
 }
 
 /// Update session.
@@ -79,8 +79,7 @@ async fn session_signin_handler(
     Path(session_id): Path<String>,
     Json(request): Json<AuthChallenge>,
 ) -> impl IntoResponse {
-    let mut sessions = ctx.sessions.write().await;
-    let session = sessions.get_mut(&session_id).ok_or(Error::NotFound)?;
+    let mut session = ctx.sessions.get(&session_id)?.ok_or(Error::NotFound)?;
     if session.status == AuthState::Unauthorized {
         if session.public_key != request.pk {
             return Err(Error::Auth("Invalid public key"));
@@ -97,6 +96,7 @@ async fn session_signin_handler(
         session.expires_at = OffsetDateTime::now_utc()
             .checked_add(auth::AUTHORIZED_SESSIONS_EXPIRATION)
             .unwrap();
+        ctx.sessions.insert(&session_id, &session)?;
 
         return Ok::<_, Error>(Json(json!({ "success": true })));
     }
@@ -114,8 +114,9 @@ async fn session_delete_handler(
     if token != session_id {
         return Err(Error::Auth("Not authorized to delete this session"));
     }
-    let mut sessions = ctx.sessions.write().await;
-    sessions.remove_entry(&token).ok_or(Error::NotFound)?;
+    if !ctx.sessions.remove(&token)? {
+        return Err(Error::NotFound);
+    }
 
     Ok::<_, Error>(Json(json!({ "success": true })))
 }
@@ -126,7 +127,10 @@ mod routes {
     use axum::body::Body;
     use axum::http::StatusCode;
 
-    use crate::api::auth::{AuthState, Session};
+    use std::collections::BTreeSet;
+
+    use crate::api::auth::{AuthState, Scope, Session};
+    use crate::api::Context;
     use crate::test::{self, get, post, put};
 
     #[tokio::test]
@@ -179,5 +183,19 @@ mod routes {
 
         assert_eq!(status, StatusCode::OK);
         assert_eq!(body.status, AuthState::Authorized);
+        assert_eq!(body.scopes, BTreeSet::from([Scope::Admin]));
+
+        // Check that the session survives a restart of the daemon.
+        let options = crate::Options {
+            aliases: Default::default(),
+            listen: std::net::SocketAddr::from(([0, 0, 0, 0], 8080)),
+            cache: None,
+        };
+        let ctx = Context::new(ctx.profile().clone(), &options).unwrap();
+        let app = super::router(ctx);
+        let response = get(&app, format!("/sessions/{}", session_info.session_id)).await;
+        let body: Session = serde_json::from_value(response.json().await).unwrap();
+
+        assert_eq!(body.status, AuthState::Authorized);
     }
 }
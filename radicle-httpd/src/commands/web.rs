@@ -1,3 +1,4 @@
+use std::collections::BTreeSet;
 use std::ffi::OsString;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::process::Command;
@@ -6,12 +7,17 @@ use std::time::Duration;
 
 use anyhow::{anyhow, Context};
 use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
 use url::{Position, Url};
 
 use radicle::crypto::{PublicKey, Signature, Signer};
+use radicle::Profile;
 
 use radicle_cli::terminal as term;
 use radicle_cli::terminal::args::{Args, Error, Help};
+use radicle_term::Element as _;
+
+use crate::api::auth;
 
 pub const HELP: Help = Help {
     name: "web",
@@ -21,15 +27,30 @@ pub const HELP: Help = Help {
 Usage
 
     rad web [<option>...] [<explorer-url>]
+    rad web sessions
+    rad web token <label> [--scope <scope>]... [--expires <days>]
+    rad web revoke <session-id | hash>
 
     Runs the Radicle HTTP Daemon and opens a Radicle web explorer to authenticate with it.
 
+    Sessions and API tokens are stored in the profile, and survive restarts of the
+    HTTP daemon. Only a hash of each token is stored. The `sessions` command lists
+    them by hash, and `revoke` removes one given its token or hash.
+
+    The `token` command creates a long-lived API token for automation, limited to
+    the given scopes: `read`, `issues`, `patches`, `discussions`, `wiki` or `admin`.
+    The token is printed on standard output, and is used as a bearer token. To push
+    over HTTP, use the token as the password of your Git credentials: `patches` tokens
+    can open and update patches, and `admin` tokens can also push branches.
+
 Options
 
     --listen, -l  <addr>     Address to bind the HTTP daemon to (default: 127.0.0.1:8080)
     --connect, -c [<addr>]   Connect the explorer to an already running daemon (default: 127.0.0.1:8080)
     --path, -p  <path>       Path to be opened in the explorer after authentication
     --[no-]open              Open the authentication URL automatically (default: open)
+    --scope <scope>          Scope granted to the API token (default: read)
+    --expires <days>         Number of days until the API token expires (default: 90)
     --help                   Print help
 "#,
 };
@@ -41,8 +62,29 @@ pub struct SessionInfo {
     pub public_key: PublicKey,
 }
 
+/// Default number of days until an API token expires.
+pub const DEFAULT_TOKEN_EXPIRATION_DAYS: u32 = 90;
+
+#[derive(Debug, Default)]
+pub enum Operation {
+    /// Authenticate the web explorer.
+    #[default]
+    Open,
+    /// List sessions and API tokens.
+    Sessions,
+    /// Create an API token.
+    Token {
+        label: String,
+        scopes: BTreeSet<auth::Scope>,
+        expires: time::Duration,
+    },
+    /// Revoke a session or API token.
+    Revoke { id: auth::SessionId },
+}
+
 #[derive(Debug)]
 pub struct Options {
+    pub op: Operation,
     pub app_url: Url,
     pub listen: SocketAddr,
     pub path: Option<String>,
@@ -62,6 +104,11 @@ impl Args for Options {
         #[allow(clippy::unwrap_used)]
         let mut app_url = Url::parse("https://app.radicle.xyz").unwrap();
         let mut open = true;
+        let mut op: Option<&str> = None;
+        let mut label = None;
+        let mut id = None;
+        let mut scopes = BTreeSet::new();
+        let mut expires = DEFAULT_TOKEN_EXPIRATION_DAYS;
 
         while let Some(arg) = parser.next()? {
             match arg {
@@ -83,14 +130,33 @@ impl Args for Options {
                         ));
                     }
                 }
+                Long("scope") if op == Some("token") => {
+                    let val = parser.value()?;
+                    scopes.insert(term::args::parse_value("scope", val)?);
+                }
+                Long("expires") if op == Some("token") => {
+                    let val = parser.value()?;
+                    expires = term::args::parse_value("expires", val)?;
+                }
                 Long("open") => open = true,
                 Long("no-open") => open = false,
                 Long("help") | Short('h') => {
                     return Err(Error::Help.into());
                 }
-                Value(val) => {
-                    let val = val.to_string_lossy();
-                    app_url = Url::parse(val.as_ref()).context("invalid explorer URL supplied")?;
+                Value(val) if op.is_none() => match val.to_string_lossy().as_ref() {
+                    "sessions" => op = Some("sessions"),
+                    "token" => op = Some("token"),
+                    "revoke" => op = Some("revoke"),
+                    val => {
+                        app_url = Url::parse(val).context("invalid explorer URL supplied")?;
+                        op = Some("open");
+                    }
+                },
+                Value(val) if op == Some("token") && label.is_none() => {
+                    label = Some(term::args::string(&val));
+                }
+                Value(val) if op == Some("revoke") && id.is_none() => {
+                    id = Some(term::args::string(&val));
                 }
                 _ => {
                     return Err(anyhow!(arg.unexpected()));
@@ -98,8 +164,28 @@ impl Args for Options {
             }
         }
 
+        let op = match op {
+            Some("sessions") => Operation::Sessions,
+            Some("token") => {
+                if scopes.is_empty() {
+                    scopes.insert(auth::Scope::Read);
+                }
+                Operation::Token {
+                    label: label
+                        .ok_or_else(|| anyhow!("a label must be specified for the token"))?,
+                    scopes,
+                    expires: time::Duration::days(expires as i64),
+                }
+            }
+            Some("revoke") => Operation::Revoke {
+                id: id.ok_or_else(|| anyhow!("a session ID must be specified"))?,
+            },
+            _ => Operation::Open,
+        };
+
         Ok((
             Options {
+                op,
                 open,
                 app_url,
                 listen: listen.unwrap_or(SocketAddr::new(
@@ -122,6 +208,122 @@ pub fn sign(signer: Box<dyn Signer>, session: &SessionInfo) -> Result<Signature,
 
 pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
     let profile = ctx.profile()?;
+
+    match options.op {
+        Operation::Open => open(options, &profile),
+        Operation::Sessions => sessions(&profile),
+        Operation::Token {
+            label,
+            scopes,
+            expires,
+        } => token(label, scopes, expires, &profile),
+        Operation::Revoke { id } => revoke(&id, &profile),
+    }
+}
+
+fn store(profile: &Profile) -> anyhow::Result<auth::Store> {
+    auth::Store::open(profile.home.node().join(auth::SESSIONS_DB_FILE))
+        .context("failed to open sessions database")
+}
+
+fn sessions(profile: &Profile) -> anyhow::Result<()> {
+    let store = store(profile)?;
+    let now = OffsetDateTime::now_utc();
+    let mut t = term::Table::new(term::table::TableOptions::bordered());
+
+    t.header([
+        term::format::default(String::from("Hash")),
+        term::format::default(String::from("Label")),
+        term::format::default(String::from("Scopes")),
+        term::format::default(String::from("Status")),
+        term::format::default(String::from("Expires")),
+    ]);
+    t.divider();
+
+    for (hash, session) in store.list()? {
+        let scopes = session
+            .scopes
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let status = if session.expires_at <= now {
+            term::format::negative(String::from("expired"))
+        } else if session.is_valid(now) {
+            term::format::positive(session.status.to_string())
+        } else {
+            term::format::dim(session.status.to_string())
+        };
+
+        t.push([
+            term::format::tertiary(hash),
+            term::format::default(session.label.unwrap_or_default()),
+            term::format::default(scopes),
+            status,
+            term::format::dim(date(session.expires_at)),
+        ]);
+    }
+
+    if t.is_empty() {
+        term::print(term::format::dim("No sessions to show."));
+    } else {
+        t.print();
+    }
+    Ok(())
+}
+
+fn token(
+    label: String,
+    scopes: BTreeSet<auth::Scope>,
+    expires: time::Duration,
+    profile: &Profile,
+) -> anyhow::Result<()> {
+    let store = store(profile)?;
+    let id = auth::session_id();
+    let issued_at = OffsetDateTime::now_utc();
+    let session = auth::Session {
+        status: auth::AuthState::Authorized,
+        public_key: *profile.id(),
+        alias: profile.config.node.alias.clone(),
+        scopes,
+        label: Some(label),
+        issued_at,
+        expires_at: issued_at
+            .checked_add(expires)
+            .ok_or_else(|| anyhow!("invalid token expiration"))?,
+    };
+    store.insert(&id, &session)?;
+
+    term::success!(
+        "Created API token {} expiring on {}",
+        term::format::highlight(session.label.unwrap_or_default()),
+        term::format::dim(date(session.expires_at)),
+    );
+    term::print(id);
+
+    Ok(())
+}
+
+fn revoke(id: &str, profile: &Profile) -> anyhow::Result<()> {
+    let store = store(profile)?;
+
+    // Sessions can be revoked with their token, or with the hash listed by `sessions`.
+    if !store.remove(id)? && !store.remove_hash(id)? {
+        anyhow::bail!("session '{id}' not found");
+    }
+    term::success!("Revoked session {}", term::format::tertiary(id));
+
+    Ok(())
+}
+
+/// Format the date of a point in time.
+fn date(time: OffsetDateTime) -> String {
+    chrono::DateTime::from_timestamp(time.unix_timestamp(), 0)
+        .map(|t| t.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+fn open(options: Options, profile: &Profile) -> anyhow::Result<()> {
     let runtime_and_handle = if options.connect.is_none() {
         tracing_subscriber::fmt::init();
 
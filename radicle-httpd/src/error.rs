@@ -14,9 +14,17 @@ pub enum GitError {
     #[error("i/o error: {0}")]
     Io(#[from] std::io::Error),
 
-    /// The service is not available.
-    #[error("service '{0}' not available")]
-    ServiceUnavailable(&'static str),
+    /// The request isn't authenticated.
+    #[error("could not authenticate: {0}")]
+    Unauthorized(&'static str),
+
+    /// The session isn't allowed to perform the request.
+    #[error("forbidden: {0}")]
+    Forbidden(&'static str),
+
+    /// The request is malformed.
+    #[error("bad request: {0}")]
+    BadRequest(String),
 
     /// Invalid identifier.
     #[error("invalid radicle identifier: {0}")]
@@ -38,6 +46,18 @@ pub enum GitError {
     #[error("git-http-backend: invalid header returned: {0:?}")]
     BackendHeader(String),
 
+    /// Git error.
+    #[error("git: {0}")]
+    Git(#[from] radicle::git::raw::Error),
+
+    /// Sessions store error.
+    #[error(transparent)]
+    Sessions(#[from] crate::api::auth::store::Error),
+
+    /// Push error.
+    #[error("push: {0}")]
+    Push(#[from] radicle_remote_helper::push::Error),
+
     /// HeaderName error.
     #[error(transparent)]
     InvalidHeaderName(#[from] axum::http::header::InvalidHeaderName),
@@ -50,7 +70,9 @@ pub enum GitError {
 impl GitError {
     pub fn status(&self) -> http::StatusCode {
         match self {
-            GitError::ServiceUnavailable(_) => http::StatusCode::SERVICE_UNAVAILABLE,
+            GitError::Unauthorized(_) => http::StatusCode::UNAUTHORIZED,
+            GitError::Forbidden(_) => http::StatusCode::FORBIDDEN,
+            GitError::BadRequest(_) => http::StatusCode::BAD_REQUEST,
             GitError::Id(_) => http::StatusCode::NOT_FOUND,
             GitError::NotFound => http::StatusCode::NOT_FOUND,
             _ => http::StatusCode::INTERNAL_SERVER_ERROR,
@@ -62,7 +84,15 @@ impl IntoResponse for GitError {
     fn into_response(self) -> Response {
         tracing::error!("{}", self);
 
-        self.status().into_response()
+        match self {
+            // Let Git tooling know that it should prompt for credentials.
+            GitError::Unauthorized(_) => (
+                self.status(),
+                [(http::header::WWW_AUTHENTICATE, "Basic realm=\"Radicle\"")],
+            )
+                .into_response(),
+            _ => self.status().into_response(),
+        }
     }
 }
 
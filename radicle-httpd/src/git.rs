@@ -1,35 +1,55 @@
-use std::collections::HashMap;
+mod pktline;
+
+use std::collections::{BTreeMap, HashMap};
 use std::io::prelude::*;
 use std::net::SocketAddr;
 use std::path::Path;
 use std::process::{Command, Stdio};
+use std::str::FromStr;
 use std::sync::Arc;
 use std::{io, net, str};
 
 use axum::body::Bytes;
 use axum::extract::{ConnectInfo, Path as AxumPath, RawQuery, State};
-use axum::http::header::HeaderName;
+use axum::http::header::{self, HeaderName};
 use axum::http::{HeaderMap, Method, StatusCode};
 use axum::response::IntoResponse;
 use axum::routing::any;
 use axum::Router;
+use base64::Engine as _;
 use flate2::write::GzDecoder;
 use hyper::body::Buf as _;
+use time::OffsetDateTime;
 
+use radicle::crypto::PublicKey;
+use radicle::git;
 use radicle::identity::RepoId;
+use radicle::node::Handle as _;
 use radicle::profile::Profile;
-use radicle::storage::{ReadRepository, ReadStorage};
+use radicle::storage::{ReadRepository, ReadStorage, WriteRepository as _};
+use radicle_remote_helper::push;
 
+use crate::api::auth;
 use crate::error::GitError as Error;
 
-pub fn router(profile: Arc<Profile>, aliases: HashMap<String, RepoId>) -> Router {
+use pktline::Packet;
+
+pub fn router(
+    profile: Arc<Profile>,
+    aliases: HashMap<String, RepoId>,
+    sessions: auth::Store,
+) -> Router {
     Router::new()
         .route("/:project/*request", any(git_handler))
-        .with_state((profile, aliases))
+        .with_state((profile, aliases, sessions))
 }
 
 async fn git_handler(
-    State((profile, aliases)): State<(Arc<Profile>, HashMap<String, RepoId>)>,
+    State((profile, aliases, sessions)): State<(
+        Arc<Profile>,
+        HashMap<String, RepoId>,
+        auth::Store,
+    )>,
     AxumPath((project, request)): AxumPath<(String, String)>,
     method: Method,
     headers: HeaderMap,
@@ -49,10 +69,39 @@ async fn git_handler(
         }
     };
 
-    let (status, headers, body) = git_http_backend(
-        &profile, method, headers, body, remote, rid, &request, query,
-    )
-    .await?;
+    let (status, headers, body) = match (request.as_str(), query.as_str()) {
+        // Pushes are only allowed with a session tied to our key.
+        ("git-receive-pack", _) => {
+            let session = authenticate(&profile, &sessions, &headers)?;
+
+            receive_pack(
+                &profile, &session, method, headers, body, remote, rid, query,
+            )
+            .await?
+        }
+        (_, "service=git-receive-pack") => {
+            let session = authenticate(&profile, &sessions, &headers)?;
+
+            git_http_backend(
+                &profile,
+                method,
+                headers,
+                body,
+                remote,
+                rid,
+                &request,
+                query,
+                Some(&session.public_key),
+            )
+            .await?
+        }
+        _ => {
+            git_http_backend(
+                &profile, method, headers, body, remote, rid, &request, query, None,
+            )
+            .await?
+        }
+    };
 
     let mut response_headers = HeaderMap::new();
     for (name, vec) in headers.iter() {
@@ -65,6 +114,46 @@ async fn git_handler(
     Ok::<_, Error>((status, response_headers, body))
 }
 
+/// Get the session token of a request. Git tooling sends credentials using basic
+/// authentication, in which case the password is the token. Bearer tokens are also
+/// accepted, eg. when set via `http.extraHeader`.
+fn token(headers: &HeaderMap) -> Option<String> {
+    let value = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+
+    if let Some(token) = value.strip_prefix("Bearer ") {
+        return Some(token.trim().to_owned());
+    }
+    let credentials = value.strip_prefix("Basic ")?;
+    let credentials = base64::prelude::BASE64_STANDARD
+        .decode(credentials.trim())
+        .ok()?;
+    let credentials = String::from_utf8(credentials).ok()?;
+    let (_, password) = credentials.split_once(':')?;
+
+    Some(password.to_owned())
+}
+
+/// Get the session of a request that needs to be authenticated.
+fn authenticate(
+    profile: &Profile,
+    sessions: &auth::Store,
+    headers: &HeaderMap,
+) -> Result<auth::Session, Error> {
+    let token = token(headers).ok_or(Error::Unauthorized("missing credentials"))?;
+    let session = sessions
+        .get(&token)?
+        .ok_or(Error::Unauthorized("unknown session"))?;
+
+    if !session.is_valid(OffsetDateTime::now_utc()) {
+        return Err(Error::Unauthorized("session is expired or not signed in"));
+    }
+    // Pushed refs are signed with our key, so they can only be pushed to our namespace.
+    if session.public_key != profile.public_key {
+        return Err(Error::Forbidden("session isn't tied to the node's key"));
+    }
+    Ok(session)
+}
+
 async fn git_http_backend(
     profile: &Profile,
     method: Method,
@@ -74,6 +163,7 @@ async fn git_http_backend(
     id: RepoId,
     path: &str,
     query: String,
+    namespace: Option<&PublicKey>,
 ) -> Result<(StatusCode, HashMap<String, Vec<String>>, Vec<u8>), Error> {
     let git_dir = radicle::storage::git::paths::repository(&profile.storage, &id);
     let content_type =
@@ -89,21 +179,26 @@ async fn git_http_backend(
         return Err(Error::NotFound);
     }
 
-    // Reject push requests.
-    match (path, query.as_str()) {
-        ("git-receive-pack", _) | (_, "service=git-receive-pack") => {
-            return Err(Error::ServiceUnavailable("git-receive-pack"));
-        }
-        _ => {}
-    };
-
     tracing::debug!("id: {:?}", id);
-    tracing::debug!("headers: {:?}", headers);
+    // Credentials must not end up in the logs.
+    tracing::debug!(
+        "headers: {:?}",
+        headers
+            .iter()
+            .filter(|(name, _)| **name != header::AUTHORIZATION)
+            .collect::<Vec<_>>()
+    );
     tracing::debug!("path: {:?}", path);
     tracing::debug!("method: {:?}", method.as_str());
     tracing::debug!("remote: {:?}", remote.to_string());
 
     let mut cmd = Command::new("git");
+    if let Some(nid) = namespace {
+        // Pushed refs go to the pusher's namespace. Setting the remote user is what
+        // enables `git-receive-pack` in `git-http-backend`.
+        cmd.env("GIT_NAMESPACE", nid.to_string())
+            .env("REMOTE_USER", nid.to_string());
+    }
     let mut child = cmd
         .arg("http-backend")
         .env("REQUEST_METHOD", method.as_str())
@@ -209,24 +304,382 @@ async fn git_http_backend(
     }
 }
 
+/// Handle a push. The pushed objects are received into storage by `git-receive-pack`, under
+/// temporary references. The reference updates are then applied the way `git-remote-rad`
+/// applies them, which includes opening and updating patches, and signing our refs.
+#[allow(clippy::too_many_arguments)]
+async fn receive_pack(
+    profile: &Profile,
+    session: &auth::Session,
+    method: Method,
+    mut headers: HeaderMap,
+    body: Bytes,
+    remote: net::SocketAddr,
+    id: RepoId,
+    query: String,
+) -> Result<(StatusCode, HashMap<String, Vec<String>>, Vec<u8>), Error> {
+    let nid = session.public_key;
+    let repo = profile.storage.repository(id)?;
+    if repo.identity_doc()?.visibility.is_private() {
+        return Err(Error::NotFound);
+    }
+
+    // The request has to be rewritten before it is passed on, so decompress it upfront.
+    let body = if headers
+        .remove(header::CONTENT_ENCODING)
+        .is_some_and(|e| e == "gzip")
+    {
+        let mut buf = Vec::new();
+        flate2::read::GzDecoder::new(body.reader()).read_to_end(&mut buf)?;
+        buf
+    } else {
+        body.to_vec()
+    };
+    let request = PushRequest::parse(&body)?;
+
+    for cmd in &request.commands {
+        // Patches can be proposed with the `patches` scope, other refs need full access.
+        let scope = if cmd.refname == *radicle::rad::PATCHES_REFNAME
+            || cmd.refname.as_str().starts_with("refs/heads/patches/")
+        {
+            auth::Scope::Patches
+        } else {
+            auth::Scope::Admin
+        };
+        if !session.allows(scope) {
+            return Err(Error::Forbidden("insufficient scope"));
+        }
+    }
+    let mut opts = radicle_remote_helper::Options::default();
+    for option in &request.options {
+        let args = option.split(' ').collect::<Vec<_>>();
+        radicle_remote_helper::push_option(&args, &mut opts)
+            .map_err(|e| Error::BadRequest(e.to_string()))?;
+    }
+
+    // Objects are received under a temporary reference per pushed commit, so that
+    // `git-receive-pack` doesn't update any of the pushed references itself.
+    let mut staged = BTreeMap::new();
+    for new in request.commands.iter().filter_map(|c| c.new) {
+        staged
+            .entry(new)
+            .or_insert_with(|| git::refs::storage::staging::patch(&nid, new));
+    }
+    let mut unpack = Ok(());
+    let mut errors = HashMap::new();
+
+    if !staged.is_empty() {
+        let mut capabilities = vec![String::from("report-status")];
+        capabilities.extend(
+            request
+                .capabilities
+                .iter()
+                .filter(|c| {
+                    *c == "quiet" || c.starts_with("agent=") || c.starts_with("object-format=")
+                })
+                .cloned(),
+        );
+        let mut backend_request = Vec::new();
+
+        for (i, (new, staging)) in staged.iter().enumerate() {
+            // A reference left over from a failed push would make this one fail.
+            repo.raw()
+                .find_reference(staging)
+                .and_then(|mut r| r.delete())
+                .ok();
+
+            let mut line = format!(
+                "{} {new} {}",
+                git::raw::Oid::zero(),
+                staging.strip_namespace()
+            );
+            if i == 0 {
+                line.push('\0');
+                line.push_str(&capabilities.join(" "));
+            }
+            line.push('\n');
+            pktline::write(&mut backend_request, line.as_bytes());
+        }
+        pktline::flush(&mut backend_request);
+        backend_request.extend_from_slice(request.pack);
+
+        let (status, response_headers, response) = git_http_backend(
+            profile,
+            method,
+            headers,
+            Bytes::from(backend_request),
+            remote,
+            id,
+            "git-receive-pack",
+            query,
+            Some(&nid),
+        )
+        .await?;
+
+        if status != StatusCode::OK {
+            return Ok((status, response_headers, response));
+        }
+        let report = ReceiveReport::parse(&response);
+
+        for (new, staging) in &staged {
+            let result = report.unpack.clone().and_then(|()| {
+                report
+                    .refs
+                    .get(staging.strip_namespace().as_str())
+                    .cloned()
+                    .unwrap_or_else(|| Err(String::from("not received")))
+            });
+            if let Err(e) = result {
+                errors.extend(
+                    request
+                        .commands
+                        .iter()
+                        .filter(|c| c.new == Some(*new))
+                        .map(|c| (c.refname.clone(), e.clone())),
+                );
+            }
+            // The objects are in storage now, and will be referenced by the pushed refs.
+            repo.raw()
+                .find_reference(staging)
+                .and_then(|mut r| r.delete())
+                .ok();
+        }
+        unpack = report.unpack;
+    }
+
+    let updates = request
+        .commands
+        .iter()
+        .filter(|c| !errors.contains_key(&c.refname))
+        .map(|c| push::Update {
+            dst: c.refname.clone(),
+            old: c.old,
+            new: c.new,
+        })
+        .collect::<Vec<_>>();
+    let mut pushed = false;
+
+    for (refname, result) in push::receive(updates, &repo, profile, opts)? {
+        match result {
+            Ok(_) => pushed = true,
+            Err(e) => {
+                errors.insert(refname, e.to_string());
+            }
+        }
+    }
+    if pushed {
+        let mut node = radicle::Node::new(profile.socket());
+        if node.is_running() {
+            // Nb. allow this to fail. The push to local storage was still successful.
+            node.announce_refs(id).ok();
+        }
+    }
+
+    // Report the status of each pushed ref, the way `git-receive-pack` would.
+    let mut report = Vec::new();
+    let unpack = match unpack {
+        Ok(()) => String::from("ok"),
+        Err(e) => e,
+    };
+    pktline::write(&mut report, format!("unpack {unpack}\n").as_bytes());
+
+    for cmd in &request.commands {
+        let line = match errors.get(&cmd.refname) {
+            Some(e) => format!("ng {} {}\n", cmd.refname, e.replace('\n', " ")),
+            None => format!("ok {}\n", cmd.refname),
+        };
+        pktline::write(&mut report, line.as_bytes());
+    }
+    pktline::flush(&mut report);
+
+    let has = |c: &str| request.capabilities.iter().any(|cap| cap == c);
+    let body = if !has("report-status") && !has("report-status-v2") {
+        Vec::new()
+    } else if has("side-band-64k") || has("side-band") {
+        // The report is sent on the primary band of the multiplexed stream.
+        let max = if has("side-band-64k") {
+            pktline::MAX_DATA_LEN - 1
+        } else {
+            pktline::MAX_SIDE_BAND_DATA_LEN - 1
+        };
+        let mut body = Vec::new();
+
+        for chunk in report.chunks(max) {
+            let mut data = vec![1];
+            data.extend_from_slice(chunk);
+            pktline::write(&mut body, &data);
+        }
+        pktline::flush(&mut body);
+        body
+    } else {
+        report
+    };
+    let headers = HashMap::from([
+        (
+            String::from("Content-Type"),
+            vec![String::from("application/x-git-receive-pack-result")],
+        ),
+        (
+            String::from("Cache-Control"),
+            vec![String::from("no-cache")],
+        ),
+    ]);
+
+    Ok((StatusCode::OK, headers, body))
+}
+
+/// A reference update requested by a push.
+#[derive(Debug, PartialEq, Eq)]
+struct PushCommand {
+    /// Expected value of the reference, or `None` if it is created.
+    old: Option<git::Oid>,
+    /// New value of the reference, or `None` if it is deleted.
+    new: Option<git::Oid>,
+    /// Reference name.
+    refname: git::RefString,
+}
+
+/// A push request, as sent by Git tooling to `git-receive-pack`.
+#[derive(Debug)]
+struct PushRequest<'a> {
+    /// Reference updates.
+    commands: Vec<PushCommand>,
+    /// Capabilities requested by the client.
+    capabilities: Vec<String>,
+    /// Push options, eg. `patch.draft`.
+    options: Vec<String>,
+    /// Pack containing the pushed objects.
+    pack: &'a [u8],
+}
+
+impl<'a> PushRequest<'a> {
+    fn parse(buf: &'a [u8]) -> Result<Self, Error> {
+        let invalid = |reason: &str| Error::BadRequest(format!("invalid push request: {reason}"));
+        let mut commands = Vec::new();
+        let mut capabilities = Vec::new();
+        let mut options = Vec::new();
+        let mut offset = 0;
+
+        while let (Packet::Data(data), n) =
+            pktline::read(&buf[offset..]).ok_or_else(|| invalid("malformed packet"))?
+        {
+            offset += n;
+
+            let line = str::from_utf8(data).map_err(|_| invalid("malformed command"))?;
+            let line = line.strip_suffix('\n').unwrap_or(line);
+            // Capabilities are sent along with the first command.
+            let (line, caps) = line.split_once('\0').unwrap_or((line, ""));
+
+            if commands.is_empty() {
+                capabilities = caps
+                    .split(' ')
+                    .filter(|c| !c.is_empty())
+                    .map(String::from)
+                    .collect();
+            }
+            let [old, new, refname] = line.split(' ').collect::<Vec<_>>()[..] else {
+                return Err(invalid("malformed command"));
+            };
+            let oid = |s: &str| {
+                git::Oid::from_str(s)
+                    .map(|oid| (!oid.is_zero()).then_some(oid))
+                    .map_err(|_| invalid("invalid object id"))
+            };
+            commands.push(PushCommand {
+                old: oid(old)?,
+                new: oid(new)?,
+                refname: git::RefString::try_from(refname)
+                    .map_err(|_| invalid("invalid reference name"))?,
+            });
+        }
+        // Skip the flush packet ending the commands.
+        offset += 4;
+
+        if capabilities.iter().any(|c| c == "push-options") {
+            while let (Packet::Data(data), n) =
+                pktline::read(&buf[offset..]).ok_or_else(|| invalid("malformed packet"))?
+            {
+                offset += n;
+
+                let option = str::from_utf8(data).map_err(|_| invalid("malformed option"))?;
+                options.push(option.trim_end_matches('\n').to_owned());
+            }
+            offset += 4;
+        }
+
+        Ok(Self {
+            commands,
+            capabilities,
+            options,
+            pack: buf.get(offset..).unwrap_or_default(),
+        })
+    }
+}
+
+/// Status report sent by `git-receive-pack`.
+#[derive(Debug)]
+struct ReceiveReport {
+    /// Whether the pack was unpacked, or why it wasn't.
+    unpack: Result<(), String>,
+    /// Whether each reference was updated, or why it wasn't.
+    refs: HashMap<String, Result<(), String>>,
+}
+
+impl ReceiveReport {
+    fn parse(buf: &[u8]) -> Self {
+        let mut unpack = Err(String::from("missing unpack status"));
+        let mut refs = HashMap::new();
+        let mut offset = 0;
+
+        while let Some((Packet::Data(data), n)) = pktline::read(&buf[offset..]) {
+            offset += n;
+
+            let line = String::from_utf8_lossy(data);
+            let line = line.trim_end();
+
+            if let Some(status) = line.strip_prefix("unpack ") {
+                unpack = if status == "ok" {
+                    Ok(())
+                } else {
+                    Err(status.to_owned())
+                };
+            } else if let Some(refname) = line.strip_prefix("ok ") {
+                refs.insert(refname.to_owned(), Ok(()));
+            } else if let Some(rest) = line.strip_prefix("ng ") {
+                let (refname, reason) = rest.split_once(' ').unwrap_or((rest, "failed"));
+                refs.insert(refname.to_owned(), Err(reason.to_owned()));
+            }
+        }
+        Self { unpack, refs }
+    }
+}
+
 #[cfg(test)]
 mod routes {
     use std::collections::HashMap;
     use std::net::SocketAddr;
     use std::str::FromStr;
 
+    use axum::body::Body;
     use axum::extract::connect_info::MockConnectInfo;
-    use axum::http::StatusCode;
+    use axum::http::{Method, Request, StatusCode};
     use radicle::identity::RepoId;
+    use radicle::storage::ReadStorage;
+    use tower::ServiceExt;
 
-    use crate::test::{self, get, RID};
+    use super::pktline;
+    use crate::test::{self, get, get_auth, HEAD, RID, SESSION_ID};
 
     #[tokio::test]
     async fn test_info_request() {
         let tmp = tempfile::tempdir().unwrap();
         let ctx = test::seed(tmp.path());
-        let app = super::router(ctx.profile().to_owned(), HashMap::new())
-            .layer(MockConnectInfo(SocketAddr::from(([0, 0, 0, 0], 8080))));
+        let app = super::router(
+            ctx.profile().to_owned(),
+            HashMap::new(),
+            ctx.sessions().clone(),
+        )
+        .layer(MockConnectInfo(SocketAddr::from(([0, 0, 0, 0], 8080))));
 
         let response = get(&app, format!("/{RID}.git/info/refs")).await;
 
@@ -240,6 +693,7 @@ mod routes {
         let app = super::router(
             ctx.profile().to_owned(),
             HashMap::from_iter([(String::from("heartwood"), RepoId::from_str(RID).unwrap())]),
+            ctx.sessions().clone(),
         )
         .layer(MockConnectInfo(SocketAddr::from(([0, 0, 0, 0], 8080))));
 
@@ -249,4 +703,108 @@ mod routes {
         let response = get(&app, "/heartwood.git/info/refs").await;
         assert_eq!(response.status(), StatusCode::OK);
     }
+
+    #[tokio::test]
+    async fn test_receive_pack_auth() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ctx = test::seed(tmp.path());
+        let app = super::router(
+            ctx.profile().to_owned(),
+            HashMap::new(),
+            ctx.sessions().clone(),
+        )
+        .layer(MockConnectInfo(SocketAddr::from(([0, 0, 0, 0], 8080))));
+        let path = format!("/{RID}.git/info/refs?service=git-receive-pack");
+
+        let response = get(&app, &path).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        test::create_session(ctx.clone()).await;
+        let response = get_auth(&app, &path, Some(SESSION_ID.to_owned())).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_receive_pack() {
+        // An empty pack, since the pushed commit is already in storage.
+        const EMPTY_PACK: &[u8] = b"PACK\0\0\0\x02\0\0\0\0\
+            \x02\x9d\x08\x82\x3b\xd8\xa8\xea\xb5\x10\xad\x6a\xc7\x5c\x82\x3c\xfd\x3e\xd3\x1e";
+
+        let tmp = tempfile::tempdir().unwrap();
+        let ctx = test::seed(tmp.path());
+        let app = super::router(
+            ctx.profile().to_owned(),
+            HashMap::new(),
+            ctx.sessions().clone(),
+        )
+        .layer(MockConnectInfo(SocketAddr::from(([0, 0, 0, 0], 8080))));
+        let push = |scope| {
+            let mut body = Vec::new();
+            pktline::write(
+                &mut body,
+                format!(
+                    "{} {HEAD} refs/heads/feature\0report-status\n",
+                    radicle::git::raw::Oid::zero()
+                )
+                .as_bytes(),
+            );
+            pktline::flush(&mut body);
+            body.extend_from_slice(EMPTY_PACK);
+
+            let app = app.clone();
+            let ctx = ctx.clone();
+            async move {
+                test::create_session_with_scopes(ctx, [scope]).await;
+                app.oneshot(
+                    Request::builder()
+                        .method(Method::POST)
+                        .uri(format!("/{RID}.git/git-receive-pack"))
+                        .header("Content-Type", "application/x-git-receive-pack-request")
+                        .header("Authorization", format!("Bearer {SESSION_ID}"))
+                        .body(Body::from(body))
+                        .unwrap(),
+                )
+                .await
+                .unwrap()
+            }
+        };
+
+        // Pushing branches requires full access.
+        let response = push(crate::api::auth::Scope::Patches).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = push(crate::api::auth::Scope::Admin).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(
+            body.as_ref(),
+            b"000eunpack ok\n001aok refs/heads/feature\n0000"
+        );
+
+        let profile = ctx.profile();
+        let repo = profile
+            .storage
+            .repository(RepoId::from_str(RID).unwrap())
+            .unwrap();
+        let feature = repo
+            .backend
+            .refname_to_id(&format!(
+                "refs/namespaces/{}/refs/heads/feature",
+                profile.public_key
+            ))
+            .unwrap();
+
+        assert_eq!(feature.to_string(), HEAD);
+        // The temporary reference is removed once the push is done.
+        assert!(repo
+            .backend
+            .find_reference(&format!(
+                "refs/namespaces/{}/refs/tmp/heads/{HEAD}",
+                profile.public_key
+            ))
+            .is_err());
+    }
 }
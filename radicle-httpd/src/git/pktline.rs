@@ -0,0 +1,62 @@
+//! Git's pkt-line format, used by the smart HTTP protocol.
+//! See `gitprotocol-common(5)`.
+
+/// Maximum size of a packet's data, excluding the length prefix.
+pub const MAX_DATA_LEN: usize = 65516;
+/// Maximum size of a packet's data when the client asked for the small side-band.
+pub const MAX_SIDE_BAND_DATA_LEN: usize = 996;
+
+/// A packet read from a pkt-line stream.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Packet<'a> {
+    /// Flush packet, ie. `0000`.
+    Flush,
+    /// Data packet.
+    Data(&'a [u8]),
+}
+
+/// Read the packet at the start of the buffer.
+/// Returns the packet and the number of bytes it took up, or `None` if the buffer doesn't
+/// start with a valid packet.
+pub fn read(buf: &[u8]) -> Option<(Packet<'_>, usize)> {
+    let len = buf.get(..4)?;
+    let len = std::str::from_utf8(len).ok()?;
+    let len = usize::from_str_radix(len, 16).ok()?;
+
+    match len {
+        0 => Some((Packet::Flush, 4)),
+        // Delimiter and response-end packets are only used in protocol v2.
+        1..=4 => None,
+        n => Some((Packet::Data(buf.get(4..n)?), n)),
+    }
+}
+
+/// Write a data packet.
+pub fn write(out: &mut Vec<u8>, data: &[u8]) {
+    out.extend_from_slice(format!("{:04x}", data.len() + 4).as_bytes());
+    out.extend_from_slice(data);
+}
+
+/// Write a flush packet.
+pub fn flush(out: &mut Vec<u8>) {
+    out.extend_from_slice(b"0000");
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_read_write() {
+        let mut buf = Vec::new();
+
+        write(&mut buf, b"unpack ok\n");
+        flush(&mut buf);
+
+        assert_eq!(buf, b"000eunpack ok\n0000");
+        assert_eq!(read(&buf), Some((Packet::Data(b"unpack ok\n"), 14)));
+        assert_eq!(read(&buf[14..]), Some((Packet::Flush, 4)));
+        assert_eq!(read(&buf[..10]), None);
+        assert_eq!(read(b"0002"), None);
+    }
+}
@@ -109,10 +109,10 @@ pub async fn run(options: Options) -> anyhow::Result<()> {
 /// Create a router consisting of other sub-routers.
 fn router(options: Options, profile: Profile) -> anyhow::Result<Router> {
     let profile = Arc::new(profile);
-    let ctx = api::Context::new(profile.clone(), &options);
+    let ctx = api::Context::new(profile.clone(), &options)?;
 
+    let git_router = git::router(profile.clone(), options.aliases, ctx.sessions().clone());
     let api_router = api::router(ctx);
-    let git_router = git::router(profile.clone(), options.aliases);
     let raw_router = raw::router(profile);
 
     let app = Router::new()
@@ -271,25 +271,36 @@ fn seed_with_signer<G: Signer>(dir: &Path, profile: radicle::Profile, signer: &G
         cache: Some(crate::DEFAULT_CACHE_SIZE),
     };
 
-    Context::new(Arc::new(profile), &options)
+    Context::new(Arc::new(profile), &options).unwrap()
 }
 
-/// Adds an authorized session to the Context::sessions HashMap.
+/// Adds an authorized session with full access to the sessions store.
 pub async fn create_session(ctx: Context) {
+    create_session_with_scopes(ctx, [auth::Scope::Admin]).await
+}
+
+/// Adds an authorized session with the given scopes to the sessions store.
+pub async fn create_session_with_scopes(
+    ctx: Context,
+    scopes: impl IntoIterator<Item = auth::Scope>,
+) {
     let issued_at = OffsetDateTime::now_utc();
-    let mut sessions = ctx.sessions().write().await;
-    sessions.insert(
-        String::from(SESSION_ID),
-        auth::Session {
-            status: auth::AuthState::Authorized,
-            public_key: ctx.profile().public_key,
-            alias: ctx.profile().config.node.alias.clone(),
-            issued_at,
-            expires_at: issued_at
-                .checked_add(auth::AUTHORIZED_SESSIONS_EXPIRATION)
-                .unwrap(),
-        },
-    );
+    ctx.sessions()
+        .insert(
+            SESSION_ID,
+            &auth::Session {
+                status: auth::AuthState::Authorized,
+                public_key: ctx.profile().public_key,
+                alias: ctx.profile().config.node.alias.clone(),
+                scopes: scopes.into_iter().collect(),
+                label: None,
+                issued_at,
+                expires_at: issued_at
+                    .checked_add(auth::AUTHORIZED_SESSIONS_EXPIRATION)
+                    .unwrap(),
+            },
+        )
+        .unwrap();
 }
 
 pub async fn get(app: &Router, path: impl ToString) -> Response {
@@ -301,6 +312,15 @@ pub async fn get(app: &Router, path: impl ToString) -> Response {
     )
 }
 
+pub async fn get_auth(app: &Router, path: impl ToString, auth: Option<String>) -> Response {
+    Response(
+        app.clone()
+            .oneshot(request(path, Method::GET, None, auth))
+            .await
+            .unwrap(),
+    )
+}
+
 pub async fn post(
     app: &Router,
     path: impl ToString,
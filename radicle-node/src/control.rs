@@ -163,6 +163,26 @@ where
                 return Err(CommandError::Runtime(e));
             }
         },
+        Command::Watch {
+            rid,
+            pattern,
+            interest,
+        } => match handle.watch(rid, pattern, interest) {
+            Ok(result) => {
+                CommandResult::updated(result).to_writer(writer)?;
+            }
+            Err(e) => {
+                return Err(CommandError::Runtime(e));
+            }
+        },
+        Command::Unwatch { rid, pattern } => match handle.unwatch(rid, pattern) {
+            Ok(result) => {
+                CommandResult::updated(result).to_writer(writer)?;
+            }
+            Err(e) => {
+                return Err(CommandError::Runtime(e));
+            }
+        },
         Command::AnnounceRefs { rid } => {
             let refs = handle.announce_refs(rid)?;
 
@@ -237,6 +257,7 @@ mod tests {
 
     use super::*;
     use crate::identity::RepoId;
+    use crate::node::notifications::{self, Interest};
     use crate::node::Handle;
     use crate::node::{Alias, Node, NodeId};
     use crate::service::policy::Scope;
@@ -317,5 +338,16 @@ mod tests {
         assert!(!handle.follow(peer, Some(Alias::new("alice"))).unwrap());
         assert!(handle.unfollow(peer).unwrap());
         assert!(!handle.unfollow(peer).unwrap());
+
+        let pattern = notifications::pattern::all();
+        assert!(handle
+            .watch(proj, pattern.clone(), Interest::Relevant)
+            .unwrap());
+        assert!(!handle
+            .watch(proj, pattern.clone(), Interest::Relevant)
+            .unwrap());
+        assert!(handle.watch(proj, pattern.clone(), Interest::None).unwrap());
+        assert!(handle.unwatch(proj, pattern.clone()).unwrap());
+        assert!(!handle.unwatch(proj, pattern).unwrap());
     }
 }
@@ -0,0 +1,385 @@
+//! Local network peer discovery.
+//!
+//! When enabled, the node periodically sends a beacon to a multicast group on the local
+//! network segment, containing its node ID and the ports it is listening on. Nodes that
+//! receive the beacon add the sender to their address book, using the source address of the
+//! beacon, and try to connect to it.
+//!
+//! N.b. beacons are not authenticated: the node ID they contain is only trusted once the
+//! connection handshake with the node succeeds. Since anyone on the network segment can send
+//! them, beacons that carry new information are rate-limited, and the number of nodes and
+//! addresses learned this way is capped.
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4, UdpSocket};
+use std::{io, time};
+
+use radicle::node::address::{self, Store as _};
+use radicle::node::config::Network;
+use radicle::node::{Address, Alias, ConnectOptions, Database, Features, Handle as _, NodeId};
+use radicle::prelude::Timestamp;
+
+use localtime::LocalTime;
+
+use crate::bounded::BoundedVec;
+use crate::runtime::Handle;
+use crate::service::limitter::TokenBucket;
+use crate::wire;
+use crate::wire::{Decode, Encode};
+
+/// The multicast group beacons are sent to.
+pub const MULTICAST_GROUP: Ipv4Addr = Ipv4Addr::new(239, 255, 82, 76);
+/// The port beacons are sent to.
+pub const MULTICAST_PORT: u16 = 8777;
+/// How often a beacon is sent.
+pub const BEACON_INTERVAL: time::Duration = time::Duration::from_secs(30);
+/// Maximum number of listening ports announced in a beacon.
+pub const MAX_BEACON_PORTS: usize = 8;
+/// How long to wait for a discovered node to accept our connection.
+pub const CONNECT_TIMEOUT: time::Duration = time::Duration::from_secs(9);
+/// Maximum number of nodes learned via discovery.
+pub const MAX_DISCOVERED_NODES: usize = 256;
+/// Maximum number of addresses learned via discovery, per node.
+pub const MAX_DISCOVERED_ADDRESSES: usize = MAX_BEACON_PORTS * 2;
+/// Number of beacons carrying new information that can be processed in a burst.
+pub const BEACON_BURST: usize = 32;
+/// Number of beacons carrying new information processed per second, after a burst.
+pub const BEACON_RATE: f64 = 1.;
+
+/// Announcement of a node on the local network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Beacon {
+    /// The network the node is running on.
+    pub network: Network,
+    /// The announcing node.
+    pub nid: NodeId,
+    /// Node alias.
+    pub alias: Alias,
+    /// Advertized features.
+    pub features: Features,
+    /// The ports the node accepts connections on.
+    pub ports: BoundedVec<u16, MAX_BEACON_PORTS>,
+}
+
+impl Beacon {
+    /// The beacon header for the given network, which allows discarding unrelated traffic.
+    fn magic(network: Network) -> [u8; 4] {
+        match network {
+            Network::Main => *b"rad\x00",
+            Network::Test => *b"rad\x01",
+        }
+    }
+
+    /// The addresses under which the node that sent this beacon from `from` is reachable.
+    pub fn addresses(&self, from: IpAddr) -> impl Iterator<Item = Address> + '_ {
+        self.ports
+            .iter()
+            .map(move |port| Address::from(SocketAddr::new(from, *port)))
+    }
+}
+
+impl Encode for Beacon {
+    fn encode<W: io::Write + ?Sized>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let mut n = 0;
+
+        n += Self::magic(self.network).encode(writer)?;
+        n += self.nid.encode(writer)?;
+        n += self.alias.encode(writer)?;
+        n += self.features.encode(writer)?;
+        n += self.ports.encode(writer)?;
+
+        Ok(n)
+    }
+}
+
+impl Decode for Beacon {
+    fn decode<R: io::Read + ?Sized>(reader: &mut R) -> Result<Self, wire::Error> {
+        let magic = <[u8; 4]>::decode(reader)?;
+        let network = [Network::Main, Network::Test]
+            .into_iter()
+            .find(|n| Self::magic(*n) == magic)
+            .ok_or(wire::Error::InvalidMagic(magic))?;
+        let nid = NodeId::decode(reader)?;
+        let alias = Alias::decode(reader)?;
+        let features = Features::decode(reader)?;
+        let ports = BoundedVec::decode(reader)?;
+
+        Ok(Self {
+            network,
+            nid,
+            alias,
+            features,
+            ports,
+        })
+    }
+}
+
+/// Local network discovery process.
+pub struct Discovery {
+    socket: UdpSocket,
+    beacon: Beacon,
+    db: Database,
+    /// Nodes that were discovered, with the addresses they were discovered under.
+    discovered: HashMap<NodeId, Vec<Address>>,
+    /// Limits the rate at which beacons carrying new information are processed.
+    limiter: TokenBucket,
+}
+
+impl Discovery {
+    /// Join the multicast group, ready to send our `beacon`.
+    pub fn bind(beacon: Beacon, db: Database) -> io::Result<Self> {
+        use socket2::{Domain, Protocol, Socket, Type};
+
+        let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+        // Nb. Allow multiple nodes on the same host to receive beacons.
+        socket.set_reuse_address(true)?;
+        socket.bind(&SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, MULTICAST_PORT).into())?;
+        socket.join_multicast_v4(&MULTICAST_GROUP, &Ipv4Addr::UNSPECIFIED)?;
+        socket.set_multicast_loop_v4(true)?;
+        socket.set_read_timeout(Some(BEACON_INTERVAL))?;
+
+        Ok(Self {
+            socket: socket.into(),
+            beacon,
+            db,
+            discovered: HashMap::new(),
+            limiter: TokenBucket::new(BEACON_BURST, BEACON_RATE, LocalTime::now()),
+        })
+    }
+
+    /// Send beacons and process the beacons of other nodes, until an unrecoverable error
+    /// occurs.
+    pub fn run(mut self, mut handle: Handle) -> io::Result<()> {
+        let beacon = wire::serialize(&self.beacon);
+        let group = SocketAddrV4::new(MULTICAST_GROUP, MULTICAST_PORT);
+        let mut buf = [0; u16::MAX as usize];
+        let mut last = None::<time::Instant>;
+
+        log::info!(target: "discovery", "Discovering peers on the local network via {group}..");
+
+        loop {
+            if last.map_or(true, |t| t.elapsed() >= BEACON_INTERVAL) {
+                if let Err(e) = self.socket.send_to(&beacon, group) {
+                    log::warn!(target: "discovery", "Failed to send beacon to {group}: {e}");
+                }
+                last = Some(time::Instant::now());
+            }
+
+            let (len, from) = match self.socket.recv_from(&mut buf) {
+                Ok(received) => received,
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                    ) =>
+                {
+                    continue;
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            match wire::deserialize::<Beacon>(&buf[..len]) {
+                Ok(beacon) => {
+                    if let Some(addr) = self.received(beacon.clone(), from.ip(), LocalTime::now()) {
+                        self.connect(&mut handle, beacon.nid, addr);
+                    }
+                }
+                Err(e) => {
+                    log::debug!(target: "discovery", "Ignoring invalid beacon from {from}: {e}");
+                }
+            }
+        }
+    }
+
+    /// Process a beacon received from the given IP address. Returns an address to connect to,
+    /// if the node wasn't known under that address yet.
+    pub fn received(&mut self, beacon: Beacon, from: IpAddr, now: LocalTime) -> Option<Address> {
+        if beacon.nid == self.beacon.nid || beacon.network != self.beacon.network {
+            return None;
+        }
+        let known = self.discovered.get(&beacon.nid);
+        let addrs = beacon
+            .addresses(from)
+            .filter(|a| known.map_or(true, |k| !k.contains(a)))
+            .collect::<Vec<_>>();
+        if addrs.is_empty() {
+            return None;
+        }
+        match known {
+            Some(known) if known.len() + addrs.len() > MAX_DISCOVERED_ADDRESSES => {
+                log::debug!(
+                    target: "discovery",
+                    "Ignoring beacon from {from}: too many addresses for node {}", beacon.nid
+                );
+                return None;
+            }
+            None if self.discovered.len() >= MAX_DISCOVERED_NODES => {
+                log::debug!(target: "discovery", "Ignoring beacon from {from}: too many nodes");
+                return None;
+            }
+            _ => {}
+        }
+        if !self.limiter.take(now) {
+            log::debug!(target: "discovery", "Ignoring beacon from {from}: rate-limited");
+            return None;
+        }
+        log::debug!(target: "discovery", "Discovered node {} at {from}", beacon.nid);
+
+        // Nb. The node information in the beacon is not signed, and is only used if we don't
+        // have a signed announcement from the node yet; hence the minimum timestamp.
+        if let Err(e) = self.db.insert(
+            &beacon.nid,
+            beacon.features,
+            beacon.alias,
+            0,
+            Timestamp::EPOCH,
+            addrs
+                .iter()
+                .map(|a| address::KnownAddress::new(a.clone(), address::Source::Local)),
+        ) {
+            log::error!(target: "discovery", "Failed to add {} to address book: {e}", beacon.nid);
+        }
+        let addr = addrs.first().cloned();
+        self.discovered.entry(beacon.nid).or_default().extend(addrs);
+
+        addr
+    }
+
+    fn connect(&self, handle: &mut Handle, nid: NodeId, addr: Address) {
+        let opts = ConnectOptions {
+            persistent: false,
+            timeout: CONNECT_TIMEOUT,
+        };
+        match handle.connect(nid, addr, opts) {
+            Ok(result) => {
+                log::debug!(target: "discovery", "Connection to discovered node {nid}: {result:?}");
+            }
+            Err(e) => {
+                log::warn!(target: "discovery", "Failed to connect to discovered node {nid}: {e}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use radicle::test::arbitrary;
+
+    fn beacon(nid: NodeId, network: Network) -> Beacon {
+        Beacon {
+            network,
+            nid,
+            alias: Alias::new("alice"),
+            features: Features::SEED,
+            ports: BoundedVec::collect_from([8776, 8777]),
+        }
+    }
+
+    fn discovery(nid: NodeId, db: Database, now: LocalTime) -> Discovery {
+        Discovery {
+            socket: UdpSocket::bind("127.0.0.1:0").unwrap(),
+            beacon: beacon(nid, Network::Test),
+            db,
+            discovered: HashMap::new(),
+            limiter: TokenBucket::new(BEACON_BURST, BEACON_RATE, now),
+        }
+    }
+
+    #[test]
+    fn test_beacon_encode_decode() {
+        let beacon = beacon(arbitrary::gen(1), Network::Test);
+        let bytes = wire::serialize(&beacon);
+
+        assert_eq!(wire::deserialize::<Beacon>(&bytes).unwrap(), beacon);
+        assert!(matches!(
+            wire::deserialize::<Beacon>(b"abcd"),
+            Err(wire::Error::InvalidMagic(_))
+        ));
+    }
+
+    #[test]
+    fn test_beacon_received() {
+        let local = arbitrary::gen::<NodeId>(1);
+        let remote = arbitrary::gen::<NodeId>(1);
+        let db = Database::memory().unwrap();
+        let now = LocalTime::now();
+        let mut discovery = discovery(local, db.clone(), now);
+        let from = IpAddr::from([192, 168, 1, 7]);
+
+        // Our own beacons and beacons from other networks are ignored.
+        assert_eq!(
+            discovery.received(beacon(local, Network::Test), from, now),
+            None
+        );
+        assert_eq!(
+            discovery.received(beacon(remote, Network::Main), from, now),
+            None
+        );
+
+        let addr = discovery
+            .received(beacon(remote, Network::Test), from, now)
+            .unwrap();
+        assert_eq!(addr.to_string(), "192.168.1.7:8776");
+
+        let entries = db
+            .entries()
+            .unwrap()
+            .filter(|e| e.node == remote)
+            .collect::<Vec<_>>();
+        assert_eq!(entries.len(), 2);
+        assert!(entries
+            .iter()
+            .all(|e| e.address.source == address::Source::Local));
+
+        // Beacons from known nodes at known addresses are ignored.
+        assert_eq!(
+            discovery.received(beacon(remote, Network::Test), from, now),
+            None
+        );
+    }
+
+    #[test]
+    fn test_beacon_limits() {
+        let now = LocalTime::now();
+        let db = Database::memory().unwrap();
+        let mut discovery = discovery(arbitrary::gen(1), db, now);
+        let remote = arbitrary::gen::<NodeId>(1);
+
+        // Addresses learned from a single node are capped.
+        for i in 0..MAX_DISCOVERED_ADDRESSES / 2 {
+            let from = IpAddr::from([10, 0, 0, i as u8]);
+            assert!(discovery
+                .received(beacon(remote, Network::Test), from, now)
+                .is_some());
+        }
+        let from = IpAddr::from([10, 0, 1, 0]);
+        assert_eq!(
+            discovery.received(beacon(remote, Network::Test), from, now),
+            None
+        );
+
+        // New information is rate-limited; the beacons above already took some tokens.
+        let mut accepted = MAX_DISCOVERED_ADDRESSES / 2;
+        while discovery
+            .received(beacon(arbitrary::gen(1), Network::Test), from, now)
+            .is_some()
+        {
+            accepted += 1;
+        }
+        assert_eq!(accepted, BEACON_BURST);
+
+        // The number of discovered nodes is capped.
+        let mut now = now;
+        while discovery.discovered.len() < MAX_DISCOVERED_NODES {
+            now = now + localtime::LocalDuration::from_secs(1);
+            discovery.received(beacon(arbitrary::gen(1), Network::Test), from, now);
+        }
+        now = now + localtime::LocalDuration::from_secs(60);
+        assert_eq!(
+            discovery.received(beacon(arbitrary::gen(1), Network::Test), from, now),
+            None
+        );
+        assert_eq!(discovery.discovered.len(), MAX_DISCOVERED_NODES);
+    }
+}
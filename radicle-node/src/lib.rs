@@ -1,9 +1,11 @@
 pub mod bounded;
 pub mod control;
 pub mod deserializer;
+pub mod discovery;
 pub mod runtime;
 pub mod service;
 pub mod signals;
+pub mod sinks;
 #[cfg(any(test, feature = "test"))]
 pub mod test;
 #[cfg(test)]
@@ -133,7 +133,16 @@ fn execute() -> anyhow::Result<()> {
         log::debug!(target: "node", "Removing existing control socket..");
         fs::remove_file(home.socket()).ok();
     }
-    Runtime::init(home, config.node, listen, proxy, signals, signer)?.run()?;
+    Runtime::init(
+        home,
+        config.node,
+        config.notifications,
+        listen,
+        proxy,
+        signals,
+        signer,
+    )?
+    .run()?;
 
     Ok(())
 }
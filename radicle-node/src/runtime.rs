@@ -24,11 +24,14 @@ use radicle::storage;
 use radicle::Storage;
 use radicle::{cob, git};
 
+use crate::bounded::BoundedVec;
 use crate::control;
 use crate::crypto::Signer;
+use crate::discovery;
 use crate::node::{routing, NodeId};
 use crate::service::message::NodeAnnouncement;
 use crate::service::{gossip, policy, Event};
+use crate::sinks;
 use crate::wire;
 use crate::wire::{Decode, Wire};
 use crate::worker;
@@ -128,6 +131,7 @@ pub struct Runtime {
     pub reactor: Reactor<wire::Control, popol::Poller>,
     pub pool: worker::Pool,
     pub local_addrs: Vec<net::SocketAddr>,
+    pub discovery: Option<discovery::Discovery>,
     pub signals: chan::Receiver<()>,
 }
 
@@ -138,6 +142,7 @@ impl Runtime {
     pub fn init<G: Signer + Ecdh + 'static>(
         home: Home,
         config: service::Config,
+        delivery: notifications::Config,
         listen: Vec<net::SocketAddr>,
         proxy: net::SocketAddr,
         signals: chan::Receiver<()>,
@@ -163,8 +168,14 @@ impl Runtime {
         log::info!(target: "node", "Opening policy database..");
         let policies = home.policies_mut()?;
         let policies = policy::Config::new(policy, scope, policies);
-        let notifications = home.notifications_mut()?;
-        let cobs_cache = cob::cache::Store::open(home.cobs().join(cob::cache::COBS_DB_FILE))?;
+        let mut notifications = home.notifications_mut()?;
+        let mut cobs_cache = cob::cache::Store::open(home.cobs().join(cob::cache::COBS_DB_FILE))?;
+
+        log::info!(target: "node", "Rebuilding stale COB cache entries..");
+        match worker::fetch::rebuild_cache(&storage, &mut cobs_cache) {
+            Ok(rebuilt) => log::info!(target: "node", "{rebuilt} COB cache entries rebuilt"),
+            Err(e) => log::error!(target: "node", "Failed to rebuild stale COB cache entries: {e}"),
+        }
 
         log::info!(target: "node", "Default seeding policy set to '{}'", &policy);
         log::info!(target: "node", "Initializing service ({:?})..", network);
@@ -250,6 +261,42 @@ impl Runtime {
         }
         let reactor = Reactor::named(wire, popol::Poller::new(), thread::name(&id, "service"))?;
         let handle = Handle::new(home.clone(), reactor.controller(), emitter);
+        let discovery = match config.discovery {
+            node::config::Discovery::Disabled => None,
+            node::config::Discovery::Multicast => {
+                let beacon = discovery::Beacon {
+                    network,
+                    nid: id,
+                    alias: config.alias.clone(),
+                    features: config.features(),
+                    ports: BoundedVec::truncate(local_addrs.iter().map(|a| a.port()).collect()),
+                };
+                Some(discovery::Discovery::bind(beacon, home.database_mut()?)?)
+            }
+        };
+
+        let control = match UnixListener::bind(home.socket()) {
+            Ok(sock) => sock,
+            Err(err) if err.kind() == io::ErrorKind::AddrInUse => {
+                return Err(Error::AlreadyRunning(home.socket()));
+            }
+            Err(err) => {
+                return Err(err.into());
+            }
+        };
+
+        if let Some(hook) = delivery.hook {
+            notifications = notifications.with_sink(sinks::Hook::spawn(&id, hook));
+        }
+        if let Some(email) = delivery.email {
+            log::info!(target: "node", "Sending notifications by email to {}..", email.to);
+            notifications = notifications.with_sink(sinks::Mailer::spawn(&id, email));
+        }
+        if delivery.socket {
+            let path = node_dir.join(node::NOTIFICATIONS_SOCKET_NAME);
+            log::info!(target: "node", "Binding notifications socket {}..", path.display());
+            notifications = notifications.with_sink(sinks::Socket::bind(&id, &path)?);
+        }
 
         let nid = *signer.public_key();
         let fetch = worker::FetchConfig {
@@ -273,15 +320,6 @@ impl Runtime {
                 policies_db: home.node().join(node::POLICIES_DB_FILE),
             },
         )?;
-        let control = match UnixListener::bind(home.socket()) {
-            Ok(sock) => sock,
-            Err(err) if err.kind() == io::ErrorKind::AddrInUse => {
-                return Err(Error::AlreadyRunning(home.socket()));
-            }
-            Err(err) => {
-                return Err(err.into());
-            }
-        };
 
         Ok(Runtime {
             id,
@@ -293,6 +331,7 @@ impl Runtime {
             pool,
             signals,
             local_addrs,
+            discovery,
         })
     }
 
@@ -306,6 +345,16 @@ impl Runtime {
             let handle = self.handle.clone();
             || control::listen(self.control, handle)
         });
+        if let Some(discovery) = self.discovery {
+            thread::spawn(&self.id, "discovery", {
+                let handle = self.handle.clone();
+                || {
+                    if let Err(e) = discovery.run(handle) {
+                        log::error!(target: "discovery", "Local network discovery failed: {e}");
+                    }
+                }
+            });
+        }
         let _signals = thread::spawn(&self.id, "signals", move || {
             if let Ok(()) = self.signals.recv() {
                 log::info!(target: "node", "Termination signal received; shutting down..");
@@ -319,8 +368,9 @@ impl Runtime {
         // Nb. We don't join the control thread here, as we have no way of notifying it that the
         // node is shutting down.
 
-        // Remove control socket file, but don't freak out if it's not there anymore.
+        // Remove socket files, but don't freak out if they're not there anymore.
         fs::remove_file(home.socket()).ok();
+        fs::remove_file(home.node().join(node::NOTIFICATIONS_SOCKET_NAME)).ok();
 
         log::debug!(target: "node", "Node shutdown completed for {}", self.id);
 
@@ -5,6 +5,8 @@ use std::sync::Arc;
 use std::{fmt, io, time};
 
 use crossbeam_channel as chan;
+use radicle::git::PatternString;
+use radicle::node::notifications;
 use radicle::node::{ConnectOptions, ConnectResult, Link, Seeds};
 use radicle::storage::refs::RefsAt;
 use reactor::poller::popol::PopolWaker;
@@ -38,6 +40,9 @@ pub enum Error {
     /// An I/O error occured.
     #[error(transparent)]
     Io(#[from] std::io::Error),
+    /// A notifications database error.
+    #[error("notifications database error: {0}")]
+    Notifications(#[from] notifications::Error),
 }
 
 impl From<chan::RecvError> for Error {
@@ -234,6 +239,23 @@ impl radicle::node::Handle for Handle {
         receiver.recv().map_err(Error::from)
     }
 
+    fn watch(
+        &mut self,
+        id: RepoId,
+        pattern: PatternString,
+        interest: notifications::Interest,
+    ) -> Result<bool, Error> {
+        // N.b. interests are only read by the fetch workers, so there is no need to go
+        // through the service.
+        let mut store = self.home.notifications_mut()?;
+        store.watch(&id, &pattern, interest).map_err(Error::from)
+    }
+
+    fn unwatch(&mut self, id: RepoId, pattern: PatternString) -> Result<bool, Error> {
+        let mut store = self.home.notifications_mut()?;
+        store.unwatch(&id, &pattern).map_err(Error::from)
+    }
+
     fn seed(&mut self, id: RepoId, scope: policy::Scope) -> Result<bool, Error> {
         let (sender, receiver) = chan::bounded(1);
         self.command(service::Command::Seed(id, scope, sender))?;
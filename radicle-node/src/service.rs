@@ -30,6 +30,7 @@ use radicle::node::refs::Store as _;
 use radicle::node::routing::Store as _;
 use radicle::node::seed;
 use radicle::node::seed::Store as _;
+use radicle::node::sync::Schedule;
 use radicle::node::{ConnectOptions, Penalty, Severity};
 use radicle::storage::refs::SIGREFS_BRANCH;
 use radicle::storage::{Inventory, RepositoryError};
@@ -291,6 +292,17 @@ impl PartialEq for QueuedFetch {
     }
 }
 
+/// Last time each scheduled sync task of a repository ran.
+#[derive(Debug, Default, Clone, Copy)]
+struct Scheduled {
+    /// Last fetch from preferred seeds.
+    fetch: Option<LocalTime>,
+    /// Last announcement of our refs.
+    announce: Option<LocalTime>,
+    /// Last fetch from all connected seeds.
+    resync: Option<LocalTime>,
+}
+
 /// Holds all node stores.
 #[derive(Debug)]
 pub struct Stores<D>(D);
@@ -395,6 +407,8 @@ pub struct Service<D, S, G> {
     last_prune: LocalTime,
     /// Last time the inventory was announced.
     last_announce: LocalTime,
+    /// Last time the scheduled sync tasks of each repository ran.
+    scheduled: HashMap<RepoId, Scheduled>,
     /// Last timestamp used for announcements.
     last_timestamp: Timestamp,
     /// Time when the service was initialized, or `None` if it wasn't initialized.
@@ -459,6 +473,7 @@ where
             last_prune: LocalTime::default(),
             last_timestamp: Timestamp::MIN,
             last_announce: LocalTime::default(),
+            scheduled: HashMap::new(),
             started_at: None,
             emitter,
             listening: vec![],
@@ -537,6 +552,11 @@ where
         &self.policies
     }
 
+    /// Get the mutable node policies.
+    pub fn policies_mut(&mut self) -> &mut policy::Config<Write> {
+        &mut self.policies
+    }
+
     /// Get the local signer.
     pub fn signer(&self) -> &G {
         &self.signer
@@ -698,6 +718,9 @@ where
             if let Err(e) = self.fetch_missing_inventory() {
                 error!(target: "service", "Error fetching missing inventory: {e}");
             }
+            if let Err(e) = self.run_schedules(now) {
+                error!(target: "service", "Error running sync schedules: {e}");
+            }
             self.outbox.wakeup(SYNC_INTERVAL);
             self.last_sync = now;
         }
@@ -1042,6 +1065,7 @@ where
                 updated,
                 namespaces,
                 clone,
+                partial,
                 doc,
             }) => {
                 info!(target: "service", "Fetched {rid} from {remote} successfully");
@@ -1062,9 +1086,16 @@ where
                     updated: updated.clone(),
                 });
 
-                // Announce our new inventory if this fetch was a full clone.
-                // Only update and announce inventory for public repositories.
-                if clone && doc.visibility.is_public() {
+                // Announce our new inventory if this fetch was a full clone, or fetched
+                // the missing history of a partial repository.
+                // Only update and announce inventory for public repositories, and never
+                // for partial repositories, since we can't serve them.
+                let unshallowed = !clone
+                    && self
+                        .storage
+                        .inventory()
+                        .map_or(false, |inventory| !inventory.contains(&rid));
+                if (clone || unshallowed) && !partial && doc.visibility.is_public() {
                     debug!(target: "service", "Updating and announcing inventory for cloned repository {rid}..");
 
                     self.storage.insert(rid);
@@ -2241,6 +2272,89 @@ where
         Ok(())
     }
 
+    /// Run the scheduled sync tasks of seeded repositories that are due.
+    ///
+    /// Since this runs as part of the "sync" task, schedules have the granularity of
+    /// [`SYNC_INTERVAL`]. Tasks that never ran since the node started are due immediately,
+    /// which catches up on anything missed while the node was offline.
+    fn run_schedules(&mut self, now: LocalTime) -> Result<(), Error> {
+        let schedules = self.policies.schedules()?.collect::<Vec<_>>();
+
+        // Forget about repositories that are no longer scheduled.
+        self.scheduled
+            .retain(|rid, _| schedules.iter().any(|s| s.rid == *rid));
+
+        for schedule in schedules {
+            let rid = schedule.rid;
+            if !self.policies.is_seeding(&rid)? {
+                continue;
+            }
+            let mut last = self.scheduled.get(&rid).copied().unwrap_or_default();
+
+            if Schedule::is_due(schedule.resync, last.resync, now) {
+                debug!(target: "service", "Running scheduled re-sync of {rid}..");
+
+                if self.fetch_scheduled(rid, false) {
+                    // A re-sync also covers our preferred seeds.
+                    last.resync = Some(now);
+                    last.fetch = Some(now);
+                }
+            } else if Schedule::is_due(schedule.fetch, last.fetch, now) {
+                debug!(target: "service", "Running scheduled fetch of {rid}..");
+
+                if self.fetch_scheduled(rid, true) {
+                    last.fetch = Some(now);
+                }
+            }
+            if Schedule::is_due(schedule.announce, last.announce, now) {
+                debug!(target: "service", "Running scheduled announcement of {rid}..");
+
+                match self.storage.get(rid) {
+                    Ok(Some(doc)) => {
+                        if let Err(e) = self.announce_own_refs(rid, doc) {
+                            error!(target: "service", "Error announcing refs of {rid}: {e}");
+                        }
+                    }
+                    Ok(None) => {
+                        debug!(target: "service", "Skipping announcement of {rid}: repository not found");
+                    }
+                    Err(e) => {
+                        error!(target: "service", "Error announcing refs of {rid}: doc error: {e}");
+                    }
+                }
+                last.announce = Some(now);
+            }
+            self.scheduled.insert(rid, last);
+        }
+        Ok(())
+    }
+
+    /// Fetch a repository from its connected seeds. If `preferred` is set, only fetch
+    /// from the seeds we are configured to stay connected to.
+    ///
+    /// Returns `false` if there were no seeds to fetch from.
+    fn fetch_scheduled(&mut self, rid: RepoId, preferred: bool) -> bool {
+        let seeds = match self.seeds(&rid) {
+            Ok(seeds) => seeds
+                .connected()
+                .map(|s| s.nid)
+                .filter(|nid| !preferred || self.config.is_persistent(nid))
+                .collect::<Vec<_>>(),
+            Err(e) => {
+                error!(target: "service", "Couldn't fetch {rid}: failed to lookup seeds: {e}");
+                return false;
+            }
+        };
+        if seeds.is_empty() {
+            debug!(target: "service", "No connected seeds found for {rid}..");
+            return false;
+        }
+        for nid in seeds {
+            self.fetch(rid, nid, FETCH_TIMEOUT, None);
+        }
+        true
+    }
+
     fn maintain_connections(&mut self) {
         let PeerConfig::Dynamic { target } = self.config.peers else {
             return;
@@ -54,6 +54,7 @@ impl AsTokens for config::RateLimit {
     }
 }
 
+/// Token bucket, refilled at a constant rate.
 #[derive(Debug)]
 pub struct TokenBucket {
     /// Token refill rate per second.
@@ -67,7 +68,7 @@ pub struct TokenBucket {
 }
 
 impl TokenBucket {
-    fn new(tokens: usize, rate: f64, now: LocalTime) -> Self {
+    pub fn new(tokens: usize, rate: f64, now: LocalTime) -> Self {
         Self {
             rate,
             capacity: tokens as f64,
@@ -84,7 +85,8 @@ impl TokenBucket {
         self.refilled_at = now;
     }
 
-    fn take(&mut self, now: LocalTime) -> bool {
+    /// Take a token, if one is available. Returns whether a token was taken.
+    pub fn take(&mut self, now: LocalTime) -> bool {
         self.refill(now);
 
         if self.tokens >= 1.0 {
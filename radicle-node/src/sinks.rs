@@ -0,0 +1,584 @@
+//! Notification delivery outside of the inbox.
+//!
+//! Sinks are attached to the node's notifications store, and receive every notification
+//! the store creates or updates. Three sinks are available, configured under the
+//! `notifications` key of the profile configuration:
+//!
+//! * A shell command hook, run for every notification, eg. to show a desktop notification.
+//! * Email, either one message per notification, or batched into periodic digests.
+//! * A Unix socket under the node directory, streaming notifications as JSON lines.
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::{fs, io, time};
+
+use crossbeam_channel as chan;
+use serde_json::json;
+
+use radicle::cob::TypedId;
+use radicle::node::notifications::config::Email;
+use radicle::node::notifications::{Notification, NotificationKind, Sink};
+use radicle::node::NodeId;
+
+use crate::runtime::thread;
+
+/// Timeout for writes to socket clients, and for reads and writes on the SMTP connection.
+pub const IO_TIMEOUT: time::Duration = time::Duration::from_secs(30);
+/// Maximum number of notifications waiting to be written to socket clients.
+/// Notifications are dropped when the queue is full, eg. because a client is slow to read.
+pub const SOCKET_QUEUE_SIZE: usize = 256;
+/// Maximum number of notifications waiting for the hook to run on them.
+/// Notifications are dropped when the queue is full, eg. because the hook is slow to exit.
+pub const HOOK_QUEUE_SIZE: usize = 256;
+
+/// Runs a shell command for every notification, from a dedicated thread.
+///
+/// The notification is passed as JSON on standard input, and its summary in the
+/// `RAD_NOTIFICATION` environment variable. Hooks are run one at a time, in the order
+/// notifications are delivered.
+pub struct Hook {
+    sender: chan::Sender<Notification>,
+}
+
+impl Hook {
+    /// Spawn the hook thread.
+    pub fn spawn(nid: &NodeId, command: String) -> Self {
+        let (sender, receiver) = chan::bounded(HOOK_QUEUE_SIZE);
+
+        thread::spawn(nid, "hook", move || {
+            for n in receiver {
+                Self::run(&command, &n);
+            }
+        });
+
+        Self { sender }
+    }
+
+    fn run(command: &str, notification: &Notification) {
+        let child = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .env("RAD_NOTIFICATION", summary(notification))
+            .env("RAD_NOTIFICATION_ID", notification.id.to_string())
+            .env("RAD_NOTIFICATION_RID", notification.repo.to_string())
+            .env("RAD_NOTIFICATION_REF", notification.qualified.as_str())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn();
+
+        let mut child = match child {
+            Ok(child) => child,
+            Err(e) => {
+                log::error!(target: "sinks", "Failed to run notification hook: {e}");
+                return;
+            }
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(payload(notification).to_string().as_bytes())
+                .ok();
+        }
+        match child.wait() {
+            Ok(status) if !status.success() => {
+                log::warn!(target: "sinks", "Notification hook exited with {status}");
+            }
+            Ok(_) => {}
+            Err(e) => log::error!(target: "sinks", "Notification hook failed: {e}"),
+        }
+    }
+}
+
+impl Sink for Hook {
+    fn deliver(&self, notification: &Notification) {
+        if let Err(chan::TrySendError::Full(_)) = self.sender.try_send(notification.clone()) {
+            log::warn!(
+                target: "sinks",
+                "Notification hook queue is full, dropping notification {}",
+                notification.id
+            );
+        }
+    }
+}
+
+/// Sends notifications by email, from a dedicated thread.
+pub struct Mailer {
+    sender: chan::Sender<Notification>,
+}
+
+impl Mailer {
+    /// Spawn the mailer thread.
+    pub fn spawn(nid: &NodeId, config: Email) -> Self {
+        let (sender, receiver) = chan::unbounded();
+
+        thread::spawn(nid, "mailer", move || Self::run(config, receiver));
+
+        Self { sender }
+    }
+
+    fn run(config: Email, receiver: chan::Receiver<Notification>) {
+        let Some(interval) = config.digest else {
+            while let Ok(n) = receiver.recv() {
+                let subject = format!("[radicle] {}", summary(&n));
+                if let Err(e) = send(&config, &subject, &body(&[n])) {
+                    log::error!(target: "sinks", "Failed to send notification email: {e}");
+                }
+            }
+            return;
+        };
+        let interval = time::Duration::from(interval);
+        let mut batch: Vec<Notification> = Vec::new();
+        let mut deadline = time::Instant::now() + interval;
+
+        loop {
+            match receiver.recv_deadline(deadline) {
+                Ok(n) => {
+                    // Only keep the latest version of a notification.
+                    batch.retain(|other| other.id != n.id);
+                    batch.push(n);
+                    continue;
+                }
+                Err(chan::RecvTimeoutError::Timeout) => {}
+                Err(chan::RecvTimeoutError::Disconnected) => break,
+            }
+            deadline = time::Instant::now() + interval;
+
+            if batch.is_empty() {
+                continue;
+            }
+            let subject = format!("[radicle] {} new notification(s)", batch.len());
+            if let Err(e) = send(&config, &subject, &body(&batch)) {
+                log::error!(target: "sinks", "Failed to send notification digest: {e}");
+            }
+            batch.clear();
+        }
+    }
+}
+
+impl Sink for Mailer {
+    fn deliver(&self, notification: &Notification) {
+        self.sender.send(notification.clone()).ok();
+    }
+}
+
+/// Streams notifications as JSON lines to the clients connected to a Unix socket.
+///
+/// Notifications are written to clients from a dedicated thread, so that slow clients
+/// don't hold up the caller.
+pub struct Socket {
+    clients: Arc<Mutex<Vec<UnixStream>>>,
+    sender: chan::Sender<String>,
+}
+
+impl Socket {
+    /// Bind the socket at the given path, and accept clients and write to them from
+    /// dedicated threads. A stale socket file at that path is replaced.
+    pub fn bind(nid: &NodeId, path: &Path) -> io::Result<Self> {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        let listener = UnixListener::bind(path)?;
+        let clients = Arc::new(Mutex::new(Vec::new()));
+        let (sender, receiver) = chan::bounded::<String>(SOCKET_QUEUE_SIZE);
+
+        thread::spawn(nid, "notifications", {
+            let clients = clients.clone();
+            move || {
+                for stream in listener.incoming() {
+                    match stream {
+                        Ok(stream) => {
+                            stream.set_write_timeout(Some(IO_TIMEOUT)).ok();
+                            clients.lock().expect("lock is not poisoned").push(stream);
+                        }
+                        Err(e) => {
+                            log::error!(target: "sinks", "Failed to accept notification client: {e}")
+                        }
+                    }
+                }
+            }
+        });
+        thread::spawn(nid, "notifications-writer", {
+            let clients = clients.clone();
+            move || {
+                for line in receiver {
+                    // Clients that can't be written to are dropped.
+                    clients
+                        .lock()
+                        .expect("lock is not poisoned")
+                        .retain_mut(|client| client.write_all(line.as_bytes()).is_ok());
+                }
+            }
+        });
+
+        Ok(Self { clients, sender })
+    }
+
+    /// Number of connected clients.
+    pub fn clients(&self) -> usize {
+        self.clients.lock().expect("lock is not poisoned").len()
+    }
+}
+
+impl Sink for Socket {
+    fn deliver(&self, notification: &Notification) {
+        let mut line = payload(notification).to_string();
+        line.push('\n');
+
+        if let Err(chan::TrySendError::Full(_)) = self.sender.try_send(line) {
+            log::warn!(
+                target: "sinks",
+                "Notification socket queue is full, dropping notification {}",
+                notification.id
+            );
+        }
+    }
+}
+
+/// JSON representation of a notification, as passed to hooks and socket clients.
+pub fn payload(n: &Notification) -> serde_json::Value {
+    let kind = match &n.kind {
+        NotificationKind::Cob { typed_id } => json!({
+            "type": "cob",
+            "typeName": typed_id.type_name,
+            "id": typed_id.id.to_string(),
+        }),
+        NotificationKind::Mention { typed_id, comment } => json!({
+            "type": "mention",
+            "typeName": typed_id.type_name,
+            "id": typed_id.id.to_string(),
+            "comment": comment,
+        }),
+        NotificationKind::Branch { name } => json!({ "type": "branch", "name": name }),
+        NotificationKind::Unknown { refname } => json!({ "type": "unknown", "refname": refname }),
+    };
+
+    json!({
+        "id": n.id,
+        "rid": n.repo,
+        "remote": n.remote,
+        "ref": n.qualified,
+        "old": n.update.old(),
+        "new": n.update.new(),
+        "kind": kind,
+        "summary": summary(n),
+        "activity": n.activity,
+        "timestamp": n.timestamp.as_millis(),
+    })
+}
+
+/// One-line summary of a notification, eg. "issue 18b7886 in rad:z3gq…: commented".
+pub fn summary(n: &Notification) -> String {
+    let what = match &n.kind {
+        NotificationKind::Cob { typed_id } => cob(typed_id),
+        NotificationKind::Mention { typed_id, .. } => format!("mention on {}", cob(typed_id)),
+        NotificationKind::Branch { name } => format!("branch {name}"),
+        NotificationKind::Unknown { refname } => refname.to_string(),
+    };
+    let what = format!("{what} in {}", n.repo);
+
+    if n.activity.is_empty() {
+        return format!("{what}: updated");
+    }
+    let mut kinds = Vec::new();
+    for activity in &n.activity {
+        let kind = activity.kind.to_string();
+        if !kinds.contains(&kind) {
+            kinds.push(kind);
+        }
+    }
+    format!("{what}: {}", kinds.join(", "))
+}
+
+fn cob(typed_id: &TypedId) -> String {
+    let id = typed_id.id.to_string();
+    let id = id.get(..7).unwrap_or(&id);
+
+    if typed_id.is_issue() {
+        format!("issue {id}")
+    } else if typed_id.is_patch() {
+        format!("patch {id}")
+    } else if typed_id.is_identity() {
+        format!("identity {id}")
+    } else {
+        format!("{} {id}", typed_id.type_name)
+    }
+}
+
+/// Email body listing the given notifications and their activity.
+fn body(notifications: &[Notification]) -> String {
+    let mut body = String::new();
+
+    for n in notifications {
+        body.push_str(&summary(n));
+        body.push('\n');
+
+        for activity in &n.activity {
+            body.push_str(&format!(
+                "  {} {} {}\n",
+                activity.author, activity.kind, activity.entry
+            ));
+        }
+        body.push('\n');
+    }
+    body
+}
+
+/// Send an email through a plain SMTP server.
+pub fn send(config: &Email, subject: &str, body: &str) -> io::Result<()> {
+    let stream = TcpStream::connect(&config.smtp)?;
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+
+    reply(&mut reader, 220)?;
+    command(&mut writer, &mut reader, "EHLO localhost", 250)?;
+    command(
+        &mut writer,
+        &mut reader,
+        &format!("MAIL FROM:<{}>", config.from),
+        250,
+    )?;
+    command(
+        &mut writer,
+        &mut reader,
+        &format!("RCPT TO:<{}>", config.to),
+        250,
+    )?;
+    command(&mut writer, &mut reader, "DATA", 354)?;
+
+    let mut message = format!(
+        "From: {}\r\nTo: {}\r\nSubject: {subject}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n",
+        config.from, config.to
+    );
+    for line in body.lines() {
+        // Lines starting with a dot are escaped, since a lone dot ends the message.
+        if line.starts_with('.') {
+            message.push('.');
+        }
+        message.push_str(line);
+        message.push_str("\r\n");
+    }
+    command(&mut writer, &mut reader, &format!("{message}."), 250)?;
+    command(&mut writer, &mut reader, "QUIT", 221)?;
+
+    Ok(())
+}
+
+/// Send an SMTP command, and check the reply code.
+fn command(
+    writer: &mut impl Write,
+    reader: &mut impl BufRead,
+    cmd: &str,
+    expected: u16,
+) -> io::Result<()> {
+    writer.write_all(cmd.as_bytes())?;
+    writer.write_all(b"\r\n")?;
+    writer.flush()?;
+
+    reply(reader, expected)
+}
+
+/// Read an SMTP reply, which may span multiple lines, and check its code.
+fn reply(reader: &mut impl BufRead, expected: u16) -> io::Result<()> {
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let code = line.get(..3).and_then(|c| c.parse::<u16>().ok());
+        if code != Some(expected) {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("unexpected SMTP reply: {}", line.trim_end()),
+            ));
+        }
+        // Multi-line replies have a dash after the code on all but the last line.
+        if line.as_bytes().get(3) != Some(&b'-') {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::TcpListener;
+
+    use localtime::LocalTime;
+    use radicle::git::refname;
+    use radicle::node::notifications::{NotificationStatus, StoreWriter};
+    use radicle::storage::RefUpdate;
+    use radicle::test::arbitrary;
+
+    use super::*;
+
+    #[test]
+    fn test_smtp_send() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let config = Email {
+            smtp: listener.local_addr().unwrap().to_string(),
+            from: String::from("node@localhost"),
+            to: String::from("alice@localhost"),
+            digest: None,
+        };
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut writer = stream.try_clone().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut received = Vec::new();
+            let mut data = false;
+
+            writer.write_all(b"220 localhost ready\r\n").unwrap();
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap() == 0 {
+                    break;
+                }
+                let line = line.trim_end().to_owned();
+                let reply: &[u8] = if data {
+                    if line == "." {
+                        data = false;
+                        b"250 queued\r\n"
+                    } else {
+                        received.push(line);
+                        continue;
+                    }
+                } else if line.starts_with("EHLO") {
+                    b"250-localhost\r\n250 8BITMIME\r\n"
+                } else if line == "DATA" {
+                    data = true;
+                    b"354 go ahead\r\n"
+                } else if line == "QUIT" {
+                    writer.write_all(b"221 bye\r\n").unwrap();
+                    break;
+                } else {
+                    b"250 ok\r\n"
+                };
+                writer.write_all(reply).unwrap();
+            }
+            received
+        });
+
+        send(&config, "Hello", "Hi Alice,\n.hidden\n").unwrap();
+        let received = server.join().unwrap();
+
+        assert!(received.contains(&String::from("Subject: Hello")));
+        assert!(received.contains(&String::from("To: alice@localhost")));
+        assert!(received.contains(&String::from("Hi Alice,")));
+        assert!(received.contains(&String::from("..hidden")));
+    }
+
+    #[test]
+    fn test_hook_sink() {
+        let tmp = tempfile::tempdir().unwrap();
+        let out = tmp.path().join("out");
+        let nid = arbitrary::gen::<NodeId>(1);
+        let hook = Hook::spawn(
+            &nid,
+            format!("cat >> {} && echo >> {}", out.display(), out.display()),
+        );
+        let mut store = StoreWriter::memory().unwrap();
+        let update = RefUpdate::Created {
+            name: refname!("refs/heads/master"),
+            oid: arbitrary::oid(),
+        };
+        store
+            .insert(
+                &arbitrary::gen(1),
+                &update,
+                LocalTime::from_millis(32188142),
+            )
+            .unwrap();
+        let notification = store.get(1).unwrap();
+
+        for _ in 0..3 {
+            hook.deliver(&notification);
+        }
+        // Hooks run one at a time, so their outputs don't interleave.
+        let lines = loop {
+            let lines = fs::read_to_string(&out).unwrap_or_default();
+            if lines.lines().count() == 3 {
+                break lines;
+            }
+            std::thread::sleep(time::Duration::from_millis(10));
+        };
+        for line in lines.lines() {
+            let payload: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(payload["id"], 1);
+        }
+    }
+
+    #[test]
+    fn test_socket_sink() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("notifications.sock");
+        let nid = arbitrary::gen::<NodeId>(1);
+        let rid = arbitrary::gen(1);
+        let socket = Socket::bind(&nid, &path).unwrap();
+        let client = UnixStream::connect(&path).unwrap();
+
+        // Wait for the client to be accepted.
+        while socket.clients() == 0 {
+            std::thread::sleep(time::Duration::from_millis(10));
+        }
+        let mut store = StoreWriter::memory().unwrap().with_sink(socket);
+        let update = RefUpdate::Created {
+            name: refname!("refs/heads/master"),
+            oid: arbitrary::oid(),
+        };
+        store
+            .insert(&rid, &update, LocalTime::from_millis(32188142))
+            .unwrap();
+
+        let mut line = String::new();
+        BufReader::new(client).read_line(&mut line).unwrap();
+        let payload: serde_json::Value = serde_json::from_str(&line).unwrap();
+
+        assert_eq!(payload["id"], 1);
+        assert_eq!(payload["kind"]["type"], "branch");
+        assert_eq!(payload["kind"]["name"], "master");
+        assert_eq!(
+            store.get(1).unwrap().status,
+            NotificationStatus::Unread,
+            "delivery doesn't mark notifications as read"
+        );
+    }
+
+    #[test]
+    fn test_socket_sink_slow_client() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("notifications.sock");
+        let nid = arbitrary::gen::<NodeId>(1);
+        let socket = Socket::bind(&nid, &path).unwrap();
+        // A client that never reads.
+        let _client = UnixStream::connect(&path).unwrap();
+
+        while socket.clients() == 0 {
+            std::thread::sleep(time::Duration::from_millis(10));
+        }
+        let mut store = StoreWriter::memory().unwrap();
+        let update = RefUpdate::Created {
+            name: refname!("refs/heads/master"),
+            oid: arbitrary::oid(),
+        };
+        store
+            .insert(
+                &arbitrary::gen(1),
+                &update,
+                LocalTime::from_millis(32188142),
+            )
+            .unwrap();
+        let notification = store.get(1).unwrap();
+        let start = time::Instant::now();
+
+        // Once the client's buffer and the queue are full, notifications are dropped
+        // instead of blocking the caller.
+        for _ in 0..SOCKET_QUEUE_SIZE * 64 {
+            socket.deliver(&notification);
+        }
+        assert!(start.elapsed() < IO_TIMEOUT);
+    }
+}
@@ -37,6 +37,7 @@ impl Arbitrary for FetchResult {
             updated: vec![],
             namespaces: HashSet::arbitrary(g),
             clone: bool::arbitrary(g),
+            partial: bool::arbitrary(g),
             doc: DocAt::arbitrary(g),
         }
     }
@@ -17,6 +17,7 @@ use radicle::crypto::{KeyPair, Seed, Signer};
 use radicle::git::refname;
 use radicle::identity::{RepoId, Visibility};
 use radicle::node::config::ConnectAddress;
+use radicle::node::notifications;
 use radicle::node::policy::store as policy;
 use radicle::node::routing::Store;
 use radicle::node::seed::Store as _;
@@ -95,6 +96,10 @@ impl Environment {
             public_explorer: explorer::Explorer::default(),
             preferred_seeds: vec![],
             web: web::Config::default(),
+            notifications: notifications::Config::default(),
+            queries: Default::default(),
+            replication: Default::default(),
+            evaluators: Default::default(),
         }
     }
 
@@ -497,6 +502,7 @@ impl<G: cyphernet::Ecdh<Pk = NodeId> + Signer + Clone> Node<G> {
         let rt = Runtime::init(
             self.home.clone(),
             self.config,
+            notifications::Config::default(),
             listen,
             proxy,
             signals,
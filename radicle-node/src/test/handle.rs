@@ -1,9 +1,10 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 use std::time;
 
 use radicle::git;
+use radicle::node::notifications::Interest;
 use radicle::storage::refs::RefsAt;
 
 use crate::identity::RepoId;
@@ -17,6 +18,7 @@ pub struct Handle {
     pub updates: Arc<Mutex<Vec<RepoId>>>,
     pub seeding: Arc<Mutex<HashSet<RepoId>>>,
     pub following: Arc<Mutex<HashSet<NodeId>>>,
+    pub watching: Arc<Mutex<HashMap<(RepoId, git::PatternString), Interest>>>,
 }
 
 impl radicle::node::Handle for Handle {
@@ -92,6 +94,29 @@ impl radicle::node::Handle for Handle {
         Ok(self.following.lock().unwrap().remove(&id))
     }
 
+    fn watch(
+        &mut self,
+        id: RepoId,
+        pattern: git::PatternString,
+        interest: Interest,
+    ) -> Result<bool, Self::Error> {
+        Ok(self
+            .watching
+            .lock()
+            .unwrap()
+            .insert((id, pattern), interest)
+            != Some(interest))
+    }
+
+    fn unwatch(&mut self, id: RepoId, pattern: git::PatternString) -> Result<bool, Self::Error> {
+        Ok(self
+            .watching
+            .lock()
+            .unwrap()
+            .remove(&(id, pattern))
+            .is_some())
+    }
+
     fn announce_refs(&mut self, id: RepoId) -> Result<RefsAt, Self::Error> {
         self.updates.lock().unwrap().push(id);
 
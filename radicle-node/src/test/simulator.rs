@@ -660,6 +660,7 @@ impl<S: WriteStorage + 'static, G: Signer> Simulation<S, G> {
                                     updated: vec![],
                                     namespaces: HashSet::new(),
                                     clone: true,
+                                    partial: false,
                                     doc: arbitrary::gen(1),
                                 })),
                             ),
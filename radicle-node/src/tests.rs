@@ -12,6 +12,7 @@ use radicle::identity::Visibility;
 use radicle::node::address::Store;
 use radicle::node::refs::Store as _;
 use radicle::node::routing::Store as _;
+use radicle::node::sync::Schedule;
 use radicle::node::{ConnectOptions, DEFAULT_TIMEOUT};
 use radicle::storage::refs::RefsAt;
 use radicle::storage::RefUpdate;
@@ -1337,6 +1338,105 @@ fn test_fetch_missing_inventory_on_schedule() {
         .unwrap();
 }
 
+#[test]
+fn test_sync_schedule() {
+    use std::collections::HashSet;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let bob = Peer::new("bob", [8, 8, 8, 8]);
+    let eve = Peer::new("eve", [9, 9, 9, 9]);
+    let storage = fixtures::storage(tmp.path().join("alice"), &MockSigner::default()).unwrap();
+    let rid = *storage.inventory().unwrap().first().unwrap();
+    let mut alice = Peer::config(
+        "alice",
+        [7, 7, 7, 7],
+        storage,
+        peer::Config {
+            config: Config {
+                connect: HashSet::from_iter([(bob.id(), bob.address()).into()]),
+                ..Config::new(node::Alias::new("alice"))
+            },
+            ..peer::Config::default()
+        },
+    );
+    let now = LocalTime::now();
+
+    alice.connect_to(&bob);
+    alice.connect_to(&eve);
+
+    for peer in [&bob, &eve] {
+        alice.receive(
+            peer.id(),
+            Message::inventory(
+                InventoryAnnouncement {
+                    inventory: vec![rid].try_into().unwrap(),
+                    timestamp: now.into(),
+                },
+                peer.signer(),
+            ),
+        );
+    }
+    alice
+        .policies_mut()
+        .set_schedule(&Schedule {
+            fetch: Some(LocalDuration::from_mins(15)),
+            ..Schedule::new(rid)
+        })
+        .unwrap();
+    alice.outbox().for_each(drop);
+
+    // Scheduled fetches only go to our preferred seeds.
+    alice.elapse(service::SYNC_INTERVAL);
+    assert_eq!(alice.fetches().collect::<Vec<_>>(), vec![(rid, bob.id())]);
+    alice.fetched(
+        rid,
+        bob.id(),
+        Err(worker::FetchError::Io(
+            io::ErrorKind::ConnectionReset.into(),
+        )),
+    );
+
+    // The next fetch only happens once the interval has passed.
+    alice.elapse(service::SYNC_INTERVAL);
+    assert_eq!(alice.fetches().count(), 0);
+    alice.elapse(LocalDuration::from_mins(15));
+    assert_eq!(alice.fetches().collect::<Vec<_>>(), vec![(rid, bob.id())]);
+    alice.fetched(
+        rid,
+        bob.id(),
+        Err(worker::FetchError::Io(
+            io::ErrorKind::ConnectionReset.into(),
+        )),
+    );
+
+    // A re-sync fetches from all connected seeds.
+    alice
+        .policies_mut()
+        .set_schedule(&Schedule {
+            resync: Some(LocalDuration::from_mins(24 * 60)),
+            ..Schedule::new(rid)
+        })
+        .unwrap();
+    alice.elapse(service::SYNC_INTERVAL);
+
+    let mut fetched = BTreeSet::new();
+    loop {
+        let next = alice.fetches().next();
+        let Some((_, nid)) = next else {
+            break;
+        };
+        fetched.insert(nid);
+        alice.fetched(
+            rid,
+            nid,
+            Err(worker::FetchError::Io(
+                io::ErrorKind::ConnectionReset.into(),
+            )),
+        );
+    }
+    assert_eq!(fetched, BTreeSet::from([bob.id(), eve.id()]));
+}
+
 #[test]
 fn test_queued_fetch_max_capacity() {
     let storage = arbitrary::nonempty_storage(3);
@@ -1454,6 +1554,7 @@ fn test_queued_fetch_from_ann_same_rid() {
             }],
             namespaces: [carol.id()].into_iter().collect(),
             clone: false,
+            partial: false,
             doc: arbitrary::gen(1),
         }),
     );
@@ -1,4 +1,5 @@
-use std::{collections::HashSet, thread, time};
+use std::num::NonZeroUsize;
+use std::{collections::HashSet, fs, thread, time};
 
 use radicle::crypto::{test::signer::MockSigner, Signer};
 use radicle::node::{Alias, ConnectResult, FetchResult, Handle as _, DEFAULT_TIMEOUT};
@@ -567,6 +568,75 @@ fn test_clone() {
         .is_ok());
 }
 
+#[test]
+fn test_clone_partial() {
+    logger::init(log::Level::Debug);
+
+    let tmp = tempfile::tempdir().unwrap();
+    let alice = Node::init(tmp.path(), Config::test(Alias::new("alice")));
+    let mut bob = Node::init(tmp.path(), Config::test(Alias::new("bob")));
+    let acme = bob.project("acme", "");
+
+    let mut alice = alice.spawn();
+    let bob = bob.spawn();
+
+    alice.connect(&bob);
+    converge([&alice, &bob]);
+
+    transport::local::register(alice.storage.clone());
+
+    let mut policies = alice.home.policies_mut().unwrap();
+    policies.set_depth(&acme, NonZeroUsize::new(1)).unwrap();
+
+    let _ = alice.handle.seed(acme, Scope::All).unwrap();
+    let result = alice.handle.fetch(acme, bob.id, DEFAULT_TIMEOUT).unwrap();
+    assert!(result.is_success());
+
+    let (_, canonical) = bob
+        .storage
+        .repository(acme)
+        .unwrap()
+        .canonical_head()
+        .unwrap();
+    let repo = alice.storage.repository(acme).unwrap();
+
+    // Only the canonical head commit was fetched.
+    assert!(repo.is_partial());
+    assert_eq!(
+        fs::read_to_string(repo.path().join("shallow")).unwrap(),
+        format!("{canonical}\n")
+    );
+    assert!(!alice.storage.inventory().unwrap().contains(&acme));
+    assert!(alice
+        .storage
+        .repositories()
+        .unwrap()
+        .iter()
+        .any(|r| r.rid == acme && r.partial));
+
+    rad::fork(acme, &alice.signer, &alice.storage).unwrap();
+
+    let working = rad::checkout(
+        acme,
+        alice.signer.public_key(),
+        tmp.path().join("clone"),
+        &alice.storage,
+    )
+    .unwrap();
+    assert_eq!(working.head().unwrap().target().unwrap(), *canonical);
+    assert!(working.is_shallow());
+
+    // Fetching without a depth fetches the missing history.
+    policies.set_depth(&acme, None).unwrap();
+
+    let result = alice.handle.fetch(acme, bob.id, DEFAULT_TIMEOUT).unwrap();
+    assert!(result.is_success());
+    assert!(!repo.is_partial());
+    assert!(!repo.path().join("shallow").exists());
+    // Once complete, the repository is announced.
+    alice.routes_to(&[(acme, bob.id), (acme, alice.id)]);
+}
+
 #[test]
 fn test_fetch_up_to_date() {
     logger::init(log::Level::Debug);
@@ -58,6 +58,8 @@ pub enum Error {
     InvalidControlMessage(u8),
     #[error("invalid protocol version header `{0:x?}`")]
     InvalidProtocolVersion([u8; 4]),
+    #[error("invalid magic header `{0:x?}`")]
+    InvalidMagic([u8; 4]),
     #[error("invalid onion address: {0}")]
     InvalidOnionAddr(#[from] tor::OnionAddrDecodeError),
     #[error("unknown address type `{0}`")]
@@ -277,7 +277,8 @@ impl Worker {
         let policy = self.policies.seed_policy(&rid)?.policy;
         let repo = self.storage.repository(rid)?;
         let doc = repo.identity_doc()?;
-        if !doc.is_visible_to(&remote) || policy == Policy::Block {
+        // N.b. partial repositories are never served, since they are not complete copies.
+        if !doc.is_visible_to(&remote) || policy == Policy::Block || repo.is_partial() {
             Err(UploadError::Unauthorized(remote, rid))
         } else {
             Ok(())
@@ -301,6 +302,7 @@ impl Worker {
         // we won't continue with any further set up of the fetch.
         let allowed = radicle_fetch::Allowed::from_config(rid, &self.policies)?;
         let blocked = radicle_fetch::BlockList::from_config(&self.policies)?;
+        let depth = self.policies.depth(&rid)?;
 
         let mut cache = self.cache.clone();
         let handle = fetch::Handle::new(
@@ -309,6 +311,7 @@ impl Worker {
             &self.storage,
             allowed,
             blocked,
+            depth,
             channels,
             notifs,
         )?;
@@ -1,13 +1,17 @@
 pub mod error;
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::num::NonZeroUsize;
 use std::str::FromStr;
 
 use localtime::LocalTime;
 
+use radicle::cob::cache::Update as _;
 use radicle::crypto::PublicKey;
-use radicle::identity::DocAt;
+use radicle::identity::{Did, DocAt};
+use radicle::node::notifications::{activity, Activity, Interest};
 use radicle::prelude::RepoId;
+use radicle::storage::git::Repository;
 use radicle::storage::refs::RefsAt;
 use radicle::storage::{
     ReadRepository, ReadStorage as _, RefUpdate, RemoteRepository, WriteRepository as _,
@@ -25,6 +29,8 @@ pub struct FetchResult {
     pub namespaces: HashSet<PublicKey>,
     /// The fetch was a full clone.
     pub clone: bool,
+    /// The repository is partial, ie. it was cloned with a limited history depth.
+    pub partial: bool,
     /// Identity doc of fetched repo.
     pub doc: DocAt,
 }
@@ -35,6 +41,7 @@ impl FetchResult {
             updated: vec![],
             namespaces: HashSet::new(),
             clone: false,
+            partial: false,
             doc,
         }
     }
@@ -58,20 +65,25 @@ impl Handle {
         storage: &Storage,
         follow: Allowed,
         blocked: BlockList,
+        depth: Option<NonZeroUsize>,
         channels: ChannelsFlush,
         notifications: node::notifications::StoreWriter,
     ) -> Result<Self, error::Handle> {
         let exists = storage.contains(&rid)?;
         if exists {
             let repo = storage.repository(rid)?;
-            let handle = radicle_fetch::Handle::new(local, repo, follow, blocked, channels)?;
+            // N.b. never turn a complete repository into a partial one.
+            let depth = depth.filter(|_| repo.is_partial());
+            let handle = radicle_fetch::Handle::new(local, repo, follow, blocked, channels)?
+                .with_depth(depth);
             Ok(Handle::Pull {
                 handle,
                 notifications,
             })
         } else {
             let (repo, tmp) = storage.lock_repository(rid)?;
-            let handle = radicle_fetch::Handle::new(local, repo, follow, blocked, channels)?;
+            let handle = radicle_fetch::Handle::new(local, repo, follow, blocked, channels)?
+                .with_depth(depth);
             Ok(Handle::Clone { handle, tmp })
         }
     }
@@ -138,12 +150,7 @@ impl Handle {
 
                 // Notifications are only posted for pulls, not clones.
                 if let Some(mut store) = notifs {
-                    // Only create notifications for repos that we have
-                    // contributed to in some way, otherwise our inbox will
-                    // be flooded by all the repos we are seeding.
-                    if repo.remote(&storage.info().key).is_ok() {
-                        notify(&rid, &applied, &mut store)?;
-                    }
+                    notify(&rid, &applied, &repo, &storage.info().key, &mut store)?;
                 }
 
                 cache_cobs(&rid, &applied.updated, &repo, cache)?;
@@ -154,6 +161,7 @@ impl Handle {
                     namespaces: remotes.into_iter().collect(),
                     doc: repo.identity_doc()?,
                     clone,
+                    partial: repo.is_partial(),
                 })
             }
         }
@@ -190,16 +198,34 @@ fn mv(tmp: tempfile::TempDir, storage: &Storage, rid: &RepoId) -> Result<(), err
 }
 
 // Post notifications for the given refs.
+//
+// Refs are filtered by the interests set on the repository. Repos without a matching
+// interest only get notifications if we have contributed to them in some way, otherwise
+// our inbox would be flooded by all the repos we are seeding.
 fn notify(
     rid: &RepoId,
     refs: &radicle_fetch::git::refs::Applied<'static>,
+    repo: &Repository,
+    local: &PublicKey,
     store: &mut node::notifications::StoreWriter,
 ) -> Result<(), error::Fetch> {
     let now = LocalTime::now();
+    let contributor = repo.remote(local).is_ok();
 
     for update in refs.updated.iter() {
-        if let Some(r) = update.name().to_namespaced() {
-            let r = r.strip_namespace();
+        if let RefUpdate::Skipped { .. } = update {
+            // Don't notify about skipped refs.
+            continue;
+        }
+        let namespaced = update.name().to_namespaced();
+        let Some(r) = namespaced
+            .as_ref()
+            .map(|r| r.strip_namespace())
+            .or_else(|| git::Qualified::from_refstr(update.name()))
+        else {
+            continue;
+        };
+        if namespaced.is_some() {
             if r == *git::refs::storage::SIGREFS_BRANCH {
                 // Don't notify about signed refs.
                 continue;
@@ -217,9 +243,33 @@ fn notify(
                 }
             }
         }
-        if let RefUpdate::Skipped { .. } = update {
-            // Don't notify about skipped refs.
-        } else if let Err(e) = store.insert(rid, update, now) {
+        let interest = match store.interest(rid, &r) {
+            Ok(Some(interest)) => interest,
+            Ok(None) if contributor => Interest::All,
+            Ok(None) => Interest::None,
+            Err(e) => {
+                log::error!(
+                    target: "worker",
+                    "Failed to get notification interest for {rid}: {e}"
+                );
+                continue;
+            }
+        };
+        if interest == Interest::None {
+            continue;
+        }
+        let mention = mention(&r, update, repo, local);
+        let interested = mention.is_some()
+            || match interest {
+                Interest::All => true,
+                Interest::Relevant => is_relevant(&r, repo, local),
+                Interest::Mentions | Interest::None => false,
+            };
+        if !interested {
+            continue;
+        }
+        let activity = activity(&r, update, &refs.updated, repo, local);
+        if let Err(e) = store.insert_activity(rid, update, &activity, mention.as_ref(), now) {
             log::error!(
                 target: "worker",
                 "Failed to update notification store for {rid}: {e}"
@@ -229,6 +279,131 @@ fn notify(
     Ok(())
 }
 
+/// Check whether the given ref is an issue or patch that was authored by,
+/// or is assigned to the local node.
+fn is_relevant(refname: &git::Qualified, repo: &Repository, local: &PublicKey) -> bool {
+    let Ok(Some(typed_id)) = cob::TypedId::from_qualified(refname) else {
+        return false;
+    };
+    let local = Did::from(*local);
+
+    if typed_id.is_issue() {
+        match cob::issue::Issues::open(repo).map(|issues| issues.get(&typed_id.id)) {
+            Ok(Ok(Some(issue))) => {
+                *issue.author().id() == local || issue.assignees().any(|did| *did == local)
+            }
+            _ => false,
+        }
+    } else if typed_id.is_patch() {
+        match cob::patch::Patches::open(repo).map(|patches| patches.get(&typed_id.id)) {
+            Ok(Ok(Some(patch))) => {
+                *patch.author().id() == local || patch.assignees().any(|did| did == local)
+            }
+            _ => false,
+        }
+    } else {
+        false
+    }
+}
+
+/// Summarize the operations on the COB under the given ref that are new to the local node.
+/// Operations authored by the local node are left out.
+fn activity(
+    refname: &git::Qualified,
+    update: &RefUpdate,
+    updated: &[RefUpdate],
+    repo: &Repository,
+    local: &PublicKey,
+) -> Vec<Activity> {
+    let Ok(Some(typed_id)) = cob::TypedId::from_qualified(refname) else {
+        return vec![];
+    };
+    let Some(new) = update.new() else {
+        return vec![];
+    };
+    let known = match activity::known(repo, &typed_id, updated) {
+        Ok(known) => known,
+        Err(e) => {
+            log::warn!(
+                target: "worker",
+                "Failed to get the known heads of {} in {}: {e}", typed_id.id, repo.id
+            );
+            return vec![];
+        }
+    };
+    match Activity::between(repo, &typed_id, new, &known) {
+        Ok(activity) => activity
+            .into_iter()
+            .filter(|a| a.author != *local)
+            .collect(),
+        Err(e) => {
+            log::warn!(
+                target: "worker",
+                "Failed to summarize activity on {} in {}: {e}", typed_id.id, repo.id
+            );
+            vec![]
+        }
+    }
+}
+
+/// Find a comment mentioning the local node that was added to the issue or patch under the
+/// given ref by this update. Comments authored by the local node are ignored.
+fn mention(
+    refname: &git::Qualified,
+    update: &RefUpdate,
+    repo: &Repository,
+    local: &PublicKey,
+) -> Option<cob::thread::CommentId> {
+    let Ok(Some(typed_id)) = cob::TypedId::from_qualified(refname) else {
+        return None;
+    };
+    let did = Did::from(*local);
+    let old = match update {
+        RefUpdate::Updated { old, .. } => Some(*old),
+        _ => None,
+    };
+    // A comment is new if it wasn't part of the history before the update.
+    let is_new = |id: &cob::thread::CommentId| match old {
+        Some(old) => *id != old && !repo.is_ancestor_of(*id, old).unwrap_or(true),
+        None => true,
+    };
+    let is_mention = |id: &cob::thread::CommentId, author: PublicKey, mentions: &[Did]| {
+        author != *local && mentions.contains(&did) && is_new(id)
+    };
+
+    if typed_id.is_issue() {
+        let issue = cob::issue::Issues::open(repo)
+            .ok()?
+            .get(&typed_id.id)
+            .ok()??;
+        let (id, _) = issue
+            .comments()
+            .filter(|(id, c)| is_mention(id, c.author(), c.mentions()))
+            .last()?;
+
+        Some(*id)
+    } else if typed_id.is_patch() {
+        let patch = cob::patch::Patches::open(repo)
+            .ok()?
+            .get(&typed_id.id)
+            .ok()??;
+        let (id, _) = patch
+            .revisions()
+            .flat_map(|(_, revision)| {
+                revision
+                    .discussion()
+                    .comments()
+                    .chain(revision.reviews().flat_map(|(_, review)| review.comments()))
+            })
+            .filter(|(id, c)| is_mention(id, c.author(), c.mentions()))
+            .last()?;
+
+        Some(*id)
+    } else {
+        None
+    }
+}
+
 /// Cache certain ref updates in our database.
 fn cache_refs<D>(repo: &RepoId, refs: &[RefUpdate], db: &mut D) -> Result<(), node::refs::Error>
 where
@@ -270,77 +445,230 @@ where
 
 /// Write new `RefUpdate`s that are related a `Patch` or an `Issue`
 /// COB to the COB cache.
-fn cache_cobs<S, C>(
+///
+/// Each object touched by the updates is evaluated at most once, no matter how many
+/// namespaces it was updated in.
+fn cache_cobs(
     rid: &RepoId,
     refs: &[RefUpdate],
-    storage: &S,
-    cache: &mut C,
-) -> Result<(), error::Cache>
-where
-    S: ReadRepository + cob::Store,
-    C: cob::cache::Update<cob::issue::Issue> + cob::cache::Update<cob::patch::Patch>,
-    C: cob::cache::Remove<cob::issue::Issue> + cob::cache::Remove<cob::patch::Patch>,
-{
-    let issues = cob::issue::Issues::open(storage)?;
-    let patches = cob::patch::Patches::open(storage)?;
+    repo: &Repository,
+    cache: &mut cob::cache::StoreWriter,
+) -> Result<(), error::Cache> {
+    let mut touched = BTreeSet::new();
     for update in refs {
         match update {
             RefUpdate::Updated { name, .. }
             | RefUpdate::Created { name, .. }
-            | RefUpdate::Deleted { name, .. } => match name.to_namespaced() {
-                Some(name) => {
-                    let Some(identifier) = cob::TypedId::from_namespaced(&name)? else {
-                        continue;
-                    };
-                    if identifier.is_issue() {
-                        if let Some(issue) = issues.get(&identifier.id)? {
-                            cache
-                                .update(rid, &identifier.id, &issue)
-                                .map(|_| ())
-                                .map_err(|e| error::Cache::Update {
-                                    id: identifier.id,
-                                    type_name: identifier.type_name,
-                                    err: e.into(),
-                                })?;
-                        } else {
-                            // N.b. the issue has been removed entirely from the
-                            // repository so we also remove it from the cache
-                            cob::cache::Remove::<cob::issue::Issue>::remove(cache, &identifier.id)
-                                .map(|_| ())
-                                .map_err(|e| error::Cache::Remove {
-                                    id: identifier.id,
-                                    type_name: identifier.type_name,
-                                    err: Box::new(e),
-                                })?;
-                        }
-                    } else if identifier.is_patch() {
-                        if let Some(patch) = patches.get(&identifier.id)? {
-                            cache
-                                .update(rid, &identifier.id, &patch)
-                                .map(|_| ())
-                                .map_err(|e| error::Cache::Update {
-                                    id: identifier.id,
-                                    type_name: identifier.type_name,
-                                    err: e.into(),
-                                })?;
-                        } else {
-                            // N.b. the patch has been removed entirely from the
-                            // repository so we also remove it from the cache
-                            cob::cache::Remove::<cob::patch::Patch>::remove(cache, &identifier.id)
-                                .map(|_| ())
-                                .map_err(|e| error::Cache::Remove {
-                                    id: identifier.id,
-                                    type_name: identifier.type_name,
-                                    err: Box::new(e),
-                                })?;
-                        }
-                    }
+            | RefUpdate::Deleted { name, .. } => {
+                let Some(name) = name.to_namespaced() else {
+                    continue;
+                };
+                if let Some(identifier) = cob::TypedId::from_namespaced(&name)? {
+                    touched.insert(identifier);
                 }
-                None => continue,
-            },
+            }
             RefUpdate::Skipped { .. } => { /* Do nothing */ }
         }
     }
+    cache_objects(rid, &touched, repo, cache)?;
 
     Ok(())
 }
+
+/// Bring the cached `Patch` and `Issue` COBs with the given identifiers up to date
+/// with the repository.
+///
+/// Objects that were cached from the same reference tips as the ones currently in
+/// the repository are skipped. Returns the number of objects that were updated or removed.
+pub(crate) fn cache_objects<'a>(
+    rid: &RepoId,
+    identifiers: impl IntoIterator<Item = &'a cob::TypedId>,
+    repo: &Repository,
+    cache: &mut cob::cache::StoreWriter,
+) -> Result<usize, error::Cache> {
+    let issues = cob::issue::Issues::open(repo)?;
+    let patches = cob::patch::Patches::open(repo)?;
+    let mut refreshed = 0;
+
+    for identifier in identifiers {
+        if !identifier.is_issue() && !identifier.is_patch() {
+            continue;
+        }
+        let tips = cob::object::Storage::objects(repo, &identifier.type_name, &identifier.id)?
+            .iter()
+            .map(|r| r.target.id)
+            .collect::<BTreeSet<_>>();
+
+        if cache.tips(identifier)?.as_ref() == Some(&tips) {
+            log::trace!(
+                target: "worker",
+                "Cached {} '{}' in {rid} is up to date", identifier.type_name, identifier.id
+            );
+            continue;
+        }
+
+        let cached = if identifier.is_issue() {
+            if let Some(issue) = issues.get(&identifier.id)? {
+                cache
+                    .update(rid, &identifier.id, &issue)
+                    .map_err(|e| error::Cache::Update {
+                        id: identifier.id,
+                        type_name: identifier.type_name.clone(),
+                        err: e.into(),
+                    })?;
+                true
+            } else {
+                // N.b. the issue has been removed entirely from the
+                // repository so we also remove it from the cache
+                cob::cache::Remove::<cob::issue::Issue>::remove(cache, &identifier.id).map_err(
+                    |e| error::Cache::Remove {
+                        id: identifier.id,
+                        type_name: identifier.type_name.clone(),
+                        err: Box::new(e),
+                    },
+                )?;
+                false
+            }
+        } else if let Some(patch) = patches.get(&identifier.id)? {
+            cache
+                .update(rid, &identifier.id, &patch)
+                .map_err(|e| error::Cache::Update {
+                    id: identifier.id,
+                    type_name: identifier.type_name.clone(),
+                    err: e.into(),
+                })?;
+            true
+        } else {
+            // N.b. the patch has been removed entirely from the
+            // repository so we also remove it from the cache
+            cob::cache::Remove::<cob::patch::Patch>::remove(cache, &identifier.id).map_err(
+                |e| error::Cache::Remove {
+                    id: identifier.id,
+                    type_name: identifier.type_name.clone(),
+                    err: Box::new(e),
+                },
+            )?;
+            false
+        };
+
+        if cached {
+            cache.set_tips(identifier, &tips)?;
+        }
+        refreshed += 1;
+    }
+
+    Ok(refreshed)
+}
+
+/// Re-evaluate the stale entries of the COB cache, eg. after a migration changed the
+/// cache schema. Returns the number of entries that were re-evaluated.
+///
+/// Failures are logged and don't prevent the other entries from being rebuilt.
+pub fn rebuild_cache(
+    storage: &Storage,
+    cache: &mut cob::cache::StoreWriter,
+) -> Result<usize, cob::cache::Error> {
+    let mut stale: BTreeMap<RepoId, Vec<cob::TypedId>> = BTreeMap::new();
+    for (rid, identifier) in cache.stale()? {
+        stale.entry(rid).or_default().push(identifier);
+    }
+    let mut rebuilt = 0;
+
+    for (rid, identifiers) in stale {
+        let result = storage
+            .repository(rid)
+            .map_err(error::Cache::from)
+            .and_then(|repo| cache_objects(&rid, &identifiers, &repo, cache));
+
+        match result {
+            Ok(n) => rebuilt += n,
+            Err(e) => {
+                log::error!(target: "worker", "Failed to rebuild COB cache for {rid}: {e}");
+            }
+        }
+    }
+    Ok(rebuilt)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use radicle::cob::issue;
+    use radicle::test::setup::NodeWithRepo;
+
+    use super::*;
+
+    #[test]
+    fn test_cache_objects() {
+        let NodeWithRepo { node, repo } = NodeWithRepo::default();
+        let mut issues = issue::Cache::no_cache(&*repo).unwrap();
+        let mut cache = cob::cache::StoreWriter::memory().unwrap();
+        let created = issues
+            .create("Flux", "It's broken.", &[], &[], [], &node.signer)
+            .unwrap();
+        let id = cob::TypedId {
+            id: *created.id(),
+            type_name: issue::TYPENAME.clone(),
+        };
+        let root = *created.root().0;
+
+        assert_eq!(
+            cache_objects(&repo.id, [&id], &repo, &mut cache).unwrap(),
+            1
+        );
+        let tips = cache.tips(&id).unwrap();
+        assert!(tips.is_some());
+
+        // Objects whose tips haven't changed are skipped.
+        assert_eq!(
+            cache_objects(&repo.id, [&id], &repo, &mut cache).unwrap(),
+            0
+        );
+
+        // Objects whose tips have changed are cached again.
+        issues
+            .get_mut(&id.id)
+            .unwrap()
+            .comment("Fixed it.", root, vec![], vec![], &node.signer)
+            .unwrap();
+        assert_eq!(
+            cache_objects(&repo.id, [&id], &repo, &mut cache).unwrap(),
+            1
+        );
+        assert_ne!(cache.tips(&id).unwrap(), tips);
+        assert_eq!(
+            cache_objects(&repo.id, [&id], &repo, &mut cache).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_rebuild_cache() {
+        let NodeWithRepo { node, repo } = NodeWithRepo::default();
+        let mut issues = issue::Cache::no_cache(&*repo).unwrap();
+        let mut cache = cob::cache::StoreWriter::memory().unwrap();
+        let created = issues
+            .create("Flux", "It's broken.", &[], &[], [], &node.signer)
+            .unwrap();
+        let id = cob::TypedId {
+            id: *created.id(),
+            type_name: issue::TYPENAME.clone(),
+        };
+        cache_objects(&repo.id, [&id], &repo, &mut cache).unwrap();
+        let tips = cache.tips(&id).unwrap();
+
+        assert!(cache.stale().unwrap().is_empty());
+        assert_eq!(rebuild_cache(&node.storage, &mut cache).unwrap(), 0);
+
+        // Migrations mark entries as stale by resetting their tips.
+        cache
+            .raw_query(|db| db.execute("UPDATE issues SET tips = NULL"))
+            .unwrap();
+        assert_eq!(cache.stale().unwrap(), vec![(repo.id, id.clone())]);
+        assert_eq!(cache.tips(&id).unwrap(), None);
+
+        assert_eq!(rebuild_cache(&node.storage, &mut cache).unwrap(), 1);
+        assert!(cache.stale().unwrap().is_empty());
+        assert_eq!(cache.tips(&id).unwrap(), tips);
+    }
+}
@@ -30,6 +30,10 @@ pub enum Fetch {
 
 #[derive(Debug, Error)]
 pub enum Cache {
+    #[error(transparent)]
+    Cache(#[from] cob::cache::Error),
+    #[error(transparent)]
+    Objects(#[from] storage::git::cob::ObjectsError),
     #[error(transparent)]
     Parse(#[from] cob::ParseIdentifierError),
     #[error(transparent)]
@@ -5,7 +5,7 @@
 //! Communication with Git tooling is done via `stdout` (`println`).
 mod fetch;
 mod list;
-mod push;
+pub mod push;
 
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -208,7 +208,7 @@ pub fn run(profile: radicle::Profile) -> Result<(), Error> {
 /// Parse a single push option. Returns `Ok` if it was successful.
 /// Note that some push options can contain spaces, eg. `patch.message="Hello World!"`,
 /// hence the arguments are passed as a slice.
-fn push_option(args: &[&str], opts: &mut Options) -> Result<(), Error> {
+pub fn push_option(args: &[&str], opts: &mut Options) -> Result<(), Error> {
     match args {
         ["hints"] => opts.hints = true,
         ["sync"] => opts.no_sync = false,
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use std::io::IsTerminal;
 use std::path::Path;
 use std::str::FromStr;
-use std::{assert_eq, io};
+use std::{assert_eq, io, thread, time};
 
 use thiserror::Error;
 
@@ -15,7 +15,9 @@ use radicle::crypto::Signer;
 use radicle::explorer::ExplorerResource;
 use radicle::identity::Did;
 use radicle::node;
+use radicle::node::sync::Replication;
 use radicle::node::{Handle, NodeId};
+use radicle::prelude::RepoId;
 use radicle::storage;
 use radicle::storage::git::transport::local::Url;
 use radicle::storage::{ReadRepository, SignRepository as _, WriteRepository};
@@ -26,6 +28,9 @@ use radicle_cli::terminal as term;
 
 use crate::{hint, read_line, Options};
 
+/// How often to check the sync status of seeds while waiting for replication.
+const REPLICATION_POLL_INTERVAL: time::Duration = time::Duration::from_millis(300);
+
 #[derive(Debug, Error)]
 pub enum Error {
     /// Public key doesn't match the remote namespace we're pushing to.
@@ -106,6 +111,20 @@ pub enum Error {
     /// Quorum error.
     #[error(transparent)]
     Quorum(#[from] radicle::storage::git::QuorumError),
+    /// Reference is managed by Radicle and can't be pushed to.
+    #[error("refusing to update restricted ref '{0}'")]
+    Restricted(git::RefString),
+    /// Reference doesn't have the value the update was based on.
+    #[error("reference '{0}' has changed since it was last fetched")]
+    Stale(git::RefString),
+    /// Replication is required, but can't be checked.
+    #[error("replication is required for this repository, but {0}")]
+    ReplicationUnchecked(&'static str),
+    /// Replication is required, but wasn't met before the timeout.
+    #[error(
+        "replication requirement not met: only {count} of {replicas} required seed(s) in sync"
+    )]
+    ReplicationUnmet { count: usize, replicas: usize },
 }
 
 /// Push command.
@@ -174,6 +193,7 @@ pub fn run(
     let signer = profile.signer()?;
     let mut line = String::new();
     let mut ok = HashMap::new();
+    let mut pushed = Vec::new();
     let hints = opts.hints || profile.hints();
 
     assert_eq!(signer.public_key(), &nid);
@@ -193,6 +213,21 @@ pub fn run(
     }
     let canonical = stored.head()?;
     let delegates = stored.delegates()?;
+    let replication = profile.config.replication.get(&stored.id);
+
+    // A required replication can only be checked by syncing with the network. Rather than
+    // skip the check, refuse to push before any ref is written.
+    if replication.map_or(false, |r| r.required) {
+        if opts.no_sync {
+            return Err(Error::ReplicationUnchecked("syncing is disabled"));
+        }
+        if !profile.policies()?.is_seeding(&stored.id)? {
+            return Err(Error::ReplicationUnchecked("you are not seeding it"));
+        }
+        if !radicle::Node::new(profile.socket()).is_running() {
+            return Err(Error::ReplicationUnchecked("your node is not running"));
+        }
+    }
 
     // For each refspec, push a ref or delete a ref.
     for spec in specs {
@@ -261,37 +296,16 @@ pub fn run(
                             && delegates.contains(&Did::from(nid))
                             && delegates.len() > 1
                         {
-                            if let Err(e) = working.find_commit(**canonical_oid) {
-                                return if git::ext::is_not_found_err(&e) {
-                                    Err(Error::MissingCanonicalHead(*canonical_oid))
-                                } else {
-                                    Err(e.into())
-                                };
-                            }
                             let head = working.find_reference(src.as_str())?;
                             let head = head.peel_to_commit()?.id();
-                            // Rollback is allowed and head is an ancestor of the canonical head.
-                            let rollback = opts.allow.rollback
-                                && working.graph_descendant_of(**canonical_oid, head)?;
-
-                            if head != **canonical_oid
-                                // Canonical head is *not* an ancestor of head.
-                                && !working.graph_descendant_of(head, **canonical_oid)?
-                                // Not a rollback.
-                                && !rollback
-                            {
-                                if hints {
-                                    hint(
-                                        "you are attempting to push a commit that would cause \
-                                        your upstream to diverge from the canonical head",
-                                    );
-                                    hint(
-                                        "to integrate the remote changes, run `git pull --rebase` \
-                                        and try again",
-                                    );
-                                }
-                                return Err(Error::HeadsDiverge(head.into(), *canonical_oid));
-                            }
+
+                            check_canonical_update(
+                                head.into(),
+                                *canonical_oid,
+                                &working,
+                                &opts,
+                                hints,
+                            )?;
                         }
                         push(
                             src,
@@ -309,16 +323,13 @@ pub fn run(
         };
 
         match result {
-            // Let Git tooling know that this ref has been pushed.
             Ok(resource) => {
-                println!("ok {}", cmd.dst());
+                pushed.push((cmd.dst().to_owned(), None));
                 ok.insert(spec, resource);
             }
-            // Let Git tooling know that there was an error pushing the ref.
-            Err(e) => println!("error {} {e}", cmd.dst()),
+            Err(e) => pushed.push((cmd.dst().to_owned(), Some(e.to_string()))),
         }
     }
-
     // Sign refs and sync if at least one ref pushed successfully.
     if !ok.is_empty() {
         let _ = stored.sign_refs(&signer)?;
@@ -340,7 +351,25 @@ pub fn run(
                 let node = radicle::Node::new(profile.socket());
                 if node.is_running() {
                     // Nb. allow this to fail. The push to local storage was still successful.
-                    sync(stored, ok.into_values().flatten(), opts, node, profile).ok();
+                    sync(
+                        stored,
+                        ok.into_values().flatten(),
+                        replication,
+                        opts,
+                        node.clone(),
+                        profile,
+                    )
+                    .ok();
+
+                    if let Some(replication) = replication {
+                        if let Err(e) = replicate(stored.id, replication, node) {
+                            // Let Git tooling know that the pushed refs weren't replicated
+                            // as required, even though they are in storage.
+                            for (_, error) in pushed.iter_mut() {
+                                error.get_or_insert_with(|| e.to_string());
+                            }
+                        }
+                    }
                 } else if hints {
                     hint("offline push, your node is not running");
                     hint("to sync with the network, run `rad node start`");
@@ -351,22 +380,199 @@ pub fn run(
         }
     }
 
+    for (dst, error) in pushed {
+        match error {
+            // Let Git tooling know that there was an error pushing the ref.
+            Some(e) => println!("error {dst} {e}"),
+            // Let Git tooling know that this ref has been pushed.
+            None => println!("ok {dst}"),
+        }
+    }
+
     // Done.
     println!();
 
     Ok(())
 }
 
+/// A reference update received by a server which writes the pushed objects to storage
+/// itself, eg. over HTTP.
+#[derive(Debug, Clone)]
+pub struct Update {
+    /// Reference being updated, relative to the pusher's namespace.
+    pub dst: git::RefString,
+    /// Expected value of the reference, or `None` if it shouldn't exist yet.
+    pub old: Option<git::Oid>,
+    /// New value of the reference, or `None` if it should be deleted.
+    pub new: Option<git::Oid>,
+}
+
+/// Result of a single reference update made with [`receive`].
+pub type UpdateResult = Result<Option<ExplorerResource>, Error>;
+
+/// Apply reference updates whose objects are already in storage, with the same checks and
+/// side-effects as a push through the remote helper: branches can't diverge from the
+/// canonical head, pushing to `refs/patches` opens a patch and pushing to a patch branch
+/// updates that patch. Updates are made to the namespace of the profile's key.
+///
+/// Returns the result of each update. Refs are signed if at least one update succeeded.
+pub fn receive(
+    updates: Vec<Update>,
+    stored: &storage::git::Repository,
+    profile: &Profile,
+    opts: Options,
+) -> Result<Vec<(git::RefString, UpdateResult)>, Error> {
+    let signer = profile.signer()?;
+    let nid = *signer.public_key();
+    let mut results = Vec::new();
+
+    for update in updates {
+        let result = receive_update(&update, &nid, stored, &signer, profile, opts.clone());
+        results.push((update.dst, result));
+    }
+
+    if results.iter().any(|(_, r)| r.is_ok()) {
+        let _ = stored.sign_refs(&signer)?;
+        let _ = stored.set_head()?;
+    }
+    Ok(results)
+}
+
+/// Apply a single received reference update.
+fn receive_update<G: Signer>(
+    update: &Update,
+    nid: &NodeId,
+    stored: &storage::git::Repository,
+    signer: &G,
+    profile: &Profile,
+    mut opts: Options,
+) -> Result<Option<ExplorerResource>, Error> {
+    let dst = git::Qualified::from_refstr(&update.dst)
+        .ok_or_else(|| Error::InvalidQualifiedRef(update.dst.clone()))?;
+
+    // These references are managed by Radicle, and can't be pushed to.
+    if update.dst.as_str().starts_with("refs/rad/") || update.dst.as_str().starts_with("refs/tmp/")
+    {
+        return Err(Error::Restricted(update.dst.clone()));
+    }
+    let working = stored.raw();
+    let (canonical_ref, canonical_oid) = stored.head()?;
+    let delegates = stored.delegates()?;
+    let refname = dst.with_namespace(nid.into());
+
+    let Some(new) = update.new else {
+        if dst == canonical_ref && delegates.contains(&Did::from(*nid)) {
+            return Err(Error::DeleteForbidden(update.dst.clone()));
+        }
+        let mut r = working.find_reference(&refname)?;
+        if update.old.is_some_and(|old| r.target() != Some(*old)) {
+            return Err(Error::Stale(update.dst.clone()));
+        }
+        r.delete()?;
+
+        return Ok(None);
+    };
+
+    // There is no one to write a patch message on the receiving end, so the
+    // message of the pushed commit is used instead.
+    if opts.message == term::patch::Message::Edit {
+        let commit = working.find_commit(*new)?;
+        opts.message = term::patch::Message::Text(commit.message().unwrap_or_default().to_owned());
+    }
+
+    if update.dst == *rad::PATCHES_REFNAME {
+        let patch = patch_create(
+            new,
+            nid,
+            working,
+            stored,
+            profile.patches_mut(stored)?,
+            signer,
+            opts,
+        )?;
+        return Ok(Some(ExplorerResource::Patch { id: patch }));
+    }
+
+    if let Some(oid) = dst.strip_prefix(git::refname!("refs/heads/patches")) {
+        let oid = git::Oid::from_str(oid)?;
+
+        update_ref(&refname, update.old, new, working)?;
+
+        return patch_revise(
+            new,
+            &oid,
+            working,
+            stored,
+            profile.patches_mut(stored)?,
+            signer,
+            opts,
+        );
+    }
+
+    if dst == canonical_ref && delegates.contains(&Did::from(*nid)) && delegates.len() > 1 {
+        check_canonical_update(new, canonical_oid, working, &opts, false)?;
+    }
+    update_ref(&refname, update.old, new, working)?;
+
+    if let Some(old) = update.old {
+        patch_merge_pushed(
+            old,
+            new,
+            &refname,
+            nid,
+            working,
+            stored,
+            profile.patches_mut(stored)?,
+            signer,
+        )?;
+    }
+    Ok(Some(ExplorerResource::Tree { oid: new }))
+}
+
+/// Check that updating the canonical head to the given commit doesn't make it diverge.
+/// Rolling back to a previous commit is allowed if the `allow.rollback` option is set.
+fn check_canonical_update(
+    head: git::Oid,
+    canonical_oid: git::Oid,
+    working: &git::raw::Repository,
+    opts: &Options,
+    hints: bool,
+) -> Result<(), Error> {
+    if let Err(e) = working.find_commit(*canonical_oid) {
+        return if git::ext::is_not_found_err(&e) {
+            Err(Error::MissingCanonicalHead(canonical_oid))
+        } else {
+            Err(e.into())
+        };
+    }
+    // Rollback is allowed and head is an ancestor of the canonical head.
+    let rollback = opts.allow.rollback && working.graph_descendant_of(*canonical_oid, *head)?;
+
+    if head != canonical_oid
+        // Canonical head is *not* an ancestor of head.
+        && !working.graph_descendant_of(*head, *canonical_oid)?
+        // Not a rollback.
+        && !rollback
+    {
+        if hints {
+            hint(
+                "you are attempting to push a commit that would cause \
+                your upstream to diverge from the canonical head",
+            );
+            hint("to integrate the remote changes, run `git pull --rebase` and try again");
+        }
+        return Err(Error::HeadsDiverge(head, canonical_oid));
+    }
+    Ok(())
+}
+
 /// Open a new patch.
 fn patch_open<G: Signer>(
     src: &git::RefStr,
     nid: &NodeId,
     working: &git::raw::Repository,
     stored: &storage::git::Repository,
-    mut patches: patch::Cache<
-        patch::Patches<'_, storage::git::Repository>,
-        cob::cache::StoreWriter,
-    >,
+    patches: patch::Cache<patch::Patches<'_, storage::git::Repository>, cob::cache::StoreWriter>,
     signer: &G,
     profile: &Profile,
     opts: Options,
@@ -384,8 +590,57 @@ fn patch_open<G: Signer>(
     // not fail, since the reference will already exist with the correct OID.
     push_ref(src, &dst, false, working, stored.raw())?;
 
+    let result = patch_create(
+        commit.id().into(),
+        nid,
+        working,
+        stored,
+        patches,
+        signer,
+        opts,
+    )
+    .and_then(|patch| {
+        // Setup current branch so that pushing updates the patch.
+        if let Some(branch) = rad::setup_patch_upstream(&patch, commit.id().into(), working, false)?
+        {
+            if let Some(name) = branch.name()? {
+                if profile.hints() {
+                    // Remove the remote portion of the name, i.e.
+                    // rad/patches/deadbeef -> patches/deadbeef
+                    let name = name.split('/').skip(1).collect::<Vec<_>>().join("/");
+                    hint(format!(
+                        "to update, run `git push` or `git push rad -f HEAD:{name}`"
+                    ));
+                }
+            }
+        }
+        Ok(Some(ExplorerResource::Patch { id: patch }))
+    });
+
+    // Delete short-lived patch head reference.
+    stored
+        .raw()
+        .find_reference(&dst)
+        .map(|mut r| r.delete())
+        .ok();
+
+    result
+}
+
+/// Create a patch proposing the given commit, which must already be in storage.
+fn patch_create<G: Signer>(
+    head: git::Oid,
+    nid: &NodeId,
+    working: &git::raw::Repository,
+    stored: &storage::git::Repository,
+    mut patches: patch::Cache<
+        patch::Patches<'_, storage::git::Repository>,
+        cob::cache::StoreWriter,
+    >,
+    signer: &G,
+    opts: Options,
+) -> Result<patch::PatchId, Error> {
     let (_, target) = stored.canonical_head()?;
-    let head = commit.id().into();
     let base = if let Some(base) = opts.base {
         base.resolve(working)?
     } else {
@@ -403,7 +658,7 @@ fn patch_open<G: Signer>(
             &description,
             patch::MergeTarget::default(),
             base,
-            commit.id(),
+            head,
             &[],
             signer,
         )
@@ -413,66 +668,37 @@ fn patch_open<G: Signer>(
             &description,
             patch::MergeTarget::default(),
             base,
-            commit.id(),
+            head,
             &[],
             signer,
         )
+    }?;
+    let action = if patch.is_draft() {
+        "drafted"
+    } else {
+        "opened"
     };
-    let result = match patch {
-        Ok(patch) => {
-            let action = if patch.is_draft() {
-                "drafted"
-            } else {
-                "opened"
-            };
-            let patch = patch.id;
-
-            eprintln!(
-                "{} Patch {} {action}",
-                term::format::positive("✓"),
-                term::format::tertiary(patch),
-            );
+    let patch = patch.id;
 
-            // Create long-lived patch head reference, now that we know the Patch ID.
-            //
-            //  refs/namespaces/<nid>/refs/heads/patches/<patch-id>
-            //
-            let refname = git::refs::patch(&patch).with_namespace(nid.into());
-            let _ = stored.raw().reference(
-                refname.as_str(),
-                commit.id(),
-                true,
-                "Create reference for patch head",
-            )?;
-
-            // Setup current branch so that pushing updates the patch.
-            if let Some(branch) =
-                rad::setup_patch_upstream(&patch, commit.id().into(), working, false)?
-            {
-                if let Some(name) = branch.name()? {
-                    if profile.hints() {
-                        // Remove the remote portion of the name, i.e.
-                        // rad/patches/deadbeef -> patches/deadbeef
-                        let name = name.split('/').skip(1).collect::<Vec<_>>().join("/");
-                        hint(format!(
-                            "to update, run `git push` or `git push rad -f HEAD:{name}`"
-                        ));
-                    }
-                }
-            }
-            Ok(Some(ExplorerResource::Patch { id: patch }))
-        }
-        Err(e) => Err(e),
-    };
+    eprintln!(
+        "{} Patch {} {action}",
+        term::format::positive("✓"),
+        term::format::tertiary(patch),
+    );
 
-    // Delete short-lived patch head reference.
-    stored
-        .raw()
-        .find_reference(&dst)
-        .map(|mut r| r.delete())
-        .ok();
+    // Create long-lived patch head reference, now that we know the Patch ID.
+    //
+    //  refs/namespaces/<nid>/refs/heads/patches/<patch-id>
+    //
+    let refname = git::refs::patch(&patch).with_namespace(nid.into());
+    let _ = stored.raw().reference(
+        refname.as_str(),
+        *head,
+        true,
+        "Create reference for patch head",
+    )?;
 
-    result.map_err(Error::from)
+    Ok(patch)
 }
 
 /// Update an existing patch.
@@ -485,37 +711,53 @@ fn patch_update<G: Signer>(
     nid: &NodeId,
     working: &git::raw::Repository,
     stored: &storage::git::Repository,
-    mut patches: patch::Cache<
-        patch::Patches<'_, storage::git::Repository>,
-        cob::cache::StoreWriter,
-    >,
+    patches: patch::Cache<patch::Patches<'_, storage::git::Repository>, cob::cache::StoreWriter>,
     signer: &G,
     opts: Options,
 ) -> Result<Option<ExplorerResource>, Error> {
     let reference = working.find_reference(src.as_str())?;
     let commit = reference.peel_to_commit()?;
-    let patch_id = radicle::cob::ObjectId::from(oid);
     let dst = dst.with_namespace(nid.into());
 
     push_ref(src, &dst, force, working, stored.raw())?;
 
+    patch_revise(
+        commit.id().into(),
+        oid,
+        working,
+        stored,
+        patches,
+        signer,
+        opts,
+    )
+}
+
+/// Add a revision to an existing patch, for the given commit, which must already be in storage.
+fn patch_revise<G: Signer>(
+    head: git::Oid,
+    oid: &git::Oid,
+    working: &git::raw::Repository,
+    stored: &storage::git::Repository,
+    mut patches: patch::Cache<
+        patch::Patches<'_, storage::git::Repository>,
+        cob::cache::StoreWriter,
+    >,
+    signer: &G,
+    opts: Options,
+) -> Result<Option<ExplorerResource>, Error> {
+    let patch_id = radicle::cob::ObjectId::from(oid);
     let Ok(mut patch) = patches.get_mut(&patch_id) else {
         return Err(Error::NotFound(patch_id));
     };
 
     // Don't update patch if it already has a revision matching this commit.
-    if patch.revisions().any(|(_, r)| *r.head() == commit.id()) {
+    if patch.revisions().any(|(_, r)| r.head() == head) {
         return Ok(None);
     }
-    let message = term::patch::get_update_message(
-        opts.message,
-        &stored.backend,
-        patch.latest().1,
-        &commit.id().into(),
-    )?;
+    let message =
+        term::patch::get_update_message(opts.message, &stored.backend, patch.latest().1, &head)?;
 
     let (_, target) = stored.canonical_head()?;
-    let head: git::Oid = commit.id().into();
     let base = if let Some(base) = opts.base {
         base.resolve(working)?
     } else {
@@ -572,22 +814,46 @@ fn push<G: Signer>(
     push_ref(src, &dst, force, working, stored.raw())?;
 
     if let Some(old) = old {
-        let proj = stored.project()?;
-        let master = &*git::Qualified::from(git::lit::refs_heads(proj.default_branch()));
-
-        // If we're pushing to the project's default branch, we want to see if any patches got
-        // merged, and if so, update the patch COB.
-        if &*dst.strip_namespace() == master {
-            let old = old.peel_to_commit()?.id();
-            // Only delegates should publish the merge result to the COB.
-            if stored.delegates()?.contains(&nid.into()) {
-                patch_merge_all(old.into(), head.into(), working, patches, signer)?;
-            }
-        }
+        let old = old.peel_to_commit()?.id();
+
+        patch_merge_pushed(
+            old.into(),
+            head.into(),
+            &dst,
+            nid,
+            working,
+            stored,
+            patches,
+            signer,
+        )?;
     }
     Ok(Some(ExplorerResource::Tree { oid: head.into() }))
 }
 
+/// If the pushed reference is the project's default branch, check whether any patches got
+/// merged, and if so, update the patch COB.
+fn patch_merge_pushed<G: Signer>(
+    old: git::Oid,
+    head: git::Oid,
+    dst: &git::Namespaced,
+    nid: &NodeId,
+    working: &git::raw::Repository,
+    stored: &storage::git::Repository,
+    patches: patch::Cache<patch::Patches<'_, storage::git::Repository>, cob::cache::StoreWriter>,
+    signer: &G,
+) -> Result<(), Error> {
+    let proj = stored.project()?;
+    let master = &*git::Qualified::from(git::lit::refs_heads(proj.default_branch()));
+
+    if &*dst.strip_namespace() == master {
+        // Only delegates should publish the merge result to the COB.
+        if stored.delegates()?.contains(&nid.into()) {
+            patch_merge_all(old, head, working, patches, signer)?;
+        }
+    }
+    Ok(())
+}
+
 /// Merge all patches that have been included in the base branch.
 fn patch_merge_all<G: Signer>(
     old: git::Oid,
@@ -669,6 +935,22 @@ fn patch_merge<C: cob::cache::Update<patch::Patch>, G: Signer>(
     Ok(())
 }
 
+/// Update a reference in storage, provided it still has the expected value.
+fn update_ref(
+    refname: &git::Namespaced,
+    old: Option<git::Oid>,
+    new: git::Oid,
+    stored: &git::raw::Repository,
+) -> Result<(), Error> {
+    let message = "Update reference from received push";
+
+    match old {
+        Some(old) => stored.reference_matching(refname.as_str(), *new, true, *old, message)?,
+        None => stored.reference(refname.as_str(), *new, false, message)?,
+    };
+    Ok(())
+}
+
 /// Push a single reference to storage.
 fn push_ref(
     src: &git::RefStr,
@@ -687,10 +969,12 @@ fn push_ref(
     Ok(())
 }
 
-/// Sync with the network.
+/// Sync with the network. If the repository has a replication requirement, the refs
+/// are announced to the seeds it names.
 fn sync(
     repo: &storage::git::Repository,
     updated: impl Iterator<Item = ExplorerResource>,
+    replication: Option<&Replication>,
     opts: Options,
     mut node: radicle::Node,
     profile: &Profile,
@@ -702,7 +986,16 @@ fn sync(
     };
     let result = cli::node::announce(
         repo,
-        cli::node::SyncSettings::default().with_profile(profile),
+        match replication {
+            Some(r) => cli::node::SyncSettings {
+                replicas: r.replicas,
+                seeds: r.seeds.clone(),
+                force: false,
+                timeout: r.timeout.into(),
+            },
+            None => cli::node::SyncSettings::default(),
+        }
+        .with_profile(profile),
         cli::node::SyncReporting {
             progress,
             completion: cli::node::SyncWriter::Stderr(io::stderr()),
@@ -740,3 +1033,66 @@ fn sync(
 
     Ok(())
 }
+
+/// Wait until the replication requirement of a repository is met, or times out, and
+/// report which seeds confirmed. Fails if the requirement is required and wasn't met.
+fn replicate(rid: RepoId, replication: &Replication, mut node: radicle::Node) -> Result<(), Error> {
+    let started = time::Instant::now();
+    let timeout = time::Duration::from(replication.timeout);
+    let confirmed = loop {
+        let seeds = match node.seeds(rid) {
+            Ok(seeds) => seeds,
+            Err(e) => {
+                eprintln!(
+                    "{} Replication could not be checked: {e}",
+                    term::format::negative("✗")
+                );
+                if replication.required {
+                    return Err(Error::ReplicationUnchecked("its seeds could not be listed"));
+                }
+                return Ok(());
+            }
+        };
+        let confirmed =
+            replication.confirmed(seeds.iter().filter(|s| s.is_synced()).map(|s| &s.nid));
+
+        if replication.is_met(&confirmed) || started.elapsed() >= timeout {
+            break confirmed;
+        }
+        thread::sleep(REPLICATION_POLL_INTERVAL);
+    };
+    let (count, replicas) = (confirmed.len(), replication.replicas);
+
+    if replication.is_met(&confirmed) {
+        eprintln!(
+            "{} Replicated to {count} of {replicas} required seed(s)",
+            term::format::positive("✓")
+        );
+    } else {
+        eprintln!(
+            "{} Replication requirement not met: only {count} of {replicas} required seed(s) in sync",
+            if replication.required {
+                term::format::negative("✗")
+            } else {
+                term::format::yellow("!")
+            }
+        );
+    }
+    for nid in &confirmed {
+        eprintln!("  {}", term::format::dim(nid));
+    }
+    if replication.is_met(&confirmed) {
+        return Ok(());
+    }
+    eprintln!(
+        "  {}",
+        term::format::dim(
+            "Your refs are in local storage; run `rad sync` to retry replicating them"
+        )
+    );
+
+    if replication.required {
+        return Err(Error::ReplicationUnmet { count, replicas });
+    }
+    Ok(())
+}
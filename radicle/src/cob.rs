@@ -1,12 +1,18 @@
 #![warn(clippy::unwrap_used)]
+pub mod archive;
 pub mod cache;
 pub mod common;
+pub mod discussion;
+pub mod external;
 pub mod identity;
 pub mod issue;
 pub mod op;
 pub mod patch;
+pub mod query;
 pub mod store;
+pub mod text;
 pub mod thread;
+pub mod wiki;
 
 #[cfg(test)]
 pub mod test;
@@ -16,9 +22,9 @@ pub use op::{ActorId, Op};
 pub use radicle_cob::{
     change, history::EntryId, object, object::collaboration::error, type_name::TypeNameParse,
     CollaborativeObject, Contents, Create, Embed, Entry, Evaluate, History, Manifest, ObjectId,
-    Store, TypeName, Update, Updated, Version,
+    Snapshot, Store, TypeName, Update, Updated, Version,
 };
-pub use radicle_cob::{create, get, git, list, remove, update};
+pub use radicle_cob::{create, get, get_from, git, list, list_from, remove, update};
 
 /// The exact identifier for a particular COB.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize)]
@@ -0,0 +1,663 @@
+//! Portable collaborative object archives.
+//!
+//! An archive is a [JSON Lines](https://jsonlines.org) file, where each line
+//! is a [`Record`]. It starts with a [`Header`], followed by the signed
+//! [`Change`]s of every exported object, in topological order, and finally
+//! the materialized [`Object`]s.
+//!
+//! Archives are self-contained: they can be verified without access to the
+//! repository they were exported from. Every change is written to a scratch
+//! repository, where its content address, commit and signature are checked,
+//! and every object is then loaded from it the same way it is loaded from
+//! storage, and compared with the archived state.
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+use std::io::{BufRead, Write};
+
+use nonempty::NonEmpty;
+use radicle_cob::signatures::ExtendedSignature;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::cob;
+use crate::cob::{identity, issue, patch, ObjectId, TypeName};
+use crate::crypto::{PublicKey, Signature};
+use crate::git;
+use crate::git::Oid;
+use crate::identity::RepoId;
+use crate::storage::git::Repository;
+
+/// Archive format version.
+pub const VERSION: u32 = 1;
+
+/// Error exporting an archive.
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Git(#[from] git::raw::Error),
+    #[error(transparent)]
+    Retrieve(#[from] cob::error::Retrieve),
+    #[error(transparent)]
+    Store(#[from] cob::store::Error),
+    #[error(transparent)]
+    Repository(#[from] crate::storage::RepositoryError),
+    #[error("change {0} has a manifest that is not valid UTF-8")]
+    Manifest(Oid),
+    #[error("change {0} has contents that are not valid UTF-8")]
+    Contents(Oid),
+    #[error("change {0} has a commit that is not valid UTF-8")]
+    Commit(Oid),
+    #[error("change {0} has an identity document that is not valid UTF-8")]
+    Document(Oid),
+}
+
+/// Error verifying an archive.
+#[derive(Debug, Error)]
+pub enum VerifyError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("invalid record on line {line}: {err}")]
+    Record {
+        line: usize,
+        #[source]
+        err: serde_json::Error,
+    },
+    #[error("archive is missing its header")]
+    MissingHeader,
+    #[error("unsupported archive version {0}")]
+    Version(u32),
+    #[error("change {id} has an invalid manifest: {err}")]
+    Manifest {
+        id: Oid,
+        #[source]
+        err: serde_json::Error,
+    },
+    #[error("change {id} is not of type {expected}")]
+    TypeName { id: Oid, expected: TypeName },
+    #[error("change {id} has contents which don't match its revision {revision}")]
+    Revision { id: Oid, revision: Oid },
+    #[error("change {id} has an embedded file '{name}' which doesn't match its hash")]
+    Embed { id: Oid, name: String },
+    #[error("change {0} has a commit which doesn't match its id")]
+    Commit(Oid),
+    #[error("change {0} doesn't match its commit")]
+    Mismatch(Oid),
+    #[error("change {id} could not be loaded: {err}")]
+    Load {
+        id: Oid,
+        #[source]
+        err: radicle_cob::git::change::error::Load,
+    },
+    #[error("change {0} has an invalid signature")]
+    Signature(Oid),
+    #[error("change {0} is empty")]
+    Empty(Oid),
+    #[error("object {typename}/{id} could not be loaded: {err}")]
+    Retrieve {
+        typename: TypeName,
+        id: ObjectId,
+        #[source]
+        err: cob::error::Retrieve,
+    },
+    #[error("object {typename}/{id} has no history in the archive")]
+    MissingHistory { typename: TypeName, id: ObjectId },
+    #[error("object {typename}/{id} has tips which don't match its history")]
+    Tips { typename: TypeName, id: ObjectId },
+    #[error("object {typename}/{id} has a state which doesn't match its history")]
+    State { typename: TypeName, id: ObjectId },
+    #[error("change {0} doesn't belong to any object")]
+    Orphan(Oid),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Git(#[from] git::raw::Error),
+}
+
+/// An archive record.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum Record {
+    /// Archive header.
+    Header(Header),
+    /// A signed change.
+    Change(Box<Change>),
+    /// A materialized object.
+    Object(Object),
+}
+
+/// Archive header.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Header {
+    /// Format version.
+    pub version: u32,
+    /// Repository the objects were exported from.
+    pub rid: RepoId,
+}
+
+/// A file embedded in a change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbedEntry {
+    /// File name.
+    pub name: String,
+    /// Content hash.
+    pub oid: Oid,
+    /// File contents. Only included for identity documents, which are needed
+    /// to replay the history of other objects.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+/// A signed change, ie. an entry in an object's history.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Change {
+    /// Change identifier.
+    pub id: Oid,
+    /// Change commit, verbatim. Its hash is the change identifier.
+    pub commit: String,
+    /// Content address of the change, signed by the author.
+    pub revision: Oid,
+    /// Change author.
+    pub author: PublicKey,
+    /// Author signature over the revision.
+    pub signature: Signature,
+    /// Resource this change relates to, eg. the repository identity.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource: Option<Oid>,
+    /// Parent changes.
+    pub parents: Vec<Oid>,
+    /// Other objects this change depends on.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related: Vec<Oid>,
+    /// Change manifest, verbatim.
+    pub manifest: String,
+    /// Change operations, verbatim.
+    pub contents: Vec<String>,
+    /// Embedded files. Only content hashes are included.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub embeds: Vec<EmbedEntry>,
+    /// Change timestamp, in seconds.
+    pub timestamp: u64,
+}
+
+impl Change {
+    /// Load a change from a repository.
+    fn load(entry: &cob::Entry, repo: &git::raw::Repository) -> Result<Self, ExportError> {
+        let tree = repo.find_tree(entry.revision.into())?;
+        let manifest = tree
+            .get_name(radicle_cob::git::change::MANIFEST_BLOB_NAME)
+            .ok_or_else(|| ExportError::Manifest(entry.id))?
+            .to_object(repo)?
+            .peel_to_blob()?;
+        let manifest = String::from_utf8(manifest.content().to_vec())
+            .map_err(|_| ExportError::Manifest(entry.id))?;
+        let contents = entry
+            .contents
+            .iter()
+            .map(|c| String::from_utf8(c.clone()).map_err(|_| ExportError::Contents(entry.id)))
+            .collect::<Result<_, _>>()?;
+        let odb = repo.odb()?;
+        let commit = odb.read(*entry.id)?;
+        let commit =
+            String::from_utf8(commit.data().to_vec()).map_err(|_| ExportError::Commit(entry.id))?;
+        let mut embeds = Vec::new();
+
+        if let Ok(e) = tree.get_path(&radicle_cob::git::change::EMBEDS_PATH) {
+            let subtree = e.to_object(repo)?.peel_to_tree()?;
+
+            for e in subtree.iter() {
+                let content = if entry.type_name() == &*identity::TYPENAME {
+                    let blob = e.to_object(repo)?.peel_to_blob()?;
+                    let content = String::from_utf8(blob.content().to_vec())
+                        .map_err(|_| ExportError::Document(entry.id))?;
+
+                    Some(content)
+                } else {
+                    None
+                };
+                embeds.push(EmbedEntry {
+                    name: String::from_utf8_lossy(e.name_bytes()).into_owned(),
+                    oid: e.id().into(),
+                    content,
+                });
+            }
+        }
+
+        Ok(Self {
+            id: entry.id,
+            commit,
+            revision: entry.revision,
+            author: entry.signature.key,
+            signature: entry.signature.sig,
+            resource: entry.resource,
+            parents: entry.parents.clone(),
+            related: entry.related.clone(),
+            manifest,
+            contents,
+            embeds,
+            timestamp: entry.timestamp,
+        })
+    }
+
+    /// Write the change to an object database, checking that it hashes to its
+    /// content address and identifier.
+    fn write(&self, odb: &git::raw::Odb) -> Result<(), VerifyError> {
+        let blob = |bytes: &[u8]| odb.write(git::raw::ObjectType::Blob, bytes);
+        let mut root = vec![(
+            radicle_cob::git::change::MANIFEST_BLOB_NAME.to_owned(),
+            false,
+            blob(self.manifest.as_bytes())?,
+        )];
+        for (ix, op) in self.contents.iter().enumerate() {
+            root.push((ix.to_string(), false, blob(op.as_bytes())?));
+        }
+        if !self.embeds.is_empty() {
+            let mut embeds = Vec::new();
+
+            for e in &self.embeds {
+                // Embeds without contents are left out of the database.
+                if let Some(content) = &e.content {
+                    if blob(content.as_bytes())? != *e.oid {
+                        return Err(VerifyError::Embed {
+                            id: self.id,
+                            name: e.name.clone(),
+                        });
+                    }
+                }
+                embeds.push((e.name.clone(), false, *e.oid));
+            }
+            root.push((
+                radicle_cob::git::change::EMBEDS_PATH.display().to_string(),
+                true,
+                tree(odb, embeds)?,
+            ));
+        }
+        if tree(odb, root)? != *self.revision {
+            return Err(VerifyError::Revision {
+                id: self.id,
+                revision: self.revision,
+            });
+        }
+        if odb.write(git::raw::ObjectType::Commit, self.commit.as_bytes())? != *self.id {
+            return Err(VerifyError::Commit(self.id));
+        }
+        Ok(())
+    }
+
+    /// Convert into a history entry.
+    fn entry(&self) -> Result<cob::Entry, VerifyError> {
+        let manifest = serde_json::from_str(&self.manifest)
+            .map_err(|err| VerifyError::Manifest { id: self.id, err })?;
+        let contents = NonEmpty::collect(self.contents.iter().map(|c| c.as_bytes().to_vec()))
+            .ok_or(VerifyError::Empty(self.id))?;
+
+        Ok(cob::Entry {
+            id: self.id,
+            revision: self.revision,
+            signature: ExtendedSignature::new(self.author, self.signature),
+            resource: self.resource,
+            parents: self.parents.clone(),
+            related: self.related.clone(),
+            manifest,
+            contents,
+            timestamp: self.timestamp,
+        })
+    }
+}
+
+/// A materialized object.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Object {
+    /// Object type.
+    pub type_name: TypeName,
+    /// Object identifier.
+    pub id: Oid,
+    /// The tips of the object's history, at the time of export.
+    pub tips: BTreeSet<Oid>,
+    /// The object state, materialized from its history.
+    pub state: serde_json::Value,
+}
+
+/// Summary of an export or verification.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Summary {
+    /// Number of objects, per type.
+    pub objects: BTreeMap<TypeName, usize>,
+    /// Number of changes.
+    pub changes: usize,
+}
+
+/// Export the identity, issues and patches of a repository to an archive.
+pub fn export<W: Write>(repo: &Repository, mut writer: W) -> Result<Summary, ExportError> {
+    let mut summary = Summary::default();
+    let mut objects = Vec::new();
+
+    write(
+        &mut writer,
+        &Record::Header(Header {
+            version: VERSION,
+            rid: repo.id,
+        }),
+    )?;
+
+    for typename in [&*identity::TYPENAME, &*issue::TYPENAME, &*patch::TYPENAME] {
+        for obj in cob::list::<NonEmpty<cob::Entry>, _>(repo, typename)? {
+            let history = obj.history();
+
+            for entry in history.sorted(|a, b| a.cmp(b)) {
+                write(
+                    &mut writer,
+                    &Record::Change(Box::new(Change::load(entry, &repo.backend)?)),
+                )?;
+                summary.changes += 1;
+            }
+            let id = *obj.id();
+            let Some(state) = state(repo, typename, &id)? else {
+                continue;
+            };
+            objects.push(Object {
+                type_name: typename.clone(),
+                id: *id,
+                tips: history.tips(),
+                state,
+            });
+            *summary.objects.entry(typename.clone()).or_default() += 1;
+        }
+    }
+    for obj in objects {
+        write(&mut writer, &Record::Object(obj))?;
+    }
+    writer.flush()?;
+
+    Ok(summary)
+}
+
+/// Verify an archive, without access to the originating repository.
+///
+/// Every change is checked against its content address, commit and signature.
+/// The history of every object is then replayed from its tips, and the
+/// resulting state is compared with the archived one.
+pub fn verify<R: BufRead>(reader: R) -> Result<(Header, Summary), VerifyError> {
+    let mut lines = reader.lines().enumerate();
+    let header = match lines.next() {
+        Some((_, line)) => match serde_json::from_str(&line?) {
+            Ok(Record::Header(header)) => header,
+            _ => return Err(VerifyError::MissingHeader),
+        },
+        None => return Err(VerifyError::MissingHeader),
+    };
+    if header.version != VERSION {
+        return Err(VerifyError::Version(header.version));
+    }
+    // Changes are written to a scratch repository, so that objects can be
+    // loaded from it the same way they are loaded from storage.
+    let tmp = tempfile::tempdir()?;
+    let repo = Repository {
+        id: header.rid,
+        backend: git::raw::Repository::init_bare(tmp.path())?,
+    };
+    let odb = repo.backend.odb()?;
+    let mut authors = BTreeMap::new();
+    let mut objects = Vec::new();
+
+    for (ix, line) in lines {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record =
+            serde_json::from_str(&line).map_err(|err| VerifyError::Record { line: ix + 1, err })?;
+
+        match record {
+            Record::Header(_) => return Err(VerifyError::MissingHeader),
+            Record::Change(change) => {
+                change.write(&odb)?;
+
+                let entry = cob::change::Storage::load(&repo.backend, change.id)
+                    .map_err(|err| VerifyError::Load { id: change.id, err })?;
+                if entry != change.entry()? {
+                    return Err(VerifyError::Mismatch(change.id));
+                }
+                if !entry.valid_signatures() {
+                    return Err(VerifyError::Signature(change.id));
+                }
+                authors.insert(change.id, change.author);
+            }
+            Record::Object(object) => objects.push(object),
+        }
+    }
+    // Point the object references at the archived tips. Each tip is stored
+    // under the namespace of its author, as it would be in storage.
+    for object in &objects {
+        let id = ObjectId::from(object.id);
+
+        for tip in &object.tips {
+            let Some(author) = authors.get(tip) else {
+                return Err(VerifyError::MissingHistory {
+                    typename: object.type_name.clone(),
+                    id,
+                });
+            };
+            repo.backend.reference(
+                &format!(
+                    "refs/namespaces/{author}/refs/cobs/{}/{id}",
+                    object.type_name
+                ),
+                **tip,
+                true,
+                "archive verification",
+            )?;
+        }
+    }
+
+    let mut summary = Summary::default();
+    let mut visited = BTreeSet::new();
+
+    for object in objects {
+        let id = ObjectId::from(object.id);
+        let typename = object.type_name;
+        let retrieve = |err| VerifyError::Retrieve {
+            typename: typename.clone(),
+            id,
+            err,
+        };
+        let obj = cob::get::<NonEmpty<cob::Entry>, _>(&repo, &typename, &id)
+            .map_err(retrieve)?
+            .ok_or_else(|| VerifyError::MissingHistory {
+                typename: typename.clone(),
+                id,
+            })?;
+
+        // Changes that fail to apply are pruned from the history, which would
+        // result in different tips.
+        if obj.history().tips() != object.tips {
+            return Err(VerifyError::Tips { typename, id });
+        }
+        for entry in obj.object().iter() {
+            if entry.type_name() != &typename {
+                return Err(VerifyError::TypeName {
+                    id: entry.id,
+                    expected: typename,
+                });
+            }
+            visited.insert(entry.id);
+        }
+        let state = match state(&repo, &typename, &id) {
+            Ok(state) => state,
+            Err(ExportError::Retrieve(err)) => return Err(retrieve(err)),
+            Err(ExportError::Json(err)) => return Err(err.into()),
+            Err(_) => None,
+        };
+        if state.as_ref() != Some(&object.state) {
+            return Err(VerifyError::State { typename, id });
+        }
+        summary.changes += obj.object().len();
+        *summary.objects.entry(typename).or_default() += 1;
+    }
+    if let Some(id) = authors.keys().find(|id| !visited.contains(*id)) {
+        return Err(VerifyError::Orphan(*id));
+    }
+    Ok((header, summary))
+}
+
+/// Materialize the state of an object, as it is archived.
+fn state(
+    repo: &Repository,
+    typename: &TypeName,
+    id: &ObjectId,
+) -> Result<Option<serde_json::Value>, ExportError> {
+    let state = if typename == &*identity::TYPENAME {
+        cob::get::<cob::identity::Identity, _>(repo, typename, id)?
+            .map(|obj| self::identity(obj.object()))
+    } else if typename == &*issue::TYPENAME {
+        cob::get::<issue::Issue, _>(repo, typename, id)?
+            .map(|obj| serde_json::to_value(obj.object()))
+            .transpose()?
+    } else if typename == &*patch::TYPENAME {
+        cob::get::<patch::Patch, _>(repo, typename, id)?
+            .map(|obj| serde_json::to_value(obj.object()))
+            .transpose()?
+    } else {
+        None
+    };
+    Ok(state)
+}
+
+/// Write a single record.
+fn write<W: Write>(writer: &mut W, record: &Record) -> Result<(), ExportError> {
+    serde_json::to_writer(&mut *writer, record)?;
+    writer.write_all(b"\n")?;
+
+    Ok(())
+}
+
+/// Write a git tree with the given entries, without checking that they exist.
+/// Each entry is a name, whether it's a sub-tree, and an object hash.
+fn tree(
+    odb: &git::raw::Odb,
+    mut entries: Vec<(String, bool, git::raw::Oid)>,
+) -> Result<git::raw::Oid, git::raw::Error> {
+    // Git sorts tree entries by name, as if sub-trees had a trailing slash.
+    let key = |(name, is_tree, _): &(String, bool, git::raw::Oid)| {
+        let mut key = name.as_bytes().to_vec();
+        if *is_tree {
+            key.push(b'/');
+        }
+        key
+    };
+    entries.sort_by_key(key);
+
+    let mut buf = Vec::new();
+    for (name, is_tree, oid) in entries {
+        buf.extend_from_slice(if is_tree { b"40000 " } else { b"100644 " });
+        buf.extend_from_slice(name.as_bytes());
+        buf.push(0);
+        buf.extend_from_slice(oid.as_bytes());
+    }
+    odb.write(git::raw::ObjectType::Tree, &buf)
+}
+
+/// JSON representation of an identity and its revisions.
+fn identity(identity: &cob::identity::Identity) -> serde_json::Value {
+    let revisions = identity
+        .revisions()
+        .map(|r| {
+            serde_json::json!({
+                "id": r.id,
+                "blob": r.blob,
+                "title": r.title,
+                "description": r.description,
+                "state": r.state,
+                "author": r.author,
+                "timestamp": r.timestamp,
+                "parent": r.parent,
+                "doc": r.doc,
+                "signatures": r
+                    .signatures()
+                    .map(|(key, sig)| (key.to_string(), sig.to_string()))
+                    .collect::<BTreeMap<_, _>>(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    serde_json::json!({
+        "id": identity.id,
+        "current": identity.current,
+        "root": identity.root,
+        "revisions": revisions,
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+    use crate::cob::issue::cache::Issues as _;
+    use crate::test;
+
+    #[test]
+    fn test_export_verify() {
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let mut issues = issue::Cache::no_cache(&*repo).unwrap();
+        let mut issue = issues
+            .create("Title", "Description", &[], &[], [], &node.signer)
+            .unwrap();
+        let (root, _) = issue.root();
+        let root = *root;
+        issue
+            .comment("Comment", root, [], [], &node.signer)
+            .unwrap();
+
+        let mut archive = Vec::new();
+        let exported = export(&repo, &mut archive).unwrap();
+        let (header, verified) = verify(archive.as_slice()).unwrap();
+
+        assert_eq!(header.rid, repo.id);
+        assert_eq!(exported, verified);
+        assert_eq!(verified.objects[&*issue::TYPENAME], 1);
+        assert_eq!(verified.objects[&*identity::TYPENAME], 1);
+        assert_eq!(verified.changes, 3);
+        assert_eq!(issues.counts().unwrap().total(), 1);
+
+        let archive = String::from_utf8(archive).unwrap();
+
+        // Tamper with the issue's comment.
+        let tampered = archive.replace("Comment", "Tampered");
+        assert!(matches!(
+            verify(tampered.as_bytes()),
+            Err(VerifyError::Revision { .. })
+        ));
+
+        // Tamper with the issue's state. The title only appears unescaped in
+        // the object record.
+        let tampered = archive.replace(r#""title":"Title""#, r#""title":"Tampered""#);
+        assert!(matches!(
+            verify(tampered.as_bytes()),
+            Err(VerifyError::State { .. })
+        ));
+
+        // Tamper with the timestamp of a change.
+        let tampered = archive
+            .lines()
+            .map(|line| match serde_json::from_str(line).unwrap() {
+                Record::Change(mut change) => {
+                    change.timestamp += 1;
+                    serde_json::to_string(&Record::Change(change)).unwrap()
+                }
+                _ => line.to_owned(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(matches!(
+            verify(tampered.as_bytes()),
+            Err(VerifyError::Mismatch(_))
+        ));
+    }
+}
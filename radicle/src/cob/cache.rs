@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::convert::Infallible;
 use std::fmt;
 use std::marker::PhantomData;
@@ -6,12 +6,20 @@ use std::path::Path;
 use std::sync::Arc;
 use std::time;
 
-use radicle_cob::ObjectId;
+use radicle_cob::{EntryId, ObjectId, Snapshot};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use sqlite as sql;
 use thiserror::Error;
 
+use crate::cob::object::ParseObjectId;
+use crate::cob::store::{Cob, Snapshots};
+use crate::cob::{issue, patch, TypeName, TypedId};
+use crate::git;
+use crate::git::Oid;
 use crate::prelude::RepoId;
 use crate::sql::transaction;
+use crate::storage::ReadRepository;
 
 /// File suffix for storing the COBs database file.
 pub const COBS_DB_FILE: &str = "cache.db";
@@ -23,7 +31,23 @@ const DB_WRITE_TIMEOUT: time::Duration = time::Duration::from_secs(6);
 
 /// Database migrations.
 /// The first migration is the creation of the initial tables.
-const MIGRATIONS: &[&str] = &[include_str!("cache/migrations/1.sql")];
+///
+/// Migrations that change how cached objects are stored should reset the `tips` of the
+/// affected entries to `null`, so that they are re-evaluated (see [`Store::stale`]).
+const MIGRATIONS: &[&str] = &[
+    include_str!("cache/migrations/1.sql"),
+    include_str!("cache/migrations/2.sql"),
+    include_str!("cache/migrations/3.sql"),
+    include_str!("cache/migrations/4.sql"),
+    include_str!("cache/migrations/5.sql"),
+    include_str!("cache/migrations/6.sql"),
+    include_str!("cache/migrations/7.sql"),
+];
+
+/// Version of the evaluation logic that snapshots are made with. Snapshots made with
+/// another version are ignored, since they may not match the state this version would
+/// evaluate.
+const SNAPSHOT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[derive(Error, Debug)]
 pub enum Error {
@@ -33,6 +57,12 @@ pub enum Error {
     /// No rows returned in query result.
     #[error("no rows returned")]
     NoRows,
+    /// JSON (de)serialization error.
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+    /// Invalid object identifier in the database.
+    #[error("invalid object id: {0}")]
+    Object(#[from] ParseObjectId),
 }
 
 /// Read and write to the store.
@@ -139,6 +169,89 @@ impl<T> Store<T> {
     pub fn version(&self) -> Result<usize, Error> {
         version(&self.db)
     }
+
+    /// Get the tips of the object references that the cached object was evaluated from.
+    ///
+    /// Returns `None` if the object isn't cached, or if its entry is stale.
+    pub fn tips(&self, id: &TypedId) -> Result<Option<BTreeSet<Oid>>, Error> {
+        let Some(table) = table(&id.type_name) else {
+            return Ok(None);
+        };
+        let mut stmt = self
+            .db
+            .prepare(format!("SELECT tips FROM {table} WHERE id = ?1"))?;
+
+        stmt.bind((1, sql::Value::String(id.id.to_string())))?;
+
+        match stmt.into_iter().next().transpose()? {
+            Some(row) => match row.read::<Option<&str>, _>("tips") {
+                Some(tips) => Ok(Some(serde_json::from_str(tips)?)),
+                None => Ok(None),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Get the cached objects that are stale and should be re-evaluated, for example
+    /// because they were cached before a schema migration.
+    pub fn stale(&self) -> Result<Vec<(RepoId, TypedId)>, Error> {
+        let mut stale = Vec::new();
+
+        for type_name in [&*issue::TYPENAME, &*patch::TYPENAME] {
+            let Some(table) = table(type_name) else {
+                continue;
+            };
+            let stmt = self
+                .db
+                .prepare(format!("SELECT id, repo FROM {table} WHERE tips IS NULL"))?;
+
+            for row in stmt.into_iter() {
+                let row = row?;
+                let id = row.read::<&str, _>("id").parse()?;
+                let rid = row.try_read::<RepoId, _>("repo")?;
+
+                stale.push((
+                    rid,
+                    TypedId {
+                        id,
+                        type_name: type_name.clone(),
+                    },
+                ));
+            }
+        }
+        Ok(stale)
+    }
+}
+
+impl Store<Write> {
+    /// Record the tips of the object references that the cached object was evaluated from.
+    ///
+    /// Returns `false` if the object isn't cached.
+    pub fn set_tips(&self, id: &TypedId, tips: &BTreeSet<Oid>) -> Result<bool, Error> {
+        let Some(table) = table(&id.type_name) else {
+            return Ok(false);
+        };
+        transaction::<_, Error>(&self.db, move |db| {
+            let mut stmt = db.prepare(format!("UPDATE {table} SET tips = ?1 WHERE id = ?2"))?;
+
+            stmt.bind((1, sql::Value::String(serde_json::to_string(tips)?)))?;
+            stmt.bind((2, sql::Value::String(id.id.to_string())))?;
+            stmt.next()?;
+
+            Ok(db.change_count() > 0)
+        })
+    }
+}
+
+/// Get the table that objects of the given type are cached in, if any.
+fn table(type_name: &TypeName) -> Option<&'static str> {
+    if *type_name == *issue::TYPENAME {
+        Some("issues")
+    } else if *type_name == *patch::TYPENAME {
+        Some("patches")
+    } else {
+        None
+    }
 }
 
 /// Get the `user_version` value from the database header.
@@ -179,6 +292,89 @@ fn migrate(db: &sql::Connection) -> Result<usize, Error> {
     Ok(version)
 }
 
+impl<T> Snapshots<T> for Store<Write>
+where
+    T: Cob + Serialize + DeserializeOwned,
+{
+    fn snapshot(&self, rid: &RepoId, id: &ObjectId) -> Option<Snapshot<T>> {
+        match snapshots::get(&self.db, rid, T::type_name(), id) {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                log::warn!(target: "cob", "Failed to read snapshot of {id}: {e}");
+                None
+            }
+        }
+    }
+
+    fn save(&self, rid: &RepoId, id: &ObjectId, tips: &BTreeSet<EntryId>, object: &T) {
+        if let Err(e) = snapshots::save(&self.db, rid, T::type_name(), id, tips, object) {
+            log::warn!(target: "cob", "Failed to save snapshot of {id}: {e}");
+        }
+    }
+}
+
+mod snapshots {
+    use super::*;
+
+    use crate::cob::TypeName;
+
+    pub(super) fn get<T: DeserializeOwned>(
+        db: &sql::ConnectionThreadSafe,
+        rid: &RepoId,
+        type_name: &TypeName,
+        id: &ObjectId,
+    ) -> Result<Option<Snapshot<T>>, Error> {
+        let mut stmt = db.prepare(
+            "SELECT tips, object
+             FROM snapshots
+             WHERE repo = ?1 AND id = ?2 AND type = ?3 AND version = ?4",
+        )?;
+
+        stmt.bind((1, sql::Value::String(rid.to_string())))?;
+        stmt.bind((2, sql::Value::String(id.to_string())))?;
+        stmt.bind((3, sql::Value::String(type_name.to_string())))?;
+        stmt.bind((4, SNAPSHOT_VERSION))?;
+
+        match stmt.into_iter().next().transpose()? {
+            None => Ok(None),
+            Some(row) => {
+                let tips = serde_json::from_str(row.read::<&str, _>("tips"))?;
+                let object = serde_json::from_str(row.read::<&str, _>("object"))?;
+
+                Ok(Some(Snapshot { tips, object }))
+            }
+        }
+    }
+
+    pub(super) fn save<T: Serialize>(
+        db: &sql::ConnectionThreadSafe,
+        rid: &RepoId,
+        type_name: &TypeName,
+        id: &ObjectId,
+        tips: &BTreeSet<EntryId>,
+        object: &T,
+    ) -> Result<(), Error> {
+        transaction::<_, Error>(db, move |db| {
+            let mut stmt = db.prepare(
+                "INSERT INTO snapshots (repo, id, type, version, tips, object)
+                  VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                  ON CONFLICT DO UPDATE
+                  SET type = (?3), version = (?4), tips = (?5), object = (?6)",
+            )?;
+
+            stmt.bind((1, sql::Value::String(rid.to_string())))?;
+            stmt.bind((2, sql::Value::String(id.to_string())))?;
+            stmt.bind((3, sql::Value::String(type_name.to_string())))?;
+            stmt.bind((4, SNAPSHOT_VERSION))?;
+            stmt.bind((5, sql::Value::String(serde_json::to_string(tips)?)))?;
+            stmt.bind((6, sql::Value::String(serde_json::to_string(object)?)))?;
+            stmt.next()?;
+
+            Ok(())
+        })
+    }
+}
+
 /// Update a COB object in the cache.
 pub trait Update<T> {
     /// The output type, if any, for a successful update.
@@ -191,6 +387,46 @@ pub trait Update<T> {
         id: &ObjectId,
         object: &T,
     ) -> Result<Self::Out, Self::UpdateError>;
+
+    /// Record the tips of the object references that the cached object was evaluated from.
+    /// Caches that don't keep track of tips ignore them.
+    fn update_tips(
+        &mut self,
+        _id: &TypedId,
+        _tips: &BTreeSet<Oid>,
+    ) -> Result<(), Self::UpdateError> {
+        Ok(())
+    }
+}
+
+/// Write an object to a cache, along with the tips of its references in the repository, so
+/// that the cached entry isn't considered stale.
+pub fn write<T, R, C>(
+    cache: &mut C,
+    repo: &R,
+    id: &TypedId,
+    object: &T,
+) -> Result<C::Out, Box<dyn std::error::Error + Send + Sync + 'static>>
+where
+    R: ReadRepository,
+    C: Update<T>,
+{
+    let tips = tips(repo, id)?;
+    let out = cache.update(&repo.id(), &id.id, object)?;
+    cache.update_tips(id, &tips)?;
+
+    Ok(out)
+}
+
+/// Get the tips of the references of an object, in every namespace of a repository.
+pub fn tips<R: ReadRepository>(repo: &R, id: &TypedId) -> Result<BTreeSet<Oid>, git::ext::Error> {
+    let tips = repo
+        .references_glob(&git::refs::storage::cobs(&id.type_name, &id.id))?
+        .into_iter()
+        .map(|(_, oid)| oid)
+        .collect();
+
+    Ok(tips)
 }
 
 /// Remove a COB object in the cache.
@@ -0,0 +1,15 @@
+-- States of objects evaluated externally
+create table if not exists "objects" (
+  -- Object ID
+  "id"            text      not null,
+  -- Repository ID
+  "repo"          text      not null,
+  -- Object type name
+  "type"          text      not null,
+  -- Digest of the evaluator and operations the state was evaluated from
+  "digest"        text      not null,
+  -- Object state in JSON format
+  "state"         text      not null,
+  -- Object IDs are only unique within a repository
+  primary key ("repo", "id")
+) strict;
@@ -0,0 +1,17 @@
+-- Snapshots of object states
+create table if not exists "snapshots" (
+  -- Repository ID
+  "repo"          text      not null,
+  -- Object ID
+  "id"            text      not null,
+  -- Object type name
+  "type"          text      not null,
+  -- Version of the software the state was evaluated with
+  "version"       text      not null,
+  -- Tips of the history the state was evaluated from, in JSON format
+  "tips"          text      not null,
+  -- Object state in JSON format
+  "object"        text      not null,
+  -- Object IDs are only unique within a repository
+  primary key ("repo", "id")
+) strict;
@@ -0,0 +1,7 @@
+-- Tips of the object references the cached issue was evaluated from, in JSON format.
+-- A `null` value means the entry is stale and should be re-evaluated.
+alter table "issues" add column "tips" text;
+
+-- Tips of the object references the cached patch was evaluated from, in JSON format.
+-- A `null` value means the entry is stale and should be re-evaluated.
+alter table "patches" add column "tips" text;
@@ -0,0 +1,6 @@
+-- Issue descriptions and patch revision descriptions now carry the state needed to
+-- merge concurrent edits. Re-evaluate cached issues and patches, and discard
+-- snapshots made without it.
+update "issues" set "tips" = null;
+update "patches" set "tips" = null;
+delete from "snapshots";
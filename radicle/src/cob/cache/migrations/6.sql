@@ -0,0 +1,5 @@
+-- Number of tasks of the cached issue that aren't done yet.
+alter table "issues" add column "tasks" integer;
+
+-- Re-evaluate cached issues, so that their number of open tasks is set.
+update "issues" set "tips" = null;
@@ -0,0 +1,9 @@
+-- Time of the latest activity on the cached issue, in milliseconds since the epoch.
+alter table "issues" add column "updated" integer;
+
+-- Time of the latest activity on the cached patch, in milliseconds since the epoch.
+alter table "patches" add column "updated" integer;
+
+-- Re-evaluate cached issues and patches, so that their latest activity time is set.
+update "issues" set "tips" = null;
+update "patches" set "tips" = null;
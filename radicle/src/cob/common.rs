@@ -97,6 +97,40 @@ impl From<PublicKey> for Author {
     }
 }
 
+/// Provenance of content that was imported from another system.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub struct Origin {
+    /// Where the content was imported from, eg. `GitHub issue #42`.
+    pub source: String,
+    /// The original author, as known to the source system.
+    pub author: String,
+    /// When the content was originally created.
+    #[serde(with = "millis")]
+    pub timestamp: Timestamp,
+}
+
+/// (De)serialize a [`Timestamp`] as a `u64`, since 128-bit integers aren't supported within
+/// internally tagged enums, such as COB actions.
+mod millis {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::{LocalTime, Timestamp};
+
+    pub fn serialize<S: Serializer>(
+        timestamp: &Timestamp,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(timestamp.as_millis())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Timestamp, D::Error> {
+        let millis = u64::deserialize(deserializer)?;
+
+        Ok(Timestamp::from(LocalTime::from_millis(millis as u128)))
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum ReactionError {
     #[error("invalid reaction")]
@@ -0,0 +1,882 @@
+use std::ops::Deref;
+use std::str::FromStr;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::cob;
+use crate::cob::common::{Author, Authorization, Label, Reaction, Timestamp, Uri};
+use crate::cob::store::Transaction;
+use crate::cob::store::{Cob, CobAction};
+use crate::cob::thread;
+use crate::cob::thread::{Comment, CommentId, Thread};
+use crate::cob::{op, store, ActorId, Embed, EntryId, ObjectId, TypeName};
+use crate::crypto::Signer;
+use crate::identity::doc::{Doc, DocError};
+use crate::prelude::{Did, ReadRepository, RepoId, Verified};
+use crate::storage::{HasRepoId, RepositoryError, WriteRepository};
+
+/// Discussion operation.
+pub type Op = cob::Op<Action>;
+
+/// Type name of a discussion.
+pub static TYPENAME: Lazy<TypeName> =
+    Lazy::new(|| FromStr::from_str("xyz.radicle.discussion").expect("type name is valid"));
+
+/// Identifier for a discussion.
+pub type DiscussionId = ObjectId;
+
+/// Discussion category, eg. `ideas` or `q&a`. Follows the same rules as labels.
+pub type Category = Label;
+
+/// Error updating or creating discussions.
+#[derive(Error, Debug)]
+pub enum Error {
+    /// Error loading the identity document.
+    #[error("identity doc failed to load: {0}")]
+    Doc(#[from] DocError),
+    #[error("thread apply failed: {0}")]
+    Thread(#[from] thread::Error),
+    #[error("store: {0}")]
+    Store(#[from] store::Error),
+    /// Action not authorized.
+    #[error("{0} not authorized to apply {1:?}")]
+    NotAuthorized(ActorId, Box<Action>),
+    /// Action not allowed.
+    #[error("action is not allowed: {0}")]
+    NotAllowed(EntryId),
+    /// Title is invalid.
+    #[error("invalid title: {0:?}")]
+    InvalidTitle(String),
+    /// The identity doc is missing.
+    #[error("identity document missing")]
+    MissingIdentity,
+    /// General error initializing a discussion.
+    #[error("initialization failed: {0}")]
+    Init(&'static str),
+    /// Error decoding an operation.
+    #[error("op decoding failed: {0}")]
+    Op(#[from] op::OpEncodingError),
+}
+
+/// Discussion state. Accumulates [`Action`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Discussion {
+    /// Title of the discussion.
+    pub(super) title: String,
+    /// Category the discussion is filed under.
+    pub(super) category: Option<Category>,
+    /// Whether the discussion is pinned by the repository delegates.
+    pub(super) pinned: bool,
+    /// Reply that was accepted as the answer to the discussion.
+    pub(super) answer: Option<CommentId>,
+    /// The conversation.
+    pub(super) thread: Thread,
+}
+
+impl store::Cob for Discussion {
+    type Action = Action;
+    type Error = Error;
+
+    fn type_name() -> &'static TypeName {
+        &TYPENAME
+    }
+
+    fn from_root<R: ReadRepository>(op: Op, repo: &R) -> Result<Self, Self::Error> {
+        let doc = op.identity_doc(repo)?.ok_or(Error::MissingIdentity)?;
+        let mut actions = op.actions.into_iter();
+        let Some(Action::Comment {
+            body,
+            reply_to: None,
+            embeds,
+            mentions,
+        }) = actions.next()
+        else {
+            return Err(Error::Init("the first action must be of type `comment`"));
+        };
+        let comment = Comment::new(op.author, body, None, None, embeds, mentions, op.timestamp);
+        let thread = Thread::new(op.id, comment);
+        let mut discussion = Discussion::new(thread);
+
+        for action in actions {
+            match discussion.authorization(&action, &op.author, &doc)? {
+                Authorization::Allow => {
+                    discussion.action(action, op.id, op.author, op.timestamp)?;
+                }
+                Authorization::Deny => {
+                    return Err(Error::NotAuthorized(op.author, Box::new(action)));
+                }
+                Authorization::Unknown => {
+                    continue;
+                }
+            }
+        }
+        Ok(discussion)
+    }
+
+    fn op<'a, R: ReadRepository, I: IntoIterator<Item = &'a cob::Entry>>(
+        &mut self,
+        op: Op,
+        _concurrent: I,
+        repo: &R,
+    ) -> Result<(), Error> {
+        let doc = op.identity_doc(repo)?.ok_or(Error::MissingIdentity)?;
+        for action in op.actions {
+            match self.authorization(&action, &op.author, &doc)? {
+                Authorization::Allow => {
+                    self.action(action, op.id, op.author, op.timestamp)?;
+                }
+                Authorization::Deny => {
+                    return Err(Error::NotAuthorized(op.author, Box::new(action)));
+                }
+                Authorization::Unknown => {
+                    continue;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R: ReadRepository> cob::Evaluate<R> for Discussion {
+    type Error = Error;
+
+    fn init(entry: &cob::Entry, repo: &R) -> Result<Self, Self::Error> {
+        let op = Op::try_from(entry)?;
+        let object = Discussion::from_root(op, repo)?;
+
+        Ok(object)
+    }
+
+    fn apply<'a, I: Iterator<Item = (&'a EntryId, &'a cob::Entry)>>(
+        &mut self,
+        entry: &cob::Entry,
+        concurrent: I,
+        repo: &R,
+    ) -> Result<(), Self::Error> {
+        let op = Op::try_from(entry)?;
+
+        self.op(op, concurrent.map(|(_, e)| e), repo)
+    }
+}
+
+impl Discussion {
+    /// Construct a new discussion.
+    pub fn new(thread: Thread) -> Self {
+        Self {
+            title: String::default(),
+            category: None,
+            pinned: false,
+            answer: None,
+            thread,
+        }
+    }
+
+    pub fn title(&self) -> &str {
+        self.title.as_str()
+    }
+
+    pub fn category(&self) -> Option<&Category> {
+        self.category.as_ref()
+    }
+
+    pub fn is_pinned(&self) -> bool {
+        self.pinned
+    }
+
+    /// The reply accepted as the answer, if any, and if it wasn't redacted since.
+    pub fn answer(&self) -> Option<(&CommentId, &Comment)> {
+        let id = self.answer.as_ref()?;
+        let comment = self.thread.comment(id)?;
+
+        Some((id, comment))
+    }
+
+    pub fn timestamp(&self) -> Timestamp {
+        self.thread
+            .comments()
+            .next()
+            .map(|(_, c)| c)
+            .expect("Discussion::timestamp: at least one comment is present")
+            .timestamp()
+    }
+
+    pub fn author(&self) -> Author {
+        self.thread
+            .comments()
+            .next()
+            .map(|(_, c)| Author::new(c.author()))
+            .expect("Discussion::author: at least one comment is present")
+    }
+
+    pub fn root(&self) -> (&CommentId, &Comment) {
+        self.thread
+            .comments()
+            .next()
+            .expect("Discussion::root: at least one comment is present")
+    }
+
+    pub fn body(&self) -> &str {
+        self.root().1.body()
+    }
+
+    pub fn thread(&self) -> &Thread {
+        &self.thread
+    }
+
+    pub fn comments(&self) -> impl Iterator<Item = (&CommentId, &thread::Comment)> {
+        self.thread.comments()
+    }
+
+    /// Iterate over all replies. Does not include the root comment.
+    pub fn replies(&self) -> impl Iterator<Item = (&CommentId, &thread::Comment)> {
+        self.comments().skip(1)
+    }
+
+    /// Apply authorization rules on discussion actions.
+    pub fn authorization(
+        &self,
+        action: &Action,
+        actor: &ActorId,
+        doc: &Doc<Verified>,
+    ) -> Result<Authorization, Error> {
+        if doc.is_delegate(actor) {
+            // A delegate is authorized to do all actions.
+            return Ok(Authorization::Allow);
+        }
+        let author: ActorId = *self.author().id().as_key();
+        let outcome = match action {
+            // Discussion authors can edit and categorize their own discussions.
+            Action::Edit { .. } | Action::Categorize { .. } => {
+                Authorization::from(*actor == author)
+            }
+            // Only delegates can pin a discussion.
+            Action::Pin { pinned } => {
+                if *pinned == self.pinned {
+                    Authorization::Allow
+                } else {
+                    Authorization::Deny
+                }
+            }
+            // Discussion authors can accept an answer to their own discussion.
+            Action::Answer { .. } => Authorization::from(*actor == author),
+            // All roles can comment on a discussion.
+            Action::Comment { .. } => Authorization::Allow,
+            // All roles can edit or redact their own comments.
+            Action::CommentEdit { id, .. } | Action::CommentRedact { id, .. } => {
+                if let Some(comment) = self.thread.comments.get(id) {
+                    if let Some(comment) = comment {
+                        Authorization::from(*actor == comment.author())
+                    } else {
+                        Authorization::Unknown
+                    }
+                } else {
+                    return Err(Error::Thread(thread::Error::Missing(*id)));
+                }
+            }
+            // All roles can react to a comment on a discussion.
+            Action::CommentReact { .. } => Authorization::Allow,
+        };
+        Ok(outcome)
+    }
+
+    /// Apply a single action to the discussion.
+    fn action(
+        &mut self,
+        action: Action,
+        entry: EntryId,
+        author: ActorId,
+        timestamp: Timestamp,
+    ) -> Result<(), Error> {
+        match action {
+            Action::Edit { title } => {
+                if title.contains('\n') || title.contains('\r') {
+                    return Err(Error::InvalidTitle(title));
+                }
+                self.title = title;
+            }
+            Action::Categorize { category } => {
+                self.category = category;
+            }
+            Action::Pin { pinned } => {
+                self.pinned = pinned;
+            }
+            Action::Answer { id } => {
+                if let Some(id) = id {
+                    let (root, _) = self.root();
+                    if id == *root {
+                        return Err(Error::NotAllowed(entry));
+                    }
+                    if !self.thread.comments.contains_key(&id) {
+                        return Err(Error::Thread(thread::Error::Missing(id)));
+                    }
+                }
+                self.answer = id;
+            }
+            Action::Comment {
+                body,
+                reply_to,
+                embeds,
+                mentions,
+            } => {
+                thread::comment(
+                    &mut self.thread,
+                    entry,
+                    author,
+                    timestamp,
+                    body,
+                    reply_to,
+                    None,
+                    embeds,
+                    mentions,
+                )?;
+            }
+            Action::CommentEdit {
+                id,
+                body,
+                embeds,
+                mentions,
+            } => {
+                thread::edit(
+                    &mut self.thread,
+                    entry,
+                    author,
+                    id,
+                    timestamp,
+                    body,
+                    embeds,
+                    mentions,
+                )?;
+            }
+            Action::CommentRedact { id } => {
+                let (root, _) = self.root();
+                if id == *root {
+                    return Err(Error::NotAllowed(entry));
+                }
+                thread::redact(&mut self.thread, entry, id)?;
+            }
+            Action::CommentReact {
+                id,
+                reaction,
+                active,
+            } => {
+                thread::react(&mut self.thread, entry, author, id, reaction, active)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Deref for Discussion {
+    type Target = Thread;
+
+    fn deref(&self) -> &Self::Target {
+        &self.thread
+    }
+}
+
+impl<R: ReadRepository> store::Transaction<Discussion, R> {
+    /// Set the discussion title.
+    pub fn edit(&mut self, title: impl ToString) -> Result<(), store::Error> {
+        self.push(Action::Edit {
+            title: title.to_string(),
+        })
+    }
+
+    /// Set or unset the discussion category.
+    pub fn categorize(&mut self, category: Option<Category>) -> Result<(), store::Error> {
+        self.push(Action::Categorize { category })
+    }
+
+    /// Pin or unpin the discussion.
+    pub fn pin(&mut self, pinned: bool) -> Result<(), store::Error> {
+        self.push(Action::Pin { pinned })
+    }
+
+    /// Accept a reply as the answer, or unset the answer.
+    pub fn answer(&mut self, id: Option<CommentId>) -> Result<(), store::Error> {
+        self.push(Action::Answer { id })
+    }
+
+    /// Comment on a discussion.
+    pub fn comment<S: ToString>(
+        &mut self,
+        body: S,
+        reply_to: CommentId,
+        embeds: Vec<Embed>,
+        mentions: Vec<Did>,
+    ) -> Result<(), store::Error> {
+        let hashed = embeds.iter().map(|e| e.hashed()).collect();
+
+        self.embed(embeds)?;
+        self.push(Action::Comment {
+            body: body.to_string(),
+            reply_to: Some(reply_to),
+            embeds: hashed,
+            mentions,
+        })
+    }
+
+    /// Edit a discussion comment.
+    pub fn edit_comment(
+        &mut self,
+        id: CommentId,
+        body: impl ToString,
+        embeds: Vec<Embed>,
+        mentions: Vec<Did>,
+    ) -> Result<(), store::Error> {
+        let hashed = embeds.iter().map(|e| e.hashed()).collect();
+
+        self.embed(embeds)?;
+        self.push(Action::CommentEdit {
+            id,
+            body: body.to_string(),
+            embeds: hashed,
+            mentions,
+        })
+    }
+
+    /// Redact a comment.
+    pub fn redact_comment(&mut self, id: CommentId) -> Result<(), store::Error> {
+        self.push(Action::CommentRedact { id })
+    }
+
+    /// React to a discussion comment.
+    pub fn react(
+        &mut self,
+        id: CommentId,
+        reaction: Reaction,
+        active: bool,
+    ) -> Result<(), store::Error> {
+        self.push(Action::CommentReact {
+            id,
+            reaction,
+            active,
+        })
+    }
+
+    /// Create the discussion thread.
+    fn thread<S: ToString>(
+        &mut self,
+        body: S,
+        embeds: impl IntoIterator<Item = Embed>,
+    ) -> Result<(), store::Error> {
+        let embeds = embeds.into_iter().collect::<Vec<_>>();
+        let hashed = embeds.iter().map(|e| e.hashed()).collect();
+
+        self.embed(embeds)?;
+        self.push(Action::Comment {
+            body: body.to_string(),
+            reply_to: None,
+            embeds: hashed,
+            mentions: vec![],
+        })
+    }
+}
+
+pub struct DiscussionMut<'a, 'g, R> {
+    id: ObjectId,
+    discussion: Discussion,
+    store: &'g mut Discussions<'a, R>,
+}
+
+impl<'a, 'g, R> std::fmt::Debug for DiscussionMut<'a, 'g, R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("DiscussionMut")
+            .field("id", &self.id)
+            .field("discussion", &self.discussion)
+            .finish()
+    }
+}
+
+impl<'a, 'g, R> DiscussionMut<'a, 'g, R>
+where
+    R: WriteRepository + cob::Store,
+{
+    /// Reload the discussion data from storage.
+    pub fn reload(&mut self) -> Result<(), store::Error> {
+        self.discussion = self
+            .store
+            .get(&self.id)?
+            .ok_or_else(|| store::Error::NotFound(TYPENAME.clone(), self.id))?;
+
+        Ok(())
+    }
+
+    /// Get the discussion id.
+    pub fn id(&self) -> &ObjectId {
+        &self.id
+    }
+
+    /// Set the discussion title.
+    pub fn edit<G: Signer>(&mut self, title: impl ToString, signer: &G) -> Result<EntryId, Error> {
+        self.transaction("Edit", signer, |tx| tx.edit(title))
+    }
+
+    /// Set or unset the discussion category.
+    pub fn categorize<G: Signer>(
+        &mut self,
+        category: Option<Category>,
+        signer: &G,
+    ) -> Result<EntryId, Error> {
+        self.transaction("Categorize", signer, |tx| tx.categorize(category))
+    }
+
+    /// Pin or unpin the discussion.
+    pub fn pin<G: Signer>(&mut self, pinned: bool, signer: &G) -> Result<EntryId, Error> {
+        self.transaction(if pinned { "Pin" } else { "Unpin" }, signer, |tx| {
+            tx.pin(pinned)
+        })
+    }
+
+    /// Accept a reply as the answer, or unset the answer.
+    pub fn accept_answer<G: Signer>(
+        &mut self,
+        id: Option<CommentId>,
+        signer: &G,
+    ) -> Result<EntryId, Error> {
+        self.transaction("Answer", signer, |tx| tx.answer(id))
+    }
+
+    /// Comment on a discussion, mentioning the given users.
+    pub fn comment<G: Signer, S: ToString>(
+        &mut self,
+        body: S,
+        reply_to: CommentId,
+        embeds: impl IntoIterator<Item = Embed>,
+        mentions: impl IntoIterator<Item = Did>,
+        signer: &G,
+    ) -> Result<EntryId, Error> {
+        self.transaction("Comment", signer, |tx| {
+            tx.comment(
+                body,
+                reply_to,
+                embeds.into_iter().collect(),
+                mentions.into_iter().collect(),
+            )
+        })
+    }
+
+    /// Edit a comment, mentioning the given users.
+    pub fn edit_comment<G: Signer, S: ToString>(
+        &mut self,
+        id: CommentId,
+        body: S,
+        embeds: impl IntoIterator<Item = Embed>,
+        mentions: impl IntoIterator<Item = Did>,
+        signer: &G,
+    ) -> Result<EntryId, Error> {
+        self.transaction("Edit comment", signer, |tx| {
+            tx.edit_comment(
+                id,
+                body,
+                embeds.into_iter().collect(),
+                mentions.into_iter().collect(),
+            )
+        })
+    }
+
+    /// Redact a comment.
+    pub fn redact_comment<G: Signer>(
+        &mut self,
+        id: CommentId,
+        signer: &G,
+    ) -> Result<EntryId, Error> {
+        self.transaction("Redact comment", signer, |tx| tx.redact_comment(id))
+    }
+
+    /// React to a discussion comment.
+    pub fn react<G: Signer>(
+        &mut self,
+        to: CommentId,
+        reaction: Reaction,
+        active: bool,
+        signer: &G,
+    ) -> Result<EntryId, Error> {
+        self.transaction("React", signer, |tx| tx.react(to, reaction, active))
+    }
+
+    pub fn transaction<G, F>(
+        &mut self,
+        message: &str,
+        signer: &G,
+        operations: F,
+    ) -> Result<EntryId, Error>
+    where
+        G: Signer,
+        F: FnOnce(&mut Transaction<Discussion, R>) -> Result<(), store::Error>,
+    {
+        let mut tx = Transaction::default();
+        operations(&mut tx)?;
+
+        let (discussion, commit) = tx.commit(message, self.id, &mut self.store.raw, signer)?;
+        self.discussion = discussion;
+
+        Ok(commit)
+    }
+}
+
+impl<'a, 'g, R> Deref for DiscussionMut<'a, 'g, R> {
+    type Target = Discussion;
+
+    fn deref(&self) -> &Self::Target {
+        &self.discussion
+    }
+}
+
+pub struct Discussions<'a, R> {
+    raw: store::Store<'a, Discussion, R>,
+}
+
+impl<'a, R> Deref for Discussions<'a, R> {
+    type Target = store::Store<'a, Discussion, R>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.raw
+    }
+}
+
+impl<'a, R> HasRepoId for Discussions<'a, R>
+where
+    R: ReadRepository,
+{
+    fn rid(&self) -> RepoId {
+        self.raw.as_ref().id()
+    }
+}
+
+impl<'a, R> Discussions<'a, R>
+where
+    R: ReadRepository + cob::Store,
+{
+    /// Open a discussions store.
+    pub fn open(repository: &'a R) -> Result<Self, RepositoryError> {
+        let identity = repository.identity_head()?;
+        let raw = store::Store::open(repository)?.identity(identity);
+
+        Ok(Self { raw })
+    }
+
+    /// Get a discussion.
+    pub fn get(&self, id: &ObjectId) -> Result<Option<Discussion>, store::Error> {
+        self.raw.get(id)
+    }
+
+    /// Get a discussion mutably.
+    pub fn get_mut<'g>(
+        &'g mut self,
+        id: &ObjectId,
+    ) -> Result<DiscussionMut<'a, 'g, R>, store::Error> {
+        let discussion = self
+            .raw
+            .get(id)?
+            .ok_or_else(move || store::Error::NotFound(TYPENAME.clone(), *id))?;
+
+        Ok(DiscussionMut {
+            id: *id,
+            discussion,
+            store: self,
+        })
+    }
+}
+
+impl<'a, R> Discussions<'a, R>
+where
+    R: WriteRepository + cob::Store,
+{
+    /// Create a new discussion.
+    pub fn create<'g, G>(
+        &'g mut self,
+        title: impl ToString,
+        body: impl ToString,
+        category: Option<Category>,
+        embeds: impl IntoIterator<Item = Embed>,
+        signer: &G,
+    ) -> Result<DiscussionMut<'a, 'g, R>, Error>
+    where
+        G: Signer,
+    {
+        let (id, discussion) =
+            Transaction::initial("Create discussion", &mut self.raw, signer, |tx| {
+                tx.thread(body, embeds)?;
+                tx.edit(title)?;
+
+                if category.is_some() {
+                    tx.categorize(category)?;
+                }
+                Ok(())
+            })?;
+
+        Ok(DiscussionMut {
+            id,
+            discussion,
+            store: self,
+        })
+    }
+
+    /// Remove a discussion.
+    pub fn remove<G: Signer>(&self, id: &ObjectId, signer: &G) -> Result<(), store::Error> {
+        self.raw.remove(id, signer)
+    }
+}
+
+/// Discussion action.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Action {
+    /// Edit discussion title.
+    #[serde(rename = "edit")]
+    Edit { title: String },
+
+    /// Set or unset the discussion category.
+    #[serde(rename = "categorize")]
+    Categorize { category: Option<Category> },
+
+    /// Pin or unpin the discussion.
+    #[serde(rename = "pin")]
+    Pin { pinned: bool },
+
+    /// Accept a reply as the answer, or unset the answer.
+    #[serde(rename = "answer")]
+    Answer { id: Option<CommentId> },
+
+    /// Comment on a thread.
+    #[serde(rename_all = "camelCase")]
+    #[serde(rename = "comment")]
+    Comment {
+        /// Comment body.
+        body: String,
+        /// Comment this is a reply to.
+        /// Should be [`None`] if it's the top-level comment.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reply_to: Option<CommentId>,
+        /// Embeded content.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        embeds: Vec<Embed<Uri>>,
+        /// Users mentioned in the comment.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        mentions: Vec<Did>,
+    },
+
+    /// Edit a comment.
+    #[serde(rename = "comment.edit")]
+    CommentEdit {
+        /// Comment being edited.
+        id: CommentId,
+        /// New value for the comment body.
+        body: String,
+        /// New value for the embeds list.
+        embeds: Vec<Embed<Uri>>,
+        /// New value for the mentions list.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        mentions: Vec<Did>,
+    },
+
+    /// Redact a comment. The root comment can't be redacted.
+    #[serde(rename = "comment.redact")]
+    CommentRedact { id: CommentId },
+
+    /// React to a comment.
+    #[serde(rename = "comment.react")]
+    CommentReact {
+        id: CommentId,
+        reaction: Reaction,
+        active: bool,
+    },
+}
+
+impl CobAction for Action {}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::test;
+
+    #[test]
+    fn test_discussion_create_and_get() {
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let mut discussions = Discussions::open(&*repo).unwrap();
+        let category = Category::new("ideas").unwrap();
+        let discussion = discussions
+            .create(
+                "Release cadence",
+                "How often should we cut releases?",
+                Some(category.clone()),
+                [],
+                &node.signer,
+            )
+            .unwrap();
+        let id = *discussion.id();
+        let discussion = discussions.get(&id).unwrap().unwrap();
+
+        assert_eq!(discussion.title(), "Release cadence");
+        assert_eq!(discussion.body(), "How often should we cut releases?");
+        assert_eq!(discussion.category(), Some(&category));
+        assert_eq!(discussion.author().id, Did::from(node.signer.public_key()));
+        assert!(!discussion.is_pinned());
+        assert!(discussion.answer().is_none());
+    }
+
+    #[test]
+    fn test_discussion_answer() {
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let mut discussions = Discussions::open(&*repo).unwrap();
+        let mut discussion = discussions
+            .create("Question", "Which license?", None, [], &node.signer)
+            .unwrap();
+        let root = **discussion.id();
+        let reply = discussion
+            .comment("MIT or Apache-2.0.", root, [], [], &node.signer)
+            .unwrap();
+
+        // The root comment can't be the answer.
+        discussion
+            .accept_answer(Some(root), &node.signer)
+            .unwrap_err();
+        discussion.accept_answer(Some(reply), &node.signer).unwrap();
+        discussion
+            .react(reply, Reaction::new('👍').unwrap(), true, &node.signer)
+            .unwrap();
+        discussion.reload().unwrap();
+
+        let (id, answer) = discussion.answer().unwrap();
+        assert_eq!(*id, reply);
+        assert_eq!(answer.body(), "MIT or Apache-2.0.");
+        assert_eq!(answer.reactions().len(), 1);
+
+        discussion.redact_comment(reply, &node.signer).unwrap();
+        assert!(discussion.answer().is_none());
+    }
+
+    #[test]
+    fn test_discussion_pin() {
+        let t = test::setup::Network::default();
+        let mut alice_discussions = Discussions::open(&*t.alice.repo).unwrap();
+        let mut bob_discussions = Discussions::open(&*t.bob.repo).unwrap();
+        let discussion = alice_discussions
+            .create("Roadmap", "What's next?", None, [], &t.alice.signer)
+            .unwrap();
+        let id = *discussion.id();
+
+        t.bob.repo.fetch(&t.alice);
+
+        let mut discussion = bob_discussions.get_mut(&id).unwrap();
+        // Bob is not a delegate, and can't pin the discussion.
+        discussion.pin(true, &t.bob.signer).unwrap_err();
+        discussion
+            .comment("Docs!", *id, [], [], &t.bob.signer)
+            .unwrap();
+        discussion.reload().unwrap();
+        assert!(!discussion.is_pinned());
+        assert_eq!(discussion.replies().count(), 1);
+
+        let mut discussion = alice_discussions.get_mut(&id).unwrap();
+        discussion.pin(true, &t.alice.signer).unwrap();
+        assert!(discussion.is_pinned());
+    }
+}
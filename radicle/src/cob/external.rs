@@ -0,0 +1,779 @@
+//! Collaborative objects of types that aren't known to this crate.
+//!
+//! Objects created with [`store::create_raw`] have no state of their own, since their
+//! operations are only interpreted by the tools that define them. To present these objects,
+//! an external evaluator can be registered for their type in the profile configuration.
+//!
+//! An evaluator is a program that is given an object's operations as JSON on its standard
+//! input, and prints the object's state as JSON on its standard output. The input looks
+//! like this:
+//!
+//! ```json
+//! {
+//!   "type": "com.example.incident",
+//!   "id": "d96f425412c9f8ad5d9a9a05c9831d0728e2338d",
+//!   "ops": [
+//!     {
+//!       "id": "d96f425412c9f8ad5d9a9a05c9831d0728e2338d",
+//!       "author": "z6MknSLrJoTcukLrE435hVNQT4JUhbvWLX4kUzqkEStBU8Vi",
+//!       "timestamp": 1671125284000,
+//!       "parents": [],
+//!       "actions": [{ "type": "open", "severity": "high" }]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Operations are given in causal order, ie. an operation always comes after its parents.
+//! Evaluators are run with an empty environment, apart from `PATH`, in an empty temporary
+//! working directory, in a process group of their own. The whole group is killed once the
+//! evaluator exits, or if it doesn't exit within its timeout, and at most
+//! [`MAX_OUTPUT`] bytes of its output are kept.
+//!
+//! Since operations come from the network, evaluators are sandboxed: they run in their own
+//! user, mount, network and IPC namespaces, with no network access and a read-only file
+//! system, and can't gain privileges, eg. through setuid programs. Sandboxing is only
+//! supported on Linux, and needs unprivileged user namespaces; evaluators fail to run if
+//! they can't be sandboxed. It can be turned off for an evaluator by setting its `sandbox`
+//! option to `false`, in which case it has access to everything the user has access to.
+//!
+//! Evaluated states are stored in the COB cache, and are re-used for as long as the object's
+//! operations and the evaluator stay the same.
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::os::unix::process::CommandExt as _;
+use std::path::PathBuf;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+use std::{env, io, thread};
+
+use nonempty::NonEmpty;
+use serde::{Deserialize, Serialize};
+use sqlite as sql;
+use thiserror::Error;
+
+use crate::cob;
+use crate::cob::cache::StoreWriter;
+use crate::cob::op::{ActorId, Op};
+use crate::cob::store;
+use crate::cob::{EntryId, ObjectId, Timestamp, TypeName};
+use crate::git;
+use crate::prelude::RepoId;
+use crate::sql::transaction;
+use crate::storage::HasRepoId;
+
+/// How long an evaluator may run for, by default.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Longest timeout an evaluator can be configured with. Longer timeouts are capped.
+pub const MAX_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+/// Maximum size of an evaluator's output, in bytes. Standard error is truncated to this
+/// size, while larger states are rejected.
+pub const MAX_OUTPUT: u64 = 8 * 1024 * 1024;
+
+/// External evaluators, by object type.
+pub type Evaluators = BTreeMap<TypeName, Evaluator>;
+
+/// Error evaluating an object.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("no evaluator is registered for objects of type `{0}`")]
+    NotRegistered(TypeName),
+    #[error("failed to run evaluator `{0}`: {1}")]
+    Spawn(PathBuf, #[source] io::Error),
+    #[error("evaluator `{0}` can't be sandboxed on this platform; set its `sandbox` option to `false` to run it unconfined")]
+    Unsandboxed(PathBuf),
+    #[error("evaluator `{0}` timed out after {1:?}")]
+    Timeout(PathBuf, Duration),
+    #[error("evaluator `{0}` returned more than {1} bytes of output")]
+    Output(PathBuf, u64),
+    #[error("evaluator `{command}` failed with {status}: {stderr}")]
+    Failed {
+        command: PathBuf,
+        status: ExitStatus,
+        stderr: String,
+    },
+    #[error("evaluator `{0}` returned invalid state: {1}")]
+    State(PathBuf, #[source] serde_json::Error),
+    #[error("invalid action in operation {0}: {1}")]
+    Action(EntryId, #[source] serde_json::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("i/o error: {0}")]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Store(#[from] store::Error),
+    #[error("cache error: {0}")]
+    Cache(#[from] sql::Error),
+}
+
+/// A program that evaluates objects of a given type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Evaluator {
+    /// Path of the program to run.
+    pub command: PathBuf,
+    /// Arguments to pass to the program.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// How long the program may run for, in seconds. Capped to [`MAX_TIMEOUT`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// Whether to run the program in a sandbox, without network access and with a
+    /// read-only file system.
+    #[serde(default = "crate::serde_ext::bool::yes")]
+    pub sandbox: bool,
+}
+
+impl Evaluator {
+    /// Create a new evaluator running the given program.
+    pub fn new(command: impl Into<PathBuf>) -> Self {
+        Self {
+            command: command.into(),
+            args: Vec::new(),
+            timeout: None,
+            sandbox: true,
+        }
+    }
+
+    /// Evaluate an object from its operations, returning its state.
+    pub fn evaluate(
+        &self,
+        type_name: &TypeName,
+        id: &ObjectId,
+        ops: &NonEmpty<Op<Vec<u8>>>,
+    ) -> Result<serde_json::Value, Error> {
+        let input = serde_json::to_vec(&Input::new(type_name, id, ops)?)?;
+
+        self.run(input)
+    }
+
+    /// Run the evaluator with the given input, and parse its output.
+    fn run(&self, input: Vec<u8>) -> Result<serde_json::Value, Error> {
+        let timeout = self
+            .timeout
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_TIMEOUT)
+            .min(MAX_TIMEOUT);
+        let cwd = tempfile::tempdir()?;
+        let mut command = Command::new(&self.command);
+        command
+            .args(&self.args)
+            .env_clear()
+            .envs(env::var_os("PATH").map(|path| ("PATH", path)))
+            .current_dir(cwd.path())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .process_group(0);
+
+        if self.sandbox {
+            if !cfg!(target_os = "linux") {
+                return Err(Error::Unsandboxed(self.command.clone()));
+            }
+            // SAFETY: `sandbox::confine` only makes system calls, which is safe to do
+            // between `fork` and `exec`.
+            unsafe {
+                command.pre_exec(sandbox::confine);
+            }
+        }
+        let mut child = command
+            .spawn()
+            .map_err(|e| Error::Spawn(self.command.clone(), e))?;
+
+        // Input and output are handled on separate threads, so that the evaluator can't
+        // block on a full pipe while we wait for it to exit.
+        let stdin = child.stdin.take().map(|mut stdin| {
+            thread::spawn(move || match stdin.write_all(&input) {
+                // The evaluator is free to exit without reading all of its input.
+                Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+                result => result,
+            })
+        });
+        let stdout = child
+            .stdout
+            .take()
+            .map(|stdout| thread::spawn(|| read(stdout, MAX_OUTPUT)));
+        let stderr = child
+            .stderr
+            .take()
+            .map(|stderr| thread::spawn(|| read(stderr, MAX_OUTPUT)));
+
+        let deadline = Instant::now() + timeout;
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                kill(&child);
+                child.wait()?;
+                // Don't wait for the pipe threads, since they only finish once every
+                // process holding the pipes is gone.
+                return Err(Error::Timeout(self.command.clone(), timeout));
+            }
+            thread::sleep(Duration::from_millis(10));
+        };
+        // Processes left behind by the evaluator could hold on to its pipes, and keep us
+        // from reading them to the end.
+        kill(&child);
+        join(stdin)?;
+
+        let (stdout, truncated) = join(stdout)?;
+        let (stderr, _) = join(stderr)?;
+
+        if truncated {
+            return Err(Error::Output(self.command.clone(), MAX_OUTPUT));
+        }
+        if !status.success() {
+            return Err(Error::Failed {
+                command: self.command.clone(),
+                status,
+                stderr: String::from_utf8_lossy(&stderr).trim().to_owned(),
+            });
+        }
+        serde_json::from_slice(&stdout).map_err(|e| Error::State(self.command.clone(), e))
+    }
+}
+
+/// Objects of types with an external evaluator, in a single repository.
+///
+/// Evaluated states are written to the COB cache, which is used instead of running the
+/// evaluator as long as the state is up to date.
+pub struct Objects<'a, R> {
+    repo: &'a R,
+    evaluators: &'a Evaluators,
+    cache: StoreWriter,
+}
+
+impl<'a, R> Objects<'a, R>
+where
+    R: cob::Store + HasRepoId,
+{
+    /// Create a new handle, using the given evaluators and cache.
+    pub fn new(repo: &'a R, evaluators: &'a Evaluators, cache: StoreWriter) -> Self {
+        Self {
+            repo,
+            evaluators,
+            cache,
+        }
+    }
+
+    /// Get the evaluator registered for the given type.
+    pub fn evaluator(&self, type_name: &TypeName) -> Result<&'a Evaluator, Error> {
+        self.evaluators
+            .get(type_name)
+            .ok_or_else(|| Error::NotRegistered(type_name.clone()))
+    }
+
+    /// Get the state of an object, returning `None` if it wasn't found.
+    pub fn get(
+        &self,
+        type_name: &TypeName,
+        id: &ObjectId,
+    ) -> Result<Option<serde_json::Value>, Error> {
+        let evaluator = self.evaluator(type_name)?;
+        let ops = match store::ops(id, type_name, self.repo) {
+            Ok(ops) => ops,
+            Err(store::Error::NotFound(_, _)) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        self.state(evaluator, type_name, id, &ops).map(Some)
+    }
+
+    /// Get the states of all objects of the given type.
+    pub fn list(&self, type_name: &TypeName) -> Result<Vec<(ObjectId, serde_json::Value)>, Error> {
+        let evaluator = self.evaluator(type_name)?;
+        let objects = cob::list::<NonEmpty<cob::Entry>, _>(self.repo, type_name)
+            .map_err(store::Error::from)?;
+
+        objects
+            .into_iter()
+            .map(|object| {
+                let ops = object.object.map(Op::from);
+                let state = self.state(evaluator, type_name, &object.id, &ops)?;
+
+                Ok((object.id, state))
+            })
+            .collect()
+    }
+
+    /// Get the state of an object from the cache, or evaluate it if the cached state is
+    /// missing or out of date.
+    fn state(
+        &self,
+        evaluator: &Evaluator,
+        type_name: &TypeName,
+        id: &ObjectId,
+        ops: &NonEmpty<Op<Vec<u8>>>,
+    ) -> Result<serde_json::Value, Error> {
+        let rid = self.repo.rid();
+        let input = serde_json::to_vec(&Input::new(type_name, id, ops)?)?;
+        let digest = digest(evaluator, &input)?;
+
+        if let Some(state) = query::get(&self.cache, &rid, id, &digest)? {
+            return Ok(state);
+        }
+        let state = evaluator.run(input)?;
+        query::update(&self.cache, &rid, type_name, id, &digest, &state)?;
+
+        Ok(state)
+    }
+}
+
+/// Evaluator input.
+#[derive(Serialize)]
+struct Input<'a> {
+    #[serde(rename = "type")]
+    type_name: &'a TypeName,
+    id: &'a ObjectId,
+    ops: Vec<InputOp>,
+}
+
+impl<'a> Input<'a> {
+    fn new(
+        type_name: &'a TypeName,
+        id: &'a ObjectId,
+        ops: &NonEmpty<Op<Vec<u8>>>,
+    ) -> Result<Self, Error> {
+        let ops = ops
+            .iter()
+            .map(|op| {
+                let actions = op
+                    .actions
+                    .iter()
+                    .map(|action| {
+                        serde_json::from_slice(action).map_err(|e| Error::Action(op.id, e))
+                    })
+                    .collect::<Result<_, _>>()?;
+
+                Ok(InputOp {
+                    id: op.id,
+                    author: op.author,
+                    timestamp: op.timestamp,
+                    parents: op.parents.clone(),
+                    actions,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Self { type_name, id, ops })
+    }
+}
+
+/// An operation, as given to evaluators.
+#[derive(Serialize)]
+struct InputOp {
+    id: EntryId,
+    author: ActorId,
+    timestamp: Timestamp,
+    parents: Vec<EntryId>,
+    actions: Vec<serde_json::Value>,
+}
+
+/// Compute a digest of everything the evaluated state depends on.
+fn digest(evaluator: &Evaluator, input: &[u8]) -> Result<git::Oid, Error> {
+    let mut bytes = serde_json::to_vec(evaluator)?;
+    bytes.push(b'\n');
+    bytes.extend_from_slice(input);
+
+    git::raw::Oid::hash_object(git::raw::ObjectType::Blob, &bytes)
+        .map(git::Oid::from)
+        .map_err(|e| Error::Io(io::Error::new(io::ErrorKind::Other, e)))
+}
+
+/// Read a pipe to the end, keeping at most `limit` bytes. The rest is discarded, so that
+/// the writer doesn't block. Returns whether anything was discarded.
+fn read(pipe: impl Read, limit: u64) -> io::Result<(Vec<u8>, bool)> {
+    let mut buf = Vec::new();
+    let mut pipe = pipe.take(limit);
+    pipe.read_to_end(&mut buf)?;
+
+    let discarded = io::copy(&mut pipe.into_inner(), &mut io::sink())?;
+
+    Ok((buf, discarded > 0))
+}
+
+/// Wait for a pipe thread to finish.
+fn join<T: Default>(handle: Option<thread::JoinHandle<io::Result<T>>>) -> io::Result<T> {
+    match handle {
+        Some(handle) => handle
+            .join()
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "evaluator pipe thread panicked"))?,
+        None => Ok(T::default()),
+    }
+}
+
+/// Kill the process group of an evaluator, which it leads.
+fn kill(child: &Child) {
+    // Nb. this fails if the group is already gone, which is fine.
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+}
+
+mod sandbox {
+    use std::io;
+
+    /// Confine the current process, which is about to run an evaluator.
+    ///
+    /// The process is moved to new user, mount, network and IPC namespaces. The new network
+    /// namespace only has a loopback interface, which is down, and every mount of the new
+    /// mount namespace is made read-only. The process also can't gain privileges anymore.
+    ///
+    /// Nb. this runs between `fork` and `exec`, where only system calls are allowed.
+    #[cfg(target_os = "linux")]
+    pub(super) fn confine() -> io::Result<()> {
+        /// Argument of the `mount_setattr` system call.
+        #[repr(C)]
+        struct MountAttr {
+            attr_set: u64,
+            attr_clr: u64,
+            propagation: u64,
+            userns_fd: u64,
+        }
+        const MOUNT_ATTR_RDONLY: u64 = 0x1;
+        const MS_PRIVATE: u64 = 1 << 18;
+        const AT_RECURSIVE: libc::c_uint = 0x8000;
+
+        let namespaces =
+            libc::CLONE_NEWUSER | libc::CLONE_NEWNS | libc::CLONE_NEWNET | libc::CLONE_NEWIPC;
+        if unsafe { libc::unshare(namespaces) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // Mounts are made private, so that making them read-only doesn't propagate outside
+        // of the sandbox.
+        let attr = MountAttr {
+            attr_set: MOUNT_ATTR_RDONLY,
+            attr_clr: 0,
+            propagation: MS_PRIVATE,
+            userns_fd: 0,
+        };
+        let root = b"/\0";
+        let result = unsafe {
+            libc::syscall(
+                libc::SYS_mount_setattr,
+                libc::AT_FDCWD,
+                root.as_ptr(),
+                AT_RECURSIVE,
+                &attr as *const MountAttr,
+                std::mem::size_of::<MountAttr>(),
+            )
+        };
+        if result != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        if unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub(super) fn confine() -> io::Result<()> {
+        unreachable!("sandbox::confine: sandboxing is only supported on Linux")
+    }
+}
+
+mod query {
+    use super::*;
+
+    pub(super) fn get(
+        cache: &StoreWriter,
+        rid: &RepoId,
+        id: &ObjectId,
+        digest: &git::Oid,
+    ) -> Result<Option<serde_json::Value>, Error> {
+        let mut stmt = cache.db.prepare(
+            "SELECT state
+             FROM objects
+             WHERE id = ?1 AND repo = ?2 AND digest = ?3",
+        )?;
+
+        stmt.bind((1, sql::Value::String(id.to_string())))?;
+        stmt.bind((2, rid))?;
+        stmt.bind((3, sql::Value::String(digest.to_string())))?;
+
+        match stmt.into_iter().next().transpose()? {
+            None => Ok(None),
+            Some(row) => {
+                let state = row.read::<&str, _>("state");
+                let state = serde_json::from_str(state)?;
+                Ok(Some(state))
+            }
+        }
+    }
+
+    pub(super) fn update(
+        cache: &StoreWriter,
+        rid: &RepoId,
+        type_name: &TypeName,
+        id: &ObjectId,
+        digest: &git::Oid,
+        state: &serde_json::Value,
+    ) -> Result<(), Error> {
+        transaction::<_, Error>(&cache.db, move |db| {
+            let mut stmt = db.prepare(
+                "INSERT INTO objects (id, repo, type, digest, state)
+                  VALUES (?1, ?2, ?3, ?4, ?5)
+                  ON CONFLICT DO UPDATE
+                  SET type = (?3), digest = (?4), state = (?5)",
+            )?;
+
+            stmt.bind((1, sql::Value::String(id.to_string())))?;
+            stmt.bind((2, rid))?;
+            stmt.bind((3, sql::Value::String(type_name.to_string())))?;
+            stmt.bind((4, sql::Value::String(digest.to_string())))?;
+            stmt.bind((5, sql::Value::String(serde_json::to_string(state)?)))?;
+            stmt.next()?;
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use std::str::FromStr;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::test;
+
+    #[test]
+    fn test_evaluate_cached() {
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let type_name = TypeName::from_str("com.example.counter").unwrap();
+        let id = store::create_raw(
+            &*repo,
+            &type_name,
+            "Create counter",
+            NonEmpty::new(json!({ "type": "add", "n": 1 })),
+            vec![],
+            &node.signer,
+        )
+        .unwrap();
+
+        // The evaluator counts its invocations in a file, and returns the number of
+        // operations it was given.
+        let tmp = tempfile::tempdir().unwrap();
+        let log = tmp.path().join("runs");
+        let evaluators = Evaluators::from([(
+            type_name.clone(),
+            Evaluator {
+                command: PathBuf::from("sh"),
+                args: vec![
+                    String::from("-c"),
+                    format!(
+                        "echo run >> {}; grep -o '\"parents\"' | wc -l",
+                        log.display()
+                    ),
+                ],
+                timeout: None,
+                sandbox: false,
+            },
+        )]);
+        let objects = Objects::new(&*repo, &evaluators, StoreWriter::memory().unwrap());
+
+        assert_eq!(objects.get(&type_name, &id).unwrap(), Some(json!(1)));
+        assert_eq!(objects.get(&type_name, &id).unwrap(), Some(json!(1)));
+        assert_eq!(std::fs::read_to_string(&log).unwrap().lines().count(), 1);
+
+        store::update_raw(
+            &*repo,
+            &type_name,
+            id,
+            "Add to counter",
+            NonEmpty::new(json!({ "type": "add", "n": 2 })),
+            vec![],
+            &node.signer,
+        )
+        .unwrap();
+
+        assert_eq!(
+            objects.list(&type_name).unwrap(),
+            vec![(id, json!(2))],
+            "the new operation invalidates the cached state"
+        );
+        assert_eq!(std::fs::read_to_string(&log).unwrap().lines().count(), 2);
+    }
+
+    #[test]
+    fn test_evaluate_errors() {
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let type_name = TypeName::from_str("com.example.counter").unwrap();
+        let id = store::create_raw(
+            &*repo,
+            &type_name,
+            "Create counter",
+            NonEmpty::new(json!({ "type": "add", "n": 1 })),
+            vec![],
+            &node.signer,
+        )
+        .unwrap();
+        let ops = store::ops(&id, &type_name, &*repo).unwrap();
+
+        let evaluator = Evaluator {
+            command: PathBuf::from("sh"),
+            args: vec![String::from("-c"), String::from("echo oops >&2; exit 1")],
+            timeout: None,
+            sandbox: true,
+        };
+        assert!(matches!(
+            evaluator.evaluate(&type_name, &id, &ops),
+            Err(Error::Failed { stderr, .. }) if stderr == "oops"
+        ));
+
+        let evaluator = Evaluator {
+            command: PathBuf::from("sleep"),
+            args: vec![String::from("5")],
+            timeout: Some(0),
+            sandbox: true,
+        };
+        assert!(matches!(
+            evaluator.evaluate(&type_name, &id, &ops),
+            Err(Error::Timeout(_, _))
+        ));
+
+        // Processes left running in the background, holding on to the evaluator's
+        // output, are killed along with it.
+        let evaluator = Evaluator {
+            command: PathBuf::from("sh"),
+            args: vec![String::from("-c"), String::from("sleep 60 & echo '{}'")],
+            timeout: Some(5),
+            sandbox: true,
+        };
+        let started = std::time::Instant::now();
+        assert_eq!(
+            evaluator.evaluate(&type_name, &id, &ops).unwrap(),
+            json!({})
+        );
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        let evaluator = Evaluator {
+            command: PathBuf::from("sh"),
+            args: vec![
+                String::from("-c"),
+                format!("head -c {} /dev/zero", MAX_OUTPUT + 1),
+            ],
+            timeout: None,
+            sandbox: true,
+        };
+        assert!(matches!(
+            evaluator.evaluate(&type_name, &id, &ops),
+            Err(Error::Output(_, _))
+        ));
+
+        // Huge timeouts are capped.
+        let evaluator = Evaluator {
+            command: PathBuf::from("sh"),
+            args: vec![String::from("-c"), String::from("echo '{}'")],
+            timeout: Some(u64::MAX),
+            sandbox: true,
+        };
+        assert_eq!(
+            evaluator.evaluate(&type_name, &id, &ops).unwrap(),
+            json!({})
+        );
+
+        let evaluators = Evaluators::new();
+        let objects = Objects::new(&*repo, &evaluators, StoreWriter::memory().unwrap());
+        assert!(matches!(
+            objects.get(&type_name, &id),
+            Err(Error::NotRegistered(_))
+        ));
+    }
+
+    #[test]
+    fn test_cached_per_repository() {
+        let cache = StoreWriter::memory().unwrap();
+        let type_name = TypeName::from_str("com.example.counter").unwrap();
+        let id = test::arbitrary::oid().into();
+        let digest = test::arbitrary::oid();
+        let (fork, upstream) = (
+            test::arbitrary::gen::<RepoId>(1),
+            test::arbitrary::gen::<RepoId>(1),
+        );
+
+        // The same object can be in more than one repository, eg. in forks.
+        query::update(&cache, &upstream, &type_name, &id, &digest, &json!(1)).unwrap();
+        query::update(&cache, &fork, &type_name, &id, &digest, &json!(2)).unwrap();
+
+        assert_eq!(
+            query::get(&cache, &upstream, &id, &digest).unwrap(),
+            Some(json!(1))
+        );
+        assert_eq!(
+            query::get(&cache, &fork, &id, &digest).unwrap(),
+            Some(json!(2))
+        );
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_evaluate_sandboxed() {
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let type_name = TypeName::from_str("com.example.counter").unwrap();
+        let id = store::create_raw(
+            &*repo,
+            &type_name,
+            "Create counter",
+            NonEmpty::new(json!({ "type": "add", "n": 1 })),
+            vec![],
+            &node.signer,
+        )
+        .unwrap();
+        let ops = store::ops(&id, &type_name, &*repo).unwrap();
+
+        // The file system is read-only.
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("file");
+        let evaluator = Evaluator {
+            command: PathBuf::from("sh"),
+            args: vec![
+                String::from("-c"),
+                format!("touch {} && echo '{{}}'", file.display()),
+            ],
+            timeout: None,
+            sandbox: true,
+        };
+        assert!(matches!(
+            evaluator.evaluate(&type_name, &id, &ops),
+            Err(Error::Failed { .. })
+        ));
+        assert!(!file.exists());
+
+        // The only network interface is the loopback interface, which is down.
+        let evaluator = Evaluator {
+            command: PathBuf::from("sh"),
+            args: vec![
+                String::from("-c"),
+                String::from(
+                    "printf '\"%s\"' \"$(tail -n +3 /proc/net/dev | cut -d: -f1 | tr -d ' \\n')\"",
+                ),
+            ],
+            timeout: None,
+            sandbox: true,
+        };
+        assert_eq!(
+            evaluator.evaluate(&type_name, &id, &ops).unwrap(),
+            json!("lo")
+        );
+
+        // Without the sandbox, the evaluator runs unconfined.
+        let evaluator = Evaluator {
+            command: PathBuf::from("sh"),
+            args: vec![
+                String::from("-c"),
+                format!("touch {} && echo '{{}}'", file.display()),
+            ],
+            timeout: None,
+            sandbox: false,
+        };
+        assert_eq!(
+            evaluator.evaluate(&type_name, &id, &ops).unwrap(),
+            json!({})
+        );
+        assert!(file.exists());
+    }
+}
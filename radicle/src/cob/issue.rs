@@ -5,17 +5,22 @@ use std::ops::Deref;
 use std::str::FromStr;
 
 use once_cell::sync::Lazy;
+use radicle_crdt::clock::Lamport;
+use radicle_crdt::List;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::cob;
-use crate::cob::common::{Author, Authorization, Label, Reaction, Timestamp, Uri};
+use crate::cob::common::{Author, Authorization, Label, Origin, Reaction, Timestamp, Uri};
 use crate::cob::store::Transaction;
 use crate::cob::store::{Cob, CobAction};
+use crate::cob::text;
+use crate::cob::text::Text;
 use crate::cob::thread;
 use crate::cob::thread::{Comment, CommentId, Thread};
-use crate::cob::{op, store, ActorId, Embed, EntryId, ObjectId, TypeName};
+use crate::cob::{op, store, ActorId, Embed, EntryId, ObjectId, TypeName, TypedId};
 use crate::crypto::Signer;
+use crate::git::Oid;
 use crate::identity::doc::{Doc, DocError};
 use crate::prelude::{Did, ReadRepository, RepoId, Verified};
 use crate::storage::{HasRepoId, RepositoryError, WriteRepository};
@@ -51,6 +56,9 @@ pub enum Error {
     /// Title is invalid.
     #[error("invalid title: {0:?}")]
     InvalidTitle(String),
+    /// Task wasn't found.
+    #[error("task {0} not found")]
+    MissingTask(TaskId),
     /// The identity doc is missing.
     #[error("identity document missing")]
     MissingIdentity,
@@ -72,6 +80,11 @@ pub enum Error {
         #[source]
         err: Box<dyn std::error::Error + Send + Sync + 'static>,
     },
+    #[error("failed to query issues in cache: {err}")]
+    CacheQuery {
+        #[source]
+        err: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
 }
 
 /// Reason why an issue was closed.
@@ -121,6 +134,67 @@ impl State {
     }
 }
 
+/// Identifier for a task of an issue. This is the id of the operation that added it.
+pub type TaskId = EntryId;
+
+/// Position of a task in a task list. Positions are ordered by the clock of the operation
+/// that placed the task there, and then by operation and task, to break ties.
+pub type TaskPosition = (Lamport, EntryId, TaskId);
+
+/// Object a task is linked to, eg. the issue or patch that implements it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum TaskLink {
+    /// An issue.
+    Issue { id: Oid },
+    /// A patch.
+    Patch { id: Oid },
+}
+
+/// A task in the task list of an issue.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Task {
+    /// Author of the task.
+    author: Author,
+    /// Task description.
+    body: String,
+    /// Whether the task is done.
+    done: bool,
+    /// Linked issue or patch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    link: Option<TaskLink>,
+    /// When the task was added.
+    timestamp: Timestamp,
+}
+
+impl Task {
+    /// Author of the task.
+    pub fn author(&self) -> &Author {
+        &self.author
+    }
+
+    /// Task description.
+    pub fn body(&self) -> &str {
+        self.body.as_str()
+    }
+
+    /// Whether the task is done.
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Linked issue or patch, if any.
+    pub fn link(&self) -> Option<&TaskLink> {
+        self.link.as_ref()
+    }
+
+    /// When the task was added.
+    pub fn timestamp(&self) -> Timestamp {
+        self.timestamp
+    }
+}
+
 /// Issue state. Accumulates [`Action`].
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -135,6 +209,13 @@ pub struct Issue {
     pub(super) labels: BTreeSet<Label>,
     /// Discussion around this issue.
     pub(super) thread: Thread,
+    /// Issue description, merged line by line from concurrent edits. The body of the
+    /// root comment is kept in sync with it.
+    #[serde(default)]
+    pub(super) description: Text,
+    /// Task list, in order.
+    #[serde(default)]
+    pub(super) tasks: List<TaskId, Task, TaskPosition>,
 }
 
 impl store::Cob for Issue {
@@ -152,13 +233,20 @@ impl store::Cob for Issue {
             body,
             reply_to: None,
             embeds,
+            mentions,
         }) = actions.next()
         else {
             return Err(Error::Init("the first action must be of type `comment`"));
         };
-        let comment = Comment::new(op.author, body, None, None, embeds, op.timestamp);
+        let mut description = Text::new();
+        text::edit(&mut description, op.id, &body, &[]);
+
+        let comment = Comment::new(op.author, body, None, None, embeds, mentions, op.timestamp);
         let thread = Thread::new(op.id, comment);
-        let mut issue = Issue::new(thread);
+        let mut issue = Issue {
+            description,
+            ..Issue::new(thread)
+        };
 
         for action in actions {
             match issue.authorization(&action, &op.author, &doc)? {
@@ -186,6 +274,7 @@ impl store::Cob for Issue {
     ) -> Result<(), Error> {
         let doc = op.identity_doc(repo)?.ok_or(Error::MissingIdentity)?;
         let concurrent = concurrent.into_iter().collect::<Vec<_>>();
+
         for action in op.actions {
             match self.authorization(&action, &op.author, &doc)? {
                 Authorization::Allow => {
@@ -242,6 +331,8 @@ impl Issue {
             state: State::default(),
             labels: BTreeSet::default(),
             thread,
+            description: Text::default(),
+            tasks: List::default(),
         }
     }
 
@@ -315,6 +406,23 @@ impl Issue {
         self.comments().skip(1)
     }
 
+    /// Tasks of the issue, in order.
+    pub fn tasks(&self) -> impl Iterator<Item = (&TaskId, &Task)> {
+        self.tasks.iter()
+    }
+
+    /// Get a task.
+    pub fn task(&self, id: &TaskId) -> Option<&Task> {
+        self.tasks.get(id)
+    }
+
+    /// Task progress, as the number of tasks done and the total number of tasks.
+    pub fn progress(&self) -> (usize, usize) {
+        let done = self.tasks.iter().filter(|(_, t)| t.done).count();
+
+        (done, self.tasks.len())
+    }
+
     /// Apply authorization rules on issue actions.
     pub fn authorization(
         &self,
@@ -355,8 +463,11 @@ impl Issue {
             }
             // All roles can comment on an issues
             Action::Comment { .. } => Authorization::Allow,
-            // All roles can edit or redact their own comments.
-            Action::CommentEdit { id, .. } | Action::CommentRedact { id, .. } => {
+            // All roles can edit or redact their own comments, and record where they were
+            // imported from.
+            Action::CommentEdit { id, .. }
+            | Action::CommentRedact { id, .. }
+            | Action::CommentOrigin { id, .. } => {
                 if let Some(comment) = self.thread.comments.get(id) {
                     if let Some(comment) = comment {
                         Authorization::from(*actor == comment.author())
@@ -369,6 +480,15 @@ impl Issue {
             }
             // All roles can react to a comment on an issue.
             Action::CommentReact { .. } => Authorization::Allow,
+            // Issue authors can manage the task list of their own issues.
+            Action::TaskAdd { .. }
+            | Action::TaskEdit { .. }
+            | Action::TaskMove { .. }
+            | Action::TaskRemove { .. } => Authorization::from(*actor == author),
+            // Assignees can also check tasks off.
+            Action::TaskCheck { .. } => {
+                Authorization::from(*actor == author || self.assignees.contains(&Did::from(*actor)))
+            }
         };
         Ok(outcome)
     }
@@ -382,7 +502,7 @@ impl Issue {
         entry: EntryId,
         author: ActorId,
         timestamp: Timestamp,
-        _concurrent: &[&cob::Entry],
+        concurrent: &[&cob::Entry],
         _doc: &Doc<Verified>,
         _repo: &R,
     ) -> Result<(), Error> {
@@ -406,6 +526,7 @@ impl Issue {
                 body,
                 reply_to,
                 embeds,
+                mentions,
             } => {
                 thread::comment(
                     &mut self.thread,
@@ -416,10 +537,31 @@ impl Issue {
                     reply_to,
                     None,
                     embeds,
+                    mentions,
                 )?;
             }
-            Action::CommentEdit { id, body, embeds } => {
-                thread::edit(&mut self.thread, entry, author, id, timestamp, body, embeds)?;
+            Action::CommentEdit {
+                id,
+                body,
+                embeds,
+                mentions,
+            } => {
+                // Edits to the issue description are merged with concurrent edits.
+                let body = if id == *self.root().0 {
+                    text::edit(&mut self.description, entry, &body, concurrent)
+                } else {
+                    body
+                };
+                thread::edit(
+                    &mut self.thread,
+                    entry,
+                    author,
+                    id,
+                    timestamp,
+                    body,
+                    embeds,
+                    mentions,
+                )?;
             }
             Action::CommentRedact { id } => {
                 let (root, _) = self.root();
@@ -435,9 +577,71 @@ impl Issue {
             } => {
                 thread::react(&mut self.thread, entry, author, id, reaction, active)?;
             }
+            Action::CommentOrigin { id, origin } => {
+                thread::origin(&mut self.thread, entry, id, origin)?;
+            }
+            Action::TaskAdd { body, after, link } => {
+                let position = (self.clock(concurrent), entry, entry);
+                let task = Task {
+                    author: Author::new(author),
+                    body,
+                    done: false,
+                    link,
+                    timestamp,
+                };
+                if !self.tasks.insert(after.as_ref(), entry, position, task) {
+                    return Err(Error::NotAllowed(entry));
+                }
+            }
+            Action::TaskEdit { id, body, link } => {
+                if let Some(task) = self.task_mut(id)? {
+                    task.body = body;
+                    task.link = link;
+                }
+            }
+            Action::TaskCheck { id, done } => {
+                if let Some(task) = self.task_mut(id)? {
+                    task.done = done;
+                }
+            }
+            Action::TaskMove { id, after } => {
+                let position = (self.clock(concurrent), entry, id);
+                if !self.tasks.move_after(&id, after.as_ref(), position) {
+                    return Err(Error::NotAllowed(entry));
+                }
+            }
+            Action::TaskRemove { id } => {
+                if !self.tasks.remove(&id) {
+                    return Err(Error::MissingTask(id));
+                }
+            }
         }
         Ok(())
     }
+
+    /// Clock for a task position placed by the operation being applied. Positions have
+    /// greater clocks than the positions that the operation has seen.
+    fn clock(&self, concurrent: &[&cob::Entry]) -> Lamport {
+        self.tasks
+            .positions()
+            .filter(|(_, op, _)| text::seen(concurrent, op))
+            .map(|(clock, _, _)| *clock)
+            .max()
+            .unwrap_or_default()
+            .tick()
+    }
+
+    /// Get a task to modify. Returns `None` if the task was removed, which can happen when
+    /// it is removed concurrently, and an error if it was never added.
+    fn task_mut(&mut self, id: TaskId) -> Result<Option<&mut Task>, Error> {
+        if self.tasks.is_removed(&id) {
+            return Ok(None);
+        }
+        self.tasks
+            .get_mut(&id)
+            .map(Some)
+            .ok_or(Error::MissingTask(id))
+    }
 }
 
 impl<'a, 'g, R, C> From<IssueMut<'a, 'g, R, C>> for (IssueId, Issue) {
@@ -468,6 +672,7 @@ impl<R: ReadRepository> store::Transaction<Issue, R> {
         id: CommentId,
         body: impl ToString,
         embeds: Vec<Embed>,
+        mentions: Vec<Did>,
     ) -> Result<(), store::Error> {
         let hashed = embeds.iter().map(|e| e.hashed()).collect();
 
@@ -476,6 +681,7 @@ impl<R: ReadRepository> store::Transaction<Issue, R> {
             id,
             body: body.to_string(),
             embeds: hashed,
+            mentions,
         })
     }
 
@@ -502,6 +708,7 @@ impl<R: ReadRepository> store::Transaction<Issue, R> {
         body: S,
         reply_to: CommentId,
         embeds: Vec<Embed>,
+        mentions: Vec<Did>,
     ) -> Result<(), store::Error> {
         let hashed = embeds.iter().map(|e| e.hashed()).collect();
 
@@ -510,6 +717,7 @@ impl<R: ReadRepository> store::Transaction<Issue, R> {
             body: body.to_string(),
             reply_to: Some(reply_to),
             embeds: hashed,
+            mentions,
         })
     }
 
@@ -534,6 +742,54 @@ impl<R: ReadRepository> store::Transaction<Issue, R> {
         })
     }
 
+    /// Record where a comment was imported from.
+    pub fn origin(&mut self, id: CommentId, origin: Origin) -> Result<(), store::Error> {
+        self.push(Action::CommentOrigin { id, origin })
+    }
+
+    /// Add a task after the given task, or at the start of the task list.
+    pub fn add_task(
+        &mut self,
+        body: impl ToString,
+        after: Option<TaskId>,
+        link: Option<TaskLink>,
+    ) -> Result<(), store::Error> {
+        self.push(Action::TaskAdd {
+            body: body.to_string(),
+            after,
+            link,
+        })
+    }
+
+    /// Edit a task.
+    pub fn edit_task(
+        &mut self,
+        id: TaskId,
+        body: impl ToString,
+        link: Option<TaskLink>,
+    ) -> Result<(), store::Error> {
+        self.push(Action::TaskEdit {
+            id,
+            body: body.to_string(),
+            link,
+        })
+    }
+
+    /// Mark a task as done or not done.
+    pub fn check_task(&mut self, id: TaskId, done: bool) -> Result<(), store::Error> {
+        self.push(Action::TaskCheck { id, done })
+    }
+
+    /// Move a task after the given task, or to the start of the task list.
+    pub fn move_task(&mut self, id: TaskId, after: Option<TaskId>) -> Result<(), store::Error> {
+        self.push(Action::TaskMove { id, after })
+    }
+
+    /// Remove a task.
+    pub fn remove_task(&mut self, id: TaskId) -> Result<(), store::Error> {
+        self.push(Action::TaskRemove { id })
+    }
+
     ////////////////////////////////////////////////////////////////////////////////////////////////
 
     /// Create the issue thread.
@@ -550,6 +806,7 @@ impl<R: ReadRepository> store::Transaction<Issue, R> {
             body: body.to_string(),
             reply_to: None,
             embeds: hashed,
+            mentions: vec![],
         })
     }
 }
@@ -614,7 +871,7 @@ where
         let (id, _) = self.root();
         let id = *id;
         self.transaction("Edit description", signer, |tx| {
-            tx.edit_comment(id, description, embeds.into_iter().collect())
+            tx.edit_comment(id, description, embeds.into_iter().collect(), vec![])
         })
     }
 
@@ -623,29 +880,41 @@ where
         self.transaction("Lifecycle", signer, |tx| tx.lifecycle(state))
     }
 
-    /// Comment on an issue.
+    /// Comment on an issue, mentioning the given users.
     pub fn comment<G: Signer, S: ToString>(
         &mut self,
         body: S,
         reply_to: CommentId,
         embeds: impl IntoIterator<Item = Embed>,
+        mentions: impl IntoIterator<Item = Did>,
         signer: &G,
     ) -> Result<EntryId, Error> {
         self.transaction("Comment", signer, |tx| {
-            tx.comment(body, reply_to, embeds.into_iter().collect())
+            tx.comment(
+                body,
+                reply_to,
+                embeds.into_iter().collect(),
+                mentions.into_iter().collect(),
+            )
         })
     }
 
-    /// Edit a comment.
+    /// Edit a comment, mentioning the given users.
     pub fn edit_comment<G: Signer, S: ToString>(
         &mut self,
         id: CommentId,
         body: S,
         embeds: impl IntoIterator<Item = Embed>,
+        mentions: impl IntoIterator<Item = Did>,
         signer: &G,
     ) -> Result<EntryId, Error> {
         self.transaction("Edit comment", signer, |tx| {
-            tx.edit_comment(id, body, embeds.into_iter().collect())
+            tx.edit_comment(
+                id,
+                body,
+                embeds.into_iter().collect(),
+                mentions.into_iter().collect(),
+            )
         })
     }
 
@@ -678,6 +947,64 @@ where
         self.transaction("React", signer, |tx| tx.react(to, reaction, active))
     }
 
+    /// Record where a comment was imported from.
+    pub fn origin<G: Signer>(
+        &mut self,
+        id: CommentId,
+        origin: Origin,
+        signer: &G,
+    ) -> Result<EntryId, Error> {
+        self.transaction("Origin", signer, |tx| tx.origin(id, origin))
+    }
+
+    /// Add a task after the given task, or at the start of the task list.
+    /// Returns the new task id.
+    pub fn add_task<G: Signer>(
+        &mut self,
+        body: impl ToString,
+        after: Option<TaskId>,
+        link: Option<TaskLink>,
+        signer: &G,
+    ) -> Result<TaskId, Error> {
+        self.transaction("Add task", signer, |tx| tx.add_task(body, after, link))
+    }
+
+    /// Edit a task.
+    pub fn edit_task<G: Signer>(
+        &mut self,
+        id: TaskId,
+        body: impl ToString,
+        link: Option<TaskLink>,
+        signer: &G,
+    ) -> Result<EntryId, Error> {
+        self.transaction("Edit task", signer, |tx| tx.edit_task(id, body, link))
+    }
+
+    /// Mark a task as done or not done.
+    pub fn check_task<G: Signer>(
+        &mut self,
+        id: TaskId,
+        done: bool,
+        signer: &G,
+    ) -> Result<EntryId, Error> {
+        self.transaction("Check task", signer, |tx| tx.check_task(id, done))
+    }
+
+    /// Move a task after the given task, or to the start of the task list.
+    pub fn move_task<G: Signer>(
+        &mut self,
+        id: TaskId,
+        after: Option<TaskId>,
+        signer: &G,
+    ) -> Result<EntryId, Error> {
+        self.transaction("Move task", signer, |tx| tx.move_task(id, after))
+    }
+
+    /// Remove a task.
+    pub fn remove_task<G: Signer>(&mut self, id: TaskId, signer: &G) -> Result<EntryId, Error> {
+        self.transaction("Remove task", signer, |tx| tx.remove_task(id))
+    }
+
     pub fn transaction<G, F>(
         &mut self,
         message: &str,
@@ -692,12 +1019,12 @@ where
         operations(&mut tx)?;
 
         let (issue, commit) = tx.commit(message, self.id, &mut self.store.raw, signer)?;
-        self.cache
-            .update(&self.store.as_ref().id(), &self.id, &issue)
-            .map_err(|e| Error::CacheUpdate {
-                id: self.id,
-                err: e.into(),
-            })?;
+        let typed_id = TypedId {
+            id: self.id,
+            type_name: TYPENAME.clone(),
+        };
+        cob::cache::write(self.cache, self.store.as_ref(), &typed_id, &issue)
+            .map_err(|err| Error::CacheUpdate { id: self.id, err })?;
         self.issue = issue;
 
         Ok(commit)
@@ -759,6 +1086,14 @@ where
 
         Ok(Self { raw })
     }
+
+    /// Resume the evaluation of issues from the given snapshots when possible.
+    /// See [`store::Store::snapshots`].
+    pub fn snapshots(self, snapshots: impl store::Snapshots<Issue> + Send + Sync + 'a) -> Self {
+        Self {
+            raw: self.raw.snapshots(snapshots),
+        }
+    }
 }
 
 impl<'a, R> Issues<'a, R>
@@ -792,9 +1127,12 @@ where
             }
             Ok(())
         })?;
-        cache
-            .update(&self.raw.as_ref().id(), &id, &issue)
-            .map_err(|e| Error::CacheUpdate { id, err: e.into() })?;
+        let typed_id = TypedId {
+            id,
+            type_name: TYPENAME.clone(),
+        };
+        cob::cache::write(cache, self.raw.as_ref(), &typed_id, &issue)
+            .map_err(|err| Error::CacheUpdate { id, err })?;
 
         Ok(IssueMut {
             id,
@@ -892,6 +1230,9 @@ pub enum Action {
         /// Embeded content.
         #[serde(default, skip_serializing_if = "Vec::is_empty")]
         embeds: Vec<Embed<Uri>>,
+        /// Users mentioned in the comment.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        mentions: Vec<Did>,
     },
 
     /// Edit a comment.
@@ -903,6 +1244,9 @@ pub enum Action {
         body: String,
         /// New value for the embeds list.
         embeds: Vec<Embed<Uri>>,
+        /// New value for the mentions list.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        mentions: Vec<Did>,
     },
 
     /// Redact a change. Not all changes can be redacted.
@@ -916,6 +1260,55 @@ pub enum Action {
         reaction: Reaction,
         active: bool,
     },
+
+    /// Record where a comment was imported from.
+    #[serde(rename = "comment.origin")]
+    CommentOrigin { id: CommentId, origin: Origin },
+
+    /// Add a task to the task list.
+    #[serde(rename = "task.add")]
+    TaskAdd {
+        /// Task description.
+        body: String,
+        /// Task this task is added after.
+        /// Should be [`None`] if it's added at the start of the list.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        after: Option<TaskId>,
+        /// Linked issue or patch.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        link: Option<TaskLink>,
+    },
+
+    /// Edit a task.
+    #[serde(rename = "task.edit")]
+    TaskEdit {
+        /// Task being edited.
+        id: TaskId,
+        /// New value for the task description.
+        body: String,
+        /// New value for the linked issue or patch.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        link: Option<TaskLink>,
+    },
+
+    /// Mark a task as done or not done.
+    #[serde(rename = "task.check")]
+    TaskCheck { id: TaskId, done: bool },
+
+    /// Move a task.
+    #[serde(rename = "task.move")]
+    TaskMove {
+        /// Task being moved.
+        id: TaskId,
+        /// Task it is moved after.
+        /// Should be [`None`] if it's moved to the start of the list.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        after: Option<TaskId>,
+    },
+
+    /// Remove a task.
+    #[serde(rename = "task.remove")]
+    TaskRemove { id: TaskId },
 }
 
 impl CobAction for Action {}
@@ -926,6 +1319,7 @@ mod test {
     use pretty_assertions::assert_eq;
 
     use super::*;
+    use crate::cob::store::Snapshots as _;
     use crate::cob::{ActorId, Reaction};
     use crate::git::Oid;
     use crate::issue::cache::Issues as _;
@@ -957,10 +1351,10 @@ mod test {
         let mut issue_bob = bob_issues.get_mut(&id).unwrap();
 
         issue_bob
-            .comment("Bob's reply", *id, vec![], &t.bob.signer)
+            .comment("Bob's reply", *id, vec![], [], &t.bob.signer)
             .unwrap();
         issue_alice
-            .comment("Alice's reply", *id, vec![], &t.alice.signer)
+            .comment("Alice's reply", *id, vec![], [], &t.alice.signer)
             .unwrap();
 
         assert_eq!(issue_bob.comments().count(), 2);
@@ -988,7 +1382,7 @@ mod test {
         t.eve.repo.fetch(&t.alice);
 
         let eve_reply = issue_eve
-            .comment("Eve's reply", *id, vec![], &t.eve.signer)
+            .comment("Eve's reply", *id, vec![], [], &t.eve.signer)
             .unwrap();
 
         t.bob.repo.fetch(&t.eve);
@@ -1267,6 +1661,30 @@ mod test {
         // TODO: Test multiple reactions from same author and different authors
     }
 
+    #[test]
+    fn test_issue_comment_origin() {
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let mut issues = Cache::no_cache(&*repo).unwrap();
+        let mut issue = issues
+            .create("My first issue", "Blah.", &[], &[], [], &node.signer)
+            .unwrap();
+
+        let (comment, _) = issue.root();
+        let comment = *comment;
+        let origin = Origin {
+            source: String::from("GitHub issue #42"),
+            author: String::from("doc"),
+            timestamp: Timestamp::from_secs(1445444940),
+        };
+        issue.origin(comment, origin.clone(), &node.signer).unwrap();
+
+        let id = issue.id;
+        let issue = issues.get(&id).unwrap().unwrap();
+
+        assert_eq!(issue.comment(&comment).unwrap().origin(), Some(&origin));
+        assert_eq!(issue.description(), "Blah.");
+    }
+
     #[test]
     fn test_issue_reply() {
         let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
@@ -1285,10 +1703,10 @@ mod test {
         let root = *root;
 
         let c1 = issue
-            .comment("Hi hi hi.", root, vec![], &node.signer)
+            .comment("Hi hi hi.", root, vec![], [], &node.signer)
             .unwrap();
         let c2 = issue
-            .comment("Ha ha ha.", root, vec![], &node.signer)
+            .comment("Ha ha ha.", root, vec![], [], &node.signer)
             .unwrap();
 
         let id = issue.id;
@@ -1299,13 +1717,17 @@ mod test {
         assert_eq!(reply1.body(), "Hi hi hi.");
         assert_eq!(reply2.body(), "Ha ha ha.");
 
-        issue.comment("Re: Hi.", c1, vec![], &node.signer).unwrap();
-        issue.comment("Re: Ha.", c2, vec![], &node.signer).unwrap();
         issue
-            .comment("Re: Ha. Ha.", c2, vec![], &node.signer)
+            .comment("Re: Hi.", c1, vec![], [], &node.signer)
+            .unwrap();
+        issue
+            .comment("Re: Ha.", c2, vec![], [], &node.signer)
             .unwrap();
         issue
-            .comment("Re: Ha. Ha. Ha.", c2, vec![], &node.signer)
+            .comment("Re: Ha. Ha.", c2, vec![], [], &node.signer)
+            .unwrap();
+        issue
+            .comment("Re: Ha. Ha. Ha.", c2, vec![], [], &node.signer)
             .unwrap();
 
         let issue = issues.get(&id).unwrap().unwrap();
@@ -1359,6 +1781,95 @@ mod test {
         assert!(labels.contains(&wontfix_label));
     }
 
+    #[test]
+    fn test_issue_cache_tips() {
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let db = cob::cache::StoreWriter::memory().unwrap();
+        let mut issues = Cache::open(Issues::open(&*repo).unwrap(), db.clone());
+        let mut issue = issues
+            .create("My first issue", "Blah.", &[], &[], [], &node.signer)
+            .unwrap();
+        let typed_id = TypedId {
+            id: issue.id,
+            type_name: TYPENAME.clone(),
+        };
+        let (c0, _) = issue.root();
+        let c0 = *c0;
+
+        assert_eq!(
+            db.tips(&typed_id).unwrap(),
+            Some(BTreeSet::from([*issue.id]))
+        );
+        let tip = issue
+            .comment("Ho ho ho.", c0, vec![], [], &node.signer)
+            .unwrap();
+
+        assert_eq!(db.tips(&typed_id).unwrap(), Some(BTreeSet::from([tip])));
+        assert!(db.stale().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_issue_snapshots() {
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let db = cob::cache::StoreWriter::memory().unwrap();
+        let rid = repo.id();
+        let mut issues = Cache::open(
+            Issues::open(&*repo).unwrap().snapshots(db.clone()),
+            db.clone(),
+        );
+        let mut issue = issues
+            .create(
+                "My first issue",
+                "Blah blah blah.",
+                &[],
+                &[],
+                [],
+                &node.signer,
+            )
+            .unwrap();
+        let (c0, _) = issue.root();
+        let c0 = *c0;
+        let id = issue.id;
+
+        let tip = issue
+            .comment("Ho ho ho.", c0, vec![], [], &node.signer)
+            .unwrap();
+
+        // Writing an object saves a snapshot of it, for its repository only.
+        let snapshot: cob::Snapshot<Issue> = db.snapshot(&rid, &id).unwrap();
+        assert_eq!(snapshot.tips, BTreeSet::from([tip]));
+        assert_eq!(
+            db.snapshot(&arbitrary::gen::<RepoId>(1), &id)
+                .map(|s: cob::Snapshot<Issue>| s.object),
+            None
+        );
+
+        // Changes made without snapshots are evaluated on top of the last snapshot.
+        let mut issues = Cache::no_cache(&*repo).unwrap();
+        let mut issue = issues.get_mut(&id).unwrap();
+        issue
+            .comment("Ha ha ha.", c0, vec![], [], &node.signer)
+            .unwrap();
+        issue
+            .lifecycle(
+                State::Closed {
+                    reason: CloseReason::Solved,
+                },
+                &node.signer,
+            )
+            .unwrap();
+
+        let snapshotted = Issues::open(&*repo).unwrap().snapshots(db.clone());
+        let plain = Issues::open(&*repo).unwrap();
+        let issue = snapshotted.get(&id).unwrap().unwrap();
+        assert_eq!(Some(&issue), plain.get(&id).unwrap().as_ref());
+        assert_eq!(issue.comments().count(), 3);
+
+        // Reading an object doesn't save a snapshot.
+        let snapshot: cob::Snapshot<Issue> = db.snapshot(&rid, &id).unwrap();
+        assert_eq!(snapshot.tips, BTreeSet::from([tip]));
+    }
+
     #[test]
     fn test_issue_comment() {
         let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
@@ -1380,10 +1891,10 @@ mod test {
         let c0 = *c0;
 
         issue
-            .comment("Ho ho ho.", c0, vec![], &node.signer)
+            .comment("Ho ho ho.", c0, vec![], [], &node.signer)
             .unwrap();
         issue
-            .comment("Ha ha ha.", c0, vec![], &node.signer)
+            .comment("Ha ha ha.", c0, vec![], [], &node.signer)
             .unwrap();
 
         let id = issue.id;
@@ -1420,7 +1931,7 @@ mod test {
         let c0 = *c0;
 
         let comment = issue
-            .comment("Ho ho ho.", c0, vec![], &node.signer)
+            .comment("Ho ho ho.", c0, vec![], [], &node.signer)
             .unwrap();
         issue.reload().unwrap();
         assert_eq!(issue.comments().count(), 2);
@@ -1476,6 +1987,64 @@ mod test {
         issues.iter().find(|i| i.title() == "Third").unwrap();
     }
 
+    #[test]
+    fn test_issue_tasks() {
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let mut issues = Cache::no_cache(&*repo).unwrap();
+        let mut issue = issues
+            .create("My first issue", "Blah", &[], &[], [], &node.signer)
+            .unwrap();
+        let link = TaskLink::Patch {
+            id: arbitrary::oid(),
+        };
+
+        let a = issue
+            .add_task("Write code", None, None, &node.signer)
+            .unwrap();
+        let b = issue
+            .add_task("Write tests", Some(a), Some(link), &node.signer)
+            .unwrap();
+        let c = issue
+            .add_task("Write docs", Some(b), None, &node.signer)
+            .unwrap();
+
+        issue.check_task(a, true, &node.signer).unwrap();
+        issue.move_task(c, None, &node.signer).unwrap();
+        assert_eq!(issue.progress(), (1, 3));
+
+        let id = issue.id;
+        let issue = issues.get(&id).unwrap().unwrap();
+        let tasks = issue.tasks().map(|(id, _)| *id).collect::<Vec<_>>();
+
+        assert_eq!(tasks, vec![c, a, b]);
+        assert!(issue.task(&a).unwrap().is_done());
+        assert_eq!(issue.task(&b).unwrap().link(), Some(&link));
+
+        let mut issue = issues.get_mut(&id).unwrap();
+        issue.remove_task(a, &node.signer).unwrap();
+        issue
+            .edit_task(b, "Write more tests", None, &node.signer)
+            .unwrap();
+
+        assert_eq!(issue.progress(), (0, 2));
+        assert_eq!(issue.task(&a), None);
+        assert_eq!(issue.task(&b).unwrap().body(), "Write more tests");
+        assert_eq!(issue.task(&b).unwrap().link(), None);
+
+        issue
+            .remove_task(arbitrary::entry_id(), &node.signer)
+            .unwrap_err();
+        issue
+            .check_task(arbitrary::entry_id(), true, &node.signer)
+            .unwrap_err();
+        // Removed tasks are left as-is, since they may have been removed concurrently.
+        issue.check_task(a, false, &node.signer).unwrap();
+        issue
+            .add_task("Invalid", Some(arbitrary::entry_id()), None, &node.signer)
+            .unwrap_err();
+        assert_eq!(issue.progress(), (0, 2));
+    }
+
     #[test]
     fn test_issue_multilines() {
         let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
@@ -1534,6 +2103,7 @@ mod test {
                 "Here's a binary file",
                 *issue.id,
                 [embed3.clone()],
+                [],
                 &node.signer,
             )
             .unwrap();
@@ -1621,7 +2191,7 @@ mod test {
         let missing = arbitrary::oid();
 
         issue
-            .comment("Invalid", missing, [], &node.signer)
+            .comment("Invalid", missing, [], [], &node.signer)
             .unwrap_err();
         assert_eq!(issue.comments().count(), 1);
         issue.reload().unwrap();
@@ -1657,7 +2227,8 @@ mod test {
         // An invalid comment which points to a missing parent.
         // Even creating it via a transaction will trigger an error.
         let mut tx = Transaction::<Issue, _>::default();
-        tx.comment("Invalid comment", missing, vec![]).unwrap();
+        tx.comment("Invalid comment", missing, vec![], vec![])
+            .unwrap();
         tx.commit("Add comment", issue.id, &mut issue.store.raw, &node.signer)
             .unwrap_err();
 
@@ -1743,7 +2314,7 @@ mod test {
         // Additionally, when adding a *valid* comment, it does not build upon the bad operation.
         issue.reload().unwrap();
         issue
-            .comment("Valid comment", *issue.id, vec![], &node.signer)
+            .comment("Valid comment", *issue.id, vec![], [], &node.signer)
             .unwrap();
         issue.reload().unwrap();
         assert_eq!(issue.comments().count(), 2);
@@ -1761,7 +2332,7 @@ mod test {
         // If Eve now writes a valid comment via the `Issue` type, it will overwrite her invalid
         // one, since it won't be loaded as a tip.
         issue
-            .comment("Eve's comment", *issue.id, vec![], &eve)
+            .comment("Eve's comment", *issue.id, vec![], [], &eve)
             .unwrap();
 
         let cob = cob::get::<NonEmpty<cob::Entry>, _>(&*repo, &type_name, issue.id())
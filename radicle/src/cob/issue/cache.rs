@@ -1,3 +1,4 @@
+use std::collections::BTreeSet;
 use std::ops::ControlFlow;
 use std::str::FromStr;
 
@@ -7,9 +8,11 @@ use thiserror::Error;
 use crate::cob;
 use crate::cob::cache;
 use crate::cob::cache::{Remove, StoreReader, StoreWriter, Update};
+use crate::cob::query::Query;
 use crate::cob::store;
-use crate::cob::{Embed, Label, ObjectId, TypeName};
+use crate::cob::{Embed, Label, ObjectId, Timestamp, TypeName};
 use crate::crypto::Signer;
+use crate::git;
 use crate::prelude::{Did, RepoId};
 use crate::sql::transaction;
 use crate::storage::{HasRepoId, ReadRepository, RepositoryError, SignRepository, WriteRepository};
@@ -32,6 +35,9 @@ pub trait Issues {
     /// List all issues that are in the store.
     fn list(&self) -> Result<Self::Iter<'_>, Self::Error>;
 
+    /// List all issues in the store that match the given [`Query`].
+    fn list_by_query(&self, query: &Query) -> Result<Self::Iter<'_>, Self::Error>;
+
     /// Get the [`IssueCounts`] of all the issues in the store.
     fn counts(&self) -> Result<IssueCounts, Self::Error>;
 
@@ -128,11 +134,8 @@ impl<'a, R, C> Cache<super::Issues<'a, R>, C> {
             .store
             .get(id)?
             .ok_or_else(|| store::Error::NotFound((*super::TYPENAME).clone(), *id))?;
-        self.update(&self.rid(), id, &issue)
-            .map_err(|e| super::Error::CacheUpdate {
-                id: *id,
-                err: e.into(),
-            })?;
+        cache::write(&mut self.cache, self.store.as_ref(), &typed_id(id), &issue)
+            .map_err(|err| super::Error::CacheUpdate { id: *id, err })?;
         Ok(())
     }
 
@@ -160,8 +163,8 @@ impl<'a, R, C> Cache<super::Issues<'a, R>, C> {
             match on_issue(&issue, &progress) {
                 ControlFlow::Continue(()) => match issue {
                     Ok((id, issue)) => {
-                        self.update(&self.rid(), &id, &issue)
-                            .map_err(|e| super::Error::CacheUpdate { id, err: e.into() })?;
+                        cache::write(&mut self.cache, self.store.as_ref(), &typed_id(&id), &issue)
+                            .map_err(|err| super::Error::CacheUpdate { id, err })?;
                     }
                     Err(_) => continue,
                 },
@@ -254,6 +257,14 @@ where
     ) -> Result<Self::Out, Self::UpdateError> {
         self.cache.update(rid, id, object)
     }
+
+    fn update_tips(
+        &mut self,
+        id: &cob::TypedId,
+        tips: &BTreeSet<git::Oid>,
+    ) -> Result<(), Self::UpdateError> {
+        self.cache.update_tips(id, tips)
+    }
 }
 
 impl<R, C> cache::Remove<Issue> for Cache<R, C>
@@ -268,12 +279,28 @@ where
     }
 }
 
+/// Time of the latest activity on an issue, ie. the time its latest comment was made.
+/// Comment edits and task changes don't count as activity.
+fn updated(issue: &Issue) -> Option<Timestamp> {
+    issue.comments().map(|(_, c)| c.timestamp()).max()
+}
+
+/// Get the typed identifier of an issue.
+fn typed_id(id: &ObjectId) -> cob::TypedId {
+    cob::TypedId {
+        id: *id,
+        type_name: super::TYPENAME.clone(),
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum UpdateError {
     #[error(transparent)]
     Json(#[from] serde_json::Error),
     #[error(transparent)]
     Sql(#[from] sql::Error),
+    #[error(transparent)]
+    Cache(#[from] cache::Error),
 }
 
 impl Update<Issue> for StoreWriter {
@@ -288,20 +315,36 @@ impl Update<Issue> for StoreWriter {
     ) -> Result<Self::Out, Self::UpdateError> {
         transaction::<_, UpdateError>(&self.db, move |db| {
             let mut stmt = db.prepare(
-                "INSERT INTO issues (id, repo, issue)
-                  VALUES (?1, ?2, ?3)
+                "INSERT INTO issues (id, repo, issue, tasks, updated)
+                  VALUES (?1, ?2, ?3, ?4, ?5)
                   ON CONFLICT DO UPDATE
-                  SET issue =  (?3)",
+                  SET issue =  (?3), tasks = (?4), updated = (?5)",
             )?;
+            let tasks = object.tasks().filter(|(_, t)| !t.done).count();
+            let updated = updated(object).map_or(sql::Value::Null, |t| {
+                sql::Value::Integer(t.as_millis() as i64)
+            });
 
             stmt.bind((1, sql::Value::String(id.to_string())))?;
             stmt.bind((2, rid))?;
             stmt.bind((3, sql::Value::String(serde_json::to_string(&object)?)))?;
+            stmt.bind((4, sql::Value::Integer(tasks as i64)))?;
+            stmt.bind((5, updated))?;
             stmt.next()?;
 
             Ok(db.change_count() > 0)
         })
     }
+
+    fn update_tips(
+        &mut self,
+        id: &cob::TypedId,
+        tips: &BTreeSet<git::Oid>,
+    ) -> Result<(), Self::UpdateError> {
+        self.set_tips(id, tips)?;
+
+        Ok(())
+    }
 }
 
 impl Remove<Issue> for StoreWriter {
@@ -355,6 +398,27 @@ where
             .map_err(super::Error::from)
     }
 
+    fn list_by_query(&self, query: &Query) -> Result<Self::Iter<'_>, Self::Error> {
+        // Without a cache, the query is run against a temporary in-memory one.
+        let rid = self.rid();
+        let mut cache =
+            StoreWriter::memory().map_err(|e| super::Error::CacheQuery { err: e.into() })?;
+
+        for result in self.store.all()? {
+            let (id, issue) = result?;
+            cache
+                .update(&rid, &id, &issue)
+                .map_err(|e| super::Error::CacheUpdate { id, err: e.into() })?;
+        }
+        let issues = query::list_by_query(&cache.db, &rid, query)
+            .and_then(|iter| iter.collect::<Result<Vec<_>, _>>())
+            .map_err(|e| super::Error::CacheQuery { err: e.into() })?;
+
+        Ok(NoCacheIter {
+            inner: Box::new(issues.into_iter().map(Ok)),
+        })
+    }
+
     fn counts(&self) -> Result<IssueCounts, Self::Error> {
         self.store.counts().map_err(super::Error::from)
     }
@@ -370,6 +434,8 @@ pub enum Error {
     Json(#[from] serde_json::Error),
     #[error(transparent)]
     Sql(#[from] sql::Error),
+    #[error(transparent)]
+    Query(#[from] cob::query::Error),
 }
 
 /// Iterator that returns a set of issues based on an SQL query.
@@ -412,6 +478,10 @@ where
         query::list(&self.cache.db, &self.rid())
     }
 
+    fn list_by_query(&self, query: &Query) -> Result<Self::Iter<'_>, Self::Error> {
+        query::list_by_query(&self.cache.db, &self.rid(), query)
+    }
+
     fn counts(&self) -> Result<IssueCounts, Self::Error> {
         query::counts(&self.cache.db, &self.rid())
     }
@@ -432,6 +502,10 @@ where
         query::list(&self.cache.db, &self.rid())
     }
 
+    fn list_by_query(&self, query: &Query) -> Result<Self::Iter<'_>, Self::Error> {
+        query::list_by_query(&self.cache.db, &self.rid(), query)
+    }
+
     fn counts(&self) -> Result<IssueCounts, Self::Error> {
         query::counts(&self.cache.db, &self.rid())
     }
@@ -484,6 +558,29 @@ mod query {
         })
     }
 
+    pub(super) fn list_by_query<'a>(
+        db: &'a sql::ConnectionThreadSafe,
+        rid: &RepoId,
+        query: &Query,
+    ) -> Result<IssuesIter<'a>, Error> {
+        let filter = query.sql(cob::query::Kind::Issue, 1)?;
+        let mut stmt = db.prepare(format!(
+            "SELECT id, issue
+             FROM issues
+             WHERE repo = ?1
+             AND {}
+             ORDER BY id
+            ",
+            filter.clause
+        ))?;
+        stmt.bind((1, rid))?;
+        filter.bind(&mut stmt, 1)?;
+
+        Ok(IssuesIter {
+            inner: stmt.into_iter(),
+        })
+    }
+
     pub(super) fn counts(
         db: &sql::ConnectionThreadSafe,
         rid: &RepoId,
@@ -519,10 +616,14 @@ mod tests {
     use std::str::FromStr;
 
     use radicle_cob::ObjectId;
+    use radicle_crdt::{Lamport, List};
 
     use crate::cob::cache::{Store, Update, Write};
-    use crate::cob::thread::Thread;
-    use crate::issue::{CloseReason, Issue, IssueCounts, IssueId, State};
+    use crate::cob::query::{self, Query};
+    use crate::cob::thread::{Comment, Thread};
+    use crate::cob::{Author, Label, Timestamp, TypedId};
+    use crate::issue::{CloseReason, Issue, IssueCounts, IssueId, State, Task};
+    use crate::prelude::Did;
     use crate::test::arbitrary;
     use crate::test::storage::MockRepository;
 
@@ -661,6 +762,114 @@ mod tests {
         assert_eq!(issues, list);
     }
 
+    #[test]
+    fn test_list_by_query() {
+        let repo = arbitrary::gen::<MockRepository>(1);
+        let mut cache = memory(repo);
+        let alice = arbitrary::gen::<Did>(1);
+        let bob = arbitrary::gen::<Did>(1);
+        let thread = |author: &Did| {
+            let comment = Comment::new(
+                **author,
+                String::from("Description"),
+                None,
+                None,
+                vec![],
+                vec![],
+                Timestamp::from_secs(1671125284),
+            );
+            Thread::new(arbitrary::oid(), comment)
+        };
+        let tasks = |done: bool| {
+            let id = arbitrary::entry_id();
+            let mut tasks = List::new();
+            tasks.insert(
+                None,
+                id,
+                (Lamport::default(), id, id),
+                Task {
+                    author: Author::new(alice),
+                    body: String::from("Task"),
+                    done,
+                    link: None,
+                    // Nb. Task times don't count as activity for `updated:` queries.
+                    timestamp: Timestamp::from_secs(1671840000),
+                },
+            );
+            tasks
+        };
+        let build = IssueId::from(arbitrary::oid());
+        let license = IssueId::from(arbitrary::oid());
+
+        cache
+            .update(
+                &cache.rid(),
+                &build,
+                &Issue {
+                    title: String::from("Fix the build"),
+                    labels: BTreeSet::from_iter([Label::new("bug").unwrap()]),
+                    assignees: BTreeSet::from_iter([bob]),
+                    tasks: tasks(false),
+                    ..Issue::new(thread(&alice))
+                },
+            )
+            .unwrap();
+        cache
+            .update(
+                &cache.rid(),
+                &license,
+                &Issue {
+                    title: String::from("Add a license"),
+                    state: State::Closed {
+                        reason: CloseReason::Solved,
+                    },
+                    tasks: tasks(true),
+                    ..Issue::new(thread(&bob))
+                },
+            )
+            .unwrap();
+
+        let list = |q: &str| {
+            cache
+                .list_by_query(&Query::from_str(q).unwrap())
+                .unwrap()
+                .map(|r| r.map(|(id, _)| id))
+                .collect::<Result<BTreeSet<_>, _>>()
+                .unwrap()
+        };
+        let both = BTreeSet::from_iter([build, license]);
+
+        assert_eq!(list(""), both);
+        assert_eq!(list("label:bug"), BTreeSet::from_iter([build]));
+        assert_eq!(list("-label:bug"), BTreeSet::from_iter([license]));
+        assert_eq!(
+            list(&format!("author:{alice}")),
+            BTreeSet::from_iter([build])
+        );
+        assert_eq!(
+            list(&format!("author:{}", bob.as_key())),
+            BTreeSet::from_iter([license])
+        );
+        assert_eq!(
+            list(&format!("assignee:{bob}")),
+            BTreeSet::from_iter([build])
+        );
+        assert_eq!(list("state:closed"), BTreeSet::from_iter([license]));
+        assert_eq!(list("LICENSE"), BTreeSet::from_iter([license]));
+        assert_eq!(list("updated:2022-12-15"), both);
+        assert_eq!(list("updated:>2022-12-15"), BTreeSet::new());
+        assert_eq!(
+            list("updated:<=2022-12-15 state:open"),
+            BTreeSet::from_iter([build])
+        );
+        assert_eq!(list("has:tasks"), BTreeSet::from_iter([build]));
+        assert_eq!(list("-has:tasks"), BTreeSet::from_iter([license]));
+        assert!(matches!(
+            cache.list_by_query(&Query::from_str("review:accepted").unwrap()),
+            Err(super::Error::Query(query::Error::Unsupported { .. }))
+        ));
+    }
+
     #[test]
     fn test_remove() {
         let repo = arbitrary::gen::<MockRepository>(1);
@@ -682,4 +891,36 @@ mod tests {
             assert_eq!(None, cache.get(id).unwrap());
         }
     }
+
+    #[test]
+    fn test_tips() {
+        let repo = arbitrary::gen::<MockRepository>(1);
+        let mut cache = memory(repo);
+        let rid = cache.rid();
+        let id = IssueId::from(arbitrary::oid());
+        let typed_id = TypedId {
+            id,
+            type_name: crate::issue::TYPENAME.clone(),
+        };
+        let tips = BTreeSet::from([arbitrary::oid(), arbitrary::oid()]);
+
+        assert!(!cache.cache.set_tips(&typed_id, &tips).unwrap());
+        assert_eq!(cache.cache.tips(&typed_id).unwrap(), None);
+
+        cache
+            .update(&rid, &id, &Issue::new(Thread::default()))
+            .unwrap();
+        assert_eq!(cache.cache.tips(&typed_id).unwrap(), None);
+        assert_eq!(cache.cache.stale().unwrap(), vec![(rid, typed_id.clone())]);
+
+        assert!(cache.cache.set_tips(&typed_id, &tips).unwrap());
+        assert_eq!(cache.cache.tips(&typed_id).unwrap(), Some(tips.clone()));
+        assert!(cache.cache.stale().unwrap().is_empty());
+
+        // Updating the object keeps the tips it was last evaluated from.
+        cache
+            .update(&rid, &id, &Issue::new(Thread::default()))
+            .unwrap();
+        assert_eq!(cache.cache.tips(&typed_id).unwrap(), Some(tips));
+    }
 }
@@ -13,13 +13,15 @@ use storage::{HasRepoId, RepositoryError};
 use thiserror::Error;
 
 use crate::cob;
-use crate::cob::common::{Author, Authorization, CodeLocation, Label, Reaction, Timestamp};
+use crate::cob::common::{Author, Authorization, CodeLocation, Label, Origin, Reaction, Timestamp};
 use crate::cob::store::Transaction;
 use crate::cob::store::{Cob, CobAction};
+use crate::cob::text;
+use crate::cob::text::Text;
 use crate::cob::thread;
 use crate::cob::thread::Thread;
 use crate::cob::thread::{Comment, CommentId, Edit, Reactions};
-use crate::cob::{op, store, ActorId, Embed, EntryId, ObjectId, TypeName, Uri};
+use crate::cob::{op, store, ActorId, Embed, EntryId, ObjectId, TypeName, TypedId, Uri};
 use crate::crypto::{PublicKey, Signer};
 use crate::git;
 use crate::identity::doc::DocError;
@@ -138,6 +140,11 @@ pub enum Error {
         #[source]
         err: Box<dyn std::error::Error + Send + Sync + 'static>,
     },
+    #[error("failed to query patches in cache: {err}")]
+    CacheQuery {
+        #[source]
+        err: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
 }
 
 /// Patch operation.
@@ -198,6 +205,9 @@ pub enum Action {
         /// Embeded content.
         #[serde(default, skip_serializing_if = "Vec::is_empty")]
         embeds: Vec<Embed<Uri>>,
+        /// Users mentioned in the comment.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        mentions: Vec<Did>,
     },
     #[serde(rename = "review.comment.edit")]
     ReviewCommentEdit {
@@ -205,6 +215,9 @@ pub enum Action {
         comment: EntryId,
         body: String,
         embeds: Vec<Embed<Uri>>,
+        /// Users mentioned in the comment.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        mentions: Vec<Did>,
     },
     #[serde(rename = "review.comment.redact")]
     ReviewCommentRedact { review: ReviewId, comment: EntryId },
@@ -251,6 +264,12 @@ pub enum Action {
     },
     #[serde(rename = "revision.redact")]
     RevisionRedact { revision: RevisionId },
+    /// Record where a revision was imported from.
+    #[serde(rename = "revision.origin")]
+    RevisionOrigin {
+        revision: RevisionId,
+        origin: Origin,
+    },
     #[serde(rename_all = "camelCase")]
     #[serde(rename = "revision.comment")]
     RevisionComment {
@@ -269,6 +288,9 @@ pub enum Action {
         /// Embeded content.
         #[serde(default, skip_serializing_if = "Vec::is_empty")]
         embeds: Vec<Embed<Uri>>,
+        /// Users mentioned in the comment.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        mentions: Vec<Did>,
     },
     /// Edit a revision comment.
     #[serde(rename = "revision.comment.edit")]
@@ -277,6 +299,9 @@ pub enum Action {
         comment: CommentId,
         body: String,
         embeds: Vec<Embed<Uri>>,
+        /// Users mentioned in the comment.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        mentions: Vec<Did>,
     },
     /// Redact a revision comment.
     #[serde(rename = "revision.comment.redact")]
@@ -697,8 +722,11 @@ impl Patch {
             }
             // Anyone can propose revisions.
             Action::Revision { .. } => Authorization::Allow,
-            // Only the revision author can edit or redact their revision.
-            Action::RevisionEdit { revision, .. } | Action::RevisionRedact { revision, .. } => {
+            // Only the revision author can edit or redact their revision, or record where it
+            // was imported from.
+            Action::RevisionEdit { revision, .. }
+            | Action::RevisionRedact { revision, .. }
+            | Action::RevisionOrigin { revision, .. } => {
                 if let Some(revision) = lookup::revision(self, revision)? {
                     Authorization::from(actor == revision.author.public_key())
                 } else {
@@ -739,7 +767,7 @@ impl Patch {
         entry: EntryId,
         author: ActorId,
         timestamp: Timestamp,
-        _concurrent: &[&cob::Entry],
+        concurrent: &[&cob::Entry],
         identity: &Doc<Verified>,
         repo: &R,
     ) -> Result<(), Error> {
@@ -781,11 +809,16 @@ impl Patch {
                 if let Some(redactable) = self.revisions.get_mut(&revision) {
                     // If the revision was redacted concurrently, there's nothing to do.
                     if let Some(revision) = redactable {
+                        // Merge the edit with concurrent edits of the description.
+                        let description =
+                            text::edit(&mut revision.text, entry, &description, concurrent);
+
                         revision.description.push(Edit::new(
                             author,
                             description,
                             timestamp,
                             embeds,
+                            vec![],
                         ));
                     }
                 } else {
@@ -823,16 +856,15 @@ impl Patch {
             } => {
                 debug_assert!(!self.revisions.contains_key(&entry));
 
+                let mut body = Text::new();
+                text::edit(&mut body, entry, &description, concurrent);
+
                 self.revisions.insert(
                     RevisionId(entry),
-                    Some(Revision::new(
-                        author.into(),
-                        description,
-                        base,
-                        oid,
-                        timestamp,
-                        resolves,
-                    )),
+                    Some(Revision {
+                        text: body,
+                        ..Revision::new(author.into(), description, base, oid, timestamp, resolves)
+                    }),
                 );
             }
             Action::RevisionReact {
@@ -852,6 +884,11 @@ impl Patch {
                     }
                 }
             }
+            Action::RevisionOrigin { revision, origin } => {
+                if let Some(revision) = lookup::revision_mut(self, &revision)? {
+                    revision.origin = Some(origin);
+                }
+            }
             Action::RevisionRedact { revision } => {
                 // Not allowed to delete the root revision.
                 let (root, _) = self.root();
@@ -924,6 +961,7 @@ impl Patch {
                 comment,
                 body,
                 embeds,
+                mentions,
             } => {
                 if let Some(review) = lookup::review_mut(self, &review)? {
                     thread::edit(
@@ -934,6 +972,7 @@ impl Patch {
                         timestamp,
                         body,
                         embeds,
+                        mentions,
                     )?;
                 }
             }
@@ -953,6 +992,7 @@ impl Patch {
                 location,
                 reply_to,
                 embeds,
+                mentions,
             } => {
                 if let Some(review) = lookup::review_mut(self, &review)? {
                     thread::comment(
@@ -964,6 +1004,7 @@ impl Patch {
                         reply_to,
                         location,
                         embeds,
+                        mentions,
                     )?;
                 }
             }
@@ -1061,6 +1102,7 @@ impl Patch {
                 reply_to,
                 embeds,
                 location,
+                mentions,
             } => {
                 if let Some(revision) = lookup::revision_mut(self, &revision)? {
                     thread::comment(
@@ -1072,6 +1114,7 @@ impl Patch {
                         reply_to,
                         location,
                         embeds,
+                        mentions,
                     )?;
                 }
             }
@@ -1080,6 +1123,7 @@ impl Patch {
                 comment,
                 body,
                 embeds,
+                mentions,
             } => {
                 if let Some(revision) = lookup::revision_mut(self, &revision)? {
                     thread::edit(
@@ -1090,6 +1134,7 @@ impl Patch {
                         timestamp,
                         body,
                         embeds,
+                        mentions,
                     )?;
                 }
             }
@@ -1143,14 +1188,20 @@ impl store::Cob for Patch {
         let Some(Action::Edit { title, target }) = actions.next() else {
             return Err(Error::Init("the second action must be of type `edit`"));
         };
-        let revision = Revision::new(
-            op.author.into(),
-            description,
-            base,
-            oid,
-            op.timestamp,
-            resolves,
-        );
+        let mut body = Text::new();
+        text::edit(&mut body, op.id, &description, &[]);
+
+        let revision = Revision {
+            text: body,
+            ..Revision::new(
+                op.author.into(),
+                description,
+                base,
+                oid,
+                op.timestamp,
+                resolves,
+            )
+        };
         let mut patch = Patch::new(title, target, (RevisionId(op.id), revision));
 
         for action in actions {
@@ -1348,6 +1399,13 @@ pub struct Revision {
         deserialize_with = "ser::deserialize_reactions"
     )]
     pub(super) reactions: BTreeMap<Option<CodeLocation>, Reactions>,
+    /// Revision description, merged from concurrent edits. The last description edit
+    /// is kept in sync with it.
+    #[serde(default)]
+    pub(super) text: Text,
+    /// Where the revision was imported from, if it was.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) origin: Option<Origin>,
 }
 
 impl Revision {
@@ -1359,7 +1417,13 @@ impl Revision {
         timestamp: Timestamp,
         resolves: BTreeSet<(EntryId, CommentId)>,
     ) -> Self {
-        let description = Edit::new(*author.public_key(), description, timestamp, Vec::default());
+        let description = Edit::new(
+            *author.public_key(),
+            description,
+            timestamp,
+            Vec::default(),
+            Vec::default(),
+        );
 
         Self {
             author,
@@ -1371,6 +1435,8 @@ impl Revision {
             timestamp,
             resolves,
             reactions: Default::default(),
+            text: Text::default(),
+            origin: None,
         }
     }
 
@@ -1415,6 +1481,11 @@ impl Revision {
         self.timestamp
     }
 
+    /// Where the revision was imported from, if it was.
+    pub fn origin(&self) -> Option<&Origin> {
+        self.origin.as_ref()
+    }
+
     /// Discussion around this revision.
     pub fn discussion(&self) -> &Thread<Comment<CodeLocation>> {
         &self.discussion
@@ -1670,6 +1741,7 @@ impl<R: ReadRepository> store::Transaction<Patch, R> {
             reply_to: None,
             location: None,
             embeds: vec![],
+            mentions: vec![],
         })
     }
 
@@ -1689,6 +1761,11 @@ impl<R: ReadRepository> store::Transaction<Patch, R> {
         })
     }
 
+    /// Record where a patch revision was imported from.
+    pub fn origin(&mut self, revision: RevisionId, origin: Origin) -> Result<(), store::Error> {
+        self.push(Action::RevisionOrigin { revision, origin })
+    }
+
     /// Comment on a patch revision.
     pub fn comment<S: ToString>(
         &mut self,
@@ -1697,6 +1774,7 @@ impl<R: ReadRepository> store::Transaction<Patch, R> {
         reply_to: Option<CommentId>,
         location: Option<CodeLocation>,
         embeds: Vec<Embed>,
+        mentions: Vec<Did>,
     ) -> Result<(), store::Error> {
         let hashed = embeds.iter().map(|e| e.hashed()).collect();
 
@@ -1707,6 +1785,7 @@ impl<R: ReadRepository> store::Transaction<Patch, R> {
             reply_to,
             location,
             embeds: hashed,
+            mentions,
         })
     }
 
@@ -1717,6 +1796,7 @@ impl<R: ReadRepository> store::Transaction<Patch, R> {
         comment: CommentId,
         body: S,
         embeds: Vec<Embed>,
+        mentions: Vec<Did>,
     ) -> Result<(), store::Error> {
         let hashed = embeds.iter().map(|e| e.hashed()).collect();
 
@@ -1726,6 +1806,7 @@ impl<R: ReadRepository> store::Transaction<Patch, R> {
             comment,
             body: body.to_string(),
             embeds: hashed,
+            mentions,
         })
     }
 
@@ -1762,6 +1843,7 @@ impl<R: ReadRepository> store::Transaction<Patch, R> {
         location: Option<CodeLocation>,
         reply_to: Option<CommentId>,
         embeds: Vec<Embed>,
+        mentions: Vec<Did>,
     ) -> Result<(), store::Error> {
         let hashed = embeds.iter().map(|e| e.hashed()).collect();
         self.embed(embeds)?;
@@ -1772,6 +1854,7 @@ impl<R: ReadRepository> store::Transaction<Patch, R> {
             location,
             reply_to,
             embeds: hashed,
+            mentions,
         })
     }
 
@@ -1800,6 +1883,7 @@ impl<R: ReadRepository> store::Transaction<Patch, R> {
         comment: EntryId,
         body: S,
         embeds: Vec<Embed>,
+        mentions: Vec<Did>,
     ) -> Result<(), store::Error> {
         let hashed = embeds.iter().map(|e| e.hashed()).collect();
 
@@ -1809,6 +1893,7 @@ impl<R: ReadRepository> store::Transaction<Patch, R> {
             comment,
             body: body.to_string(),
             embeds: hashed,
+            mentions,
         })
     }
 
@@ -1946,12 +2031,12 @@ where
         operations(&mut tx)?;
 
         let (patch, commit) = tx.commit(message, self.id, &mut self.store.raw, signer)?;
-        self.cache
-            .update(&self.store.as_ref().id(), &self.id, &patch)
-            .map_err(|e| Error::CacheUpdate {
-                id: self.id,
-                err: e.into(),
-            })?;
+        let typed_id = TypedId {
+            id: self.id,
+            type_name: TYPENAME.clone(),
+        };
+        cob::cache::write(self.cache, self.store.as_ref(), &typed_id, &patch)
+            .map_err(|err| Error::CacheUpdate { id: self.id, err })?;
         self.patch = patch;
 
         Ok(commit)
@@ -2012,7 +2097,7 @@ where
         self.transaction("Create thread", signer, |tx| tx.thread(revision, body))
     }
 
-    /// Comment on a patch revision.
+    /// Comment on a patch revision, mentioning the given users.
     pub fn comment<G: Signer, S: ToString>(
         &mut self,
         revision: RevisionId,
@@ -2020,6 +2105,7 @@ where
         reply_to: Option<CommentId>,
         location: Option<CodeLocation>,
         embeds: impl IntoIterator<Item = Embed>,
+        mentions: impl IntoIterator<Item = Did>,
         signer: &G,
     ) -> Result<EntryId, Error> {
         self.transaction("Comment", signer, |tx| {
@@ -2029,6 +2115,7 @@ where
                 reply_to,
                 location,
                 embeds.into_iter().collect(),
+                mentions.into_iter().collect(),
             )
         })
     }
@@ -2047,17 +2134,34 @@ where
         })
     }
 
-    /// Edit a comment on a patch revision.
+    /// Record where a patch revision was imported from.
+    pub fn origin<G: Signer>(
+        &mut self,
+        revision: RevisionId,
+        origin: Origin,
+        signer: &G,
+    ) -> Result<EntryId, Error> {
+        self.transaction("Origin", signer, |tx| tx.origin(revision, origin))
+    }
+
+    /// Edit a comment on a patch revision, mentioning the given users.
     pub fn comment_edit<G: Signer, S: ToString>(
         &mut self,
         revision: RevisionId,
         comment: CommentId,
         body: S,
         embeds: impl IntoIterator<Item = Embed>,
+        mentions: impl IntoIterator<Item = Did>,
         signer: &G,
     ) -> Result<EntryId, Error> {
         self.transaction("Edit comment", signer, |tx| {
-            tx.comment_edit(revision, comment, body, embeds.into_iter().collect())
+            tx.comment_edit(
+                revision,
+                comment,
+                body,
+                embeds.into_iter().collect(),
+                mentions.into_iter().collect(),
+            )
         })
     }
 
@@ -2095,6 +2199,7 @@ where
         location: Option<CodeLocation>,
         reply_to: Option<CommentId>,
         embeds: impl IntoIterator<Item = Embed>,
+        mentions: impl IntoIterator<Item = Did>,
         signer: &G,
     ) -> Result<EntryId, Error> {
         self.transaction("Review comment", signer, |tx| {
@@ -2104,6 +2209,7 @@ where
                 location,
                 reply_to,
                 embeds.into_iter().collect(),
+                mentions.into_iter().collect(),
             )
         })
     }
@@ -2115,10 +2221,17 @@ where
         comment: EntryId,
         body: S,
         embeds: impl IntoIterator<Item = Embed>,
+        mentions: impl IntoIterator<Item = Did>,
         signer: &G,
     ) -> Result<EntryId, Error> {
         self.transaction("Edit review comment", signer, |tx| {
-            tx.edit_review_comment(review, comment, body, embeds.into_iter().collect())
+            tx.edit_review_comment(
+                review,
+                comment,
+                body,
+                embeds.into_iter().collect(),
+                mentions.into_iter().collect(),
+            )
         })
     }
 
@@ -2360,6 +2473,14 @@ where
         Ok(Self { raw })
     }
 
+    /// Resume the evaluation of patches from the given snapshots when possible.
+    /// See [`store::Store::snapshots`].
+    pub fn snapshots(self, snapshots: impl store::Snapshots<Patch> + Send + Sync + 'a) -> Self {
+        Self {
+            raw: self.raw.snapshots(snapshots),
+        }
+    }
+
     /// Patches count by state.
     pub fn counts(&self) -> Result<PatchCounts, store::Error> {
         let all = self.all()?;
@@ -2539,9 +2660,12 @@ where
             }
             Ok(())
         })?;
-        cache
-            .update(&self.raw.as_ref().id(), &id, &patch)
-            .map_err(|e| Error::CacheUpdate { id, err: e.into() })?;
+        let typed_id = TypedId {
+            id,
+            type_name: TYPENAME.clone(),
+        };
+        cob::cache::write(cache, self.raw.as_ref(), &typed_id, &patch)
+            .map_err(|err| Error::CacheUpdate { id, err })?;
 
         Ok(PatchMut {
             id,
@@ -2809,7 +2933,15 @@ mod test {
         let (revision_id, _) = patch.revisions().last().unwrap();
         assert!(
             patch
-                .comment(revision_id, "patch comment", None, None, [], &alice.signer)
+                .comment(
+                    revision_id,
+                    "patch comment",
+                    None,
+                    None,
+                    [],
+                    [],
+                    &alice.signer
+                )
                 .is_ok(),
             "can comment on patch"
         );
@@ -3052,6 +3184,72 @@ mod test {
         assert_eq!(patch.revisions().count(), 1);
     }
 
+    #[test]
+    fn test_revision_edit_concurrent() {
+        let base = arbitrary::oid();
+        let oid = arbitrary::oid();
+        let repo = gen::<MockRepository>(1);
+        let time = Timestamp::now();
+        let alice = MockSigner::default();
+        let mut h0: cob::test::HistoryBuilder<Patch> = cob::test::history(
+            &[
+                Action::Revision {
+                    description: String::from("Intro\n\nDetails"),
+                    base,
+                    oid,
+                    resolves: Default::default(),
+                },
+                Action::Edit {
+                    title: String::from("Some patch"),
+                    target: MergeTarget::Delegates,
+                },
+            ],
+            time,
+            &alice,
+        );
+        let revision = RevisionId(*h0.root().id());
+
+        let mut h1 = h0.clone();
+        h1.commit(
+            &Action::RevisionEdit {
+                revision,
+                description: String::from("Better intro\n\nDetails"),
+                embeds: Vec::default(),
+            },
+            &alice,
+        );
+
+        let mut h2 = h0.clone();
+        h2.commit(
+            &Action::RevisionEdit {
+                revision,
+                description: String::from("Intro\n\nDetails\n\nTesting notes"),
+                embeds: Vec::default(),
+            },
+            &alice,
+        );
+
+        h0.merge(h1);
+        h0.merge(h2);
+
+        let patch = Patch::from_history(&h0, &repo).unwrap();
+        let (_, r) = patch.root();
+        assert_eq!(r.description(), "Better intro\n\nDetails\n\nTesting notes");
+
+        // An edit made after seeing both concurrent edits replaces the merged description.
+        h0.commit(
+            &Action::RevisionEdit {
+                revision,
+                description: String::from("Details"),
+                embeds: Vec::default(),
+            },
+            &alice,
+        );
+        let patch = Patch::from_history(&h0, &repo).unwrap();
+        let (_, r) = patch.root();
+        assert_eq!(r.description(), "Details");
+    }
+
     #[test]
     fn test_revision_reaction() {
         let base = git::Oid::from_str("cb18e95ada2bb38aadd8e6cef0963ce37a87add3").unwrap();
@@ -3168,6 +3366,7 @@ mod test {
                 Some(location.clone()),
                 None,
                 [],
+                [],
                 &alice.signer,
             )
             .unwrap();
@@ -1,3 +1,4 @@
+use std::collections::BTreeSet;
 use std::ops::ControlFlow;
 use std::str::FromStr;
 
@@ -7,8 +8,9 @@ use thiserror::Error;
 use crate::cob;
 use crate::cob::cache::{self, StoreReader};
 use crate::cob::cache::{Remove, StoreWriter, Update};
+use crate::cob::query::Query;
 use crate::cob::store;
-use crate::cob::{Label, ObjectId, TypeName};
+use crate::cob::{Label, ObjectId, Timestamp, TypeName};
 use crate::crypto::Signer;
 use crate::git;
 use crate::prelude::RepoId;
@@ -40,6 +42,9 @@ pub trait Patches {
     /// List all patches that are in the store.
     fn list(&self) -> Result<Self::Iter<'_>, Self::Error>;
 
+    /// List all patches in the store that match the given [`Query`].
+    fn list_by_query(&self, query: &Query) -> Result<Self::Iter<'_>, Self::Error>;
+
     /// List all patches in the store that match the provided
     /// `status`.
     ///
@@ -195,11 +200,8 @@ impl<'a, R, C> Cache<super::Patches<'a, R>, C> {
             .store
             .get(id)?
             .ok_or_else(|| store::Error::NotFound((*super::TYPENAME).clone(), *id))?;
-        self.update(&self.rid(), id, &issue)
-            .map_err(|e| super::Error::CacheUpdate {
-                id: *id,
-                err: e.into(),
-            })?;
+        cache::write(&mut self.cache, self.store.as_ref(), &typed_id(id), &issue)
+            .map_err(|err| super::Error::CacheUpdate { id: *id, err })?;
         Ok(())
     }
 
@@ -227,8 +229,8 @@ impl<'a, R, C> Cache<super::Patches<'a, R>, C> {
             match callback(&patch, &progress) {
                 ControlFlow::Continue(()) => match patch {
                     Ok((id, patch)) => {
-                        self.update(&self.rid(), &id, &patch)
-                            .map_err(|e| super::Error::CacheUpdate { id, err: e.into() })?;
+                        cache::write(&mut self.cache, self.store.as_ref(), &typed_id(&id), &patch)
+                            .map_err(|err| super::Error::CacheUpdate { id, err })?;
                     }
                     Err(_) => continue,
                 },
@@ -321,6 +323,14 @@ where
     ) -> Result<Self::Out, Self::UpdateError> {
         self.cache.update(rid, id, object)
     }
+
+    fn update_tips(
+        &mut self,
+        id: &cob::TypedId,
+        tips: &BTreeSet<git::Oid>,
+    ) -> Result<(), Self::UpdateError> {
+        self.cache.update_tips(id, tips)
+    }
 }
 
 impl<R, C> cache::Remove<Patch> for Cache<R, C>
@@ -335,12 +345,37 @@ where
     }
 }
 
+/// Get the typed identifier of a patch.
+fn typed_id(id: &ObjectId) -> cob::TypedId {
+    cob::TypedId {
+        id: *id,
+        type_name: super::TYPENAME.clone(),
+    }
+}
+
+/// Time of the latest activity on a patch, ie. the time its latest revision, comment,
+/// review or merge was made. Comment and description edits don't count as activity.
+fn updated(patch: &Patch) -> Option<Timestamp> {
+    let merges = patch.merges().map(|(_, m)| m.timestamp);
+    let revisions = patch.revisions().flat_map(|(_, r)| {
+        let comments = r.discussion().comments().map(|(_, c)| c.timestamp());
+        let reviews = r.reviews().flat_map(|(_, review)| {
+            let comments = review.comments().map(|(_, c)| c.timestamp());
+            comments.chain(Some(review.timestamp()))
+        });
+        comments.chain(reviews).chain(Some(r.timestamp()))
+    });
+    revisions.chain(merges).max()
+}
+
 #[derive(Debug, Error)]
 pub enum UpdateError {
     #[error(transparent)]
     Json(#[from] serde_json::Error),
     #[error(transparent)]
     Sql(#[from] sql::Error),
+    #[error(transparent)]
+    Cache(#[from] cache::Error),
 }
 
 impl Update<Patch> for StoreWriter {
@@ -355,20 +390,34 @@ impl Update<Patch> for StoreWriter {
     ) -> Result<Self::Out, Self::UpdateError> {
         transaction::<_, UpdateError>(&self.db, move |db| {
             let mut stmt = db.prepare(
-                "INSERT INTO patches (id, repo, patch)
-                  VALUES (?1, ?2, ?3)
+                "INSERT INTO patches (id, repo, patch, updated)
+                  VALUES (?1, ?2, ?3, ?4)
                   ON CONFLICT DO UPDATE
-                  SET patch =  (?3)",
+                  SET patch =  (?3), updated = (?4)",
             )?;
+            let updated = updated(object).map_or(sql::Value::Null, |t| {
+                sql::Value::Integer(t.as_millis() as i64)
+            });
 
             stmt.bind((1, sql::Value::String(id.to_string())))?;
             stmt.bind((2, rid))?;
             stmt.bind((3, sql::Value::String(serde_json::to_string(&object)?)))?;
+            stmt.bind((4, updated))?;
             stmt.next()?;
 
             Ok(db.change_count() > 0)
         })
     }
+
+    fn update_tips(
+        &mut self,
+        id: &cob::TypedId,
+        tips: &BTreeSet<git::Oid>,
+    ) -> Result<(), Self::UpdateError> {
+        self.set_tips(id, tips)?;
+
+        Ok(())
+    }
 }
 
 impl Remove<Patch> for StoreWriter {
@@ -400,6 +449,8 @@ pub enum Error {
     Json(#[from] serde_json::Error),
     #[error(transparent)]
     Sql(#[from] sql::Error),
+    #[error(transparent)]
+    Query(#[from] cob::query::Error),
 }
 
 /// Iterator that returns a set of patches based on an SQL query.
@@ -448,6 +499,10 @@ where
         query::list(&self.cache.db, &self.rid())
     }
 
+    fn list_by_query(&self, query: &Query) -> Result<Self::Iter<'_>, Self::Error> {
+        query::list_by_query(&self.cache.db, &self.rid(), query)
+    }
+
     fn list_by_status(&self, status: &Status) -> Result<Self::Iter<'_>, Self::Error> {
         query::list_by_status(&self.cache.db, &self.rid(), status)
     }
@@ -493,6 +548,27 @@ where
             .map_err(super::Error::from)
     }
 
+    fn list_by_query(&self, query: &Query) -> Result<Self::Iter<'_>, Self::Error> {
+        // Without a cache, the query is run against a temporary in-memory one.
+        let rid = self.rid();
+        let mut cache =
+            StoreWriter::memory().map_err(|e| super::Error::CacheQuery { err: e.into() })?;
+
+        for result in self.store.all()? {
+            let (id, patch) = result?;
+            cache
+                .update(&rid, &id, &patch)
+                .map_err(|e| super::Error::CacheUpdate { id, err: e.into() })?;
+        }
+        let patches = query::list_by_query(&cache.db, &rid, query)
+            .and_then(|iter| iter.collect::<Result<Vec<_>, _>>())
+            .map_err(|e| super::Error::CacheQuery { err: e.into() })?;
+
+        Ok(NoCacheIter {
+            inner: Box::new(patches.into_iter().map(Ok)),
+        })
+    }
+
     fn list_by_status(&self, status: &Status) -> Result<Self::Iter<'_>, Self::Error> {
         let status = *status;
         self.store
@@ -536,6 +612,10 @@ where
         query::list(&self.cache.db, &self.rid())
     }
 
+    fn list_by_query(&self, query: &Query) -> Result<Self::Iter<'_>, Self::Error> {
+        query::list_by_query(&self.cache.db, &self.rid(), query)
+    }
+
     fn list_by_status(&self, status: &Status) -> Result<Self::Iter<'_>, Self::Error> {
         query::list_by_status(&self.cache.db, &self.rid(), status)
     }
@@ -647,6 +727,29 @@ mod query {
         })
     }
 
+    pub(super) fn list_by_query<'a>(
+        db: &'a sql::ConnectionThreadSafe,
+        rid: &RepoId,
+        query: &Query,
+    ) -> Result<PatchesIter<'a>, Error> {
+        let filter = query.sql(cob::query::Kind::Patch, 1)?;
+        let mut stmt = db.prepare(format!(
+            "SELECT id, patch
+             FROM patches
+             WHERE repo = ?1
+             AND {}
+             ORDER BY id
+            ",
+            filter.clause
+        ))?;
+        stmt.bind((1, rid))?;
+        filter.bind(&mut stmt, 1)?;
+
+        Ok(PatchesIter {
+            inner: stmt.into_iter(),
+        })
+    }
+
     pub(super) fn counts(
         db: &sql::ConnectionThreadSafe,
         rid: &RepoId,
@@ -688,10 +791,12 @@ mod tests {
     use radicle_cob::ObjectId;
 
     use crate::cob::cache::{Store, Update, Write};
+    use crate::cob::query::{self, Query};
     use crate::cob::thread::{Comment, Thread};
-    use crate::cob::{Author, Timestamp};
+    use crate::cob::{Author, Label, Timestamp};
     use crate::patch::{
-        ByRevision, MergeTarget, Patch, PatchCounts, PatchId, Revision, RevisionId, State, Status,
+        ByRevision, MergeTarget, Patch, PatchCounts, PatchId, Review, Revision, RevisionId, State,
+        Status, Verdict,
     };
     use crate::prelude::Did;
     use crate::test::arbitrary;
@@ -725,6 +830,7 @@ mod tests {
             None,
             None,
             vec![],
+            vec![],
             Timestamp::now(),
         );
         let thread = Thread::new(arbitrary::oid(), comment);
@@ -953,6 +1059,105 @@ mod tests {
         assert_eq!(patches, list);
     }
 
+    #[test]
+    fn test_list_by_query() {
+        let repo = arbitrary::gen::<MockRepository>(1);
+        let mut cache = memory(repo);
+        let alice = arbitrary::gen::<Did>(1);
+        let bob = arbitrary::gen::<Did>(1);
+        let timestamp = Timestamp::from_secs(1671125284);
+        let revision = |author: &Did| {
+            Revision::new(
+                Author { id: *author },
+                String::from("Description"),
+                arbitrary::oid(),
+                arbitrary::oid(),
+                timestamp,
+                BTreeSet::new(),
+            )
+        };
+        let fix = PatchId::from(arbitrary::oid());
+        let docs = PatchId::from(arbitrary::oid());
+
+        let mut accepted = revision(&alice);
+        let mut review = Review::new(
+            Author { id: bob },
+            Some(Verdict::Accept),
+            None,
+            vec![],
+            // Reviews count as activity for `updated:` queries.
+            Timestamp::from_secs(1671840000),
+        );
+        review.comments = Thread::new(
+            arbitrary::oid(),
+            Comment::new(
+                *bob,
+                String::from("Nit"),
+                None,
+                None,
+                vec![],
+                vec![],
+                timestamp,
+            ),
+        );
+        accepted.reviews.insert(*bob, Some(review));
+
+        cache
+            .update(
+                &cache.rid(),
+                &fix,
+                &Patch::new(
+                    String::from("Fix the build"),
+                    MergeTarget::Delegates,
+                    (RevisionId::from(arbitrary::oid()), accepted),
+                ),
+            )
+            .unwrap();
+        cache
+            .update(
+                &cache.rid(),
+                &docs,
+                &Patch {
+                    state: State::Draft,
+                    labels: BTreeSet::from_iter([Label::new("docs").unwrap()]),
+                    assignees: BTreeSet::from_iter([*alice]),
+                    ..Patch::new(
+                        String::from("Improve documentation"),
+                        MergeTarget::Delegates,
+                        (RevisionId::from(arbitrary::oid()), revision(&bob)),
+                    )
+                },
+            )
+            .unwrap();
+
+        let list = |q: &str| {
+            cache
+                .list_by_query(&Query::from_str(q).unwrap())
+                .unwrap()
+                .map(|r| r.map(|(id, _)| id))
+                .collect::<Result<BTreeSet<_>, _>>()
+                .unwrap()
+        };
+
+        assert_eq!(list("review:accepted"), BTreeSet::from_iter([fix]));
+        assert_eq!(list("review:rejected"), BTreeSet::new());
+        assert_eq!(list("has:unresolved"), BTreeSet::from_iter([fix]));
+        assert_eq!(list("-has:unresolved"), BTreeSet::from_iter([docs]));
+        assert_eq!(list(&format!("author:{bob}")), BTreeSet::from_iter([docs]));
+        assert_eq!(
+            list(&format!("assignee:{alice}")),
+            BTreeSet::from_iter([docs])
+        );
+        assert_eq!(list("state:draft label:docs"), BTreeSet::from_iter([docs]));
+        assert_eq!(list("state:open"), BTreeSet::from_iter([fix]));
+        assert_eq!(list("updated:2022-12-15"), BTreeSet::from_iter([docs]));
+        assert_eq!(list("updated:>2022-12-15"), BTreeSet::from_iter([fix]));
+        assert!(matches!(
+            cache.list_by_query(&Query::from_str("state:closed").unwrap()),
+            Err(super::Error::Query(query::Error::Unsupported { .. }))
+        ));
+    }
+
     #[test]
     fn test_remove() {
         let repo = arbitrary::gen::<MockRepository>(1);
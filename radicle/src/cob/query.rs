@@ -0,0 +1,711 @@
+//! A small query language for filtering issues and patches in the COB cache.
+//!
+//! A query is a whitespace-separated list of terms, all of which must match:
+//!
+//! * `author:<did>`: the object was opened by the given user.
+//! * `label:<label>`: the object has the given label.
+//! * `assignee:<did>`: the given user is assigned to the object.
+//! * `state:<state>`: the object is in the given state, ie. `open` or `closed` for issues,
+//!   and `draft`, `open`, `archived` or `merged` for patches.
+//! * `updated:<op><date>`: the latest activity on the object happened before or after the
+//!   given `YYYY-MM-DD` date (UTC), where `<op>` is one of `>`, `>=`, `<` or `<=`. Without
+//!   an operator, the activity must have happened on that date.
+//! * `review:<verdict>`: the latest revision of the patch was `accepted` or `rejected` by a
+//!   reviewer.
+//! * `has:unresolved`: the patch has unresolved review comments.
+//! * `has:tasks`: the issue has tasks that aren't done yet.
+//! * `title:<text>`: the title contains the given text. Terms without a key are
+//!   interpreted as title terms.
+//!
+//! Terms are negated by prefixing them with `-`, and values containing whitespace can be
+//! surrounded by double quotes, eg. `-title:"fix typo"`. Within quotes, `\"` and `\\` stand
+//! for a literal quote and backslash.
+//!
+//! Queries are compiled to SQL against the COB cache tables with [`Query::sql`].
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use sqlite as sql;
+use thiserror::Error;
+
+use crate::cob::patch::Verdict;
+use crate::cob::Label;
+use crate::crypto::PublicKey;
+use crate::prelude::Did;
+
+/// Milliseconds in a day.
+const DAY: i64 = 24 * 60 * 60 * 1000;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum Error {
+    #[error("unterminated quote in query")]
+    UnterminatedQuote,
+    #[error("unknown query key '{0}'")]
+    UnknownKey(String),
+    #[error("invalid value '{value}' for query key '{key}'")]
+    InvalidValue { key: &'static str, value: String },
+    #[error("query term '{term}' cannot be used to filter {kind}s")]
+    Unsupported { term: String, kind: Kind },
+}
+
+/// The kind of object a query is compiled for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Kind {
+    Issue,
+    Patch,
+}
+
+impl Kind {
+    /// The cache table column holding the object JSON.
+    fn column(&self) -> &'static str {
+        match self {
+            Self::Issue => "issue",
+            Self::Patch => "patch",
+        }
+    }
+
+    /// The cache table holding objects of this kind.
+    fn table(&self) -> &'static str {
+        match self {
+            Self::Issue => "issues",
+            Self::Patch => "patches",
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.column())
+    }
+}
+
+/// Object state matched by a `state:` term.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Status {
+    Open,
+    Closed,
+    Draft,
+    Archived,
+    Merged,
+}
+
+impl Status {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Closed => "closed",
+            Self::Draft => "draft",
+            Self::Archived => "archived",
+            Self::Merged => "merged",
+        }
+    }
+
+    fn applies_to(&self, kind: Kind) -> bool {
+        match kind {
+            Kind::Issue => matches!(self, Self::Open | Self::Closed),
+            Kind::Patch => !matches!(self, Self::Closed),
+        }
+    }
+}
+
+impl FromStr for Status {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "open" => Ok(Self::Open),
+            "closed" => Ok(Self::Closed),
+            "draft" => Ok(Self::Draft),
+            "archived" => Ok(Self::Archived),
+            "merged" => Ok(Self::Merged),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Comparison of an `updated:` term.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Cmp {
+    /// On the given day.
+    On,
+    /// After the given day.
+    After,
+    /// On or after the given day.
+    Since,
+    /// Before the given day.
+    Before,
+    /// On or before the given day.
+    Until,
+}
+
+impl Cmp {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::On => "",
+            Self::After => ">",
+            Self::Since => ">=",
+            Self::Before => "<",
+            Self::Until => "<=",
+        }
+    }
+}
+
+/// A calendar date, in UTC.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+impl Date {
+    /// Construct a new date, returning `None` if it is invalid.
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Self> {
+        let days = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+            2 => 28,
+            _ => return None,
+        };
+        (1..=days)
+            .contains(&day)
+            .then_some(Self { year, month, day })
+    }
+
+    /// Milliseconds since the epoch, at the start of the day.
+    pub fn as_millis(&self) -> i64 {
+        // Days from civil, see <http://howardhinnant.github.io/date_algorithms.html>.
+        let (month, day) = (self.month as i64, self.day as i64);
+        let year = self.year as i64 - i64::from(month <= 2);
+        let era = year.div_euclid(400);
+        let yoe = year.rem_euclid(400);
+        let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+        (era * 146097 + doe - 719468) * DAY
+    }
+}
+
+impl FromStr for Date {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(3, '-');
+        let (Some(year), Some(month), Some(day)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(());
+        };
+        let year = year.parse().map_err(|_| ())?;
+        let month = month.parse().map_err(|_| ())?;
+        let day = day.parse().map_err(|_| ())?;
+
+        Self::new(year, month, day).ok_or(())
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// A single query filter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Filter {
+    /// `author:<did>`
+    Author(PublicKey),
+    /// `label:<label>`
+    Label(Label),
+    /// `assignee:<did>`
+    Assignee(PublicKey),
+    /// `state:<state>`
+    State(Status),
+    /// `updated:<op><date>`
+    Updated(Cmp, Date),
+    /// `review:<verdict>`
+    Review(Verdict),
+    /// `has:unresolved`
+    Unresolved,
+    /// `has:tasks`
+    Tasks,
+    /// `title:<text>`, or a term without a key.
+    Title(String),
+}
+
+impl Filter {
+    fn parse(term: &str) -> Result<Self, Error> {
+        let Some((key, value)) = term.split_once(':') else {
+            return Ok(Self::Title(term.to_owned()));
+        };
+        let invalid = |key: &'static str| Error::InvalidValue {
+            key,
+            value: value.to_owned(),
+        };
+
+        match key {
+            "author" => parse_key(value)
+                .map(Self::Author)
+                .ok_or_else(|| invalid("author")),
+            "assignee" => parse_key(value)
+                .map(Self::Assignee)
+                .ok_or_else(|| invalid("assignee")),
+            "label" => Label::new(value)
+                .map(Self::Label)
+                .map_err(|_| invalid("label")),
+            "state" => value.parse().map(Self::State).map_err(|_| invalid("state")),
+            "updated" => {
+                let (cmp, date) = if let Some(date) = value.strip_prefix(">=") {
+                    (Cmp::Since, date)
+                } else if let Some(date) = value.strip_prefix("<=") {
+                    (Cmp::Until, date)
+                } else if let Some(date) = value.strip_prefix('>') {
+                    (Cmp::After, date)
+                } else if let Some(date) = value.strip_prefix('<') {
+                    (Cmp::Before, date)
+                } else {
+                    (Cmp::On, value)
+                };
+                date.parse()
+                    .map(|date| Self::Updated(cmp, date))
+                    .map_err(|_| invalid("updated"))
+            }
+            "review" => match value {
+                "accepted" => Ok(Self::Review(Verdict::Accept)),
+                "rejected" => Ok(Self::Review(Verdict::Reject)),
+                _ => Err(invalid("review")),
+            },
+            "has" => match value {
+                "unresolved" => Ok(Self::Unresolved),
+                "tasks" => Ok(Self::Tasks),
+                _ => Err(invalid("has")),
+            },
+            "title" if !value.is_empty() => Ok(Self::Title(value.to_owned())),
+            "title" => Err(invalid("title")),
+            _ => Err(Error::UnknownKey(key.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Author(key) => write!(f, "author:{}", Did::from(*key)),
+            Self::Assignee(key) => write!(f, "assignee:{}", Did::from(*key)),
+            Self::Label(label) => write!(f, "label:{}", label.name()),
+            Self::State(status) => write!(f, "state:{}", status.as_str()),
+            Self::Updated(cmp, date) => write!(f, "updated:{}{date}", cmp.as_str()),
+            Self::Review(Verdict::Accept) => write!(f, "review:accepted"),
+            Self::Review(Verdict::Reject) => write!(f, "review:rejected"),
+            Self::Unresolved => write!(f, "has:unresolved"),
+            Self::Tasks => write!(f, "has:tasks"),
+            Self::Title(text) if text.contains(':') || text.starts_with('-') => {
+                write!(f, "title:{}", quote(text))
+            }
+            Self::Title(text) => write!(f, "{}", quote(text)),
+        }
+    }
+}
+
+/// A query term, ie. a possibly negated filter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Term {
+    /// Whether the filter must not match.
+    pub negated: bool,
+    /// The filter.
+    pub filter: Filter,
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negated {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", self.filter)
+    }
+}
+
+/// An SQL `WHERE` clause, and the values of its parameters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sql {
+    /// The clause. Parameters are numbered, starting after the offset given to [`Query::sql`].
+    pub clause: String,
+    /// Parameter values, in order.
+    pub params: Vec<sql::Value>,
+}
+
+impl Sql {
+    /// Bind the parameters to the statement, starting after the given offset.
+    pub fn bind(self, stmt: &mut sql::Statement, offset: usize) -> Result<(), sql::Error> {
+        for (i, value) in self.params.into_iter().enumerate() {
+            stmt.bind((offset + i + 1, value))?;
+        }
+        Ok(())
+    }
+}
+
+/// A query for issues or patches.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Query {
+    terms: Vec<Term>,
+}
+
+impl Query {
+    /// The query terms.
+    pub fn terms(&self) -> &[Term] {
+        &self.terms
+    }
+
+    /// Whether the query has no terms, and thus matches everything.
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Whether the query has a `state:` term.
+    pub fn has_state(&self) -> bool {
+        self.terms
+            .iter()
+            .any(|t| matches!(t.filter, Filter::State(_)))
+    }
+
+    /// Combine two queries into one that matches when both do.
+    pub fn and(mut self, other: Query) -> Self {
+        self.terms.extend(other.terms);
+        self
+    }
+
+    /// Compile the query to an SQL `WHERE` clause over the cache table of the given kind.
+    /// Parameters are numbered starting from `offset + 1`.
+    pub fn sql(&self, kind: Kind, offset: usize) -> Result<Sql, Error> {
+        let col = kind.column();
+        let mut clauses = Vec::new();
+        let mut params = Vec::new();
+        let mut param = |value: sql::Value| {
+            params.push(value);
+            format!("?{}", offset + params.len())
+        };
+        let unsupported = |term: &Term| Error::Unsupported {
+            term: term.to_string(),
+            kind,
+        };
+
+        for term in &self.terms {
+            let clause = match &term.filter {
+                Filter::Author(key) => match kind {
+                    Kind::Issue => format!(
+                        "json_extract({col}, '$.thread.comments.\"' || ({col}->>'$.thread.timeline[0]') || '\".author') = {}",
+                        param(sql::Value::String(key.to_string()))
+                    ),
+                    Kind::Patch => format!(
+                        "{col}->>'$.author.id' = {}",
+                        param(sql::Value::String(Did::from(*key).to_string()))
+                    ),
+                },
+                Filter::Assignee(key) => {
+                    // Issue assignees are stored as DIDs, patch assignees as keys.
+                    let value = match kind {
+                        Kind::Issue => Did::from(*key).to_string(),
+                        Kind::Patch => key.to_string(),
+                    };
+                    format!(
+                        "EXISTS (SELECT 1 FROM json_each({col}, '$.assignees') WHERE value = {})",
+                        param(sql::Value::String(value))
+                    )
+                }
+                Filter::Label(label) => format!(
+                    "EXISTS (SELECT 1 FROM json_each({col}, '$.labels') WHERE value = {})",
+                    param(sql::Value::String(label.name().to_owned()))
+                ),
+                Filter::State(status) if status.applies_to(kind) => format!(
+                    "{col}->>'$.state.status' = {}",
+                    param(sql::Value::String(status.as_str().to_owned()))
+                ),
+                Filter::State(_) => return Err(unsupported(term)),
+                Filter::Updated(cmp, date) => {
+                    // The time of the latest activity is stored alongside the cached object.
+                    let updated = format!("{}.updated", kind.table());
+                    let start = date.as_millis();
+                    let end = start + DAY;
+
+                    match cmp {
+                        Cmp::On => format!(
+                            "{updated} >= {} AND {updated} < {}",
+                            param(sql::Value::Integer(start)),
+                            param(sql::Value::Integer(end))
+                        ),
+                        Cmp::After => format!("{updated} >= {}", param(sql::Value::Integer(end))),
+                        Cmp::Since => format!("{updated} >= {}", param(sql::Value::Integer(start))),
+                        Cmp::Before => format!("{updated} < {}", param(sql::Value::Integer(start))),
+                        Cmp::Until => format!("{updated} < {}", param(sql::Value::Integer(end))),
+                    }
+                }
+                Filter::Review(verdict) if kind == Kind::Patch => {
+                    // The latest revision is the latest one by the patch author.
+                    let latest = format!(
+                        "(SELECT r.value
+                          FROM json_each({col}, '$.timeline') AS e
+                          JOIN json_each({col}, '$.revisions') AS r ON r.key = e.value
+                          WHERE r.value->>'$.author.id' = {col}->>'$.author.id'
+                          ORDER BY e.key DESC
+                          LIMIT 1)"
+                    );
+                    format!(
+                        "EXISTS (SELECT 1 FROM json_each({latest}, '$.reviews') AS v
+                                 WHERE v.value->>'$.verdict' = {})",
+                        param(sql::Value::String(verdict.to_string()))
+                    )
+                }
+                Filter::Unresolved if kind == Kind::Patch => format!(
+                    "EXISTS (SELECT 1 FROM json_tree({col}, '$.revisions') AS t
+                             WHERE t.key = 'resolved' AND t.value = 0
+                             AND t.fullkey LIKE '%.reviews.%')"
+                ),
+                // The number of open tasks is stored alongside the cached issue.
+                Filter::Tasks if kind == Kind::Issue => format!("{}.tasks > 0", kind.table()),
+                Filter::Review(_) | Filter::Unresolved | Filter::Tasks => {
+                    return Err(unsupported(term))
+                }
+                Filter::Title(text) => {
+                    let pattern = text
+                        .replace('\\', "\\\\")
+                        .replace('%', "\\%")
+                        .replace('_', "\\_");
+                    format!(
+                        "{col}->>'$.title' LIKE {} ESCAPE '\\'",
+                        param(sql::Value::String(format!("%{pattern}%")))
+                    )
+                }
+            };
+
+            if term.negated {
+                clauses.push(format!("NOT ({clause})"));
+            } else {
+                clauses.push(format!("({clause})"));
+            }
+        }
+
+        let clause = if clauses.is_empty() {
+            String::from("TRUE")
+        } else {
+            clauses.join(" AND ")
+        };
+        Ok(Sql { clause, params })
+    }
+}
+
+impl FromStr for Query {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let terms = tokenize(s)?
+            .into_iter()
+            .map(|token| {
+                let (negated, token) = match token.strip_prefix('-') {
+                    Some(rest) if !rest.is_empty() => (true, rest),
+                    _ => (false, token.as_str()),
+                };
+                Filter::parse(token).map(|filter| Term { negated, filter })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { terms })
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let terms = self.terms.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+
+        write!(f, "{}", terms.join(" "))
+    }
+}
+
+impl TryFrom<String> for Query {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Query> for String {
+    fn from(query: Query) -> Self {
+        query.to_string()
+    }
+}
+
+/// Split a query into terms, on whitespace outside of double quotes. Inside double quotes,
+/// a backslash escapes the next character.
+fn tokenize(s: &str) -> Result<Vec<String>, Error> {
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut quoted = false;
+    let mut started = false;
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if quoted => {
+                token.extend(chars.next());
+            }
+            '"' => {
+                quoted = !quoted;
+                started = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if started {
+                    tokens.push(std::mem::take(&mut token));
+                    started = false;
+                }
+            }
+            c => {
+                token.push(c);
+                started = true;
+            }
+        }
+    }
+    if quoted {
+        return Err(Error::UnterminatedQuote);
+    }
+    if started {
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+/// Quote a value if it contains whitespace, quotes or backslashes. Quotes and backslashes
+/// are escaped with a backslash.
+fn quote(value: &str) -> String {
+    if value.is_empty() || value.contains(|c: char| c.is_whitespace() || c == '"' || c == '\\') {
+        let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+
+        format!("\"{escaped}\"")
+    } else {
+        value.to_owned()
+    }
+}
+
+/// Parse a DID or a public key.
+fn parse_key(value: &str) -> Option<PublicKey> {
+    Did::from_str(value)
+        .map(|did| *did.as_key())
+        .or_else(|_| PublicKey::from_str(value))
+        .ok()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let query = Query::from_str(
+            r#"author:did:key:z6MknSLrJoTcukLrE435hVNQT4JUhbvWLX4kUzqkEStBU8Vi -title:"fix: typo" updated:>=2024-02-29 typo"#,
+        )
+        .unwrap();
+
+        assert_eq!(query.terms().len(), 4);
+        assert_eq!(
+            query.terms()[1],
+            Term {
+                negated: true,
+                filter: Filter::Title(String::from("fix: typo")),
+            }
+        );
+        assert_eq!(
+            query.terms()[2].filter,
+            Filter::Updated(Cmp::Since, Date::new(2024, 2, 29).unwrap())
+        );
+        assert_eq!(query.terms()[3].filter, Filter::Title(String::from("typo")));
+        assert_eq!(query.to_string().parse::<Query>().unwrap(), query);
+
+        assert_eq!(
+            Query::from_str("status:open"),
+            Err(Error::UnknownKey(String::from("status")))
+        );
+        assert_eq!(
+            Query::from_str("updated:2023-02-29"),
+            Err(Error::InvalidValue {
+                key: "updated",
+                value: String::from("2023-02-29")
+            })
+        );
+        assert_eq!(
+            Query::from_str("title:\"fix typo"),
+            Err(Error::UnterminatedQuote)
+        );
+    }
+
+    #[test]
+    fn test_quote_roundtrip() {
+        for title in [
+            "fix typo",
+            "say \"hello\"",
+            "C:\\Windows",
+            "trailing\\",
+            "\"",
+            "-negative",
+            "key: value",
+        ] {
+            let query = Query {
+                terms: vec![Term {
+                    negated: false,
+                    filter: Filter::Title(title.to_owned()),
+                }],
+            };
+            assert_eq!(
+                query.to_string().parse::<Query>().unwrap(),
+                query,
+                "{title}"
+            );
+        }
+        assert_eq!(
+            Query::from_str(r#""a \"b\" \\c""#).unwrap().terms()[0].filter,
+            Filter::Title(String::from(r#"a "b" \c"#))
+        );
+    }
+
+    #[test]
+    fn test_date() {
+        assert_eq!(Date::new(1970, 1, 1).unwrap().as_millis(), 0);
+        assert_eq!(
+            Date::new(2022, 12, 15).unwrap().as_millis(),
+            1671062400 * 1000
+        );
+        assert_eq!(Date::new(1969, 12, 31).unwrap().as_millis(), -DAY);
+    }
+
+    #[test]
+    fn test_sql() {
+        let query = Query::from_str("review:accepted").unwrap();
+
+        assert!(matches!(
+            query.sql(Kind::Issue, 1),
+            Err(Error::Unsupported { .. })
+        ));
+        assert!(matches!(
+            Query::from_str("has:tasks").unwrap().sql(Kind::Patch, 1),
+            Err(Error::Unsupported { .. })
+        ));
+
+        let sql = Query::from_str("state:open -label:bug")
+            .unwrap()
+            .sql(Kind::Issue, 1)
+            .unwrap();
+
+        assert_eq!(
+            sql.clause,
+            "(issue->>'$.state.status' = ?2) AND NOT (EXISTS (SELECT 1 FROM json_each(issue, '$.labels') WHERE value = ?3))"
+        );
+        assert_eq!(
+            sql.params,
+            vec![
+                sql::Value::String(String::from("open")),
+                sql::Value::String(String::from("bug"))
+            ]
+        );
+    }
+}
@@ -1,6 +1,7 @@
 //! Generic COB storage.
 #![allow(clippy::large_enum_variant)]
 #![allow(clippy::type_complexity)]
+use std::collections::BTreeSet;
 use std::fmt::Debug;
 use std::marker::PhantomData;
 
@@ -98,12 +99,28 @@ pub enum Error {
         #[source]
         err: git::raw::Error,
     },
+    #[error(transparent)]
+    Repository(Box<crate::storage::RepositoryError>),
+    #[error("objects of type `{0}` can't be created or updated from raw actions")]
+    Builtin(TypeName),
+}
+
+/// Snapshots of object states, which let a [`Store`] resume the evaluation of an object
+/// instead of replaying its entire history. See [`cob::Snapshot`].
+///
+/// Snapshots are an optimization: failing to read or write them should not be an error.
+pub trait Snapshots<T> {
+    /// Get the latest snapshot of an object in the given repository, if any.
+    fn snapshot(&self, rid: &RepoId, id: &ObjectId) -> Option<cob::Snapshot<T>>;
+    /// Save a snapshot of an object in the given repository, replacing any previous one.
+    fn save(&self, rid: &RepoId, id: &ObjectId, tips: &BTreeSet<EntryId>, object: &T);
 }
 
 /// Storage for collaborative objects of a specific type `T` in a single repository.
 pub struct Store<'a, T, R> {
     identity: Option<git::Oid>,
     repo: &'a R,
+    snapshots: Option<Box<dyn Snapshots<T> + Send + Sync + 'a>>,
     witness: PhantomData<T>,
 }
 
@@ -122,6 +139,7 @@ where
         Ok(Self {
             repo,
             identity: None,
+            snapshots: None,
             witness: PhantomData,
         })
     }
@@ -131,9 +149,19 @@ where
         Self {
             repo: self.repo,
             witness: self.witness,
+            snapshots: self.snapshots,
             identity: Some(identity),
         }
     }
+
+    /// Return a new store that evaluates objects from the given snapshots when possible,
+    /// and saves a new snapshot whenever it creates or updates an object.
+    pub fn snapshots(self, snapshots: impl Snapshots<T> + Send + Sync + 'a) -> Self {
+        Self {
+            snapshots: Some(Box::new(snapshots)),
+            ..self
+        }
+    }
 }
 
 impl<'a, T, R> Store<'a, T, R>
@@ -169,6 +197,7 @@ where
             },
         )?;
         self.repo.sign_refs(signer).map_err(Error::SignRefs)?;
+        self.save(&updated.object);
 
         Ok(updated)
     }
@@ -199,10 +228,23 @@ where
             },
         )?;
         self.repo.sign_refs(signer).map_err(Error::SignRefs)?;
+        self.save(&cob);
 
         Ok((*cob.id(), cob.object))
     }
 
+    /// Save a snapshot of an object we just wrote.
+    fn save(&self, cob: &CollaborativeObject<T>) {
+        if let Some(snapshots) = &self.snapshots {
+            snapshots.save(
+                &self.repo.id(),
+                cob.id(),
+                &cob.history().tips(),
+                cob.object(),
+            );
+        }
+    }
+
     /// Remove an object.
     pub fn remove<G: Signer>(&self, id: &ObjectId, signer: &G) -> Result<(), Error> {
         let name = git::refs::storage::cob(signer.public_key(), T::type_name(), id);
@@ -232,16 +274,21 @@ where
 {
     /// Get an object.
     pub fn get(&self, id: &ObjectId) -> Result<Option<T>, Error> {
-        cob::get::<T, _>(self.repo, T::type_name(), id)
-            .map(|r| r.map(|cob| cob.object))
-            .map_err(Error::from)
+        let rid = self.repo.id();
+        let snapshot = self.snapshots.as_ref().and_then(|s| s.snapshot(&rid, id));
+        let cob = cob::get_from::<T, _>(self.repo, T::type_name(), id, snapshot)?;
+
+        Ok(cob.map(|cob| cob.object))
     }
 
     /// Return all objects.
     pub fn all(
         &self,
     ) -> Result<impl ExactSizeIterator<Item = Result<(ObjectId, T), Error>> + 'a, Error> {
-        let raw = cob::list::<T, _>(self.repo, T::type_name())?;
+        let rid = self.repo.id();
+        let raw = cob::list_from::<T, _, _>(self.repo, T::type_name(), |id| {
+            self.snapshots.as_ref().and_then(|s| s.snapshot(&rid, id))
+        })?;
 
         Ok(raw.into_iter().map(|o| Ok((*o.id(), o.object))))
     }
@@ -358,6 +405,100 @@ pub fn ops<R: cob::Store>(
     }
 }
 
+/// Create an object of any type from JSON actions. Unlike [`Store::create`], the type doesn't
+/// have to be known to this crate, which lets tooling define its own collaborative objects.
+/// Objects of the types defined here can't be created this way.
+pub fn create_raw<R, G>(
+    repo: &R,
+    type_name: &TypeName,
+    message: &str,
+    actions: NonEmpty<serde_json::Value>,
+    embeds: Vec<Embed>,
+    signer: &G,
+) -> Result<ObjectId, Error>
+where
+    R: ReadRepository + SignRepository + cob::Store,
+    G: Signer,
+{
+    if is_builtin(type_name) {
+        return Err(Error::Builtin(type_name.clone()));
+    }
+    let identity = repo
+        .identity_head()
+        .map_err(|e| Error::Repository(Box::new(e)))?;
+    let contents = actions.try_map(encoding::encode)?;
+    let cob = cob::create::<NonEmpty<cob::Entry>, _, G>(
+        repo,
+        signer,
+        Some(identity),
+        vec![],
+        signer.public_key(),
+        Create {
+            type_name: type_name.clone(),
+            version: Version::default(),
+            message: message.to_owned(),
+            embeds,
+            contents,
+        },
+    )?;
+    repo.sign_refs(signer).map_err(Error::SignRefs)?;
+
+    Ok(*cob.id())
+}
+
+/// Update an object of any type with JSON actions. See [`create_raw`].
+pub fn update_raw<R, G>(
+    repo: &R,
+    type_name: &TypeName,
+    object_id: ObjectId,
+    message: &str,
+    actions: NonEmpty<serde_json::Value>,
+    embeds: Vec<Embed>,
+    signer: &G,
+) -> Result<EntryId, Error>
+where
+    R: ReadRepository + SignRepository + cob::Store,
+    G: Signer,
+{
+    if is_builtin(type_name) {
+        return Err(Error::Builtin(type_name.clone()));
+    }
+    let identity = repo
+        .identity_head()
+        .map_err(|e| Error::Repository(Box::new(e)))?;
+    let changes = actions.try_map(encoding::encode)?;
+    let updated = cob::update::<NonEmpty<cob::Entry>, _, G>(
+        repo,
+        signer,
+        Some(identity),
+        vec![],
+        signer.public_key(),
+        Update {
+            object_id,
+            type_name: type_name.clone(),
+            message: message.to_owned(),
+            embeds,
+            changes,
+        },
+    )?;
+    repo.sign_refs(signer).map_err(Error::SignRefs)?;
+
+    Ok(updated.head)
+}
+
+/// Whether objects of this type are defined by this crate, and must be managed through
+/// their typed API.
+fn is_builtin(type_name: &TypeName) -> bool {
+    [
+        &*cob::issue::TYPENAME,
+        &*cob::patch::TYPENAME,
+        &*cob::identity::TYPENAME,
+        &*cob::discussion::TYPENAME,
+        &*cob::wiki::TYPENAME,
+    ]
+    .contains(&type_name)
+}
+
 pub mod encoding {
     use serde::Serialize;
 
@@ -399,10 +540,24 @@ pub mod test {
         let children = history.children_of(root.id());
         let op = Op::try_from(root)?;
         let initial = T::from_root(op, repo).map_err(HistoryError::Apply)?;
-        let obj = history.traverse(initial, &children, |mut acc, _, entry| {
+        let graph = history.graph();
+        let obj = history.traverse(initial, &children, |mut acc, id, entry| {
+            // Entries that are neither ancestors nor descendants of this one.
+            let related = graph
+                .ancestors(id)
+                .into_iter()
+                .chain(graph.descendants(id))
+                .collect::<BTreeSet<_>>();
+            let concurrent = graph
+                .sorted()
+                .into_iter()
+                .filter(|k| k != id && !related.contains(k))
+                .filter_map(|k| graph.get(&k))
+                .map(|n| &n.value);
+
             match Op::try_from(entry) {
                 Ok(op) => {
-                    if let Err(err) = acc.op(op, [], repo) {
+                    if let Err(err) = acc.op(op, concurrent, repo) {
                         log::warn!("Error applying op to `{}` state: {err}", T::type_name());
                         return ControlFlow::Break(acc);
                     }
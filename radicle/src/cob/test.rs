@@ -100,9 +100,9 @@ where
 
     pub fn commit<G: Signer>(&mut self, action: &T::Action, signer: &G) -> git::ext::Oid {
         let timestamp = self.time;
-        let tips = self.tips();
+        let parents = self.tips().into_iter().collect::<Vec<_>>();
         let revision = arbitrary::oid();
-        let (data, oid) = encoded::<T, _>(action, timestamp, tips, signer);
+        let (data, oid) = encoded::<T, _>(action, timestamp, parents.clone(), signer);
         let manifest = Manifest::new(T::type_name().clone(), Version::default());
         let signature = signer.sign(data.as_slice());
         let signature = ExtendedSignature::new(*signer.public_key(), signature);
@@ -113,7 +113,7 @@ where
             contents: NonEmpty::new(data),
             timestamp: timestamp.as_secs(),
             revision,
-            parents: vec![],
+            parents,
             related: vec![],
             manifest,
         };
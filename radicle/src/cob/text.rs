@@ -0,0 +1,27 @@
+//! Text fields that can be edited concurrently.
+//!
+//! Concurrent edits are merged line by line, see [`radicle_crdt::Text`]. Changes to the
+//! same line are not merged, instead both versions of the line are kept.
+use crate::cob;
+use crate::cob::EntryId;
+
+/// Text that can be edited concurrently. Edits are identified by the operation that
+/// made them.
+pub type Text = radicle_crdt::Text<EntryId>;
+
+/// Check whether an operation has seen another operation that was applied before it.
+///
+/// Operations are applied in causal order, so any operation applied before it is either
+/// one of its ancestors in the change graph, or is concurrent with it.
+pub fn seen(concurrent: &[&cob::Entry], op: &EntryId) -> bool {
+    !concurrent.iter().any(|e| e.id() == op)
+}
+
+/// Edit a text on behalf of the operation being applied, and return the resulting text.
+///
+/// The given body is what the author wants the text to be, based on the text as they
+/// saw it. The edits of the `concurrent` operations, which they hadn't seen, are kept.
+pub fn edit(text: &mut Text, op: EntryId, body: &str, concurrent: &[&cob::Entry]) -> String {
+    text.edit(op, body, |e| seen(concurrent, e));
+    text.to_string()
+}
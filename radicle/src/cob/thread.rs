@@ -7,11 +7,12 @@ use serde::{ser::SerializeStruct, Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::cob;
-use crate::cob::common::{Reaction, Timestamp, Uri};
+use crate::cob::common::{Origin, Reaction, Timestamp, Uri};
 use crate::cob::store::Cob;
 use crate::cob::{op, ActorId, Embed, EntryId, Op};
 use crate::git;
-use crate::prelude::ReadRepository;
+use crate::node::{Alias, AliasStore};
+use crate::prelude::{Did, ReadRepository};
 
 /// Type name of a thread, as well as the domain for all thread operations.
 /// Note that threads are not usually used standalone. They are embeded into other COBs.
@@ -64,6 +65,9 @@ pub struct Edit {
     pub body: String,
     /// Edit embed list.
     pub embeds: Vec<Embed<Uri>>,
+    /// Users mentioned in the edit.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mentions: Vec<Did>,
 }
 
 impl Edit {
@@ -73,16 +77,62 @@ impl Edit {
         body: String,
         timestamp: Timestamp,
         embeds: Vec<Embed<Uri>>,
+        mentions: Vec<Did>,
     ) -> Self {
         Self {
             author,
             timestamp,
             body,
             embeds,
+            mentions,
         }
     }
 }
 
+/// Parse the users mentioned in a comment body.
+///
+/// Users are mentioned with `@did:key:...`, or with `@alias`, where the alias is
+/// resolved with the given store. Aliases that don't resolve to exactly one node
+/// are ignored.
+pub fn mentions(body: &str, aliases: &impl AliasStore) -> Vec<Did> {
+    let mut mentions = Vec::new();
+    let mut prev = None::<char>;
+
+    for (i, c) in body.char_indices() {
+        let start = c == '@' && !prev.is_some_and(|p| p.is_alphanumeric() || p == '@');
+        prev = Some(c);
+
+        if !start {
+            continue;
+        }
+        let rest = &body[i + c.len_utf8()..];
+        let end = rest
+            .find(|c: char| c.is_whitespace() || ",;!?()[]{}<>\"'`@".contains(c))
+            .unwrap_or(rest.len());
+        let name = rest[..end].trim_end_matches(['.', ':']);
+
+        let did = if name.starts_with("did:") {
+            Did::from_str(name).ok()
+        } else if let Ok(alias) = Alias::from_str(name) {
+            let nodes = aliases.reverse_lookup(&alias);
+            let mut nodes = nodes.into_iter();
+
+            match (nodes.next(), nodes.next()) {
+                (Some(nid), None) => Some(Did::from(nid)),
+                _ => None,
+            }
+        } else {
+            None
+        };
+        if let Some(did) = did {
+            if !mentions.contains(&did) {
+                mentions.push(did);
+            }
+        }
+    }
+    mentions
+}
+
 /// The `Infallible` type does not have a `Serialize`/`Deserialize`
 /// implementation. The `Never` type imitates `Infallible` and
 /// provides the derived implementations.
@@ -106,6 +156,9 @@ pub struct Comment<T = Never> {
     pub(in crate::cob) location: Option<T>,
     /// Whether the comment has been resolved.
     pub(in crate::cob) resolved: bool,
+    /// Where the comment was imported from, if it was.
+    #[serde(default)]
+    pub(in crate::cob) origin: Option<Origin>,
 }
 
 impl<T: Serialize> Serialize for Comment<T> {
@@ -113,7 +166,7 @@ impl<T: Serialize> Serialize for Comment<T> {
     where
         S: serde::ser::Serializer,
     {
-        let mut state = serializer.serialize_struct("Comment", 8)?;
+        let mut state = serializer.serialize_struct("Comment", 10)?;
         state.serialize_field("author", &self.author())?;
         if let Some(to) = self.reply_to {
             state.serialize_field("replyTo", &to)?;
@@ -125,11 +178,18 @@ impl<T: Serialize> Serialize for Comment<T> {
         if let Some(location) = self.location() {
             state.serialize_field("location", &location)?;
         }
+        if let Some(origin) = self.origin() {
+            state.serialize_field("origin", origin)?;
+        }
 
         let embeds = self.embeds();
         if !embeds.is_empty() {
             state.serialize_field("embeds", self.embeds())?;
         }
+        let mentions = self.mentions();
+        if !mentions.is_empty() {
+            state.serialize_field("mentions", mentions)?;
+        }
         state.end()
     }
 }
@@ -142,9 +202,10 @@ impl<L> Comment<L> {
         reply_to: Option<CommentId>,
         location: Option<L>,
         embeds: Vec<Embed<Uri>>,
+        mentions: Vec<Did>,
         timestamp: Timestamp,
     ) -> Self {
-        let edit = Edit::new(author, body, timestamp, embeds);
+        let edit = Edit::new(author, body, timestamp, embeds, mentions);
 
         Self {
             author,
@@ -153,6 +214,7 @@ impl<L> Comment<L> {
             reply_to,
             location,
             resolved: false,
+            origin: None,
         }
     }
 
@@ -194,9 +256,11 @@ impl<L> Comment<L> {
         author: ActorId,
         body: String,
         embeds: Vec<Embed<Uri>>,
+        mentions: Vec<Did>,
         timestamp: Timestamp,
     ) {
-        self.edits.push(Edit::new(author, body, timestamp, embeds));
+        self.edits
+            .push(Edit::new(author, body, timestamp, embeds, mentions));
     }
 
     /// Comment reactions.
@@ -219,6 +283,11 @@ impl<L> Comment<L> {
         self.resolved
     }
 
+    /// Where the comment was imported from, if it was.
+    pub fn origin(&self) -> Option<&Origin> {
+        self.origin.as_ref()
+    }
+
     /// Return the embedded media.
     pub fn embeds(&self) -> &[Embed<Uri>] {
         // SAFETY: There is always at least one edit. This is guaranteed by the [`Comment`]
@@ -227,6 +296,14 @@ impl<L> Comment<L> {
         &self.edits.last().unwrap().embeds
     }
 
+    /// Return the users mentioned in the comment.
+    pub fn mentions(&self) -> &[Did] {
+        // SAFETY: There is always at least one edit. This is guaranteed by the [`Comment`]
+        // constructor.
+        #[allow(clippy::unwrap_used)]
+        &self.edits.last().unwrap().mentions
+    }
+
     pub fn resolve(&mut self) {
         self.resolved = true;
     }
@@ -363,10 +440,20 @@ impl Thread {
     ) -> Result<(), Error> {
         match action {
             Action::Comment { body, reply_to } => {
-                comment(self, entry, author, timestamp, body, reply_to, None, vec![])?;
+                comment(
+                    self,
+                    entry,
+                    author,
+                    timestamp,
+                    body,
+                    reply_to,
+                    None,
+                    vec![],
+                    vec![],
+                )?;
             }
             Action::Edit { id, body } => {
-                edit(self, entry, author, id, timestamp, body, vec![])?;
+                edit(self, entry, author, id, timestamp, body, vec![], vec![])?;
             }
             Action::Redact { id } => {
                 redact(self, entry, id)?;
@@ -431,6 +518,7 @@ impl cob::store::Cob for Thread {
             None,
             None,
             vec![],
+            vec![],
         )?;
 
         for action in actions {
@@ -493,6 +581,7 @@ pub fn comment<L>(
     reply_to: Option<CommentId>,
     location: Option<L>,
     embeds: Vec<Embed<Uri>>,
+    mentions: Vec<Did>,
 ) -> Result<(), Error> {
     if body.is_empty() {
         return Err(Error::Comment(id));
@@ -510,7 +599,7 @@ pub fn comment<L>(
     thread.comments.insert(
         id,
         Some(Comment::new(
-            author, body, reply_to, location, embeds, timestamp,
+            author, body, reply_to, location, embeds, mentions, timestamp,
         )),
     );
 
@@ -525,6 +614,7 @@ pub fn edit<L>(
     timestamp: Timestamp,
     body: String,
     embeds: Vec<Embed<Uri>>,
+    mentions: Vec<Did>,
 ) -> Result<(), Error> {
     if body.is_empty() {
         return Err(Error::Edit(id));
@@ -539,7 +629,7 @@ pub fn edit<L>(
     // that as an error.
     if let Some(comment) = thread.comments.get_mut(&comment) {
         if let Some(comment) = comment {
-            comment.edit(author, body, embeds, timestamp);
+            comment.edit(author, body, embeds, mentions, timestamp);
         }
     } else {
         return Err(Error::Missing(comment));
@@ -584,6 +674,24 @@ pub fn react<T>(
     Ok(())
 }
 
+pub fn origin<T>(
+    thread: &mut Thread<Comment<T>>,
+    id: EntryId,
+    comment: EntryId,
+    origin: Origin,
+) -> Result<(), Error> {
+    let Some(comment) = thread.comments.get_mut(&comment) else {
+        return Err(Error::Missing(comment));
+    };
+    if let Some(comment) = comment {
+        debug_assert!(!thread.timeline.contains(&id));
+        thread.timeline.push(id);
+
+        comment.origin = Some(origin);
+    }
+    Ok(())
+}
+
 pub fn resolve<T>(
     thread: &mut Thread<Comment<T>>,
     id: EntryId,
@@ -908,6 +1016,31 @@ mod tests {
         assert_eq!(comment.body(), "Hoho World!");
     }
 
+    #[test]
+    fn test_mentions() {
+        let alice = MockSigner::default();
+        let bob = MockSigner::default();
+        let eve = MockSigner::default();
+        let aliases = std::collections::HashMap::from_iter([
+            (*alice.public_key(), Alias::new("alice")),
+            (*bob.public_key(), Alias::new("bob")),
+            (*eve.public_key(), Alias::new("bob")),
+        ]);
+        let alice = Did::from(*alice.public_key());
+        let carol = Did::from(*MockSigner::default().public_key());
+
+        assert_eq!(
+            mentions(&format!("Hey @alice, have a look. cc @{carol}."), &aliases),
+            vec![alice, carol]
+        );
+        // Repeated mentions are only recorded once.
+        assert_eq!(mentions("@alice: ping @alice!", &aliases), vec![alice]);
+        // Ambiguous and unknown aliases are ignored.
+        assert_eq!(mentions("@bob @dave", &aliases), vec![]);
+        // E-mail addresses aren't mentions.
+        assert_eq!(mentions("alice@alice.xyz", &aliases), vec![]);
+    }
+
     #[test]
     fn test_comment_redact_missing() {
         let repo = gen::<MockRepository>(1);
@@ -0,0 +1,880 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Deref;
+use std::path::Path;
+use std::str::FromStr;
+
+use once_cell::sync::Lazy;
+use radicle_crdt::clock::Lamport;
+use radicle_crdt::{LWWReg, Max, Redactable};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::cob;
+use crate::cob::common::{Author, Authorization, Timestamp, Uri};
+use crate::cob::store::Transaction;
+use crate::cob::store::{Cob, CobAction};
+use crate::cob::{op, store, ActorId, Embed, EntryId, ObjectId, TypeName};
+use crate::crypto::Signer;
+use crate::git;
+use crate::identity::doc::{Doc, DocError};
+use crate::prelude::{ReadRepository, RepoId, Verified};
+use crate::storage::{HasRepoId, RepositoryError, WriteRepository};
+
+/// Wiki page operation.
+pub type Op = cob::Op<Action>;
+
+/// Type name of a wiki page.
+pub static TYPENAME: Lazy<TypeName> =
+    Lazy::new(|| FromStr::from_str("xyz.radicle.wiki").expect("type name is valid"));
+
+/// Identifier for a wiki page.
+pub type PageId = ObjectId;
+
+/// Identifier for a page revision. This is the id of the operation that created it.
+pub type RevisionId = EntryId;
+
+/// Error updating or creating wiki pages.
+#[derive(Error, Debug)]
+pub enum Error {
+    /// Error loading the identity document.
+    #[error("identity doc failed to load: {0}")]
+    Doc(#[from] DocError),
+    #[error("store: {0}")]
+    Store(#[from] store::Error),
+    #[error("git: {0}")]
+    Git(#[from] git::raw::Error),
+    /// Action not authorized.
+    #[error("{0} not authorized to apply {1:?}")]
+    NotAuthorized(ActorId, Action),
+    /// Action not allowed.
+    #[error("action is not allowed: {0}")]
+    NotAllowed(EntryId),
+    /// Title is invalid.
+    #[error("invalid title: {0:?}")]
+    InvalidTitle(String),
+    /// Another page already has this path.
+    #[error("a page with path '{0}' already exists")]
+    PathExists(PagePath),
+    /// The revision doesn't exist.
+    #[error("revision {0} not found")]
+    MissingRevision(RevisionId),
+    /// The revision was redacted.
+    #[error("revision {0} was redacted")]
+    RedactedRevision(RevisionId),
+    /// The identity doc is missing.
+    #[error("identity document missing")]
+    MissingIdentity,
+    /// General error initializing a page.
+    #[error("initialization failed: {0}")]
+    Init(&'static str),
+    /// Error decoding an operation.
+    #[error("op decoding failed: {0}")]
+    Op(#[from] op::OpEncodingError),
+}
+
+/// Error parsing a page path.
+#[derive(Error, Debug)]
+#[error("invalid page path {0:?}")]
+pub struct PagePathError(String);
+
+/// Path of a wiki page, eg. `guides/install`.
+///
+/// Paths are made of non-empty segments separated by `/`, without whitespace,
+/// and without `.` or `..` segments.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PagePath(String);
+
+impl PagePath {
+    pub fn new(path: impl ToString) -> Result<Self, PagePathError> {
+        let path = path.to_string();
+        let valid = !path.is_empty()
+            && !path.chars().any(|c| c.is_whitespace() || c.is_control())
+            && path
+                .split('/')
+                .all(|s| !s.is_empty() && s != "." && s != "..");
+
+        if !valid {
+            return Err(PagePathError(path));
+        }
+        Ok(Self(path))
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl FromStr for PagePath {
+    type Err = PagePathError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl TryFrom<String> for PagePath {
+    type Error = PagePathError;
+
+    fn try_from(path: String) -> Result<Self, Self::Error> {
+        Self::new(path)
+    }
+}
+
+impl fmt::Display for PagePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<PagePath> for String {
+    fn from(PagePath(path): PagePath) -> Self {
+        path
+    }
+}
+
+/// A revision of a page body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Revision {
+    /// Author of the revision.
+    author: Author,
+    /// Markdown body of the page.
+    body: String,
+    /// Content embedded in the body.
+    embeds: Vec<Embed<Uri>>,
+    /// When the revision was made.
+    timestamp: Timestamp,
+}
+
+impl Revision {
+    pub fn author(&self) -> &Author {
+        &self.author
+    }
+
+    pub fn body(&self) -> &str {
+        self.body.as_str()
+    }
+
+    pub fn embeds(&self) -> &[Embed<Uri>] {
+        &self.embeds
+    }
+
+    pub fn timestamp(&self) -> Timestamp {
+        self.timestamp
+    }
+}
+
+/// Wiki page state. Accumulates [`Action`].
+///
+/// Concurrent edits are resolved with last-write-wins registers, ordered by a Lamport
+/// clock derived from the causal history of each operation. Ties are broken by value,
+/// so that all peers converge on the same state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page {
+    /// Title of the page.
+    title: LWWReg<Max<String>>,
+    /// Path of the page within the wiki.
+    path: LWWReg<Max<PagePath>>,
+    /// Current revision of the page body.
+    head: LWWReg<Max<RevisionId>>,
+    /// All revisions of the page body, in history order.
+    revisions: Vec<(RevisionId, Redactable<Revision>)>,
+    /// Clock of every operation applied to the page.
+    clocks: BTreeMap<EntryId, Lamport>,
+    /// Author of the page.
+    author: Author,
+    /// When the page was created.
+    timestamp: Timestamp,
+}
+
+impl store::Cob for Page {
+    type Action = Action;
+    type Error = Error;
+
+    fn type_name() -> &'static TypeName {
+        &TYPENAME
+    }
+
+    fn from_root<R: ReadRepository>(op: Op, _repo: &R) -> Result<Self, Self::Error> {
+        let mut title = None;
+        let mut path = None;
+        let mut revision = None;
+
+        for action in op.actions {
+            match action {
+                Action::Edit { title: t } => {
+                    title = Some(validate_title(t)?);
+                }
+                Action::Move { path: p } => {
+                    path = Some(p);
+                }
+                Action::Revise { body, embeds } => {
+                    revision = Some(Revision {
+                        author: Author::new(op.author),
+                        body,
+                        embeds,
+                        timestamp: op.timestamp,
+                    });
+                }
+                Action::Redact { .. } => {
+                    return Err(Error::Init("the root operation can't redact revisions"));
+                }
+            }
+        }
+        let (Some(title), Some(path), Some(revision)) = (title, path, revision) else {
+            return Err(Error::Init(
+                "the root operation must set a title, a path and a body",
+            ));
+        };
+        let clock = Lamport::initial().tick();
+
+        Ok(Self {
+            title: LWWReg::new(Max::from(title), clock),
+            path: LWWReg::new(Max::from(path), clock),
+            head: LWWReg::new(Max::from(op.id), clock),
+            revisions: vec![(op.id, Redactable::Present(revision))],
+            clocks: BTreeMap::from([(op.id, clock)]),
+            author: Author::new(op.author),
+            timestamp: op.timestamp,
+        })
+    }
+
+    fn op<'a, R: ReadRepository, I: IntoIterator<Item = &'a cob::Entry>>(
+        &mut self,
+        op: Op,
+        _concurrent: I,
+        repo: &R,
+    ) -> Result<(), Error> {
+        let doc = op.identity_doc(repo)?.ok_or(Error::MissingIdentity)?;
+        let clock = self.clock(&op);
+
+        for action in op.actions {
+            match self.authorization(&action, &op.author, &doc)? {
+                Authorization::Allow => {
+                    self.action(action, op.id, op.author, op.timestamp, clock)?;
+                }
+                Authorization::Deny => {
+                    return Err(Error::NotAuthorized(op.author, action));
+                }
+                Authorization::Unknown => {
+                    continue;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R: ReadRepository> cob::Evaluate<R> for Page {
+    type Error = Error;
+
+    fn init(entry: &cob::Entry, repo: &R) -> Result<Self, Self::Error> {
+        let op = Op::try_from(entry)?;
+        let object = Page::from_root(op, repo)?;
+
+        Ok(object)
+    }
+
+    fn apply<'a, I: Iterator<Item = (&'a EntryId, &'a cob::Entry)>>(
+        &mut self,
+        entry: &cob::Entry,
+        concurrent: I,
+        repo: &R,
+    ) -> Result<(), Self::Error> {
+        let op = Op::try_from(entry)?;
+
+        self.op(op, concurrent.map(|(_, e)| e), repo)
+    }
+}
+
+impl Page {
+    pub fn title(&self) -> &str {
+        self.title.get().get()
+    }
+
+    pub fn path(&self) -> &PagePath {
+        self.path.get().get()
+    }
+
+    /// The current revision of the page.
+    pub fn revision(&self) -> (&RevisionId, Option<&Revision>) {
+        let id = self.head.get().get();
+
+        (id, self.revision_by_id(id))
+    }
+
+    /// The current body of the page.
+    pub fn body(&self) -> &str {
+        self.revision().1.map_or("", |r| r.body())
+    }
+
+    pub fn author(&self) -> &Author {
+        &self.author
+    }
+
+    /// When the page was created.
+    pub fn timestamp(&self) -> Timestamp {
+        self.timestamp
+    }
+
+    /// When the page body was last revised.
+    pub fn updated_at(&self) -> Timestamp {
+        self.revision().1.map_or(self.timestamp, |r| r.timestamp())
+    }
+
+    /// Get a revision of the page body. Returns `None` if it doesn't exist or was redacted.
+    pub fn revision_by_id(&self, id: &RevisionId) -> Option<&Revision> {
+        self.revisions
+            .iter()
+            .find(|(r, _)| r == id)
+            .and_then(|(_, r)| r.get())
+    }
+
+    /// Iterate over the history of the page body, oldest revision first.
+    /// Redacted revisions are included, without their content.
+    pub fn history(&self) -> impl DoubleEndedIterator<Item = (&RevisionId, Option<&Revision>)> {
+        self.revisions.iter().map(|(id, r)| (id, r.get()))
+    }
+
+    /// Get a unified diff of the page body between two revisions.
+    pub fn diff(&self, from: &RevisionId, to: &RevisionId) -> Result<String, Error> {
+        let old = self.revision_for_diff(from)?;
+        let new = self.revision_for_diff(to)?;
+        let path = Path::new(self.path().as_str());
+        let mut patch = git::raw::Patch::from_buffers(
+            old.body.as_bytes(),
+            Some(path),
+            new.body.as_bytes(),
+            Some(path),
+            None,
+        )?;
+        let buf = patch.to_buf()?;
+
+        Ok(String::from_utf8_lossy(&buf).into_owned())
+    }
+
+    fn revision_for_diff(&self, id: &RevisionId) -> Result<&Revision, Error> {
+        let (_, revision) = self
+            .revisions
+            .iter()
+            .find(|(r, _)| r == id)
+            .ok_or(Error::MissingRevision(*id))?;
+
+        revision.get().ok_or(Error::RedactedRevision(*id))
+    }
+
+    /// Apply authorization rules on page actions.
+    pub fn authorization(
+        &self,
+        action: &Action,
+        actor: &ActorId,
+        doc: &Doc<Verified>,
+    ) -> Result<Authorization, Error> {
+        if doc.is_delegate(actor) {
+            // A delegate is authorized to do all actions.
+            return Ok(Authorization::Allow);
+        }
+        let outcome = match action {
+            // Anyone can edit the wiki.
+            Action::Edit { .. } | Action::Move { .. } | Action::Revise { .. } => {
+                Authorization::Allow
+            }
+            // Revision authors can redact their own revisions.
+            Action::Redact { revision } => {
+                let Some((_, r)) = self.revisions.iter().find(|(id, _)| id == revision) else {
+                    return Err(Error::MissingRevision(*revision));
+                };
+                if let Some(r) = r.get() {
+                    Authorization::from(*actor == *r.author.id().as_key())
+                } else {
+                    Authorization::Unknown
+                }
+            }
+        };
+        Ok(outcome)
+    }
+
+    /// Compute the clock of an operation, and record it.
+    fn clock(&mut self, op: &Op) -> Lamport {
+        let mut clock = op
+            .parents
+            .iter()
+            .filter_map(|p| self.clocks.get(p))
+            .max()
+            .copied()
+            .unwrap_or_default();
+        let clock = clock.tick();
+
+        self.clocks.insert(op.id, clock);
+
+        clock
+    }
+
+    /// Apply a single action to the page.
+    fn action(
+        &mut self,
+        action: Action,
+        entry: EntryId,
+        author: ActorId,
+        timestamp: Timestamp,
+        clock: Lamport,
+    ) -> Result<(), Error> {
+        match action {
+            Action::Edit { title } => {
+                self.title.set(validate_title(title)?, clock);
+            }
+            Action::Move { path } => {
+                self.path.set(path, clock);
+            }
+            Action::Revise { body, embeds } => {
+                if self.revisions.iter().any(|(id, _)| *id == entry) {
+                    return Err(Error::NotAllowed(entry));
+                }
+                self.revisions.push((
+                    entry,
+                    Redactable::Present(Revision {
+                        author: Author::new(author),
+                        body,
+                        embeds,
+                        timestamp,
+                    }),
+                ));
+                self.head.set(entry, clock);
+            }
+            Action::Redact { revision } => {
+                // The current revision can't be redacted, only revised.
+                if revision == *self.head.get().get() {
+                    return Err(Error::NotAllowed(entry));
+                }
+                let Some((_, r)) = self.revisions.iter_mut().find(|(id, _)| *id == revision) else {
+                    return Err(Error::MissingRevision(revision));
+                };
+                *r = Redactable::Redacted;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn validate_title(title: String) -> Result<String, Error> {
+    if title.trim().is_empty() || title.contains('\n') || title.contains('\r') {
+        return Err(Error::InvalidTitle(title));
+    }
+    Ok(title)
+}
+
+impl<R: ReadRepository> store::Transaction<Page, R> {
+    /// Set the page title.
+    pub fn edit(&mut self, title: impl ToString) -> Result<(), store::Error> {
+        self.push(Action::Edit {
+            title: title.to_string(),
+        })
+    }
+
+    /// Set the page path.
+    pub fn move_to(&mut self, path: PagePath) -> Result<(), store::Error> {
+        self.push(Action::Move { path })
+    }
+
+    /// Revise the page body.
+    pub fn revise(
+        &mut self,
+        body: impl ToString,
+        embeds: impl IntoIterator<Item = Embed>,
+    ) -> Result<(), store::Error> {
+        let embeds = embeds.into_iter().collect::<Vec<_>>();
+        let hashed = embeds.iter().map(|e| e.hashed()).collect();
+
+        self.embed(embeds)?;
+        self.push(Action::Revise {
+            body: body.to_string(),
+            embeds: hashed,
+        })
+    }
+
+    /// Redact a revision of the page body.
+    pub fn redact(&mut self, revision: RevisionId) -> Result<(), store::Error> {
+        self.push(Action::Redact { revision })
+    }
+}
+
+pub struct PageMut<'a, 'g, R> {
+    id: ObjectId,
+    page: Page,
+    store: &'g mut Wiki<'a, R>,
+}
+
+impl<'a, 'g, R> std::fmt::Debug for PageMut<'a, 'g, R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("PageMut")
+            .field("id", &self.id)
+            .field("page", &self.page)
+            .finish()
+    }
+}
+
+impl<'a, 'g, R> PageMut<'a, 'g, R>
+where
+    R: WriteRepository + cob::Store,
+{
+    /// Reload the page data from storage.
+    pub fn reload(&mut self) -> Result<(), store::Error> {
+        self.page = self
+            .store
+            .get(&self.id)?
+            .ok_or_else(|| store::Error::NotFound(TYPENAME.clone(), self.id))?;
+
+        Ok(())
+    }
+
+    /// Get the page id.
+    pub fn id(&self) -> &ObjectId {
+        &self.id
+    }
+
+    /// Set the page title.
+    pub fn edit<G: Signer>(&mut self, title: impl ToString, signer: &G) -> Result<EntryId, Error> {
+        self.transaction("Edit", signer, |tx| tx.edit(title))
+    }
+
+    /// Set the page path. Fails if another page already has this path.
+    pub fn move_to<G: Signer>(&mut self, path: PagePath, signer: &G) -> Result<EntryId, Error> {
+        self.store.check_path(&path, Some(&self.id))?;
+        self.transaction("Move", signer, |tx| tx.move_to(path))
+    }
+
+    /// Revise the page body.
+    pub fn revise<G: Signer>(
+        &mut self,
+        body: impl ToString,
+        embeds: impl IntoIterator<Item = Embed>,
+        signer: &G,
+    ) -> Result<RevisionId, Error> {
+        self.transaction("Revise", signer, |tx| tx.revise(body, embeds))
+    }
+
+    /// Redact a revision of the page body. The current revision can't be redacted.
+    pub fn redact<G: Signer>(
+        &mut self,
+        revision: RevisionId,
+        signer: &G,
+    ) -> Result<EntryId, Error> {
+        self.transaction("Redact revision", signer, |tx| tx.redact(revision))
+    }
+
+    pub fn transaction<G, F>(
+        &mut self,
+        message: &str,
+        signer: &G,
+        operations: F,
+    ) -> Result<EntryId, Error>
+    where
+        G: Signer,
+        F: FnOnce(&mut Transaction<Page, R>) -> Result<(), store::Error>,
+    {
+        let mut tx = Transaction::default();
+        operations(&mut tx)?;
+
+        let (page, commit) = tx.commit(message, self.id, &mut self.store.raw, signer)?;
+        self.page = page;
+
+        Ok(commit)
+    }
+}
+
+impl<'a, 'g, R> Deref for PageMut<'a, 'g, R> {
+    type Target = Page;
+
+    fn deref(&self) -> &Self::Target {
+        &self.page
+    }
+}
+
+/// The wiki of a repository, made of pages.
+pub struct Wiki<'a, R> {
+    raw: store::Store<'a, Page, R>,
+}
+
+impl<'a, R> Deref for Wiki<'a, R> {
+    type Target = store::Store<'a, Page, R>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.raw
+    }
+}
+
+impl<'a, R> HasRepoId for Wiki<'a, R>
+where
+    R: ReadRepository,
+{
+    fn rid(&self) -> RepoId {
+        self.raw.as_ref().id()
+    }
+}
+
+impl<'a, R> Wiki<'a, R>
+where
+    R: ReadRepository + cob::Store,
+{
+    /// Open a wiki store.
+    pub fn open(repository: &'a R) -> Result<Self, RepositoryError> {
+        let identity = repository.identity_head()?;
+        let raw = store::Store::open(repository)?.identity(identity);
+
+        Ok(Self { raw })
+    }
+
+    /// Get a page.
+    pub fn get(&self, id: &ObjectId) -> Result<Option<Page>, store::Error> {
+        self.raw.get(id)
+    }
+
+    /// Get a page mutably.
+    pub fn get_mut<'g>(&'g mut self, id: &ObjectId) -> Result<PageMut<'a, 'g, R>, store::Error> {
+        let page = self
+            .raw
+            .get(id)?
+            .ok_or_else(move || store::Error::NotFound(TYPENAME.clone(), *id))?;
+
+        Ok(PageMut {
+            id: *id,
+            page,
+            store: self,
+        })
+    }
+
+    /// Find a page by path.
+    ///
+    /// If concurrent edits left more than one page with the same path, the oldest one
+    /// is returned.
+    pub fn find(&self, path: &PagePath) -> Result<Option<(PageId, Page)>, store::Error> {
+        let mut found: Option<(PageId, Page)> = None;
+
+        for result in self.raw.all()? {
+            let Ok((id, page)) = result else {
+                // Skip pages that failed to load.
+                continue;
+            };
+            if page.path() != path {
+                continue;
+            }
+            let older = match &found {
+                Some((other, p)) => (page.timestamp(), id) < (p.timestamp(), *other),
+                None => true,
+            };
+            if older {
+                found = Some((id, page));
+            }
+        }
+        Ok(found)
+    }
+
+    /// Check that no other page than `id` has the given path.
+    fn check_path(&self, path: &PagePath, id: Option<&PageId>) -> Result<(), Error> {
+        match self.find(path)? {
+            Some((other, _)) if Some(&other) != id => Err(Error::PathExists(path.clone())),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<'a, R> Wiki<'a, R>
+where
+    R: WriteRepository + cob::Store,
+{
+    /// Create a new page. Fails if a page with the same path already exists.
+    pub fn create<'g, G>(
+        &'g mut self,
+        path: PagePath,
+        title: impl ToString,
+        body: impl ToString,
+        embeds: impl IntoIterator<Item = Embed>,
+        signer: &G,
+    ) -> Result<PageMut<'a, 'g, R>, Error>
+    where
+        G: Signer,
+    {
+        self.check_path(&path, None)?;
+
+        let (id, page) = Transaction::initial("Create page", &mut self.raw, signer, |tx| {
+            tx.edit(title)?;
+            tx.move_to(path)?;
+            tx.revise(body, embeds)?;
+
+            Ok(())
+        })?;
+
+        Ok(PageMut {
+            id,
+            page,
+            store: self,
+        })
+    }
+
+    /// Remove a page.
+    pub fn remove<G: Signer>(&self, id: &ObjectId, signer: &G) -> Result<(), store::Error> {
+        self.raw.remove(id, signer)
+    }
+}
+
+/// Wiki page action.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Action {
+    /// Edit page title.
+    #[serde(rename = "edit")]
+    Edit { title: String },
+
+    /// Move the page to a new path.
+    #[serde(rename = "move")]
+    Move { path: PagePath },
+
+    /// Revise the page body.
+    #[serde(rename = "revise")]
+    Revise {
+        /// New page body.
+        body: String,
+        /// Content embedded in the body.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        embeds: Vec<Embed<Uri>>,
+    },
+
+    /// Redact a revision of the page body.
+    #[serde(rename = "revision.redact")]
+    Redact { revision: RevisionId },
+}
+
+impl CobAction for Action {}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::test;
+
+    #[test]
+    fn test_page_path() {
+        assert!(PagePath::new("install").is_ok());
+        assert!(PagePath::new("guides/install").is_ok());
+        assert!(PagePath::new("").is_err());
+        assert!(PagePath::new("/install").is_err());
+        assert!(PagePath::new("guides/").is_err());
+        assert!(PagePath::new("guides/../install").is_err());
+        assert!(PagePath::new("getting started").is_err());
+    }
+
+    #[test]
+    fn test_page_create_and_revise() {
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let mut wiki = Wiki::open(&*repo).unwrap();
+        let path = PagePath::new("guides/install").unwrap();
+        let mut page = wiki
+            .create(
+                path.clone(),
+                "Installation",
+                "Run `make`.\n",
+                [],
+                &node.signer,
+            )
+            .unwrap();
+        let id = *page.id();
+        let (first, _) = page.revision();
+        let first = *first;
+
+        assert_eq!(page.title(), "Installation");
+        assert_eq!(page.path(), &path);
+        assert_eq!(page.body(), "Run `make`.\n");
+
+        let second = page
+            .revise("Run `make install`.\n", [], &node.signer)
+            .unwrap();
+        let page = wiki.get(&id).unwrap().unwrap();
+
+        assert_eq!(page.body(), "Run `make install`.\n");
+        assert_eq!(page.revision().0, &second);
+        assert_eq!(
+            page.history().map(|(id, _)| *id).collect::<Vec<_>>(),
+            vec![first, second]
+        );
+
+        let diff = page.diff(&first, &second).unwrap();
+        assert!(diff.contains("-Run `make`."));
+        assert!(diff.contains("+Run `make install`."));
+
+        let (found, _) = wiki.find(&path).unwrap().unwrap();
+        assert_eq!(found, id);
+        assert!(wiki.create(path, "Install", "", [], &node.signer).is_err());
+    }
+
+    #[test]
+    fn test_page_redact() {
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let mut wiki = Wiki::open(&*repo).unwrap();
+        let mut page = wiki
+            .create(
+                PagePath::new("secrets").unwrap(),
+                "Secrets",
+                "password: hunter2",
+                [],
+                &node.signer,
+            )
+            .unwrap();
+        let (first, _) = page.revision();
+        let first = *first;
+
+        // The current revision can't be redacted.
+        page.redact(first, &node.signer).unwrap_err();
+        page.revise("password: ***", [], &node.signer).unwrap();
+        page.redact(first, &node.signer).unwrap();
+        page.reload().unwrap();
+
+        assert!(page.revision_by_id(&first).is_none());
+        assert_eq!(page.history().count(), 2);
+        assert_eq!(page.body(), "password: ***");
+        assert!(matches!(
+            page.diff(&first, page.revision().0),
+            Err(Error::RedactedRevision(_))
+        ));
+    }
+
+    #[test]
+    fn test_page_concurrent_edits() {
+        let t = test::setup::Network::default();
+        let mut alice_wiki = Wiki::open(&*t.alice.repo).unwrap();
+        let mut bob_wiki = Wiki::open(&*t.bob.repo).unwrap();
+        let page = alice_wiki
+            .create(
+                PagePath::new("faq").unwrap(),
+                "FAQ",
+                "Nothing yet.",
+                [],
+                &t.alice.signer,
+            )
+            .unwrap();
+        let id = *page.id();
+
+        t.bob.repo.fetch(&t.alice);
+
+        // Bob isn't a delegate, but can still edit the wiki.
+        let mut page = bob_wiki.get_mut(&id).unwrap();
+        page.revise("Bob's answers.", [], &t.bob.signer).unwrap();
+
+        let mut page = alice_wiki.get_mut(&id).unwrap();
+        page.revise("Alice's answers.", [], &t.alice.signer)
+            .unwrap();
+
+        t.alice.repo.fetch(&t.bob);
+        t.bob.repo.fetch(&t.alice);
+
+        // Both peers converge on the same page.
+        let alice = alice_wiki.get(&id).unwrap().unwrap();
+        let bob = bob_wiki.get(&id).unwrap().unwrap();
+
+        assert_eq!(alice.history().count(), 3);
+        assert_eq!(alice.body(), bob.body());
+        assert_eq!(alice.revision().0, bob.revision().0);
+    }
+}
@@ -0,0 +1,419 @@
+//! Cross-repository view of the issues and patches that need the user's attention.
+//!
+//! Items are read from the COB caches of all seeded repositories, and fall into one of
+//! the following categories:
+//!
+//! * Patches awaiting the user's review: open patches by others, on repositories the user
+//!   is a delegate of or on which the user is assigned, whose latest revision the user
+//!   hasn't reviewed.
+//! * Patches authored by the user, with activity by others since the user last acted on them.
+//! * Open issues assigned to the user.
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::cob::issue::cache::Issues as _;
+use crate::cob::patch::cache::Patches as _;
+use crate::cob::{issue, patch, ActorId, ObjectId, Timestamp};
+use crate::identity::{Did, RepoId};
+use crate::node::policy;
+use crate::profile::{self, Profile};
+use crate::storage::{self, ReadStorage};
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Profile(#[from] profile::Error),
+    #[error(transparent)]
+    Storage(#[from] storage::Error),
+    #[error(transparent)]
+    Repository(#[from] storage::RepositoryError),
+    #[error(transparent)]
+    Policy(#[from] policy::store::Error),
+    #[error(transparent)]
+    Issues(#[from] issue::cache::Error),
+    #[error(transparent)]
+    Patches(#[from] patch::cache::Error),
+}
+
+/// Why an item is on the dashboard.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Category {
+    /// A patch awaiting the user's review.
+    Review,
+    /// A patch by the user, with new activity.
+    Authored,
+    /// An issue assigned to the user.
+    Assigned,
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Review => write!(f, "review"),
+            Self::Authored => write!(f, "authored"),
+            Self::Assigned => write!(f, "assigned"),
+        }
+    }
+}
+
+impl FromStr for Category {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "review" => Ok(Self::Review),
+            "authored" => Ok(Self::Authored),
+            "assigned" => Ok(Self::Assigned),
+            _ => Err(format!(
+                "invalid category '{s}', expected 'review', 'authored' or 'assigned'"
+            )),
+        }
+    }
+}
+
+/// Dashboard item ordering.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SortBy {
+    /// Most recently updated first.
+    #[default]
+    Updated,
+    /// By repository name, then most recently updated first.
+    Repository,
+    /// By category, then most recently updated first.
+    Category,
+}
+
+impl FromStr for SortBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "updated" => Ok(Self::Updated),
+            "repository" => Ok(Self::Repository),
+            "category" => Ok(Self::Category),
+            _ => Err(format!(
+                "invalid sort order '{s}', expected 'updated', 'repository' or 'category'"
+            )),
+        }
+    }
+}
+
+/// Selects and orders dashboard items.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Filter {
+    /// Only show these categories. All categories are shown if empty.
+    pub categories: BTreeSet<Category>,
+    /// Only show these repositories. All seeded repositories are shown if empty.
+    pub repos: BTreeSet<RepoId>,
+    /// Item ordering.
+    pub sort: SortBy,
+}
+
+/// The issue or patch behind a dashboard item.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Object {
+    Issue(issue::IssueId, Box<issue::Issue>),
+    Patch(patch::PatchId, Box<patch::Patch>),
+}
+
+impl Object {
+    /// The COB identifier.
+    pub fn id(&self) -> &ObjectId {
+        match self {
+            Self::Issue(id, _) | Self::Patch(id, _) => id,
+        }
+    }
+
+    /// The COB title.
+    pub fn title(&self) -> &str {
+        match self {
+            Self::Issue(_, issue) => issue.title(),
+            Self::Patch(_, patch) => patch.title(),
+        }
+    }
+
+    /// The COB author.
+    pub fn author(&self) -> ActorId {
+        match self {
+            Self::Issue(_, issue) => *issue.author().public_key(),
+            Self::Patch(_, patch) => *patch.author().public_key(),
+        }
+    }
+}
+
+/// An issue or patch that needs the user's attention.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item {
+    /// Repository of the item.
+    pub rid: RepoId,
+    /// Name of the repository.
+    pub name: String,
+    /// Why the item is on the dashboard.
+    pub category: Category,
+    /// The issue or patch.
+    pub object: Object,
+    /// Time of the latest activity on the item.
+    pub updated: Timestamp,
+}
+
+/// Get the dashboard items of the given profile.
+pub fn items(profile: &Profile, filter: &Filter) -> Result<Vec<Item>, Error> {
+    let me = profile.did();
+    let policies = profile.policies()?;
+    let mut items = Vec::new();
+
+    for info in profile.storage.repositories()? {
+        if !filter.repos.is_empty() && !filter.repos.contains(&info.rid) {
+            continue;
+        }
+        if !policies.is_seeding(&info.rid)? {
+            continue;
+        }
+        // Skip repositories without a project payload.
+        let Ok(project) = info.doc.project() else {
+            continue;
+        };
+        let name = project.name().to_owned();
+        let delegate = info.doc.is_delegate(me.as_key());
+        let repo = profile.storage.repository(info.rid)?;
+        let mut push = |category: Category, object: Object, updated: Timestamp| {
+            if filter.categories.is_empty() || filter.categories.contains(&category) {
+                items.push(Item {
+                    rid: info.rid,
+                    name: name.clone(),
+                    category,
+                    object,
+                    updated,
+                });
+            }
+        };
+
+        for result in profile.issues(&repo)?.list()? {
+            let (id, issue) = result?;
+
+            if let Some(category) = issue_category(&issue, &me) {
+                let updated = issue_updated(&issue);
+                push(category, Object::Issue(id, Box::new(issue)), updated);
+            }
+        }
+        for result in profile.patches(&repo)?.list()? {
+            let (id, patch) = result?;
+
+            if let Some(category) = patch_category(&patch, &me, delegate) {
+                let updated = patch_updated(&patch);
+                push(category, Object::Patch(id, Box::new(patch)), updated);
+            }
+        }
+    }
+
+    items.sort_by(|a, b| {
+        let by_updated = b
+            .updated
+            .cmp(&a.updated)
+            .then(a.object.id().cmp(b.object.id()));
+
+        match filter.sort {
+            SortBy::Updated => by_updated,
+            SortBy::Repository => a.name.cmp(&b.name).then(by_updated),
+            SortBy::Category => a.category.cmp(&b.category).then(by_updated),
+        }
+    });
+
+    Ok(items)
+}
+
+/// Get the category of an issue, if it's relevant to `me`.
+fn issue_category(issue: &issue::Issue, me: &Did) -> Option<Category> {
+    if matches!(issue.state(), issue::State::Open) && issue.assignees().any(|a| a == me) {
+        return Some(Category::Assigned);
+    }
+    None
+}
+
+/// Get the category of a patch, if it's relevant to `me`.
+fn patch_category(patch: &patch::Patch, me: &Did, delegate: bool) -> Option<Category> {
+    if !patch.is_open() {
+        return None;
+    }
+    let me = me.as_key();
+
+    if patch.author().public_key() == me {
+        return has_new_activity(me, patch_activity(patch)).then_some(Category::Authored);
+    }
+
+    let requested = delegate || patch.assignees().any(|a| a.as_key() == me);
+    let (_, latest) = patch.latest();
+
+    if requested && latest.review(me).is_none() {
+        return Some(Category::Review);
+    }
+    None
+}
+
+/// Check whether others were active since the user's latest activity.
+///
+/// Since timestamps only have a resolution of one second, activity by others
+/// happening at the same time as the user's is considered new.
+fn has_new_activity(me: &ActorId, activity: impl Iterator<Item = (ActorId, Timestamp)>) -> bool {
+    let mut mine = None;
+    let mut theirs = None;
+
+    for (author, timestamp) in activity {
+        if author == *me {
+            mine = mine.max(Some(timestamp));
+        } else {
+            theirs = theirs.max(Some(timestamp));
+        }
+    }
+    theirs.is_some() && theirs >= mine
+}
+
+/// Time of the latest activity on an issue.
+fn issue_updated(issue: &issue::Issue) -> Timestamp {
+    issue
+        .comments()
+        .map(|(_, c)| c.timestamp())
+        .max()
+        .unwrap_or_else(|| issue.timestamp())
+}
+
+/// Time of the latest activity on a patch.
+fn patch_updated(patch: &patch::Patch) -> Timestamp {
+    patch_activity(patch)
+        .map(|(_, timestamp)| timestamp)
+        .max()
+        .unwrap_or_else(|| patch.timestamp())
+}
+
+/// Authors and times of the revisions, reviews, comments and merges of a patch.
+fn patch_activity(patch: &patch::Patch) -> impl Iterator<Item = (ActorId, Timestamp)> + '_ {
+    let revisions = patch.revisions().flat_map(|(_, revision)| {
+        let reviews = revision.reviews().flat_map(|(author, review)| {
+            std::iter::once((*author, review.timestamp()))
+                .chain(review.comments().map(|(_, c)| (c.author(), c.timestamp())))
+        });
+        let comments = revision
+            .discussion()
+            .comments()
+            .map(|(_, c)| (c.author(), c.timestamp()));
+
+        std::iter::once((*revision.author().public_key(), revision.timestamp()))
+            .chain(reviews)
+            .chain(comments)
+    });
+    let merges = patch
+        .merges()
+        .map(|(author, merge)| (*author, merge.timestamp));
+
+    revisions.chain(merges)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cob::patch::{MergeTarget, Verdict};
+    use crate::crypto::test::signer::MockSigner;
+    use crate::crypto::Signer as _;
+    use crate::test;
+    use crate::test::arbitrary;
+
+    #[test]
+    fn test_patch_category() {
+        let alice = test::setup::NodeWithRepo::default();
+        let bob = MockSigner::default();
+        let checkout = alice.repo.checkout();
+        let branch = checkout.branch_with([("README", b"Hello World!")]);
+        let mut patches = patch::Cache::no_cache(&*alice.repo).unwrap();
+        let mut patch = patches
+            .create(
+                "My first patch",
+                "Blah blah blah.",
+                MergeTarget::Delegates,
+                branch.base,
+                branch.oid,
+                &[],
+                &alice.signer,
+            )
+            .unwrap();
+        let alice_did = Did::from(*alice.signer.public_key());
+        let bob_did = Did::from(*bob.public_key());
+
+        // Nothing happened since alice opened the patch.
+        assert_eq!(patch_category(&patch, &alice_did, true), None);
+        // Bob is only asked to review as a delegate.
+        assert_eq!(
+            patch_category(&patch, &bob_did, true),
+            Some(Category::Review)
+        );
+        assert_eq!(patch_category(&patch, &bob_did, false), None);
+
+        let (revision, _) = patch.latest();
+        patch
+            .review(
+                revision,
+                Some(Verdict::Accept),
+                Some("LGTM".to_owned()),
+                vec![],
+                &bob,
+            )
+            .unwrap();
+
+        // Bob reviewed the latest revision.
+        assert_eq!(patch_category(&patch, &bob_did, true), None);
+    }
+
+    #[test]
+    fn test_new_activity() {
+        let alice = arbitrary::gen::<ActorId>(1);
+        let bob = arbitrary::gen::<ActorId>(1);
+        let t = Timestamp::from_secs;
+
+        assert!(!has_new_activity(&alice, [(alice, t(1))].into_iter()));
+        assert!(has_new_activity(
+            &alice,
+            [(alice, t(1)), (bob, t(2))].into_iter()
+        ));
+        assert!(has_new_activity(
+            &alice,
+            [(alice, t(1)), (bob, t(1))].into_iter()
+        ));
+        assert!(!has_new_activity(
+            &alice,
+            [(alice, t(1)), (bob, t(2)), (alice, t(3))].into_iter()
+        ));
+    }
+
+    #[test]
+    fn test_issue_category() {
+        let alice = test::setup::NodeWithRepo::default();
+        let bob = Did::from(*MockSigner::default().public_key());
+        let mut issues = issue::Cache::no_cache(&*alice.repo).unwrap();
+        let mut issue = issues
+            .create("No license", "We need one.", &[], &[bob], [], &alice.signer)
+            .unwrap();
+
+        assert_eq!(issue_category(&issue, &bob), Some(Category::Assigned));
+        assert_eq!(
+            issue_category(&issue, &alice.signer.public_key().into()),
+            None
+        );
+
+        issue
+            .lifecycle(
+                issue::State::Closed {
+                    reason: issue::CloseReason::Solved,
+                },
+                &alice.signer,
+            )
+            .unwrap();
+
+        // Closed issues need no attention.
+        assert_eq!(issue_category(&issue, &bob), None);
+    }
+}
@@ -11,6 +11,7 @@ pub mod policy;
 pub mod refs;
 pub mod routing;
 pub mod seed;
+pub mod sync;
 pub mod timestamp;
 
 use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
@@ -60,6 +61,8 @@ pub const NODE_DB_FILE: &str = "node.db";
 pub const POLICIES_DB_FILE: &str = "policies.db";
 /// Filename of notifications database under the node directory.
 pub const NOTIFICATIONS_DB_FILE: &str = "notifications.db";
+/// Filename of the socket streaming new notifications, under the node directory.
+pub const NOTIFICATIONS_SOCKET_NAME: &str = "notifications.sock";
 /// Filename of last node announcement, when running in debug mode.
 #[cfg(debug_assertions)]
 pub const NODE_ANNOUNCEMENT_FILE: &str = "announcement.wire.debug";
@@ -489,6 +492,21 @@ pub enum Command {
     #[serde(rename_all = "camelCase")]
     Unfollow { nid: NodeId },
 
+    /// Set the notification interest in references of a repository.
+    #[serde(rename_all = "camelCase")]
+    Watch {
+        rid: RepoId,
+        pattern: git::PatternString,
+        interest: notifications::Interest,
+    },
+
+    /// Remove the notification interest set on references of a repository.
+    #[serde(rename_all = "camelCase")]
+    Unwatch {
+        rid: RepoId,
+        pattern: git::PatternString,
+    },
+
     /// Get the node's status.
     Status,
 
@@ -885,6 +903,16 @@ pub trait Handle: Clone + Sync + Send {
     fn unseed(&mut self, id: RepoId) -> Result<bool, Self::Error>;
     /// Unfollow the given peer.
     fn unfollow(&mut self, id: NodeId) -> Result<bool, Self::Error>;
+    /// Set the notification interest in the references of a repo matching the given
+    /// pattern. Returns `false` if the interest was already set.
+    fn watch(
+        &mut self,
+        id: RepoId,
+        pattern: git::PatternString,
+        interest: notifications::Interest,
+    ) -> Result<bool, Self::Error>;
+    /// Remove the notification interest set on the given pattern.
+    fn unwatch(&mut self, id: RepoId, pattern: git::PatternString) -> Result<bool, Self::Error>;
     /// Notify the service that a project has been updated, and announce local refs.
     fn announce_refs(&mut self, id: RepoId) -> Result<RefsAt, Self::Error>;
     /// Announce local inventory.
@@ -1140,6 +1168,32 @@ impl Handle for Node {
         Ok(response.updated)
     }
 
+    fn watch(
+        &mut self,
+        rid: RepoId,
+        pattern: git::PatternString,
+        interest: notifications::Interest,
+    ) -> Result<bool, Error> {
+        let mut line = self.call::<Success>(
+            Command::Watch {
+                rid,
+                pattern,
+                interest,
+            },
+            DEFAULT_TIMEOUT,
+        )?;
+        let response = line.next().ok_or(Error::EmptyResponse)??;
+
+        Ok(response.updated)
+    }
+
+    fn unwatch(&mut self, rid: RepoId, pattern: git::PatternString) -> Result<bool, Error> {
+        let mut line = self.call::<Success>(Command::Unwatch { rid, pattern }, DEFAULT_TIMEOUT)?;
+        let response = line.next().ok_or(Error::EmptyResponse)??;
+
+        Ok(response.updated)
+    }
+
     fn announce_refs(&mut self, rid: RepoId) -> Result<RefsAt, Error> {
         let refs: RefsAt = self
             .call(Command::AnnounceRefs { rid }, DEFAULT_TIMEOUT)?
@@ -1197,12 +1251,21 @@ impl Handle for Node {
 pub trait AliasStore {
     /// Returns alias of a `NodeId`.
     fn alias(&self, nid: &NodeId) -> Option<Alias>;
+    /// Returns the nodes known under the given alias.
+    fn reverse_lookup(&self, alias: &Alias) -> BTreeSet<NodeId>;
 }
 
 impl AliasStore for HashMap<NodeId, Alias> {
     fn alias(&self, nid: &NodeId) -> Option<Alias> {
         self.get(nid).map(ToOwned::to_owned)
     }
+
+    fn reverse_lookup(&self, alias: &Alias) -> BTreeSet<NodeId> {
+        self.iter()
+            .filter(|(_, a)| *a == alias)
+            .map(|(nid, _)| *nid)
+            .collect()
+    }
 }
 
 #[cfg(test)]
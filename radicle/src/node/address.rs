@@ -176,6 +176,8 @@ pub enum Source {
     /// An address that came from some source external to the system, eg.
     /// specified by the user or added directly to the address manager.
     Imported,
+    /// An address that was discovered on the local network.
+    Local,
 }
 
 impl std::fmt::Display for Source {
@@ -184,6 +186,7 @@ impl std::fmt::Display for Source {
             Self::Peer => write!(f, "Peer"),
             Self::Bootstrap => write!(f, "Bootstrap"),
             Self::Imported => write!(f, "Imported"),
+            Self::Local => write!(f, "Local"),
         }
     }
 }
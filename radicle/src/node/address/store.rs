@@ -1,3 +1,4 @@
+use std::collections::BTreeSet;
 use std::str::FromStr;
 
 use localtime::LocalTime;
@@ -41,6 +42,8 @@ pub trait Store {
     fn get(&self, id: &NodeId) -> Result<Option<Node>, Error>;
     /// Get the addresses of a node.
     fn addresses_of(&self, node: &NodeId) -> Result<Vec<KnownAddress>, Error>;
+    /// Get the nodes that announced the given alias.
+    fn nodes_by_alias(&self, alias: &Alias) -> Result<BTreeSet<NodeId>, Error>;
     /// Insert a node with associated addresses into the store.
     ///
     /// Returns `true` if the node or addresses were updated, and `false` otherwise.
@@ -108,6 +111,17 @@ impl Store for Database {
         }
     }
 
+    fn nodes_by_alias(&self, alias: &Alias) -> Result<BTreeSet<NodeId>, Error> {
+        let mut stmt = self.db.prepare("SELECT id FROM nodes WHERE alias = ?")?;
+        stmt.bind((1, alias.as_ref()))?;
+
+        let mut nodes = BTreeSet::new();
+        for row in stmt.into_iter() {
+            nodes.insert(row?.read::<NodeId, _>("id"));
+        }
+        Ok(nodes)
+    }
+
     fn addresses_of(&self, node: &NodeId) -> Result<Vec<KnownAddress>, Error> {
         let mut addrs = Vec::new();
         let mut stmt = self.db.prepare(
@@ -331,6 +345,12 @@ where
             .map(|node| node.map(|n| n.alias))
             .unwrap_or(None)
     }
+
+    /// Lookup the nodes with the given alias.
+    /// Calls `Self::nodes_by_alias` under the hood.
+    fn reverse_lookup(&self, alias: &Alias) -> BTreeSet<NodeId> {
+        self.nodes_by_alias(alias).unwrap_or_default()
+    }
 }
 
 impl TryFrom<&sql::Value> for Source {
@@ -346,6 +366,7 @@ impl TryFrom<&sql::Value> for Source {
                 "bootstrap" => Ok(Source::Bootstrap),
                 "peer" => Ok(Source::Peer),
                 "imported" => Ok(Source::Imported),
+                "local" => Ok(Source::Local),
                 _ => Err(err),
             },
             _ => Err(err),
@@ -359,6 +380,7 @@ impl sql::BindableWithIndex for Source {
             Self::Bootstrap => "bootstrap".bind(stmt, i),
             Self::Peer => "peer".bind(stmt, i),
             Self::Imported => "imported".bind(stmt, i),
+            Self::Local => "local".bind(stmt, i),
         }
     }
 }
@@ -234,6 +234,18 @@ impl Default for PeerConfig {
     }
 }
 
+/// Local network peer discovery.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Discovery {
+    /// Don't discover peers on the local network.
+    #[default]
+    Disabled,
+    /// Announce the node on the local network segment via IP multicast,
+    /// and add the nodes announcing themselves to the address book.
+    Multicast,
+}
+
 /// Service configuration.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -271,6 +283,9 @@ pub struct Config {
     /// Default seeding scope.
     #[serde(default)]
     pub scope: Scope,
+    /// Local network peer discovery.
+    #[serde(default)]
+    pub discovery: Discovery,
 }
 
 impl Config {
@@ -294,6 +309,7 @@ impl Config {
             workers: DEFAULT_WORKERS,
             policy: Policy::default(),
             scope: Scope::default(),
+            discovery: Discovery::default(),
         }
     }
 
@@ -1,16 +1,24 @@
+pub mod activity;
+pub mod config;
 pub mod store;
 
+use std::fmt;
+use std::str::FromStr;
+
 use localtime::LocalTime;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use sqlite as sql;
 use thiserror::Error;
 
 use crate::cob;
-use crate::cob::TypedId;
-use crate::git::{BranchName, Qualified};
+use crate::cob::thread::CommentId;
+use crate::cob::{ObjectId, TypeName, TypedId};
+use crate::git::{BranchName, PatternString, Qualified};
 use crate::prelude::RepoId;
 use crate::storage::{RefUpdate, RemoteId};
 
+pub use activity::{Activity, ActivityKind};
+pub use config::Config;
 pub use store::{Error, Store};
 /// Read and write to the store.
 pub type StoreWriter = Store<store::Write>;
@@ -20,6 +28,15 @@ pub type StoreReader = Store<store::Read>;
 /// Unique identifier for a notification.
 pub type NotificationId = u32;
 
+/// A destination for new notifications, eg. a desktop notifier or a mailbox.
+/// Sinks are attached to a [`StoreWriter`], which delivers every notification it
+/// creates or updates.
+pub trait Sink: Send + Sync {
+    /// Deliver a notification. This is called from the writer's thread, and so
+    /// should not block.
+    fn deliver(&self, notification: &Notification);
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 pub enum NotificationStatus {
     ReadAt(LocalTime),
@@ -47,6 +64,9 @@ pub struct Notification {
     pub update: RefUpdate,
     /// Notification kind.
     pub kind: NotificationKind,
+    /// Summary of the COB operations behind the update, oldest first.
+    /// Empty if the update isn't on an issue or patch.
+    pub activity: Vec<Activity>,
     /// Read status.
     pub status: NotificationStatus,
     /// Timestamp of the update.
@@ -61,16 +81,36 @@ pub enum NotificationKind {
         #[serde(flatten)]
         typed_id: TypedId,
     },
+    /// A comment on a COB mentioned the user.
+    Mention {
+        #[serde(flatten)]
+        typed_id: TypedId,
+        /// The comment that mentioned the user.
+        comment: CommentId,
+    },
     /// A source branch changed.
     Branch { name: BranchName },
     /// Unknown reference.
     Unknown { refname: Qualified<'static> },
 }
 
+impl NotificationKind {
+    /// A mention in a comment on the COB under the given reference.
+    pub fn mention(refname: Qualified, comment: CommentId) -> Result<Self, NotificationKindError> {
+        match TypedId::from_qualified(&refname)? {
+            Some(typed_id) => Ok(Self::Mention { typed_id, comment }),
+            None => Err(NotificationKindError::NotCob(refname.to_owned())),
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum NotificationKindError {
     #[error("invalid cob identifier: {0}")]
     TypedId(#[from] cob::ParseIdentifierError),
+    /// Mentions can only be made on COBs.
+    #[error("reference {0} is not a cob")]
+    NotCob(Qualified<'static>),
     /// Invalid Git ref format.
     #[error("invalid ref format: {0}")]
     RefFormat(#[from] radicle_git_ext::ref_format::Error),
@@ -127,3 +167,107 @@ impl sql::BindableWithIndex for &NotificationStatus {
         }
     }
 }
+
+/// Interest in the updates of a repository, or of some of its references.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Interest {
+    /// Get notified of all updates.
+    #[default]
+    All,
+    /// Only get notified of updates to issues and patches you authored or are
+    /// assigned to.
+    Relevant,
+    /// Only get notified when you are mentioned.
+    Mentions,
+    /// Don't get notified.
+    None,
+}
+
+impl fmt::Display for Interest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => f.write_str("all"),
+            Self::Relevant => f.write_str("relevant"),
+            Self::Mentions => f.write_str("mentions"),
+            Self::None => f.write_str("none"),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("invalid notification interest: {0:?}")]
+pub struct ParseInterestError(String);
+
+impl FromStr for Interest {
+    type Err = ParseInterestError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(Self::All),
+            "relevant" => Ok(Self::Relevant),
+            "mentions" => Ok(Self::Mentions),
+            "none" => Ok(Self::None),
+            _ => Err(ParseInterestError(s.to_owned())),
+        }
+    }
+}
+
+impl sql::BindableWithIndex for Interest {
+    fn bind<I: sql::ParameterIndex>(self, stmt: &mut sql::Statement<'_>, i: I) -> sql::Result<()> {
+        self.to_string().as_str().bind(stmt, i)
+    }
+}
+
+impl TryFrom<&sql::Value> for Interest {
+    type Error = sql::Error;
+
+    fn try_from(value: &sql::Value) -> Result<Self, Self::Error> {
+        let message = Some("sql: invalid notification interest".to_owned());
+
+        match value {
+            sql::Value::String(interest) => Interest::from_str(interest).map_err(|_| sql::Error {
+                code: None,
+                message,
+            }),
+            _ => Err(sql::Error {
+                code: None,
+                message,
+            }),
+        }
+    }
+}
+
+/// A rule setting the interest in the references of a repository that match a pattern.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rule {
+    /// Repository the rule applies to.
+    pub repo: RepoId,
+    /// Pattern matching the qualified references the rule applies to.
+    pub pattern: PatternString,
+    /// Interest in the matching references.
+    pub interest: Interest,
+}
+
+/// Reference patterns that rules can be set on.
+pub mod pattern {
+    use super::*;
+
+    /// Matches all references of a repository.
+    pub fn all() -> PatternString {
+        PatternString::try_from("refs/*").expect("pattern: `refs/*` is a valid pattern")
+    }
+
+    /// Matches all objects of the given type.
+    pub fn cobs(type_name: &TypeName) -> PatternString {
+        PatternString::try_from(format!("refs/cobs/{type_name}/*"))
+            .expect("pattern: type names are valid reference components")
+    }
+
+    /// Matches the object with the given identifier, whatever its type.
+    pub fn cob(id: &ObjectId) -> PatternString {
+        PatternString::try_from(format!("refs/cobs/*/{id}"))
+            .expect("pattern: object identifiers are valid reference components")
+    }
+}
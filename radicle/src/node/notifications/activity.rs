@@ -0,0 +1,292 @@
+//! Summaries of the operations behind a COB notification.
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::cob;
+use crate::cob::patch::{RevisionId, Verdict};
+use crate::cob::{issue, patch, ActorId, EntryId, Op, Timestamp, TypedId};
+use crate::git;
+use crate::git::Oid;
+use crate::storage::{ReadRepository, RefUpdate};
+
+#[derive(Error, Debug)]
+pub enum Error {
+    /// Error loading a COB change.
+    #[error("failed to load change: {0}")]
+    Load(#[from] cob::git::change::error::Load),
+    /// Git error.
+    #[error("git: {0}")]
+    Git(#[from] crate::git::ext::Error),
+}
+
+/// A summary of an operation on a COB.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Activity {
+    /// Entry under which the operation lives.
+    pub entry: EntryId,
+    /// Author of the operation.
+    pub author: ActorId,
+    /// Time of the operation.
+    pub timestamp: Timestamp,
+    /// What the operation did.
+    #[serde(flatten)]
+    pub kind: ActivityKind,
+}
+
+/// What an operation did to a COB.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum ActivityKind {
+    /// The COB was created.
+    Opened,
+    /// The COB was edited, eg. its title changed.
+    Edited,
+    /// A comment was added. The comment id is the entry id.
+    Commented,
+    /// A patch revision was pushed. The revision id is the entry id.
+    Revised,
+    /// A patch revision was reviewed.
+    Reviewed { verdict: Option<Verdict> },
+    /// A patch revision was merged.
+    Merged { revision: RevisionId },
+    /// The state of the COB changed, eg. an issue was closed.
+    StateChanged { state: String },
+}
+
+impl fmt::Display for ActivityKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Opened => write!(f, "opened"),
+            Self::Edited => write!(f, "edited"),
+            Self::Commented => write!(f, "commented"),
+            Self::Revised => write!(f, "pushed a new revision"),
+            Self::Reviewed {
+                verdict: Some(verdict),
+            } => write!(f, "reviewed ({verdict})"),
+            Self::Reviewed { verdict: None } => write!(f, "reviewed"),
+            Self::Merged { .. } => write!(f, "merged"),
+            Self::StateChanged { state } => write!(f, "marked as {state}"),
+        }
+    }
+}
+
+impl Activity {
+    /// Summarize the operations of an issue or patch that are reachable from `new`, but
+    /// not from any of the `known` heads. Activity is in causal order, oldest first.
+    /// Operations on other COB types are not summarized.
+    pub fn between<R>(
+        repo: &R,
+        typed_id: &TypedId,
+        new: Oid,
+        known: &[Oid],
+    ) -> Result<Vec<Self>, Error>
+    where
+        R: ReadRepository + cob::Store,
+    {
+        if !typed_id.is_issue() && !typed_id.is_patch() {
+            return Ok(vec![]);
+        }
+        let mut walk = repo.revwalk(new).map_err(git::ext::Error::from)?;
+        walk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME | git2::Sort::REVERSE)
+            .map_err(git::ext::Error::from)?;
+        for head in known {
+            walk.hide((*head).into()).map_err(git::ext::Error::from)?;
+        }
+        // COB changes also have the repository identity and patch revisions as parents.
+        // Don't walk into their history, only into changes of this object.
+        let mut entries = HashMap::new();
+        let mut is_foreign = |oid: git2::Oid| match repo.load(oid.into()) {
+            Ok(entry) if entry.manifest.type_name == typed_id.type_name => {
+                entries.insert(entry.id, entry);
+                false
+            }
+            _ => true,
+        };
+        let order = walk
+            .with_hide_callback(&mut is_foreign)
+            .map_err(git::ext::Error::from)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(git::ext::Error::from)?;
+
+        let mut activity = Vec::new();
+        for id in order {
+            let Some(entry) = entries.remove(&Oid::from(id)) else {
+                continue;
+            };
+            let kinds = if *entry.id() == *typed_id.id {
+                vec![ActivityKind::Opened]
+            } else if typed_id.is_issue() {
+                match Op::<issue::Action>::try_from(&entry) {
+                    Ok(op) => op.actions.iter().filter_map(issue_activity).collect(),
+                    Err(_) => continue,
+                }
+            } else {
+                match Op::<patch::Action>::try_from(&entry) {
+                    Ok(op) => op.actions.iter().filter_map(patch_activity).collect(),
+                    Err(_) => continue,
+                }
+            };
+            let mut seen = Vec::new();
+            for kind in kinds {
+                if seen.contains(&kind) {
+                    continue;
+                }
+                seen.push(kind.clone());
+                activity.push(Activity {
+                    entry: *entry.id(),
+                    author: *entry.author(),
+                    timestamp: Timestamp::from_secs(entry.timestamp),
+                    kind,
+                });
+            }
+        }
+        Ok(activity)
+    }
+}
+
+/// Get the heads of a COB in every namespace of a repository, as they were before the given
+/// ref updates. These are the heads to pass to [`Activity::between`] to find out what the
+/// updates brought in.
+pub fn known<R: ReadRepository>(
+    repo: &R,
+    typed_id: &TypedId,
+    updated: &[RefUpdate],
+) -> Result<Vec<Oid>, Error> {
+    let mut known = Vec::new();
+    for (refname, head) in
+        repo.references_glob(&git::refs::storage::cobs(&typed_id.type_name, &typed_id.id))?
+    {
+        match updated
+            .iter()
+            .find(|u| u.name().as_str() == refname.as_str())
+        {
+            Some(update) => known.extend(update.old()),
+            None => known.push(head),
+        }
+    }
+    Ok(known)
+}
+
+fn issue_activity(action: &issue::Action) -> Option<ActivityKind> {
+    match action {
+        issue::Action::Edit { .. } => Some(ActivityKind::Edited),
+        issue::Action::Comment { .. } => Some(ActivityKind::Commented),
+        issue::Action::Lifecycle { state } => Some(ActivityKind::StateChanged {
+            state: state.to_string(),
+        }),
+        _ => None,
+    }
+}
+
+fn patch_activity(action: &patch::Action) -> Option<ActivityKind> {
+    match action {
+        patch::Action::Edit { .. } | patch::Action::RevisionEdit { .. } => {
+            Some(ActivityKind::Edited)
+        }
+        patch::Action::Revision { .. } => Some(ActivityKind::Revised),
+        patch::Action::RevisionComment { .. } | patch::Action::ReviewComment { .. } => {
+            Some(ActivityKind::Commented)
+        }
+        patch::Action::Review { verdict, .. } => Some(ActivityKind::Reviewed { verdict: *verdict }),
+        patch::Action::Merge { revision, .. } => Some(ActivityKind::Merged {
+            revision: *revision,
+        }),
+        patch::Action::Lifecycle { state } => Some(ActivityKind::StateChanged {
+            state: match state {
+                patch::Lifecycle::Open => "open",
+                patch::Lifecycle::Draft => "draft",
+                patch::Lifecycle::Archived => "archived",
+            }
+            .to_owned(),
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::cob::issue::{CloseReason, State};
+    use crate::cob::thread;
+    use crate::test;
+
+    #[test]
+    fn test_issue_activity() {
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let mut issues = issue::Cache::no_cache(&*repo).unwrap();
+        let mut issue = issues
+            .create("No license", "We need one.", &[], &[], [], &node.signer)
+            .unwrap();
+        let typed_id = TypedId {
+            type_name: issue::TYPENAME.clone(),
+            id: *issue.id(),
+        };
+        let opened: Oid = **issue.id();
+        let (root, _) = issue.root();
+        let root: thread::CommentId = *root;
+        let commented = issue.comment("MIT?", root, [], [], &node.signer).unwrap();
+        let closed = issue
+            .lifecycle(
+                State::Closed {
+                    reason: CloseReason::Solved,
+                },
+                &node.signer,
+            )
+            .unwrap();
+        let kinds = |activity: Vec<Activity>| {
+            activity
+                .into_iter()
+                .map(|a| (a.entry, a.kind))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            kinds(Activity::between(&*repo, &typed_id, closed, &[]).unwrap()),
+            vec![
+                (opened, ActivityKind::Opened),
+                (commented, ActivityKind::Commented),
+                (
+                    closed,
+                    ActivityKind::StateChanged {
+                        state: String::from("closed")
+                    }
+                ),
+            ]
+        );
+        assert_eq!(
+            kinds(Activity::between(&*repo, &typed_id, closed, &[commented]).unwrap()),
+            vec![(
+                closed,
+                ActivityKind::StateChanged {
+                    state: String::from("closed")
+                }
+            )]
+        );
+        assert_eq!(
+            Activity::between(&*repo, &typed_id, closed, &[closed]).unwrap(),
+            vec![]
+        );
+
+        let update = RefUpdate::Updated {
+            name: git::refs::storage::cob(
+                crypto::Signer::public_key(&node.signer),
+                &typed_id.type_name,
+                &typed_id.id,
+            )
+            .to_ref_string(),
+            old: commented,
+            new: closed,
+        };
+        assert_eq!(known(&*repo, &typed_id, &[]).unwrap(), vec![closed]);
+        assert_eq!(
+            known(&*repo, &typed_id, &[update]).unwrap(),
+            vec![commented]
+        );
+    }
+}
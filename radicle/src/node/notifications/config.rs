@@ -0,0 +1,36 @@
+//! Notification delivery configuration.
+use localtime::LocalDuration;
+use serde::{Deserialize, Serialize};
+
+/// Configures where notifications are delivered, besides the inbox.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Config {
+    /// Shell command run for every new notification, eg. `notify-send "$RAD_NOTIFICATION"`.
+    /// The notification is passed as JSON on standard input. Commands are run one at a time.
+    #[serde(default)]
+    pub hook: Option<String>,
+    /// Deliver notifications by email.
+    #[serde(default)]
+    pub email: Option<Email>,
+    /// Stream notifications as JSON lines to clients of the notifications socket.
+    #[serde(default)]
+    pub socket: bool,
+}
+
+/// Email delivery configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Email {
+    /// Address of the SMTP server, eg. `localhost:25`. Connections are not encrypted
+    /// and not authenticated, so this should be a local mail relay.
+    pub smtp: String,
+    /// Sender address.
+    pub from: String,
+    /// Recipient address.
+    pub to: String,
+    /// If set, notifications are batched, and a digest is sent at most once per
+    /// interval. Otherwise, every notification is sent on its own.
+    #[serde(default)]
+    pub digest: Option<LocalDuration>,
+}
@@ -31,6 +31,10 @@ create table if not exists "repository-notification-interests" (
   -- "all" - get all updates
   -- "none" - get no updates
   -- "relevant" - get updates if relevant to you
+  -- "mentions" - get updates if you are mentioned
+  --
+  -- Only one interest is kept per glob. When more than one glob matches
+  -- a reference, the longest one applies.
   "interest"           text      not null,
   --
   unique ("repo", "glob", "interest")
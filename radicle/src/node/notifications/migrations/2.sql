@@ -0,0 +1,3 @@
+-- Comment that mentioned the local user, if the update is a mention.
+-- Null if the update doesn't mention the user.
+alter table "repository-notifications" add column "mention" text default null;
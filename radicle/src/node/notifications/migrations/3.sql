@@ -0,0 +1,3 @@
+-- Summary of the COB operations behind the update, as a JSON array.
+-- Null if the update isn't on a COB, or has no summary.
+alter table "repository-notifications" add column "activity" text default null;
@@ -9,14 +9,17 @@ use localtime::LocalTime;
 use sqlite as sql;
 use thiserror::Error;
 
+use crate::cob::thread::CommentId;
 use crate::git;
-use crate::git::{Oid, RefError, RefString};
+use crate::git::{Oid, PatternStr, PatternString, Qualified, RefError, RefStr, RefString};
+use crate::node::db;
 use crate::prelude::RepoId;
 use crate::sql::transaction;
 use crate::storage::RefUpdate;
 
 use super::{
-    Notification, NotificationId, NotificationKind, NotificationKindError, NotificationStatus,
+    Activity, Interest, Notification, NotificationId, NotificationKind, NotificationKindError,
+    NotificationStatus, Rule, Sink,
 };
 
 /// How long to wait for the database lock to be released before failing a read.
@@ -24,6 +27,14 @@ const DB_READ_TIMEOUT: time::Duration = time::Duration::from_secs(3);
 /// How long to wait for the database lock to be released before failing a write.
 const DB_WRITE_TIMEOUT: time::Duration = time::Duration::from_secs(6);
 
+/// Database migrations.
+/// The first migration is the creation of the initial tables.
+const MIGRATIONS: &[&str] = &[
+    include_str!("migrations/1.sql"),
+    include_str!("migrations/2.sql"),
+    include_str!("migrations/3.sql"),
+];
+
 #[derive(Error, Debug)]
 pub enum Error {
     /// I/O error.
@@ -32,6 +43,9 @@ pub enum Error {
     /// An Internal error.
     #[error("internal error: {0}")]
     Internal(#[from] sql::Error),
+    /// Database version error.
+    #[error("database error: {0}")]
+    Database(#[from] db::Error),
     /// Timestamp error.
     #[error("invalid timestamp: {0}")]
     Timestamp(#[from] TryFromIntError),
@@ -41,9 +55,20 @@ pub enum Error {
     /// Invalid Git ref format.
     #[error("invalid ref format: {0}")]
     RefFormat(#[from] git_ext::ref_format::Error),
+    /// Invalid activity encoding.
+    #[error("invalid activity: {0}")]
+    Activity(#[from] serde_json::Error),
     /// Invalid notification kind.
     #[error("invalid notification kind: {0}")]
     NotificationKind(#[from] NotificationKindError),
+    /// The database schema is older than this version of the store. Read-only
+    /// stores can't migrate the database.
+    #[error(
+        "database is at version {0}, but version {} is required; \
+        open it for writing to migrate it",
+        MIGRATIONS.len()
+    )]
+    Outdated(usize),
     /// Not found.
     #[error("notification {0} not found")]
     NotificationNotFound(NotificationId),
@@ -63,6 +88,7 @@ pub struct Write;
 #[derive(Clone)]
 pub struct Store<T> {
     db: Arc<sql::ConnectionThreadSafe>,
+    sinks: Vec<Arc<dyn Sink>>,
     marker: PhantomData<T>,
 }
 
@@ -73,8 +99,6 @@ impl<T> fmt::Debug for Store<T> {
 }
 
 impl Store<Read> {
-    const SCHEMA: &'static str = include_str!("schema.sql");
-
     /// Same as [`Self::open`], but in read-only mode. This is useful to have multiple
     /// open databases, as no locking is required.
     pub fn reader<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
@@ -83,10 +107,15 @@ impl Store<Read> {
             sqlite::OpenFlags::new().with_read_only(),
         )?;
         db.set_busy_timeout(DB_READ_TIMEOUT.as_millis() as usize)?;
-        db.execute(Self::SCHEMA)?;
+
+        let version = db::version(&db)?;
+        if version < MIGRATIONS.len() {
+            return Err(Error::Outdated(version));
+        }
 
         Ok(Self {
             db: Arc::new(db),
+            sinks: Vec::new(),
             marker: PhantomData,
         })
     }
@@ -97,27 +126,27 @@ impl Store<Read> {
             ":memory:",
             sqlite::OpenFlags::new().with_read_only(),
         )?;
-        db.execute(Self::SCHEMA)?;
+        migrate(&db)?;
 
         Ok(Self {
             db: Arc::new(db),
+            sinks: Vec::new(),
             marker: PhantomData,
         })
     }
 }
 
 impl Store<Write> {
-    const SCHEMA: &'static str = include_str!("schema.sql");
-
     /// Open a policy store at the given path. Creates a new store if it
     /// doesn't exist.
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
         let mut db = sql::Connection::open_thread_safe(path)?;
         db.set_busy_timeout(DB_WRITE_TIMEOUT.as_millis() as usize)?;
-        db.execute(Self::SCHEMA)?;
+        migrate(&db)?;
 
         Ok(Self {
             db: Arc::new(db),
+            sinks: Vec::new(),
             marker: PhantomData,
         })
     }
@@ -125,10 +154,11 @@ impl Store<Write> {
     /// Create a new in-memory address book.
     pub fn memory() -> Result<Self, Error> {
         let db = sql::Connection::open_thread_safe(":memory:")?;
-        db.execute(Self::SCHEMA)?;
+        migrate(&db)?;
 
         Ok(Self {
             db: Arc::new(db),
+            sinks: Vec::new(),
             marker: PhantomData,
         })
     }
@@ -137,10 +167,17 @@ impl Store<Write> {
     pub fn read_only(self) -> Store<Read> {
         Store {
             db: self.db,
+            sinks: Vec::new(),
             marker: PhantomData,
         }
     }
 
+    /// Deliver new notifications to the given sink, in addition to storing them.
+    pub fn with_sink(mut self, sink: impl Sink + 'static) -> Self {
+        self.sinks.push(Arc::new(sink));
+        self
+    }
+
     /// Set notification read status for the given notifications.
     pub fn set_status(
         &mut self,
@@ -164,29 +201,94 @@ impl Store<Write> {
     }
 
     /// Insert a notification. Resets the status to *unread* if it already exists.
+    /// An unread mention stays a mention.
     pub fn insert(
         &mut self,
         repo: &RepoId,
         update: &RefUpdate,
         timestamp: LocalTime,
     ) -> Result<bool, Error> {
-        let mut stmt = self.db.prepare(
-            "INSERT INTO `repository-notifications` (repo, ref, old, new, timestamp)
-             VALUES (?1, ?2, ?3, ?4, ?5)
-             ON CONFLICT DO UPDATE
-             SET old = ?3, new = ?4, timestamp = ?5, status = null",
-        )?;
-        let old = update.old().map(|o| o.to_string());
-        let new = update.new().map(|o| o.to_string());
+        self.insert_activity(repo, update, &[], None, timestamp)
+    }
 
-        stmt.bind((1, repo))?;
-        stmt.bind((2, update.name().as_str()))?;
-        stmt.bind((3, old.as_deref()))?;
-        stmt.bind((4, new.as_deref()))?;
-        stmt.bind((5, i64::try_from(timestamp.as_millis())?))?;
-        stmt.next()?;
+    /// Insert a notification for a comment mentioning the user. Replaces any notification
+    /// for the same reference.
+    pub fn insert_mention(
+        &mut self,
+        repo: &RepoId,
+        update: &RefUpdate,
+        comment: &CommentId,
+        timestamp: LocalTime,
+    ) -> Result<bool, Error> {
+        self.insert_activity(repo, update, &[], Some(comment), timestamp)
+    }
 
-        Ok(self.db.change_count() > 0)
+    /// Insert a notification along with the activity behind it, and optionally a comment
+    /// mentioning the user. If an unread notification already exists for the same
+    /// reference, the activity is appended to it, and an existing mention is kept unless
+    /// a new one is given.
+    pub fn insert_activity(
+        &mut self,
+        repo: &RepoId,
+        update: &RefUpdate,
+        activity: &[Activity],
+        mention: Option<&CommentId>,
+        timestamp: LocalTime,
+    ) -> Result<bool, Error> {
+        let inserted = transaction::<_, Error>(&self.db, |db| {
+            let mut stmt = db.prepare(
+                "SELECT activity FROM `repository-notifications`
+                 WHERE repo = ?1 AND ref = ?2 AND status IS NULL",
+            )?;
+            stmt.bind((1, repo))?;
+            stmt.bind((2, update.name().as_str()))?;
+
+            let mut unread = Vec::new();
+            if let Some(Ok(row)) = stmt.into_iter().next() {
+                if let Some(json) = row.try_read::<Option<&str>, _>("activity")? {
+                    unread = serde_json::from_str::<Vec<Activity>>(json)?;
+                }
+            }
+            unread.extend_from_slice(activity);
+
+            let activity = if unread.is_empty() {
+                None
+            } else {
+                Some(serde_json::to_string(&unread)?)
+            };
+            let mut stmt = db.prepare(
+                "INSERT INTO `repository-notifications`
+                   (repo, ref, old, new, timestamp, mention, activity)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                 ON CONFLICT DO UPDATE
+                 SET old = ?3, new = ?4, timestamp = ?5,
+                     mention = COALESCE(?6, CASE WHEN status IS NULL THEN mention END),
+                     activity = ?7, status = null",
+            )?;
+            let old = update.old().map(|o| o.to_string());
+            let new = update.new().map(|o| o.to_string());
+            let mention = mention.map(|c| c.to_string());
+
+            stmt.bind((1, repo))?;
+            stmt.bind((2, update.name().as_str()))?;
+            stmt.bind((3, old.as_deref()))?;
+            stmt.bind((4, new.as_deref()))?;
+            stmt.bind((5, i64::try_from(timestamp.as_millis())?))?;
+            stmt.bind((6, mention.as_deref()))?;
+            stmt.bind((7, activity.as_deref()))?;
+            stmt.next()?;
+
+            Ok(db.change_count() > 0)
+        })?;
+
+        if inserted && !self.sinks.is_empty() {
+            if let Some(notification) = self.get_by_ref(repo, update.name())? {
+                for sink in &self.sinks {
+                    sink.deliver(&notification);
+                }
+            }
+        }
+        Ok(inserted)
     }
 
     /// Delete the given notifications.
@@ -229,6 +331,63 @@ impl Store<Write> {
 
         Ok(self.db.change_count())
     }
+
+    /// Set the interest in the references of a repo matching the given pattern.
+    /// Replaces any interest previously set on the same pattern.
+    /// Returns `false` if the interest was already set.
+    pub fn watch(
+        &mut self,
+        repo: &RepoId,
+        pattern: &PatternStr,
+        interest: Interest,
+    ) -> Result<bool, Error> {
+        transaction(&self.db, |db| {
+            let mut stmt = db.prepare(
+                "SELECT interest FROM `repository-notification-interests`
+                 WHERE repo = ?1 AND glob = ?2",
+            )?;
+            stmt.bind((1, repo))?;
+            stmt.bind((2, pattern.as_str()))?;
+
+            if let Some(Ok(row)) = stmt.into_iter().next() {
+                if row.try_read::<Interest, _>("interest")? == interest {
+                    return Ok(false);
+                }
+            }
+            let mut stmt = db.prepare(
+                "DELETE FROM `repository-notification-interests`
+                 WHERE repo = ?1 AND glob = ?2",
+            )?;
+            stmt.bind((1, repo))?;
+            stmt.bind((2, pattern.as_str()))?;
+            stmt.next()?;
+
+            let mut stmt = db.prepare(
+                "INSERT INTO `repository-notification-interests` (repo, glob, interest)
+                 VALUES (?1, ?2, ?3)",
+            )?;
+            stmt.bind((1, repo))?;
+            stmt.bind((2, pattern.as_str()))?;
+            stmt.bind((3, interest))?;
+            stmt.next()?;
+
+            Ok(true)
+        })
+    }
+
+    /// Remove the interest set on the given pattern.
+    /// Returns `false` if no interest was set.
+    pub fn unwatch(&mut self, repo: &RepoId, pattern: &PatternStr) -> Result<bool, Error> {
+        let mut stmt = self.db.prepare(
+            "DELETE FROM `repository-notification-interests`
+             WHERE repo = ?1 AND glob = ?2",
+        )?;
+        stmt.bind((1, repo))?;
+        stmt.bind((2, pattern.as_str()))?;
+        stmt.next()?;
+
+        Ok(self.db.change_count() > 0)
+    }
 }
 
 /// `Read` methods for `Store`. This implies that a
@@ -237,7 +396,7 @@ impl<T> Store<T> {
     /// Get a specific notification.
     pub fn get(&self, id: NotificationId) -> Result<Notification, Error> {
         let mut stmt = self.db.prepare(
-            "SELECT rowid, repo, ref, old, new, status, timestamp
+            "SELECT rowid, repo, ref, old, new, status, timestamp, mention, activity
              FROM `repository-notifications`
              WHERE rowid = ?",
         )?;
@@ -249,10 +408,26 @@ impl<T> Store<T> {
         Err(Error::NotificationNotFound(id))
     }
 
+    /// Get the notification for the given repository ref, if any.
+    fn get_by_ref(&self, repo: &RepoId, refname: &RefStr) -> Result<Option<Notification>, Error> {
+        let mut stmt = self.db.prepare(
+            "SELECT rowid, repo, ref, old, new, status, timestamp, mention, activity
+             FROM `repository-notifications`
+             WHERE repo = ?1 AND ref = ?2",
+        )?;
+        stmt.bind((1, repo))?;
+        stmt.bind((2, refname.as_str()))?;
+
+        stmt.into_iter()
+            .next()
+            .map(|row| parse::notification(row?))
+            .transpose()
+    }
+
     /// Get all notifications.
     pub fn all(&self) -> Result<impl Iterator<Item = Result<Notification, Error>> + '_, Error> {
         let stmt = self.db.prepare(
-            "SELECT rowid, repo, ref, old, new, status, timestamp
+            "SELECT rowid, repo, ref, old, new, status, timestamp, mention, activity
              FROM `repository-notifications`
              ORDER BY timestamp DESC",
         )?;
@@ -270,7 +445,7 @@ impl<T> Store<T> {
         until: LocalTime,
     ) -> Result<impl Iterator<Item = Result<Notification, Error>> + '_, Error> {
         let mut stmt = self.db.prepare(
-            "SELECT rowid, repo, ref, old, new, status, timestamp
+            "SELECT rowid, repo, ref, old, new, status, timestamp, mention, activity
              FROM `repository-notifications`
              WHERE timestamp >= ?1 AND timestamp < ?2
              ORDER BY timestamp",
@@ -294,7 +469,7 @@ impl<T> Store<T> {
         order_by: &str,
     ) -> Result<impl Iterator<Item = Result<Notification, Error>> + '_, Error> {
         let mut stmt = self.db.prepare(format!(
-            "SELECT rowid, repo, ref, old, new, status, timestamp
+            "SELECT rowid, repo, ref, old, new, status, timestamp, mention, activity
              FROM `repository-notifications`
              WHERE repo = ?
              ORDER BY {order_by} DESC",
@@ -307,6 +482,46 @@ impl<T> Store<T> {
         }))
     }
 
+    /// Get the interest in the given reference of a repo, if any was set.
+    /// When more than one pattern matches, the most specific one applies.
+    pub fn interest(&self, repo: &RepoId, refname: &Qualified) -> Result<Option<Interest>, Error> {
+        let mut stmt = self.db.prepare(
+            "SELECT interest FROM `repository-notification-interests`
+             WHERE repo = ?1 AND ?2 GLOB glob
+             ORDER BY length(glob) DESC
+             LIMIT 1",
+        )?;
+        stmt.bind((1, repo))?;
+        stmt.bind((2, refname.as_str()))?;
+
+        if let Some(Ok(row)) = stmt.into_iter().next() {
+            return Ok(Some(row.try_read::<Interest, _>("interest")?));
+        }
+        Ok(None)
+    }
+
+    /// Get all interest rules, ordered by repo and pattern.
+    pub fn rules(&self) -> Result<impl Iterator<Item = Result<Rule, Error>> + '_, Error> {
+        let stmt = self.db.prepare(
+            "SELECT repo, glob, interest
+             FROM `repository-notification-interests`
+             ORDER BY repo, glob",
+        )?;
+
+        Ok(stmt.into_iter().map(move |row| {
+            let row = row?;
+            let repo = row.try_read::<RepoId, _>("repo")?;
+            let pattern = PatternString::try_from(row.try_read::<&str, _>("glob")?)?;
+            let interest = row.try_read::<Interest, _>("interest")?;
+
+            Ok(Rule {
+                repo,
+                pattern,
+                interest,
+            })
+        }))
+    }
+
     /// Get the total notification count.
     pub fn count(&self) -> Result<usize, Error> {
         let stmt = self
@@ -342,6 +557,20 @@ impl<T> Store<T> {
     }
 }
 
+/// Migrate the database to the latest schema.
+fn migrate(db: &sql::Connection) -> Result<usize, Error> {
+    let mut version = db::version(db)?;
+    for migration in MIGRATIONS.iter().skip(version) {
+        transaction(db, |db| {
+            db.execute(migration)?;
+            version = db::bump(db)?;
+
+            Ok::<_, Error>(())
+        })?;
+    }
+    Ok(version)
+}
+
 mod parse {
     use super::*;
 
@@ -377,7 +606,25 @@ mod parse {
         let timestamp = row.try_read::<i64, _>("timestamp")?;
         let timestamp = LocalTime::from_millis(timestamp as u128);
         let qualified = qualified.to_owned();
-        let kind = NotificationKind::try_from(qualified.clone())?;
+        let kind = match row.try_read::<Option<&str>, _>("mention")? {
+            Some(comment) => {
+                let comment = CommentId::from_str(comment).map_err(|e| {
+                    Error::Internal(sql::Error {
+                        code: None,
+                        message: Some(format!(
+                            "sql: invalid oid in `mention` column: {comment:?}: {e}"
+                        )),
+                    })
+                })?;
+                NotificationKind::mention(qualified.clone(), comment)?
+            }
+            None => NotificationKind::try_from(qualified.clone())?,
+        };
+
+        let activity = match row.try_read::<Option<&str>, _>("activity")? {
+            Some(json) => serde_json::from_str(json)?,
+            None => Vec::new(),
+        };
 
         Ok(Notification {
             id,
@@ -387,6 +634,7 @@ mod parse {
             qualified,
             status,
             kind,
+            activity,
             timestamp,
         })
     }
@@ -397,8 +645,21 @@ mod test {
     use radicle_git_ext::ref_format::{qualified, refname};
 
     use super::*;
+    use crate::node::notifications::{pattern, ActivityKind};
     use crate::{cob, node::NodeId, test::arbitrary};
 
+    #[test]
+    fn test_reader_outdated() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("notifications.db");
+
+        sql::Connection::open(&path).unwrap();
+        assert!(matches!(Store::reader(&path), Err(Error::Outdated(0))));
+
+        Store::open(&path).unwrap();
+        assert!(Store::reader(&path).is_ok());
+    }
+
     #[test]
     fn test_clear() {
         let mut db = Store::open(":memory:").unwrap();
@@ -463,6 +724,7 @@ mod test {
                 kind: NotificationKind::Branch {
                     name: refname!("dev")
                 },
+                activity: vec![],
                 status: NotificationStatus::Unread,
                 timestamp: time3,
             }
@@ -478,6 +740,7 @@ mod test {
                 kind: NotificationKind::Branch {
                     name: refname!("other")
                 },
+                activity: vec![],
                 status: NotificationStatus::Unread,
                 timestamp: time2,
             }
@@ -493,6 +756,7 @@ mod test {
                 kind: NotificationKind::Branch {
                     name: refname!("master")
                 },
+                activity: vec![],
                 status: NotificationStatus::Unread,
                 timestamp: time1,
             }
@@ -582,6 +846,7 @@ mod test {
                     name: refname!("master")
                 },
                 // Status is reset to "unread".
+                activity: vec![],
                 status: NotificationStatus::Unread,
                 timestamp: time2,
             }
@@ -630,10 +895,157 @@ mod test {
                         id: "d87dcfe8c2b3200e78b128d9b959cfdf7063fefe".parse().unwrap(),
                     },
                 },
+                activity: vec![],
                 status: NotificationStatus::Unread,
                 timestamp,
             }
         );
         assert!(notifs.next().is_none());
     }
+
+    #[test]
+    fn test_mention_notifications() {
+        let repo = arbitrary::gen::<RepoId>(1);
+        let comment = arbitrary::oid();
+        let timestamp = LocalTime::from_millis(32189874);
+        let mut db = Store::open(":memory:").unwrap();
+        let update = RefUpdate::Updated {
+            name: refname!("refs/cobs/xyz.radicle.issue/d87dcfe8c2b3200e78b128d9b959cfdf7063fefe"),
+            old: arbitrary::oid(),
+            new: arbitrary::oid(),
+        };
+        let typed_id = cob::TypedId {
+            type_name: cob::issue::TYPENAME.clone(),
+            id: "d87dcfe8c2b3200e78b128d9b959cfdf7063fefe".parse().unwrap(),
+        };
+
+        assert!(db
+            .insert_mention(&repo, &update, &comment, timestamp)
+            .unwrap());
+        assert_eq!(
+            db.get(1).unwrap().kind,
+            NotificationKind::Mention {
+                typed_id: typed_id.clone(),
+                comment,
+            }
+        );
+
+        // An unread mention stays a mention on further updates.
+        assert!(db.insert(&repo, &update, timestamp).unwrap());
+        assert_eq!(
+            db.get(1).unwrap().kind,
+            NotificationKind::Mention {
+                typed_id: typed_id.clone(),
+                comment,
+            }
+        );
+
+        // Once read, further updates are plain COB updates.
+        db.set_status(NotificationStatus::ReadAt(timestamp), &[1])
+            .unwrap();
+        assert!(db.insert(&repo, &update, timestamp).unwrap());
+        assert_eq!(db.get(1).unwrap().kind, NotificationKind::Cob { typed_id });
+    }
+
+    #[test]
+    fn test_activity_notifications() {
+        let repo = arbitrary::gen::<RepoId>(1);
+        let author = arbitrary::gen::<NodeId>(1);
+        let timestamp = LocalTime::from_millis(32189874);
+        let mut db = Store::open(":memory:").unwrap();
+        let update = RefUpdate::Updated {
+            name: refname!("refs/cobs/xyz.radicle.issue/d87dcfe8c2b3200e78b128d9b959cfdf7063fefe"),
+            old: arbitrary::oid(),
+            new: arbitrary::oid(),
+        };
+        let activity = |kind| Activity {
+            entry: arbitrary::oid(),
+            author,
+            timestamp: cob::Timestamp::from_secs(32189),
+            kind,
+        };
+        let commented = activity(ActivityKind::Commented);
+        let edited = activity(ActivityKind::Edited);
+        let closed = activity(ActivityKind::StateChanged {
+            state: String::from("closed"),
+        });
+
+        assert!(db
+            .insert_activity(&repo, &update, &[commented.clone()], None, timestamp)
+            .unwrap());
+        assert_eq!(db.get(1).unwrap().activity, vec![commented.clone()]);
+
+        // Activity accumulates while the notification is unread.
+        assert!(db
+            .insert_activity(&repo, &update, &[edited.clone()], None, timestamp)
+            .unwrap());
+        assert_eq!(db.get(1).unwrap().activity, vec![commented, edited]);
+
+        // Once read, new activity replaces the old.
+        db.set_status(NotificationStatus::ReadAt(timestamp), &[1])
+            .unwrap();
+        assert!(db
+            .insert_activity(&repo, &update, &[closed.clone()], None, timestamp)
+            .unwrap());
+        assert_eq!(db.get(1).unwrap().activity, vec![closed]);
+    }
+
+    #[test]
+    fn test_interests() {
+        let repo = arbitrary::gen::<RepoId>(1);
+        let other = arbitrary::gen::<RepoId>(1);
+        let issue = cob::ObjectId::from(arbitrary::oid());
+        let issue_ref =
+            qualified!("refs/cobs/xyz.radicle.issue").join(git::Component::from(&issue));
+        let mut db = Store::open(":memory:").unwrap();
+
+        assert_eq!(db.interest(&repo, &issue_ref).unwrap(), None);
+
+        assert!(db
+            .watch(&repo, &pattern::all(), Interest::Relevant)
+            .unwrap());
+        assert!(!db
+            .watch(&repo, &pattern::all(), Interest::Relevant)
+            .unwrap());
+        assert_eq!(
+            db.interest(&repo, &qualified!("refs/heads/master"))
+                .unwrap(),
+            Some(Interest::Relevant)
+        );
+        assert_eq!(db.interest(&other, &issue_ref).unwrap(), None);
+
+        // More specific patterns take precedence.
+        assert!(db
+            .watch(
+                &repo,
+                &pattern::cobs(&cob::issue::TYPENAME),
+                Interest::Mentions
+            )
+            .unwrap());
+        assert_eq!(
+            db.interest(&repo, &issue_ref).unwrap(),
+            Some(Interest::Mentions)
+        );
+        assert!(db
+            .watch(&repo, &pattern::cob(&issue), Interest::None)
+            .unwrap());
+        assert_eq!(
+            db.interest(&repo, &issue_ref).unwrap(),
+            Some(Interest::None)
+        );
+
+        // Setting a new interest on a pattern replaces the previous one.
+        assert!(db
+            .watch(&repo, &pattern::cob(&issue), Interest::All)
+            .unwrap());
+        assert_eq!(db.interest(&repo, &issue_ref).unwrap(), Some(Interest::All));
+        assert_eq!(db.rules().unwrap().count(), 3);
+
+        assert!(db.unwatch(&repo, &pattern::cob(&issue)).unwrap());
+        assert!(!db.unwatch(&repo, &pattern::cob(&issue)).unwrap());
+        assert_eq!(
+            db.interest(&repo, &issue_ref).unwrap(),
+            Some(Interest::Mentions)
+        );
+    }
 }
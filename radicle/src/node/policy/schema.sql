@@ -29,3 +29,30 @@ create table if not exists "seeding" (
   "policy"             text      default 'allow'
   --
 ) strict;
+
+-- Partial replication settings for seeded repositories.
+--
+-- Repositories listed here are replicated shallowly: only the canonical
+-- branch of the delegates is fetched, up to the given commit depth.
+create table if not exists "partial" (
+  -- Repository ID.
+  "id"                 text      primary key not null,
+  -- Maximum number of commits to fetch from the tip of the canonical branch.
+  "depth"              integer   not null
+  --
+) strict;
+
+-- Sync schedules of seeded repositories.
+--
+-- Each interval is in seconds. Tasks with no interval are not scheduled.
+create table if not exists "schedule" (
+  -- Repository ID.
+  "id"                 text      primary key not null,
+  -- Interval at which to fetch from preferred seeds.
+  "fetch"              integer,
+  -- Interval at which to re-announce our refs.
+  "announce"           integer,
+  -- Interval at which to fetch from all connected seeds.
+  "resync"             integer
+  --
+) strict;
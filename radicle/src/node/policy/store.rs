@@ -1,11 +1,16 @@
 #![allow(clippy::type_complexity)]
+use std::collections::BTreeSet;
 use std::marker::PhantomData;
+use std::num::NonZeroUsize;
 use std::path::Path;
 use std::{fmt, io, ops::Not as _, str::FromStr, time};
 
+use localtime::LocalDuration;
+
 use sqlite as sql;
 use thiserror::Error;
 
+use crate::node::sync::Schedule;
 use crate::node::{Alias, AliasStore};
 use crate::prelude::{NodeId, RepoId};
 
@@ -179,6 +184,59 @@ impl Store<Write> {
         Ok(self.db.change_count() > 0)
     }
 
+    /// Set a repository's partial replication depth. If `None`, the repository
+    /// is replicated in full.
+    pub fn set_depth(&mut self, id: &RepoId, depth: Option<NonZeroUsize>) -> Result<bool, Error> {
+        let Some(depth) = depth else {
+            let mut stmt = self.db.prepare("DELETE FROM `partial` WHERE id = ?")?;
+
+            stmt.bind((1, id))?;
+            stmt.next()?;
+
+            return Ok(self.db.change_count() > 0);
+        };
+        let mut stmt = self.db.prepare(
+            "INSERT INTO `partial` (id, depth)
+             VALUES (?1, ?2)
+             ON CONFLICT DO UPDATE
+             SET depth = ?2 WHERE depth != ?2",
+        )?;
+
+        stmt.bind((1, id))?;
+        stmt.bind((2, depth.get() as i64))?;
+        stmt.next()?;
+
+        Ok(self.db.change_count() > 0)
+    }
+
+    /// Set a repository's sync schedule. An empty schedule removes it.
+    pub fn set_schedule(&mut self, schedule: &Schedule) -> Result<bool, Error> {
+        if schedule.is_empty() {
+            let mut stmt = self.db.prepare("DELETE FROM `schedule` WHERE id = ?")?;
+
+            stmt.bind((1, &schedule.rid))?;
+            stmt.next()?;
+
+            return Ok(self.db.change_count() > 0);
+        }
+        let mut stmt = self.db.prepare(
+            "INSERT INTO `schedule` (id, fetch, announce, resync)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT DO UPDATE
+             SET fetch = ?2, announce = ?3, resync = ?4
+             WHERE fetch IS NOT ?2 OR announce IS NOT ?3 OR resync IS NOT ?4",
+        )?;
+        let secs = |d: Option<LocalDuration>| d.map(|d| d.as_secs() as i64);
+
+        stmt.bind((1, &schedule.rid))?;
+        stmt.bind((2, secs(schedule.fetch)))?;
+        stmt.bind((3, secs(schedule.announce)))?;
+        stmt.bind((4, secs(schedule.resync)))?;
+        stmt.next()?;
+
+        Ok(self.db.change_count() > 0)
+    }
+
     /// Unfollow a node.
     pub fn unfollow(&mut self, id: &NodeId) -> Result<bool, Error> {
         let mut stmt = self.db.prepare("DELETE FROM `following` WHERE id = ?")?;
@@ -195,8 +253,13 @@ impl Store<Write> {
 
         stmt.bind((1, id))?;
         stmt.next()?;
+        drop(stmt);
 
-        Ok(self.db.change_count() > 0)
+        let unseeded = self.db.change_count() > 0;
+        self.set_depth(id, None)?;
+        self.set_schedule(&Schedule::new(*id))?;
+
+        Ok(unseeded)
     }
 }
 
@@ -269,6 +332,64 @@ impl<T> Store<T> {
         Ok(None)
     }
 
+    /// Get a repository's partial replication depth, if it is replicated partially.
+    pub fn depth(&self, id: &RepoId) -> Result<Option<NonZeroUsize>, Error> {
+        let mut stmt = self
+            .db
+            .prepare("SELECT depth FROM `partial` WHERE id = ?")?;
+
+        stmt.bind((1, id))?;
+
+        if let Some(Ok(row)) = stmt.into_iter().next() {
+            let depth = row.read::<i64, _>("depth");
+
+            return Ok(usize::try_from(depth).ok().and_then(NonZeroUsize::new));
+        }
+        Ok(None)
+    }
+
+    /// Get a repository's sync schedule, if it has one.
+    pub fn schedule(&self, id: &RepoId) -> Result<Option<Schedule>, Error> {
+        let mut stmt = self
+            .db
+            .prepare("SELECT id, fetch, announce, resync FROM `schedule` WHERE id = ?")?;
+
+        stmt.bind((1, id))?;
+
+        if let Some(Ok(row)) = stmt.into_iter().next() {
+            return Ok(Some(Self::schedule_from_row(&row)));
+        }
+        Ok(None)
+    }
+
+    /// Get the sync schedules of all repositories.
+    pub fn schedules(&self) -> Result<Box<dyn Iterator<Item = Schedule>>, Error> {
+        let mut stmt = self
+            .db
+            .prepare("SELECT id, fetch, announce, resync FROM `schedule`")?
+            .into_iter();
+        let mut entries = Vec::new();
+
+        while let Some(Ok(row)) = stmt.next() {
+            entries.push(Self::schedule_from_row(&row));
+        }
+        Ok(Box::new(entries.into_iter()))
+    }
+
+    fn schedule_from_row(row: &sql::Row) -> Schedule {
+        let interval = |column: &str| {
+            row.read::<Option<i64>, _>(column)
+                .and_then(|secs| u64::try_from(secs).ok())
+                .map(LocalDuration::from_secs)
+        };
+        Schedule {
+            rid: row.read("id"),
+            fetch: interval("fetch"),
+            announce: interval("announce"),
+            resync: interval("resync"),
+        }
+    }
+
     /// Get node follow policies.
     pub fn follow_policies(&self) -> Result<Box<dyn Iterator<Item = FollowPolicy>>, Error> {
         let mut stmt = self
@@ -328,6 +449,22 @@ impl<T> AliasStore for Store<T> {
             .map(|node| node.and_then(|n| n.alias))
             .unwrap_or(None)
     }
+
+    /// Lookup the followed nodes with the given alias.
+    fn reverse_lookup(&self, alias: &Alias) -> BTreeSet<NodeId> {
+        let Ok(mut stmt) = self
+            .db
+            .prepare("SELECT id FROM `following` WHERE alias = ?")
+        else {
+            return BTreeSet::new();
+        };
+        if stmt.bind((1, alias.as_ref())).is_err() {
+            return BTreeSet::new();
+        }
+        stmt.into_iter()
+            .filter_map(|row| row.ok()?.try_read::<NodeId, _>("id").ok())
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -361,6 +498,57 @@ mod test {
         assert!(!db.is_seeding(&id).unwrap());
     }
 
+    #[test]
+    fn test_repo_depth() {
+        let id = arbitrary::gen::<RepoId>(1);
+        let depth = NonZeroUsize::new(3).unwrap();
+        let mut db = Store::open(":memory:").unwrap();
+
+        assert_eq!(db.depth(&id).unwrap(), None);
+        assert!(db.seed(&id, Scope::All).unwrap());
+        assert!(db.set_depth(&id, Some(depth)).unwrap());
+        assert!(!db.set_depth(&id, Some(depth)).unwrap());
+        assert_eq!(db.depth(&id).unwrap(), Some(depth));
+        assert!(db.set_depth(&id, None).unwrap());
+        assert_eq!(db.depth(&id).unwrap(), None);
+        assert!(db.set_depth(&id, Some(depth)).unwrap());
+        assert!(db.unseed(&id).unwrap());
+        assert_eq!(db.depth(&id).unwrap(), None);
+    }
+
+    #[test]
+    fn test_repo_schedule() {
+        let id = arbitrary::gen::<RepoId>(1);
+        let mut db = Store::open(":memory:").unwrap();
+        let schedule = Schedule {
+            fetch: Some(LocalDuration::from_mins(15)),
+            resync: Some(LocalDuration::from_mins(24 * 60)),
+            ..Schedule::new(id)
+        };
+
+        assert_eq!(db.schedule(&id).unwrap(), None);
+        assert!(db.seed(&id, Scope::All).unwrap());
+        assert!(db.set_schedule(&schedule).unwrap());
+        assert!(!db.set_schedule(&schedule).unwrap());
+        assert_eq!(db.schedule(&id).unwrap(), Some(schedule.clone()));
+        assert_eq!(
+            db.schedules().unwrap().collect::<Vec<_>>(),
+            vec![schedule.clone()]
+        );
+
+        let updated = Schedule {
+            announce: Some(LocalDuration::from_mins(60)),
+            ..schedule.clone()
+        };
+        assert!(db.set_schedule(&updated).unwrap());
+        assert_eq!(db.schedule(&id).unwrap(), Some(updated));
+        assert!(db.set_schedule(&Schedule::new(id)).unwrap());
+        assert_eq!(db.schedule(&id).unwrap(), None);
+        assert!(db.set_schedule(&schedule).unwrap());
+        assert!(db.unseed(&id).unwrap());
+        assert_eq!(db.schedule(&id).unwrap(), None);
+    }
+
     #[test]
     fn test_node_policies() {
         let ids = arbitrary::vec::<NodeId>(3);
@@ -0,0 +1,199 @@
+//! Replication requirements and sync schedules of repositories.
+use std::collections::BTreeSet;
+
+use localtime::{LocalDuration, LocalTime};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::node::NodeId;
+use crate::prelude::RepoId;
+
+/// Default time to wait for a replication requirement to be met.
+pub const DEFAULT_REPLICATION_TIMEOUT: LocalDuration = LocalDuration::from_secs(30);
+
+/// Error validating a [`Replication`] requirement.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ReplicationError {
+    /// More replicas are required than there are seeds to count towards the requirement.
+    #[error("{replicas} replica(s) required, but only {seeds} seed(s) can count towards it")]
+    TooManyReplicas { replicas: usize, seeds: usize },
+}
+
+/// How many seeds should hold our latest refs of a repository after a push.
+///
+/// A seed counts towards the requirement once its copy of our `rad/sigrefs` matches
+/// ours, as recorded in the node's sync status.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", try_from = "RawReplication")]
+pub struct Replication {
+    /// Number of seeds that must be in sync with our refs.
+    pub replicas: usize,
+    /// If set, only these seeds count towards the requirement.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub seeds: BTreeSet<NodeId>,
+    /// How long to wait for the requirement to be met.
+    #[serde(default = "default_timeout")]
+    pub timeout: LocalDuration,
+    /// Whether the requirement must be checked on every push. Pushes are refused if it
+    /// can't be, eg. when syncing is disabled. If it isn't met in time, this is reported
+    /// as an error, although the refs are pushed either way.
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// A [`Replication`] requirement, as found in the configuration, before validation.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawReplication {
+    replicas: usize,
+    #[serde(default)]
+    seeds: BTreeSet<NodeId>,
+    #[serde(default = "default_timeout")]
+    timeout: LocalDuration,
+    #[serde(default)]
+    required: bool,
+}
+
+impl TryFrom<RawReplication> for Replication {
+    type Error = ReplicationError;
+
+    fn try_from(raw: RawReplication) -> Result<Self, Self::Error> {
+        // If only some seeds count, there must be enough of them to meet the requirement.
+        if !raw.seeds.is_empty() && raw.replicas > raw.seeds.len() {
+            return Err(ReplicationError::TooManyReplicas {
+                replicas: raw.replicas,
+                seeds: raw.seeds.len(),
+            });
+        }
+        Ok(Self {
+            replicas: raw.replicas,
+            seeds: raw.seeds,
+            timeout: raw.timeout,
+            required: raw.required,
+        })
+    }
+}
+
+impl Replication {
+    /// Get the seeds, out of the given synced seeds, that count towards the requirement.
+    pub fn confirmed<'a>(&self, synced: impl IntoIterator<Item = &'a NodeId>) -> BTreeSet<NodeId> {
+        synced
+            .into_iter()
+            .filter(|nid| self.seeds.is_empty() || self.seeds.contains(nid))
+            .copied()
+            .collect()
+    }
+
+    /// Check whether the requirement is met by the given confirmed seeds.
+    pub fn is_met(&self, confirmed: &BTreeSet<NodeId>) -> bool {
+        confirmed.len() >= self.replicas
+    }
+}
+
+fn default_timeout() -> LocalDuration {
+    DEFAULT_REPLICATION_TIMEOUT
+}
+
+/// Periodic sync tasks for a seeded repository, run by the node in addition to
+/// gossip-triggered fetches.
+///
+/// Tasks that are not set are never run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    /// Repository the schedule applies to.
+    pub rid: RepoId,
+    /// Fetch from our preferred seeds at this interval.
+    pub fetch: Option<LocalDuration>,
+    /// Re-announce our refs at this interval.
+    pub announce: Option<LocalDuration>,
+    /// Fetch from all connected seeds of the repository at this interval.
+    pub resync: Option<LocalDuration>,
+}
+
+impl Schedule {
+    /// Create an empty schedule for a repository.
+    pub fn new(rid: RepoId) -> Self {
+        Self {
+            rid,
+            fetch: None,
+            announce: None,
+            resync: None,
+        }
+    }
+
+    /// Check whether the schedule has no tasks.
+    pub fn is_empty(&self) -> bool {
+        self.fetch.is_none() && self.announce.is_none() && self.resync.is_none()
+    }
+
+    /// Check whether a task with the given interval, last run at `last`, is due.
+    /// Tasks that were never run are due immediately.
+    pub fn is_due(
+        interval: Option<LocalDuration>,
+        last: Option<LocalTime>,
+        now: LocalTime,
+    ) -> bool {
+        match (interval, last) {
+            (None, _) => false,
+            (Some(_), None) => true,
+            (Some(interval), Some(last)) => now - last >= interval,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::arbitrary;
+
+    #[test]
+    fn test_replication() {
+        let [a, b, c] = [
+            arbitrary::gen::<NodeId>(1),
+            arbitrary::gen::<NodeId>(1),
+            arbitrary::gen::<NodeId>(1),
+        ];
+        let any: Replication = serde_json::from_str(r#"{ "replicas": 2 }"#).unwrap();
+
+        assert_eq!(any.timeout, DEFAULT_REPLICATION_TIMEOUT);
+        assert!(!any.required);
+        assert!(!any.is_met(&any.confirmed([&a])));
+        assert!(any.is_met(&any.confirmed([&a, &c])));
+
+        let preferred = Replication {
+            replicas: 1,
+            seeds: BTreeSet::from([a, b]),
+            timeout: LocalDuration::from_secs(3),
+            required: true,
+        };
+        assert_eq!(preferred.confirmed([&b, &c]), BTreeSet::from([b]));
+        assert!(!preferred.is_met(&preferred.confirmed([&c])));
+        assert!(preferred.is_met(&preferred.confirmed([&a])));
+
+        let err = serde_json::from_value::<Replication>(serde_json::json!({
+            "replicas": 3,
+            "seeds": [a, b],
+        }))
+        .unwrap_err();
+        assert!(err.to_string().contains("3 replica(s) required"), "{err}");
+    }
+
+    #[test]
+    fn test_schedule_is_due() {
+        let now = LocalTime::from_secs(3600);
+        let interval = Some(LocalDuration::from_mins(15));
+
+        assert!(!Schedule::is_due(None, None, now));
+        assert!(Schedule::is_due(interval, None, now));
+        assert!(!Schedule::is_due(
+            interval,
+            Some(now - LocalDuration::from_mins(14)),
+            now
+        ));
+        assert!(Schedule::is_due(
+            interval,
+            Some(now - LocalDuration::from_mins(15)),
+            now
+        ));
+    }
+}
@@ -10,6 +10,7 @@
 //!     node/
 //!       control.sock                           # Node control socket
 //!
+use std::collections::{BTreeMap, BTreeSet};
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::{fs, io};
@@ -25,6 +26,7 @@ use crate::node::policy::config::store::Read;
 use crate::node::{notifications, policy, Alias, AliasStore};
 use crate::prelude::Did;
 use crate::prelude::NodeId;
+use crate::prelude::RepoId;
 use crate::storage::git::transport;
 use crate::storage::git::Storage;
 use crate::storage::{self, ReadRepository};
@@ -138,6 +140,18 @@ pub struct Config {
     /// CLI configuration.
     #[serde(default)]
     pub cli: cli::Config,
+    /// Notification delivery configuration.
+    #[serde(default)]
+    pub notifications: notifications::Config,
+    /// Named issue and patch queries.
+    #[serde(default)]
+    pub queries: BTreeMap<String, cob::query::Query>,
+    /// Replication requirements for pushes, per repository.
+    #[serde(default)]
+    pub replication: BTreeMap<RepoId, node::sync::Replication>,
+    /// External evaluators for object types defined by other tools.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub evaluators: cob::external::Evaluators,
     /// Node configuration.
     pub node: node::Config,
 }
@@ -152,6 +166,10 @@ impl Config {
             preferred_seeds: node.network.public_seeds(),
             web: web::Config::default(),
             cli: cli::Config::default(),
+            notifications: notifications::Config::default(),
+            queries: BTreeMap::new(),
+            replication: BTreeMap::new(),
+            evaluators: BTreeMap::new(),
             node,
         }
     }
@@ -305,6 +323,25 @@ impl Profile {
         }
     }
 
+    /// Return a handle for objects of types with an external evaluator, using the COBs cache
+    /// to store their states.
+    pub fn external_cobs<'a, R>(
+        &'a self,
+        repository: &'a R,
+    ) -> Result<cob::external::Objects<'a, R>, Error>
+    where
+        R: ReadRepository + cob::Store,
+    {
+        let path = self.cobs().join(cob::cache::COBS_DB_FILE);
+        let db = cob::cache::Store::open(path)?;
+
+        Ok(cob::external::Objects::new(
+            repository,
+            &self.config.evaluators,
+            db,
+        ))
+    }
+
     /// Get radicle home.
     pub fn home(&self) -> &Home {
         &self.home
@@ -348,6 +385,10 @@ impl AliasStore for Profile {
     fn alias(&self, nid: &NodeId) -> Option<Alias> {
         self.aliases().alias(nid)
     }
+
+    fn reverse_lookup(&self, alias: &Alias) -> BTreeSet<NodeId> {
+        self.aliases().reverse_lookup(alias)
+    }
 }
 
 /// Holds multiple alias stores, and will try
@@ -366,6 +407,20 @@ impl AliasStore for Aliases {
             .and_then(|db| db.alias(nid))
             .or_else(|| self.db.as_ref().and_then(|db| db.alias(nid)))
     }
+
+    /// Lookup the nodes known under the given `alias`, in both `policies.db`
+    /// and `addresses.db`.
+    fn reverse_lookup(&self, alias: &Alias) -> BTreeSet<NodeId> {
+        let mut nodes = self
+            .policies
+            .as_ref()
+            .map(|db| db.reverse_lookup(alias))
+            .unwrap_or_default();
+        if let Some(db) = &self.db {
+            nodes.extend(db.reverse_lookup(alias));
+        }
+        nodes
+    }
 }
 
 /// Get the path to the radicle home folder.
@@ -514,7 +569,7 @@ impl Home {
     {
         let path = self.cobs().join(cob::cache::COBS_DB_FILE);
         let db = cob::cache::Store::open(path)?;
-        let store = cob::issue::Issues::open(repository)?;
+        let store = cob::issue::Issues::open(repository)?.snapshots(db.clone());
         Ok(cob::issue::Cache::open(store, db))
     }
 
@@ -542,7 +597,7 @@ impl Home {
     {
         let path = self.cobs().join(cob::cache::COBS_DB_FILE);
         let db = cob::cache::Store::open(path)?;
-        let store = cob::patch::Patches::open(repository)?;
+        let store = cob::patch::Patches::open(repository)?.snapshots(db.clone());
         Ok(cob::patch::Cache::open(store, db))
     }
 }
@@ -1,7 +1,7 @@
 #![allow(clippy::let_unit_value)]
-use std::io;
 use std::path::Path;
 use std::str::FromStr;
+use std::{fs, io};
 
 use once_cell::sync::Lazy;
 use thiserror::Error;
@@ -204,6 +204,8 @@ pub enum CheckoutError {
     NotFound(RepoId),
     #[error("repository: {0}")]
     Repository(#[from] RepositoryError),
+    #[error("i/o: {0}")]
+    Io(#[from] io::Error),
 }
 
 /// Checkout a project from storage as a working copy.
@@ -232,12 +234,32 @@ pub fn checkout<P: AsRef<Path>, S: storage::ReadStorage>(
         &url,
         &url.clone().with_namespace(*remote),
     )?;
-    git::fetch(&repo, &REMOTE_NAME).map_err(CheckoutError::Fetch)?;
+
+    let stored = storage.repository(proj)?;
+    let remote_head_ref = git::refs::workdir::remote_branch(&REMOTE_NAME, project.default_branch());
+
+    if stored.is_partial() {
+        // If the stored repository is partial, so is the working copy. Since `git2`
+        // is unable to fetch from partial repositories, we fetch the default branch,
+        // which is the only branch of a partial repository, using the `git` CLI.
+        let head = stored.reference_oid(remote, &git::refs::branch(project.default_branch()))?;
+        let workdir = repo.workdir().unwrap_or(path.as_ref());
+
+        fs::copy(stored.path().join("shallow"), repo.path().join("shallow"))?;
+        git::process::fetch_local(workdir, &stored, [head])?;
+
+        repo.reference(
+            &remote_head_ref,
+            *head,
+            true,
+            "checkout: partial repository (radicle)",
+        )?;
+    } else {
+        git::fetch(&repo, &REMOTE_NAME).map_err(CheckoutError::Fetch)?;
+    }
 
     {
         // Setup default branch.
-        let remote_head_ref =
-            git::refs::workdir::remote_branch(&REMOTE_NAME, project.default_branch());
 
         let remote_head_commit = repo.find_reference(&remote_head_ref)?.peel_to_commit()?;
         let branch = repo
@@ -42,6 +42,8 @@ pub struct RepositoryInfo<V> {
     /// Local signed refs, if any.
     /// Repositories with this set to `None` are ones that are seeded but not forked.
     pub refs: Option<refs::SignedRefsAt>,
+    /// Whether the repository is partial, ie. was replicated with a limited history depth.
+    pub partial: bool,
 }
 
 /// Describes one or more namespaces.
@@ -469,6 +471,11 @@ pub trait ReadRepository: Sized + ValidateRepository {
     /// Returns `true` if there are no references in the repository.
     fn is_empty(&self) -> Result<bool, git2::Error>;
 
+    /// Check whether this is a partial repository, ie. one that was replicated
+    /// with a limited history depth. Partial repositories have their history
+    /// cut off at the commits listed in the Git `shallow` file.
+    fn is_partial(&self) -> bool;
+
     /// The [`Path`] to the git repository.
     fn path(&self) -> &Path;
 
@@ -190,6 +190,7 @@ impl ReadStorage for Storage {
                 head,
                 doc,
                 refs,
+                partial: repo.is_partial(),
             });
         }
         Ok(repos)
@@ -276,6 +277,7 @@ impl Storage {
                 head,
                 doc: repo.identity_doc()?.into(),
                 refs: refs::SignedRefsAt::load(self.info.key, &repo)?,
+                partial: repo.is_partial(),
             };
             infos.push(info);
             Ok(infos)
@@ -302,8 +304,11 @@ impl Storage {
         let repos = self.repositories()?;
         Ok(repos
             .into_iter()
-            .filter(|r| r.doc.visibility.is_public())
-            .map(|r| r.rid))
+            // N.b. partial repositories are not announced, since we can't serve them in full.
+            .filter(|r| r.doc.visibility.is_public() && !r.partial)
+            .map(|r| r.rid)
+            .collect::<Vec<_>>()
+            .into_iter())
     }
 }
 
@@ -635,6 +640,10 @@ impl ReadRepository for Repository {
         Ok(self.remotes()?.next().is_none())
     }
 
+    fn is_partial(&self) -> bool {
+        self.backend.is_shallow()
+    }
+
     fn path(&self) -> &Path {
         self.backend.path()
     }
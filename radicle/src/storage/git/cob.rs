@@ -262,6 +262,10 @@ impl<'a, R: storage::ReadRepository> ReadRepository for DraftStore<'a, R> {
         self.repo.is_empty()
     }
 
+    fn is_partial(&self) -> bool {
+        self.repo.is_partial()
+    }
+
     fn head(&self) -> Result<(Qualified, Oid), RepositoryError> {
         self.repo.head()
     }
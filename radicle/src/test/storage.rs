@@ -99,6 +99,7 @@ impl ReadStorage for MockStorage {
                 head: r.head().unwrap().1,
                 doc: r.doc.clone().into(),
                 refs: None,
+                partial: false,
             })
             .collect())
     }
@@ -200,6 +201,10 @@ impl ReadRepository for MockRepository {
         Ok(self.remotes.is_empty())
     }
 
+    fn is_partial(&self) -> bool {
+        false
+    }
+
     fn head(&self) -> Result<(fmt::Qualified, Oid), RepositoryError> {
         Ok((fmt::qualified!("refs/heads/master"), arbitrary::oid()))
     }